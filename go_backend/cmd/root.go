@@ -17,6 +17,7 @@ import (
 	"mix/internal/config"
 	"mix/internal/db"
 	"mix/internal/format"
+	"mix/internal/grpcserver"
 	httphandlers "mix/internal/http"
 	"mix/internal/llm/agent"
 	"mix/internal/logging"
@@ -68,6 +69,7 @@ and content creation workflows.`,
 		query, _ := cmd.Flags().GetString("query")
 		httpPort, _ := cmd.Flags().GetInt("http-port")
 		httpHost, _ := cmd.Flags().GetString("http-host")
+		grpcPort, _ := cmd.Flags().GetInt("grpc-port")
 		skipPermissions, _ := cmd.Flags().GetBool("dangerously-skip-permissions")
 
 		// Validate format option
@@ -113,6 +115,16 @@ and content creation workflows.`,
 		// Initialize MCP tools early for both modes
 		initMCPTools(ctx, app)
 
+		// gRPC runs alongside the HTTP server, never on its own
+		if httpPort > 0 && grpcPort > 0 {
+			go func() {
+				defer logging.RecoverPanic("gRPC-server", nil)
+				if err := grpcserver.Serve(ctx, app, httpHost, grpcPort); err != nil {
+					logging.Error("gRPC server stopped", "error", err)
+				}
+			}()
+		}
+
 		// HTTP server mode (blocks, no other modes)
 		if httpPort > 0 {
 			return startHTTPServer(ctx, app, httpHost, httpPort)
@@ -203,7 +215,7 @@ Usage examples:
   echo '{"method": "sessions.list", "id": 1}' | %s --query json --output-format json
   echo '{"method": "sessions.create", "params": {"title": "New Session"}, "id": 1}' | %s --query json --output-format json
   
-Available methods: sessions.list, sessions.create, sessions.select, sessions.delete, tools.list, mcp.list, commands.list`,
+Available methods: sessions.list, sessions.create, sessions.select, sessions.delete, tools.list, mcp.list, mcp.resources, commands.list`,
 			os.Args[0], os.Args[0])
 	}
 
@@ -275,7 +287,7 @@ func startHTTPServer(ctx context.Context, app *app.App, host string, port int) e
 
 	// Add SSE streaming endpoint
 	mux.HandleFunc("/stream", func(w http.ResponseWriter, r *http.Request) {
-		httphandlers.HandleSSEStream(ctx, handler, w, r)
+		httphandlers.HandleSSEStream(logging.WithTraceID(ctx, logging.TraceID(r.Context())), handler, w, r)
 	})
 
 	// Add message queue endpoint for persistent SSE
@@ -292,7 +304,7 @@ func startHTTPServer(ctx context.Context, app *app.App, host string, port int) e
 		// Set CORS headers
 		w.Header().Set("Access-Control-Allow-Origin", "*")
 		w.Header().Set("Access-Control-Allow-Methods", "POST, OPTIONS")
-		w.Header().Set("Access-Control-Allow-Headers", "Content-Type")
+		w.Header().Set("Access-Control-Allow-Headers", "Content-Type, Authorization, "+httphandlers.TraceIDHeader)
 		w.Header().Set("Content-Type", "application/json")
 
 		// Handle preflight OPTIONS request
@@ -333,12 +345,16 @@ func startHTTPServer(ctx context.Context, app *app.App, host string, port int) e
 			return
 		}
 
+		// Requests outlive the connection, so they run on the server's context
+		// with the trace id carried over
+		reqCtx := logging.WithTraceID(ctx, logging.TraceID(r.Context()))
+
 		// Log the incoming request
-		logging.Debug("HTTP Request: method=%s\n", request.Method)
+		logging.FromContext(reqCtx).Debug("HTTP request", "method", request.Method)
 		logging.Debug("HTTP Request Body: %s\n", string(body))
 
 		// Handle the request
-		response := handler.Handle(ctx, &request)
+		response := handler.Handle(reqCtx, &request)
 
 		// Log the response
 		if responseJSON, err := json.Marshal(response); err == nil {
@@ -351,10 +367,18 @@ func startHTTPServer(ctx context.Context, app *app.App, host string, port int) e
 		json.NewEncoder(w).Encode(response)
 	})
 
+	if err := httphandlers.ValidateLoopbackHost(host); err != nil {
+		return err
+	}
+	token := os.Getenv(httphandlers.TokenEnv)
+	if token == "" {
+		return httphandlers.ErrNoToken
+	}
+
 	addr := host + ":" + strconv.Itoa(port)
 	server := &http.Server{
 		Addr:         addr,
-		Handler:      mux,
+		Handler:      httphandlers.WithTraceID(httphandlers.RequireToken(token, os.Getenv(httphandlers.IdentityEnv), mux)),
 		ReadTimeout:  5 * time.Minute,
 		WriteTimeout: 10 * time.Minute,
 		IdleTimeout:  15 * time.Minute, // Prevent 60-second drops
@@ -407,7 +431,8 @@ func init() {
 
 	// HTTP server flags
 	rootCmd.Flags().Int("http-port", 0, "Start HTTP JSON-RPC server on this port (0 = disabled)")
-	rootCmd.Flags().String("http-host", "localhost", "HTTP server host")
+	rootCmd.Flags().String("http-host", "127.0.0.1", "HTTP server host (loopback only)")
+	rootCmd.Flags().Int("grpc-port", 0, "Also serve the agent over gRPC on this port in HTTP mode (0 = disabled)")
 
 	// Permission flags
 	rootCmd.Flags().Bool("dangerously-skip-permissions", false, "Skip all permission prompts (DANGEROUS - use only in trusted environments)")
@@ -2,6 +2,7 @@ package api
 
 import (
 	"context"
+	"crypto/sha256"
 	"encoding/json"
 	"fmt"
 	"log"
@@ -13,7 +14,12 @@ import (
 	"mix/internal/commands"
 	"mix/internal/config"
 	"mix/internal/llm/agent"
+	"mix/internal/llm/models"
+	"mix/internal/llm/prompt"
 	"mix/internal/llm/tools"
+	"mix/internal/logging"
+	"mix/internal/message"
+	"mix/internal/session"
 )
 
 // JSON-RPC Request
@@ -44,6 +50,7 @@ type SessionData struct {
 	PromptTokens     int64     `json:"promptTokens"`
 	CompletionTokens int64     `json:"completionTokens"`
 	Cost             float64   `json:"cost"`
+	Workspace        string    `json:"workspace"`
 	CreatedAt        time.Time `json:"createdAt"`
 }
 
@@ -59,6 +66,14 @@ type MCPServerData struct {
 	Tools     []ToolData `json:"tools"`
 }
 
+type MCPResourceData struct {
+	Server      string `json:"server"`
+	URI         string `json:"uri"`
+	Name        string `json:"name"`
+	Description string `json:"description"`
+	MimeType    string `json:"mimeType"`
+}
+
 type CommandData struct {
 	Name        string `json:"name"`
 	Description string `json:"description"`
@@ -71,6 +86,8 @@ type MessageData struct {
 	Role      string `json:"role"`
 	Content   string `json:"content"`
 	Response  string `json:"response,omitempty"`
+	Model     string `json:"model,omitempty"`
+	CreatedAt int64  `json:"createdAt,omitempty"`
 }
 
 // Query handler
@@ -127,14 +144,24 @@ func (h *QueryHandler) Handle(ctx context.Context, req *QueryRequest) *QueryResp
 		return h.handleMessagesSend(ctx, req)
 	case "messages.history":
 		return h.handleMessagesHistory(ctx, req)
+	case "messages.list":
+		return h.handleMessagesList(ctx, req)
+	case "messages.get":
+		return h.handleMessagesGet(ctx, req)
 	case "messages.cross-session-history":
 		return h.handleMessagesCrossSessionHistory(ctx, req)
 	case "mcp.list":
 		return h.handleMCPList(ctx, req)
+	case "mcp.resources":
+		return h.handleMCPResources(ctx, req)
 	case "commands.list":
 		return h.handleCommandsList(ctx, req)
 	case "commands.get":
 		return h.handleCommandsGet(ctx, req)
+	case "health.check":
+		return h.handleHealthCheck(ctx, req)
+	case "agent.info":
+		return h.handleAgentInfo(ctx, req)
 	default:
 		return &QueryResponse{
 			Error: &QueryError{
@@ -173,8 +200,78 @@ func (h *QueryHandler) GetSupportedQueryTypes() []string {
 	return []string{"sessions", "tools", "mcp", "commands"}
 }
 
+// handleHealthCheck answers the desktop app's liveness probe through the same
+// JSON-RPC path prompts take
+func (h *QueryHandler) handleHealthCheck(ctx context.Context, req *QueryRequest) *QueryResponse {
+	return &QueryResponse{
+		Result: map[string]string{"status": "ok"},
+		ID:     req.ID,
+	}
+}
+
+// handleAgentInfo reports the main agent's model and a hash of its system
+// prompt, so the desktop app can tell when cached replies went stale
+func (h *QueryHandler) handleAgentInfo(ctx context.Context, req *QueryRequest) *QueryResponse {
+	agentCfg, ok := config.Get().Agents[config.AgentMain]
+	if !ok {
+		return &QueryResponse{
+			Error: &QueryError{
+				Code:    -32000,
+				Message: "main agent is not configured",
+			},
+			ID: req.ID,
+		}
+	}
+	model, ok := models.SupportedModels[agentCfg.Model]
+	if !ok {
+		return &QueryResponse{
+			Error: &QueryError{
+				Code:    -32000,
+				Message: fmt.Sprintf("unsupported model %s", agentCfg.Model),
+			},
+			ID: req.ID,
+		}
+	}
+
+	systemPrompt := prompt.GetAgentPrompt(config.AgentMain, model.Provider)
+	return &QueryResponse{
+		Result: map[string]string{
+			"model":            string(model.ID),
+			"systemPromptHash": fmt.Sprintf("%x", sha256.Sum256([]byte(systemPrompt))),
+		},
+		ID: req.ID,
+	}
+}
+
 func (h *QueryHandler) handleSessionsList(ctx context.Context, req *QueryRequest) *QueryResponse {
-	sessions, err := h.app.Sessions.List(ctx)
+	// Defaults to the active project's sessions; "all" lists every project
+	var params struct {
+		Workspace string `json:"workspace"`
+		All       bool   `json:"all"`
+	}
+	if len(req.Params) > 0 {
+		if err := json.Unmarshal(req.Params, &params); err != nil {
+			return &QueryResponse{
+				Error: &QueryError{
+					Code:    -32602,
+					Message: "Invalid params: " + err.Error(),
+				},
+				ID: req.ID,
+			}
+		}
+	}
+
+	var sessions []session.Session
+	var err error
+	if params.All {
+		sessions, err = h.app.Sessions.List(ctx)
+	} else {
+		workspace := params.Workspace
+		if workspace == "" {
+			workspace = config.WorkingDirectory()
+		}
+		sessions, err = h.app.Sessions.ListByWorkspace(ctx, workspace)
+	}
 	if err != nil {
 		return &QueryResponse{
 			Error: &QueryError{
@@ -194,6 +291,7 @@ func (h *QueryHandler) handleSessionsList(ctx context.Context, req *QueryRequest
 			PromptTokens:     s.PromptTokens,
 			CompletionTokens: s.CompletionTokens,
 			Cost:             s.Cost,
+			Workspace:        s.Workspace,
 			CreatedAt:        time.Unix(s.CreatedAt, 0),
 		})
 	}
@@ -247,6 +345,7 @@ func (h *QueryHandler) handleSessionsGet(ctx context.Context, req *QueryRequest)
 		PromptTokens:     session.PromptTokens,
 		CompletionTokens: session.CompletionTokens,
 		Cost:             session.Cost,
+		Workspace:        session.Workspace,
 		CreatedAt:        time.Unix(session.CreatedAt, 0),
 	}
 
@@ -285,6 +384,7 @@ func (h *QueryHandler) handleSessionsCurrent(ctx context.Context, req *QueryRequ
 		PromptTokens:     currentSession.PromptTokens,
 		CompletionTokens: currentSession.CompletionTokens,
 		Cost:             currentSession.Cost,
+		Workspace:        currentSession.Workspace,
 		CreatedAt:        time.Unix(currentSession.CreatedAt, 0),
 	}
 
@@ -405,6 +505,7 @@ func (h *QueryHandler) handleSessionsCreate(ctx context.Context, req *QueryReque
 		PromptTokens:     session.PromptTokens,
 		CompletionTokens: session.CompletionTokens,
 		Cost:             session.Cost,
+		Workspace:        session.Workspace,
 		CreatedAt:        time.Unix(session.CreatedAt, 0),
 	}
 
@@ -417,7 +518,7 @@ func (h *QueryHandler) handleSessionsCreate(ctx context.Context, req *QueryReque
 func (h *QueryHandler) handleMCPList(ctx context.Context, req *QueryRequest) *QueryResponse {
 	cfg := config.Get()
 
-	var result []MCPServerData
+	result := []MCPServerData{}
 
 	if len(cfg.MCPServers) == 0 {
 		return &QueryResponse{
@@ -459,7 +560,7 @@ func (h *QueryHandler) handleMCPList(ctx context.Context, req *QueryRequest) *Qu
 		}
 
 		// Convert tools to ToolData
-		var toolsData []ToolData
+		toolsData := []ToolData{}
 		for _, tool := range tools {
 			info := tool.Info()
 			// Remove server prefix from tool name for cleaner display
@@ -495,6 +596,40 @@ func (h *QueryHandler) handleMCPList(ctx context.Context, req *QueryRequest) *Qu
 	}
 }
 
+func (h *QueryHandler) handleMCPResources(ctx context.Context, req *QueryRequest) *QueryResponse {
+	result := []MCPResourceData{}
+
+	// Create temporary manager for informational listing
+	tempManager := agent.NewMCPClientManager()
+	defer tempManager.Close()
+	serverResources := agent.GetMcpResources(ctx, tempManager)
+
+	for server, resources := range serverResources {
+		for _, resource := range resources {
+			result = append(result, MCPResourceData{
+				Server:      server,
+				URI:         resource.URI,
+				Name:        resource.Name,
+				Description: resource.Description,
+				MimeType:    resource.MIMEType,
+			})
+		}
+	}
+
+	// Sort by server, then URI, for consistent output
+	sort.Slice(result, func(i, j int) bool {
+		if result[i].Server != result[j].Server {
+			return result[i].Server < result[j].Server
+		}
+		return result[i].URI < result[j].URI
+	})
+
+	return &QueryResponse{
+		Result: result,
+		ID:     req.ID,
+	}
+}
+
 func (h *QueryHandler) handleCommandsList(ctx context.Context, req *QueryRequest) *QueryResponse {
 	allCommands := h.commandRegistry.GetAllCommands()
 
@@ -693,6 +828,7 @@ func (h *QueryHandler) handleMessagesSend(ctx context.Context, req *QueryRequest
 	}
 
 	// Send message to agent
+	logging.FromContext(ctx).Info("HTTP prompt", "session_id", params.SessionID)
 	done, err := h.app.CoderAgent.Run(ctx, params.SessionID, params.Content)
 	if err != nil {
 		return &QueryResponse{
@@ -796,6 +932,118 @@ func (h *QueryHandler) handleMessagesHistory(ctx context.Context, req *QueryRequ
 	}
 }
 
+// handleMessagesList returns the full transcript of a session, skipping
+// tool-only messages that have no text
+func (h *QueryHandler) handleMessagesList(ctx context.Context, req *QueryRequest) *QueryResponse {
+	var params struct {
+		SessionID string `json:"sessionId"`
+	}
+
+	if err := json.Unmarshal(req.Params, &params); err != nil {
+		return &QueryResponse{
+			Error: &QueryError{
+				Code:    -32602,
+				Message: "Invalid params: " + err.Error(),
+			},
+			ID: req.ID,
+		}
+	}
+
+	if params.SessionID == "" {
+		return &QueryResponse{
+			Error: &QueryError{
+				Code:    -32602,
+				Message: "Missing required parameter: sessionId",
+			},
+			ID: req.ID,
+		}
+	}
+
+	messages, err := h.app.Messages.List(ctx, params.SessionID)
+	if err != nil {
+		return &QueryResponse{
+			Error: &QueryError{
+				Code:    -32000,
+				Message: "Failed to list messages: " + err.Error(),
+			},
+			ID: req.ID,
+		}
+	}
+
+	result := []MessageData{}
+	for _, msg := range messages {
+		if msg.Role != message.User && msg.Role != message.Assistant {
+			continue
+		}
+		content := msg.Content().String()
+		if content == "" {
+			continue
+		}
+		result = append(result, MessageData{
+			ID:        msg.ID,
+			SessionID: msg.SessionID,
+			Role:      string(msg.Role),
+			Content:   content,
+			Model:     string(msg.Model),
+			CreatedAt: msg.CreatedAt,
+		})
+	}
+
+	return &QueryResponse{
+		Result: result,
+		ID:     req.ID,
+	}
+}
+
+func (h *QueryHandler) handleMessagesGet(ctx context.Context, req *QueryRequest) *QueryResponse {
+	var params struct {
+		ID string `json:"id"`
+	}
+
+	if err := json.Unmarshal(req.Params, &params); err != nil {
+		return &QueryResponse{
+			Error: &QueryError{
+				Code:    -32602,
+				Message: "Invalid params: " + err.Error(),
+			},
+			ID: req.ID,
+		}
+	}
+
+	if params.ID == "" {
+		return &QueryResponse{
+			Error: &QueryError{
+				Code:    -32602,
+				Message: "Missing required parameter: id",
+			},
+			ID: req.ID,
+		}
+	}
+
+	msg, err := h.app.Messages.Get(ctx, params.ID)
+	if err != nil {
+		return &QueryResponse{
+			Error: &QueryError{
+				Code:    -32000,
+				Message: "Failed to get message: " + err.Error(),
+			},
+			ID: req.ID,
+		}
+	}
+
+	return &QueryResponse{
+		Result: MessageData{
+			ID:        msg.ID,
+			SessionID: msg.SessionID,
+			Role:      string(msg.Role),
+			Content:   msg.Content().String(),
+			Model:     string(msg.Model),
+			CreatedAt: msg.CreatedAt,
+		},
+		ID: req.ID,
+	}
+}
+
 func (h *QueryHandler) handleMessagesCrossSessionHistory(ctx context.Context, req *QueryRequest) *QueryResponse {
 	var params struct {
 		ExcludeSessionID string `json:"excludeSessionId"`
@@ -25,14 +25,20 @@ type App struct {
 
 	CoderAgent agent.Service
 
+	queries *db.Queries
+
 	// Current session tracking for API session selection
 	currentSessionID string
 }
 
 func New(ctx context.Context, conn *sql.DB) (*App, error) {
-	q := db.New(conn)
+	// Statements are prepared once instead of on every call
+	q, err := db.Prepare(ctx, conn)
+	if err != nil {
+		return nil, fmt.Errorf("failed to prepare queries: %w", err)
+	}
 	sessions := session.NewService(q)
-	messages := message.NewService(q)
+	messages := message.NewService(q, conn)
 	files := history.NewService(q, conn)
 
 	app := &App{
@@ -40,12 +46,12 @@ func New(ctx context.Context, conn *sql.DB) (*App, error) {
 		Messages:    messages,
 		History:     files,
 		Permissions: permission.NewPermissionService(),
+		queries:     q,
 	}
 
 	// Create MCP manager for this agent
 	mcpManager := agent.NewMCPClientManager()
 
-	var err error
 	app.CoderAgent, err = agent.NewAgent(
 		config.AgentMain,
 		app.Sessions,
@@ -161,5 +167,8 @@ func (a *App) GetCurrentSessionID() string {
 
 // Shutdown performs a clean shutdown of the application
 func (app *App) Shutdown() {
+	if err := app.queries.Close(); err != nil {
+		logging.Error("Failed to close prepared statements", "error", err)
+	}
 	logging.Info("Application shutdown completed")
 }
@@ -281,8 +281,8 @@ func createSessionHandler(app *app.App) func(ctx context.Context, args string) (
 
 func createSessionsHandler(app *app.App) func(ctx context.Context, args string) (string, error) {
 	return func(ctx context.Context, args string) (string, error) {
-		// Get all sessions from the database
-		sessions, err := app.Sessions.List(ctx)
+		// Get the active project's sessions from the database
+		sessions, err := app.Sessions.ListByWorkspace(ctx, config.WorkingDirectory())
 		if err != nil {
 			return returnError("sessions", fmt.Sprintf("Error retrieving sessions: %v", err))
 		}
@@ -35,6 +35,8 @@ type MCPServer struct {
 	Headers      map[string]string `json:"headers"`
 	AllowedTools []string          `json:"allowedTools,omitempty"`
 	DeniedTools  []string          `json:"deniedTools,omitempty"`
+	// Seconds a tool call may take, 30 when unset
+	Timeout      int               `json:"timeout,omitempty"`
 }
 
 type AgentName string
@@ -133,6 +135,7 @@ func Load(workingDir string, debug bool, skipPermissions bool) (*Config, error)
 		return cfg, fmt.Errorf("failed to unmarshal config: %w", err)
 	}
 
+	mergeAppMCPServers()
 	applyDefaultValues()
 	defaultLevel := slog.LevelInfo
 	if cfg.Debug {
@@ -321,6 +324,24 @@ func mergeLocalConfig(workingDir string) {
 	}
 }
 
+// mergeAppMCPServers adds the MCP servers configured in the desktop app, which
+// passes them as JSON in MIX_MCP_SERVERS. They override config file entries.
+func mergeAppMCPServers() {
+	raw := os.Getenv("MIX_MCP_SERVERS")
+	if raw == "" {
+		return
+	}
+
+	var servers map[string]MCPServer
+	if err := json.Unmarshal([]byte(raw), &servers); err != nil {
+		logging.Warn("ignoring invalid MIX_MCP_SERVERS", "error", err)
+		return
+	}
+	for name, server := range servers {
+		cfg.MCPServers[name] = server
+	}
+}
+
 // applyDefaultValues sets default values for configuration fields that need processing.
 func applyDefaultValues() {
 	// Set default MCP type if not specified
@@ -1,13 +1,18 @@
 package db
 
 import (
+	"bytes"
 	"database/sql"
 	"fmt"
+	"io"
+	"net/url"
 	"os"
 	"path/filepath"
+	"strings"
 
 	_ "github.com/ncruces/go-sqlite3/driver"
 	_ "github.com/ncruces/go-sqlite3/embed"
+	_ "github.com/ncruces/go-sqlite3/vfs/xts"
 
 	"mix/internal/config"
 	"mix/internal/logging"
@@ -15,6 +20,125 @@ import (
 	"github.com/pressly/goose/v3"
 )
 
+// KeyEnv holds the hex encoded AES-XTS key for an encrypted database. It is set
+// by the desktop app, which keeps the key in the OS keychain.
+const KeyEnv = "MIX_DB_KEY"
+
+var plaintextHeader = []byte("SQLite format 3\x00")
+
+// pragmas are passed in the data source so that every pooled connection gets
+// them, not just the one that happens to run an Exec. The busy timeout lets a
+// writer wait for the streaming flush instead of failing with SQLITE_BUSY.
+var pragmas = []string{
+	"busy_timeout(5000)",
+	"foreign_keys(1)",
+	"journal_mode(WAL)",
+	"synchronous(NORMAL)",
+	"cache_size(-8000)",
+	"temp_store(MEMORY)",
+}
+
+func encryptedURI(path, key string) string {
+	return "file:" + filepath.ToSlash(path) + "?vfs=xts&hexkey=" + key
+}
+
+// withPragmas appends the connection pragmas to a "file:" URI
+func withPragmas(uri string) string {
+	separator := "?"
+	if strings.Contains(uri, "?") {
+		separator = "&"
+	}
+	for _, pragma := range pragmas {
+		uri += separator + "_pragma=" + url.QueryEscape(pragma)
+		separator = "&"
+	}
+	// Writes take the lock up front, so concurrent writers wait on the busy
+	// timeout instead of failing when a deferred read lock can't be upgraded
+	return uri + "&_txlock=immediate"
+}
+
+// isPlaintext reports whether the file at path is an unencrypted SQLite database
+func isPlaintext(path string) (bool, error) {
+	f, err := os.Open(path)
+	if err != nil {
+		return false, err
+	}
+	defer f.Close()
+
+	header := make([]byte, len(plaintextHeader))
+	if _, err := io.ReadFull(f, header); err != nil {
+		return false, nil
+	}
+	return bytes.Equal(header, plaintextHeader), nil
+}
+
+// encryptPlaintext migrates an existing plaintext database to an encrypted copy
+// in place. The plaintext file and its WAL are removed afterwards.
+func encryptPlaintext(dbPath, key string) error {
+	plain, err := isPlaintext(dbPath)
+	if os.IsNotExist(err) {
+		return nil
+	}
+	if err != nil {
+		return fmt.Errorf("failed to inspect database: %w", err)
+	}
+	if !plain {
+		return nil
+	}
+
+	logging.Info("Encrypting existing database", "path", dbPath)
+
+	src, err := sql.Open("sqlite3", dbPath)
+	if err != nil {
+		return fmt.Errorf("failed to open plaintext database: %w", err)
+	}
+	// Fold the WAL into the main file first, so whatever -wal is left behind
+	// after the swap holds nothing the encrypted copy lacks
+	if _, err := src.Exec("PRAGMA wal_checkpoint(TRUNCATE)"); err != nil {
+		src.Close()
+		return fmt.Errorf("failed to checkpoint database: %w", err)
+	}
+	tmpPath := dbPath + ".encrypted"
+	os.Remove(tmpPath)
+	if _, err := src.Exec("VACUUM INTO ?", encryptedURI(tmpPath, key)); err != nil {
+		src.Close()
+		os.Remove(tmpPath)
+		return fmt.Errorf("failed to encrypt database: %w", err)
+	}
+	src.Close()
+
+	// Until the rename the plaintext database is untouched, and a crash only
+	// leaves a temp file that the next attempt replaces
+	if err := syncFile(tmpPath); err != nil {
+		os.Remove(tmpPath)
+		return fmt.Errorf("failed to flush encrypted database: %w", err)
+	}
+	if err := os.Rename(tmpPath, dbPath); err != nil {
+		os.Remove(tmpPath)
+		return fmt.Errorf("failed to replace plaintext database: %w", err)
+	}
+	if err := syncFile(filepath.Dir(dbPath)); err != nil {
+		logging.Warn("Failed to flush data directory", "error", err)
+	}
+
+	for _, suffix := range []string{"-wal", "-shm"} {
+		if err := os.Remove(dbPath + suffix); err != nil && !os.IsNotExist(err) {
+			return fmt.Errorf("failed to remove stale %s file: %w", suffix, err)
+		}
+	}
+	return nil
+}
+
+// syncFile flushes a file, or a directory's entries, to disk
+func syncFile(path string) error {
+	f, err := os.Open(path)
+	if err != nil {
+		return err
+	}
+	defer f.Close()
+	return f.Sync()
+}
+
 func Connect() (*sql.DB, error) {
 	dataDir := config.Get().Data.Directory
 	if dataDir == "" {
@@ -24,8 +148,16 @@ func Connect() (*sql.DB, error) {
 		return nil, fmt.Errorf("failed to create data directory: %w", err)
 	}
 	dbPath := filepath.Join(dataDir, "mix.db")
+	dataSource := "file:" + filepath.ToSlash(dbPath)
+	if key := os.Getenv(KeyEnv); key != "" {
+		if err := encryptPlaintext(dbPath, key); err != nil {
+			return nil, err
+		}
+		dataSource = encryptedURI(dbPath, key)
+	}
+
 	// Open the SQLite database
-	db, err := sql.Open("sqlite3", dbPath)
+	db, err := sql.Open("sqlite3", withPragmas(dataSource))
 	if err != nil {
 		return nil, fmt.Errorf("failed to open database: %w", err)
 	}
@@ -36,23 +168,6 @@ func Connect() (*sql.DB, error) {
 		return nil, fmt.Errorf("failed to connect to database: %w", err)
 	}
 
-	// Set pragmas for better performance
-	pragmas := []string{
-		"PRAGMA foreign_keys = ON;",
-		"PRAGMA journal_mode = WAL;",
-		"PRAGMA page_size = 4096;",
-		"PRAGMA cache_size = -8000;",
-		"PRAGMA synchronous = NORMAL;",
-	}
-
-	for _, pragma := range pragmas {
-		if _, err = db.Exec(pragma); err != nil {
-			logging.Error("Failed to set pragma", pragma, err)
-		} else {
-			logging.Debug("Set pragma", "pragma", pragma)
-		}
-	}
-
 	goose.SetBaseFS(FS)
 
 	if err := goose.SetDialect("sqlite3"); err != nil {
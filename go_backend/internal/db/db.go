@@ -81,6 +81,9 @@ func Prepare(ctx context.Context, db DBTX) (*Queries, error) {
 	if q.listSessionsStmt, err = db.PrepareContext(ctx, listSessions); err != nil {
 		return nil, fmt.Errorf("error preparing query ListSessions: %w", err)
 	}
+	if q.listSessionsByWorkspaceStmt, err = db.PrepareContext(ctx, listSessionsByWorkspace); err != nil {
+		return nil, fmt.Errorf("error preparing query ListSessionsByWorkspace: %w", err)
+	}
 	if q.listUserMessageHistoryStmt, err = db.PrepareContext(ctx, listUserMessageHistory); err != nil {
 		return nil, fmt.Errorf("error preparing query ListUserMessageHistory: %w", err)
 	}
@@ -193,6 +196,11 @@ func (q *Queries) Close() error {
 			err = fmt.Errorf("error closing listSessionsStmt: %w", cerr)
 		}
 	}
+	if q.listSessionsByWorkspaceStmt != nil {
+		if cerr := q.listSessionsByWorkspaceStmt.Close(); cerr != nil {
+			err = fmt.Errorf("error closing listSessionsByWorkspaceStmt: %w", cerr)
+		}
+	}
 	if q.listUserMessageHistoryStmt != nil {
 		if cerr := q.listUserMessageHistoryStmt.Close(); cerr != nil {
 			err = fmt.Errorf("error closing listUserMessageHistoryStmt: %w", cerr)
@@ -271,6 +279,7 @@ type Queries struct {
 	listNewFilesStmt                    *sql.Stmt
 	listPreviousSessionsUserHistoryStmt *sql.Stmt
 	listSessionsStmt                    *sql.Stmt
+	listSessionsByWorkspaceStmt         *sql.Stmt
 	listUserMessageHistoryStmt          *sql.Stmt
 	updateFileStmt                      *sql.Stmt
 	updateMessageStmt                   *sql.Stmt
@@ -300,6 +309,7 @@ func (q *Queries) WithTx(tx *sql.Tx) *Queries {
 		listNewFilesStmt:                    q.listNewFilesStmt,
 		listPreviousSessionsUserHistoryStmt: q.listPreviousSessionsUserHistoryStmt,
 		listSessionsStmt:                    q.listSessionsStmt,
+		listSessionsByWorkspaceStmt:         q.listSessionsByWorkspaceStmt,
 		listUserMessageHistoryStmt:          q.listUserMessageHistoryStmt,
 		updateFileStmt:                      q.updateFileStmt,
 		updateMessageStmt:                   q.updateMessageStmt,
@@ -0,0 +1,17 @@
+-- +goose Up
+-- +goose StatementBegin
+-- Message lists are always ordered by time, per session or across sessions
+-- for the user's prompt history
+CREATE INDEX IF NOT EXISTS idx_messages_session_created ON messages (session_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_messages_role_created ON messages (role, created_at);
+DROP INDEX IF EXISTS idx_messages_session_id;
+CREATE INDEX IF NOT EXISTS idx_sessions_parent_created ON sessions (parent_session_id, created_at);
+-- +goose StatementEnd
+
+-- +goose Down
+-- +goose StatementBegin
+DROP INDEX IF EXISTS idx_sessions_parent_created;
+CREATE INDEX IF NOT EXISTS idx_messages_session_id ON messages (session_id);
+DROP INDEX IF EXISTS idx_messages_role_created;
+DROP INDEX IF EXISTS idx_messages_session_created;
+-- +goose StatementEnd
@@ -28,6 +28,7 @@ type Querier interface {
 	ListNewFiles(ctx context.Context) ([]File, error)
 	ListPreviousSessionsUserHistory(ctx context.Context, arg ListPreviousSessionsUserHistoryParams) ([]Message, error)
 	ListSessions(ctx context.Context) ([]Session, error)
+	ListSessionsByWorkspace(ctx context.Context, workspace string) ([]Session, error)
 	ListUserMessageHistory(ctx context.Context, arg ListUserMessageHistoryParams) ([]Message, error)
 	UpdateFile(ctx context.Context, arg UpdateFileParams) (File, error)
 	UpdateMessage(ctx context.Context, arg UpdateMessageParams) error
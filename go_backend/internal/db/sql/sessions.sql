@@ -8,6 +8,7 @@ INSERT INTO sessions (
     completion_tokens,
     cost,
     summary_message_id,
+    workspace,
     updated_at,
     created_at
 ) VALUES (
@@ -19,6 +20,7 @@ INSERT INTO sessions (
     ?,
     ?,
     null,
+    ?,
     strftime('%s', 'now'),
     strftime('%s', 'now')
 ) RETURNING *;
@@ -34,6 +36,14 @@ FROM sessions
 WHERE parent_session_id is NULL
 ORDER BY created_at DESC;
 
+-- Sessions created before sessions were tied to a workspace show up everywhere
+-- name: ListSessionsByWorkspace :many
+SELECT *
+FROM sessions
+WHERE parent_session_id is NULL
+  AND (workspace = ? OR workspace = '')
+ORDER BY created_at DESC;
+
 -- name: UpdateSession :one
 UPDATE sessions
 SET
@@ -0,0 +1,188 @@
+// Package grpcserver exposes the agent over gRPC as a lower-latency alternative
+// to the HTTP JSON-RPC server. The service and its messages are generated from
+// proto/mix/sidecar/v1/sidecar.proto, which the desktop app also builds from.
+package grpcserver
+
+//go:generate protoc -I ../../proto --go_out=../.. --go_opt=module=mix --go-grpc_out=../.. --go-grpc_opt=module=mix mix/sidecar/v1/sidecar.proto
+
+import (
+	"context"
+	"crypto/subtle"
+	"errors"
+	"fmt"
+	"net"
+	"os"
+	"strconv"
+
+	"mix/internal/app"
+	"mix/internal/grpcserver/sidecarpb"
+	httphandlers "mix/internal/http"
+	"mix/internal/llm/agent"
+	"mix/internal/logging"
+
+	"google.golang.org/grpc"
+	"google.golang.org/grpc/codes"
+	"google.golang.org/grpc/metadata"
+	"google.golang.org/grpc/status"
+)
+
+type server struct {
+	sidecarpb.UnimplementedSidecarServer
+	app      *app.App
+	identity string
+}
+
+// sessionFor returns the requested session, or a new one when none is given
+func (s *server) sessionFor(ctx context.Context, req *sidecarpb.PromptRequest) (string, error) {
+	if req.Content == "" {
+		return "", status.Error(codes.InvalidArgument, "content is required")
+	}
+	if req.SessionId != "" {
+		return req.SessionId, nil
+	}
+	sess, err := s.app.Sessions.Create(ctx, "New Session")
+	if err != nil {
+		return "", status.Errorf(codes.Internal, "failed to create session: %v", err)
+	}
+	return sess.ID, nil
+}
+
+func (s *server) Prompt(ctx context.Context, req *sidecarpb.PromptRequest) (*sidecarpb.PromptReply, error) {
+	ctx = logging.WithTraceID(ctx, req.TraceId)
+	sessionID, err := s.sessionFor(ctx, req)
+	if err != nil {
+		return nil, err
+	}
+	logging.FromContext(ctx).Info("gRPC prompt", "session_id", sessionID)
+
+	done, err := s.app.CoderAgent.Run(ctx, sessionID, req.Content)
+	if err != nil {
+		return nil, status.Errorf(codes.Internal, "failed to send message: %v", err)
+	}
+	result := <-done
+	if result.Error != nil {
+		if errors.Is(result.Error, context.Canceled) || errors.Is(result.Error, agent.ErrRequestCancelled) {
+			return nil, status.Error(codes.Canceled, "prompt was cancelled")
+		}
+		return nil, status.Errorf(codes.Internal, "agent processing failed: %v", result.Error)
+	}
+
+	return &sidecarpb.PromptReply{
+		SessionId: sessionID,
+		MessageId: result.Message.ID,
+		Content:   result.Message.Content().String(),
+	}, nil
+}
+
+func (s *server) StreamPrompt(req *sidecarpb.PromptRequest, stream grpc.ServerStreamingServer[sidecarpb.PromptChunk]) error {
+	ctx := logging.WithTraceID(stream.Context(), req.TraceId)
+	sessionID, err := s.sessionFor(ctx, req)
+	if err != nil {
+		return err
+	}
+	logging.FromContext(ctx).Info("gRPC streaming prompt", "session_id", sessionID)
+
+	events, err := s.app.CoderAgent.Run(ctx, sessionID, req.Content)
+	if err != nil {
+		return status.Errorf(codes.Internal, "failed to send message: %v", err)
+	}
+	for {
+		select {
+		case <-ctx.Done():
+			// The client went away or cancelled the call
+			s.app.CoderAgent.Cancel(sessionID)
+			return status.FromContextError(ctx.Err()).Err()
+
+		case event, ok := <-events:
+			if !ok {
+				return nil
+			}
+			if event.Error != nil {
+				return stream.Send(&sidecarpb.PromptChunk{Kind: "error", Content: event.Error.Error(), Done: true})
+			}
+			if event.Type != agent.AgentEventTypeResponse {
+				continue
+			}
+			for _, toolCall := range event.Message.ToolCalls() {
+				if err := stream.Send(&sidecarpb.PromptChunk{Kind: "tool", Content: toolCall.Name}); err != nil {
+					return err
+				}
+			}
+			if event.Done {
+				return stream.Send(&sidecarpb.PromptChunk{Kind: "content", Content: event.Message.Content().String(), Done: true})
+			}
+		}
+	}
+}
+
+func (s *server) Health(ctx context.Context, req *sidecarpb.HealthRequest) (*sidecarpb.HealthReply, error) {
+	return &sidecarpb.HealthReply{Status: "ok", Identity: s.identity}, nil
+}
+
+func (s *server) Cancel(ctx context.Context, req *sidecarpb.CancelRequest) (*sidecarpb.CancelReply, error) {
+	if req.SessionId == "" {
+		return nil, status.Error(codes.InvalidArgument, "session_id is required")
+	}
+	s.app.CoderAgent.Cancel(req.SessionId)
+	return &sidecarpb.CancelReply{}, nil
+}
+
+// authorize applies the same shared-token check as the HTTP server
+func authorize(ctx context.Context, token string) error {
+	if token == "" {
+		return status.Error(codes.Unauthenticated, "no token configured")
+	}
+	md, _ := metadata.FromIncomingContext(ctx)
+	for _, value := range md.Get("authorization") {
+		provided := httphandlers.BearerToken(value)
+		if subtle.ConstantTimeCompare([]byte(provided), []byte(token)) == 1 {
+			return nil
+		}
+	}
+	return status.Error(codes.Unauthenticated, "invalid token")
+}
+
+// Serve blocks until ctx is cancelled or the listener fails
+func Serve(ctx context.Context, app *app.App, host string, port int) error {
+	if err := httphandlers.ValidateLoopbackHost(host); err != nil {
+		return err
+	}
+	token := os.Getenv(httphandlers.TokenEnv)
+	if token == "" {
+		return httphandlers.ErrNoToken
+	}
+
+	addr := net.JoinHostPort(host, strconv.Itoa(port))
+	listener, err := net.Listen("tcp", addr)
+	if err != nil {
+		return fmt.Errorf("gRPC server failed to listen: %w", err)
+	}
+
+	grpcServer := grpc.NewServer(
+		grpc.UnaryInterceptor(func(ctx context.Context, req any, info *grpc.UnaryServerInfo, handler grpc.UnaryHandler) (any, error) {
+			if err := authorize(ctx, token); err != nil {
+				return nil, err
+			}
+			return handler(ctx, req)
+		}),
+		grpc.StreamInterceptor(func(srv any, ss grpc.ServerStream, info *grpc.StreamServerInfo, handler grpc.StreamHandler) error {
+			if err := authorize(ss.Context(), token); err != nil {
+				return err
+			}
+			return handler(srv, ss)
+		}),
+	)
+	sidecarpb.RegisterSidecarServer(grpcServer, &server{app: app, identity: os.Getenv(httphandlers.IdentityEnv)})
+
+	go func() {
+		<-ctx.Done()
+		logging.Info("Shutting down gRPC server")
+		grpcServer.GracefulStop()
+	}()
+
+	logging.Info("gRPC server ready", "address", addr)
+	if err := grpcServer.Serve(listener); err != nil {
+		return fmt.Errorf("gRPC server failed: %v", err)
+	}
+	return nil
+}
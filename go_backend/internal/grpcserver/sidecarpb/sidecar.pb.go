@@ -0,0 +1,470 @@
+// Code generated by protoc-gen-go. DO NOT EDIT.
+// versions:
+// 	protoc-gen-go v1.36.6
+// 	protoc        v5.29.3
+// source: mix/sidecar/v1/sidecar.proto
+
+package sidecarpb
+
+import (
+	protoreflect "google.golang.org/protobuf/reflect/protoreflect"
+	protoimpl "google.golang.org/protobuf/runtime/protoimpl"
+	reflect "reflect"
+	sync "sync"
+	unsafe "unsafe"
+)
+
+const (
+	// Verify that this generated code is sufficiently up-to-date.
+	_ = protoimpl.EnforceVersion(20 - protoimpl.MinVersion)
+	// Verify that runtime/protoimpl is sufficiently up-to-date.
+	_ = protoimpl.EnforceVersion(protoimpl.MaxVersion - 20)
+)
+
+type PromptRequest struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	// Empty starts a new session
+	SessionId     string                 `protobuf:"bytes,1,opt,name=session_id,json=sessionId,proto3" json:"session_id,omitempty"`
+	Content       string                 `protobuf:"bytes,2,opt,name=content,proto3" json:"content,omitempty"`
+	TraceId       string                 `protobuf:"bytes,3,opt,name=trace_id,json=traceId,proto3" json:"trace_id,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *PromptRequest) Reset() {
+	*x = PromptRequest{}
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[0]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *PromptRequest) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*PromptRequest) ProtoMessage() {}
+
+func (x *PromptRequest) ProtoReflect() protoreflect.Message {
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[0]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use PromptRequest.ProtoReflect.Descriptor instead.
+func (*PromptRequest) Descriptor() ([]byte, []int) {
+	return file_mix_sidecar_v1_sidecar_proto_rawDescGZIP(), []int{0}
+}
+
+func (x *PromptRequest) GetSessionId() string {
+	if x != nil {
+		return x.SessionId
+	}
+	return ""
+}
+
+func (x *PromptRequest) GetContent() string {
+	if x != nil {
+		return x.Content
+	}
+	return ""
+}
+
+func (x *PromptRequest) GetTraceId() string {
+	if x != nil {
+		return x.TraceId
+	}
+	return ""
+}
+
+type PromptReply struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	SessionId     string                 `protobuf:"bytes,1,opt,name=session_id,json=sessionId,proto3" json:"session_id,omitempty"`
+	MessageId     string                 `protobuf:"bytes,2,opt,name=message_id,json=messageId,proto3" json:"message_id,omitempty"`
+	Content       string                 `protobuf:"bytes,3,opt,name=content,proto3" json:"content,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *PromptReply) Reset() {
+	*x = PromptReply{}
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[1]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *PromptReply) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*PromptReply) ProtoMessage() {}
+
+func (x *PromptReply) ProtoReflect() protoreflect.Message {
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[1]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use PromptReply.ProtoReflect.Descriptor instead.
+func (*PromptReply) Descriptor() ([]byte, []int) {
+	return file_mix_sidecar_v1_sidecar_proto_rawDescGZIP(), []int{1}
+}
+
+func (x *PromptReply) GetSessionId() string {
+	if x != nil {
+		return x.SessionId
+	}
+	return ""
+}
+
+func (x *PromptReply) GetMessageId() string {
+	if x != nil {
+		return x.MessageId
+	}
+	return ""
+}
+
+func (x *PromptReply) GetContent() string {
+	if x != nil {
+		return x.Content
+	}
+	return ""
+}
+
+// One streamed update
+type PromptChunk struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	// "tool", "content" or "error"
+	Kind          string                 `protobuf:"bytes,1,opt,name=kind,proto3" json:"kind,omitempty"`
+	Content       string                 `protobuf:"bytes,2,opt,name=content,proto3" json:"content,omitempty"`
+	Done          bool                   `protobuf:"varint,3,opt,name=done,proto3" json:"done,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *PromptChunk) Reset() {
+	*x = PromptChunk{}
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[2]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *PromptChunk) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*PromptChunk) ProtoMessage() {}
+
+func (x *PromptChunk) ProtoReflect() protoreflect.Message {
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[2]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use PromptChunk.ProtoReflect.Descriptor instead.
+func (*PromptChunk) Descriptor() ([]byte, []int) {
+	return file_mix_sidecar_v1_sidecar_proto_rawDescGZIP(), []int{2}
+}
+
+func (x *PromptChunk) GetKind() string {
+	if x != nil {
+		return x.Kind
+	}
+	return ""
+}
+
+func (x *PromptChunk) GetContent() string {
+	if x != nil {
+		return x.Content
+	}
+	return ""
+}
+
+func (x *PromptChunk) GetDone() bool {
+	if x != nil {
+		return x.Done
+	}
+	return false
+}
+
+type HealthRequest struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *HealthRequest) Reset() {
+	*x = HealthRequest{}
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[3]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *HealthRequest) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*HealthRequest) ProtoMessage() {}
+
+func (x *HealthRequest) ProtoReflect() protoreflect.Message {
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[3]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use HealthRequest.ProtoReflect.Descriptor instead.
+func (*HealthRequest) Descriptor() ([]byte, []int) {
+	return file_mix_sidecar_v1_sidecar_proto_rawDescGZIP(), []int{3}
+}
+
+type HealthReply struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	Status        string                 `protobuf:"bytes,1,opt,name=status,proto3" json:"status,omitempty"`
+	// Echoes MIX_SIDECAR_IDENTITY so the app can tell it spawned this server
+	Identity      string                 `protobuf:"bytes,2,opt,name=identity,proto3" json:"identity,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *HealthReply) Reset() {
+	*x = HealthReply{}
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[4]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *HealthReply) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*HealthReply) ProtoMessage() {}
+
+func (x *HealthReply) ProtoReflect() protoreflect.Message {
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[4]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use HealthReply.ProtoReflect.Descriptor instead.
+func (*HealthReply) Descriptor() ([]byte, []int) {
+	return file_mix_sidecar_v1_sidecar_proto_rawDescGZIP(), []int{4}
+}
+
+func (x *HealthReply) GetStatus() string {
+	if x != nil {
+		return x.Status
+	}
+	return ""
+}
+
+func (x *HealthReply) GetIdentity() string {
+	if x != nil {
+		return x.Identity
+	}
+	return ""
+}
+
+type CancelRequest struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	SessionId     string                 `protobuf:"bytes,1,opt,name=session_id,json=sessionId,proto3" json:"session_id,omitempty"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *CancelRequest) Reset() {
+	*x = CancelRequest{}
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[5]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *CancelRequest) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*CancelRequest) ProtoMessage() {}
+
+func (x *CancelRequest) ProtoReflect() protoreflect.Message {
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[5]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use CancelRequest.ProtoReflect.Descriptor instead.
+func (*CancelRequest) Descriptor() ([]byte, []int) {
+	return file_mix_sidecar_v1_sidecar_proto_rawDescGZIP(), []int{5}
+}
+
+func (x *CancelRequest) GetSessionId() string {
+	if x != nil {
+		return x.SessionId
+	}
+	return ""
+}
+
+type CancelReply struct {
+	state         protoimpl.MessageState `protogen:"open.v1"`
+	unknownFields protoimpl.UnknownFields
+	sizeCache     protoimpl.SizeCache
+}
+
+func (x *CancelReply) Reset() {
+	*x = CancelReply{}
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[6]
+	ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+	ms.StoreMessageInfo(mi)
+}
+
+func (x *CancelReply) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*CancelReply) ProtoMessage() {}
+
+func (x *CancelReply) ProtoReflect() protoreflect.Message {
+	mi := &file_mix_sidecar_v1_sidecar_proto_msgTypes[6]
+	if x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use CancelReply.ProtoReflect.Descriptor instead.
+func (*CancelReply) Descriptor() ([]byte, []int) {
+	return file_mix_sidecar_v1_sidecar_proto_rawDescGZIP(), []int{6}
+}
+
+var File_mix_sidecar_v1_sidecar_proto protoreflect.FileDescriptor
+
+const file_mix_sidecar_v1_sidecar_proto_rawDesc = "" +
+	"\n" +
+	"\x1cmix/sidecar/v1/sidecar.proto\x12\x0emix.sidecar.v1\"c\n" +
+	"\rPromptRequest\x12\x1d\n" +
+	"\n" +
+	"session_id\x18\x01 \x01(\tR\tsessionId\x12\x18\n" +
+	"\x07content\x18\x02 \x01(\tR\x07content\x12\x19\n" +
+	"\x08trace_id\x18\x03 \x01(\tR\x07traceId\"e\n" +
+	"\x0bPromptReply\x12\x1d\n" +
+	"\n" +
+	"session_id\x18\x01 \x01(\tR\tsessionId\x12\x1d\n" +
+	"\n" +
+	"message_id\x18\x02 \x01(\tR\tmessageId\x12\x18\n" +
+	"\x07content\x18\x03 \x01(\tR\x07content\"O\n" +
+	"\x0bPromptChunk\x12\x12\n" +
+	"\x04kind\x18\x01 \x01(\tR\x04kind\x12\x18\n" +
+	"\x07content\x18\x02 \x01(\tR\x07content\x12\x12\n" +
+	"\x04done\x18\x03 \x01(\x08R\x04done\"\x0f\n" +
+	"\rHealthRequest\"A\n" +
+	"\x0bHealthReply\x12\x16\n" +
+	"\x06status\x18\x01 \x01(\tR\x06status\x12\x1a\n" +
+	"\x08identity\x18\x02 \x01(\tR\x08identity\".\n" +
+	"\rCancelRequest\x12\x1d\n" +
+	"\n" +
+	"session_id\x18\x01 \x01(\tR\tsessionId\"\r\n" +
+	"\x0bCancelReply2\xa9\x02\n" +
+	"\x07Sidecar\x12D\n" +
+	"\x06Prompt\x12\x1d.mix.sidecar.v1.PromptRequest\x1a\x1b.mix.sidecar.v1.PromptReply\x12L\n" +
+	"\x0cStreamPrompt\x12\x1d.mix.sidecar.v1.PromptRequest\x1a\x1b.mix.sidecar.v1.PromptChunk0\x01\x12D\n" +
+	"\x06Health\x12\x1d.mix.sidecar.v1.HealthRequest\x1a\x1b.mix.sidecar.v1.HealthReply\x12D\n" +
+	"\x06Cancel\x12\x1d.mix.sidecar.v1.CancelRequest\x1a\x1b.mix.sidecar.v1.CancelReplyB#Z!mix/internal/grpcserver/sidecarpbb\x06proto3"
+
+var (
+	file_mix_sidecar_v1_sidecar_proto_rawDescOnce sync.Once
+	file_mix_sidecar_v1_sidecar_proto_rawDescData []byte
+)
+
+func file_mix_sidecar_v1_sidecar_proto_rawDescGZIP() []byte {
+	file_mix_sidecar_v1_sidecar_proto_rawDescOnce.Do(func() {
+		file_mix_sidecar_v1_sidecar_proto_rawDescData = protoimpl.X.CompressGZIP(unsafe.Slice(unsafe.StringData(file_mix_sidecar_v1_sidecar_proto_rawDesc), len(file_mix_sidecar_v1_sidecar_proto_rawDesc)))
+	})
+	return file_mix_sidecar_v1_sidecar_proto_rawDescData
+}
+
+var file_mix_sidecar_v1_sidecar_proto_msgTypes = make([]protoimpl.MessageInfo, 7)
+var file_mix_sidecar_v1_sidecar_proto_goTypes = []any{
+	(*PromptRequest)(nil), // 0: mix.sidecar.v1.PromptRequest
+	(*PromptReply)(nil),   // 1: mix.sidecar.v1.PromptReply
+	(*PromptChunk)(nil),   // 2: mix.sidecar.v1.PromptChunk
+	(*HealthRequest)(nil), // 3: mix.sidecar.v1.HealthRequest
+	(*HealthReply)(nil),   // 4: mix.sidecar.v1.HealthReply
+	(*CancelRequest)(nil), // 5: mix.sidecar.v1.CancelRequest
+	(*CancelReply)(nil),   // 6: mix.sidecar.v1.CancelReply
+}
+var file_mix_sidecar_v1_sidecar_proto_depIdxs = []int32{
+	0, // 0: mix.sidecar.v1.Sidecar.Prompt:input_type -> mix.sidecar.v1.PromptRequest
+	0, // 1: mix.sidecar.v1.Sidecar.StreamPrompt:input_type -> mix.sidecar.v1.PromptRequest
+	3, // 2: mix.sidecar.v1.Sidecar.Health:input_type -> mix.sidecar.v1.HealthRequest
+	5, // 3: mix.sidecar.v1.Sidecar.Cancel:input_type -> mix.sidecar.v1.CancelRequest
+	1, // 4: mix.sidecar.v1.Sidecar.Prompt:output_type -> mix.sidecar.v1.PromptReply
+	2, // 5: mix.sidecar.v1.Sidecar.StreamPrompt:output_type -> mix.sidecar.v1.PromptChunk
+	4, // 6: mix.sidecar.v1.Sidecar.Health:output_type -> mix.sidecar.v1.HealthReply
+	6, // 7: mix.sidecar.v1.Sidecar.Cancel:output_type -> mix.sidecar.v1.CancelReply
+	4, // [4:8] is the sub-list for method output_type
+	0, // [0:4] is the sub-list for method input_type
+	0, // [0:0] is the sub-list for extension type_name
+	0, // [0:0] is the sub-list for extension extendee
+	0, // [0:0] is the sub-list for field type_name
+}
+
+func init() { file_mix_sidecar_v1_sidecar_proto_init() }
+func file_mix_sidecar_v1_sidecar_proto_init() {
+	if File_mix_sidecar_v1_sidecar_proto != nil {
+		return
+	}
+	type x struct{}
+	out := protoimpl.TypeBuilder{
+		File: protoimpl.DescBuilder{
+			GoPackagePath: reflect.TypeOf(x{}).PkgPath(),
+			RawDescriptor: unsafe.Slice(unsafe.StringData(file_mix_sidecar_v1_sidecar_proto_rawDesc), len(file_mix_sidecar_v1_sidecar_proto_rawDesc)),
+			NumEnums:      0,
+			NumMessages:   7,
+			NumExtensions: 0,
+			NumServices:   1,
+		},
+		GoTypes:           file_mix_sidecar_v1_sidecar_proto_goTypes,
+		DependencyIndexes: file_mix_sidecar_v1_sidecar_proto_depIdxs,
+		MessageInfos:      file_mix_sidecar_v1_sidecar_proto_msgTypes,
+	}.Build()
+	File_mix_sidecar_v1_sidecar_proto = out.File
+	file_mix_sidecar_v1_sidecar_proto_goTypes = nil
+	file_mix_sidecar_v1_sidecar_proto_depIdxs = nil
+}
@@ -0,0 +1,74 @@
+package http
+
+import (
+	"crypto/subtle"
+	"errors"
+	"fmt"
+	"net"
+	"net/http"
+	"strings"
+)
+
+const (
+	// Environment variables set by the desktop app when it spawns the sidecar
+	TokenEnv    = "MIX_SIDECAR_TOKEN"
+	IdentityEnv = "MIX_SIDECAR_IDENTITY"
+
+	// IdentityHeader carries the server identity back so clients can tell the
+	// real sidecar apart from another process listening on the same port
+	IdentityHeader = "X-Mix-Server-Identity"
+)
+
+// ValidateLoopbackHost rejects hosts that would expose the server beyond this machine
+func ValidateLoopbackHost(host string) error {
+	if host == "localhost" {
+		return nil
+	}
+	if ip := net.ParseIP(host); ip != nil && ip.IsLoopback() {
+		return nil
+	}
+	return fmt.Errorf("refusing to bind HTTP server to non-loopback host %q", host)
+}
+
+// ErrNoToken is returned when a server is started without MIX_SIDECAR_TOKEN.
+// Serving without one would hand the agent to any local process, so there is
+// no unauthenticated mode; run a standalone server with a token of your own.
+var ErrNoToken = errors.New(TokenEnv + " is not set, refusing to serve without auth")
+
+// BearerToken returns the token from an "Authorization: Bearer" header, or ""
+func BearerToken(header string) string {
+	token, ok := strings.CutPrefix(header, "Bearer ")
+	if !ok {
+		return ""
+	}
+	return token
+}
+
+// RequireToken wraps every route with shared-token auth. The token is only
+// read from the Authorization header; query parameters end up in logs and
+// browser history. An empty token fails closed.
+func RequireToken(token, identity string, next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Access-Control-Expose-Headers", IdentityHeader)
+
+		// CORS preflight requests never carry credentials
+		if r.Method == http.MethodOptions {
+			w.Header().Set("Access-Control-Allow-Origin", "*")
+			w.Header().Set("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
+			w.Header().Set("Access-Control-Allow-Headers", "Content-Type, Authorization")
+			w.WriteHeader(http.StatusOK)
+			return
+		}
+
+		provided := BearerToken(r.Header.Get("Authorization"))
+		if token == "" || subtle.ConstantTimeCompare([]byte(provided), []byte(token)) != 1 {
+			http.Error(w, "Unauthorized", http.StatusUnauthorized)
+			return
+		}
+
+		if identity != "" {
+			w.Header().Set(IdentityHeader, identity)
+		}
+		next.ServeHTTP(w, r)
+	})
+}
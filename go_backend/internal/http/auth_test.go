@@ -0,0 +1,71 @@
+package http
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func serveWithToken(token, identity string, r *http.Request) *httptest.ResponseRecorder {
+	next := http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.WriteHeader(http.StatusOK)
+	})
+	recorder := httptest.NewRecorder()
+	RequireToken(token, identity, next).ServeHTTP(recorder, r)
+	return recorder
+}
+
+func TestRequireToken_AcceptsBearerHeader(t *testing.T) {
+	r := httptest.NewRequest(http.MethodPost, "/rpc", nil)
+	r.Header.Set("Authorization", "Bearer secret")
+
+	recorder := serveWithToken("secret", "server-id", r)
+
+	assert.Equal(t, http.StatusOK, recorder.Code)
+	assert.Equal(t, "server-id", recorder.Header().Get(IdentityHeader))
+}
+
+func TestRequireToken_RejectsWrongOrMissingToken(t *testing.T) {
+	for _, header := range []string{"", "Bearer wrong", "secret", "Basic secret"} {
+		r := httptest.NewRequest(http.MethodPost, "/rpc", nil)
+		if header != "" {
+			r.Header.Set("Authorization", header)
+		}
+
+		recorder := serveWithToken("secret", "server-id", r)
+
+		assert.Equal(t, http.StatusUnauthorized, recorder.Code, "header %q", header)
+		assert.Empty(t, recorder.Header().Get(IdentityHeader), "header %q", header)
+	}
+}
+
+func TestRequireToken_IgnoresQueryToken(t *testing.T) {
+	r := httptest.NewRequest(http.MethodGet, "/stream?sessionId=s&token=secret", nil)
+
+	recorder := serveWithToken("secret", "server-id", r)
+
+	assert.Equal(t, http.StatusUnauthorized, recorder.Code)
+}
+
+func TestRequireToken_FailsClosedWithoutToken(t *testing.T) {
+	for _, header := range []string{"", "Bearer ", "Bearer anything"} {
+		r := httptest.NewRequest(http.MethodPost, "/rpc", nil)
+		if header != "" {
+			r.Header.Set("Authorization", header)
+		}
+
+		recorder := serveWithToken("", "", r)
+
+		assert.Equal(t, http.StatusUnauthorized, recorder.Code, "header %q", header)
+	}
+}
+
+func TestRequireToken_AllowsPreflight(t *testing.T) {
+	r := httptest.NewRequest(http.MethodOptions, "/rpc", nil)
+
+	recorder := serveWithToken("secret", "server-id", r)
+
+	assert.Equal(t, http.StatusOK, recorder.Code)
+}
@@ -0,0 +1,39 @@
+package http
+
+import (
+	"net/http"
+
+	"mix/internal/logging"
+)
+
+// TraceIDHeader carries the app's per-prompt trace id.
+const TraceIDHeader = "X-Trace-Id"
+
+// Trace ids are UUIDs; anything longer or with other characters is dropped
+// rather than written to the logs.
+const maxTraceIDLength = 64
+
+func validTraceID(traceID string) bool {
+	if len(traceID) == 0 || len(traceID) > maxTraceIDLength {
+		return false
+	}
+	for _, c := range traceID {
+		if !(c == '-' || c >= '0' && c <= '9' || c >= 'a' && c <= 'z' || c >= 'A' && c <= 'Z') {
+			return false
+		}
+	}
+	return true
+}
+
+// WithTraceID stores the request's X-Trace-Id in its context, see
+// logging.FromContext.
+func WithTraceID(next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		traceID := r.Header.Get(TraceIDHeader)
+		if !validTraceID(traceID) {
+			next.ServeHTTP(w, r)
+			return
+		}
+		next.ServeHTTP(w, r.WithContext(logging.WithTraceID(r.Context(), traceID)))
+	})
+}
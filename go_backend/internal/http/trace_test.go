@@ -0,0 +1,37 @@
+package http
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"mix/internal/logging"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func traceIDSeenBy(header string) string {
+	var seen string
+	next := http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		seen = logging.TraceID(r.Context())
+	})
+	r := httptest.NewRequest(http.MethodPost, "/rpc", nil)
+	if header != "" {
+		r.Header.Set(TraceIDHeader, header)
+	}
+	WithTraceID(next).ServeHTTP(httptest.NewRecorder(), r)
+	return seen
+}
+
+func TestWithTraceID_StoresHeaderInContext(t *testing.T) {
+	traceID := "0b6f2c1e-8a4d-4f0e-9c1a-3d5e7f9a1b2c"
+
+	assert.Equal(t, traceID, traceIDSeenBy(traceID))
+}
+
+func TestWithTraceID_DropsMissingOrInvalidIds(t *testing.T) {
+	for _, header := range []string{"", "bad id", "id\nforged=1", strings.Repeat("a", maxTraceIDLength+1)} {
+		assert.Empty(t, traceIDSeenBy(header), "header %q", header)
+	}
+}
@@ -175,7 +175,9 @@ func (b *mcpTool) Info() tools.ToolInfo {
 	}
 }
 
-func runTool(ctx context.Context, c *client.Client, toolName string, input string) (tools.ToolResponse, error) {
+const defaultToolTimeout = 30 * time.Second
+
+func runTool(ctx context.Context, c *client.Client, toolName string, input string, timeout time.Duration) (tools.ToolResponse, error) {
 	// Client is already initialized by the manager, just call the tool
 	toolRequest := mcp.CallToolRequest{}
 	toolRequest.Params.Name = toolName
@@ -184,7 +186,7 @@ func runTool(ctx context.Context, c *client.Client, toolName string, input strin
 		return tools.NewTextErrorResponse(fmt.Sprintf("error parsing parameters: %s", err)), nil
 	}
 	toolRequest.Params.Arguments = args
-	callCtx, cancel := context.WithTimeout(ctx, 30*time.Second)
+	callCtx, cancel := context.WithTimeout(ctx, timeout)
 	defer cancel()
 	result, err := c.CallTool(callCtx, toolRequest)
 	if err != nil {
@@ -229,7 +231,11 @@ func (b *mcpTool) Run(ctx context.Context, params tools.ToolCall) (tools.ToolRes
 		return tools.NewTextErrorResponse(err.Error()), nil
 	}
 
-	return runTool(ctx, c, b.tool.Name, params.Input)
+	timeout := defaultToolTimeout
+	if b.mcpConfig.Timeout > 0 {
+		timeout = time.Duration(b.mcpConfig.Timeout) * time.Second
+	}
+	return runTool(ctx, c, b.tool.Name, params.Input, timeout)
 }
 
 func NewMcpTool(name string, tool mcp.Tool, permissions permission.Service, mcpConfig config.MCPServer, manager *MCPClientManager) tools.BaseTool {
@@ -309,3 +315,29 @@ func GetMcpTools(ctx context.Context, permissions permission.Service, manager *M
 
 	return allTools
 }
+
+// GetMcpResources lists the resources exposed by each configured server, keyed
+// by server name. Servers that fail to connect or don't support resources are
+// left out.
+func GetMcpResources(ctx context.Context, manager *MCPClientManager) map[string][]mcp.Resource {
+	resources := make(map[string][]mcp.Resource)
+
+	for name, m := range config.Get().MCPServers {
+		c, err := manager.GetClient(ctx, name, m)
+		if err != nil {
+			logging.Error("error getting mcp client", "server", name, "error", err)
+			continue
+		}
+
+		listCtx, cancel := context.WithTimeout(ctx, 3*time.Second)
+		result, err := c.ListResources(listCtx, mcp.ListResourcesRequest{})
+		cancel()
+		if err != nil {
+			logging.Debug("error listing resources", "server", name, "error", err)
+			continue
+		}
+		resources[name] = result.Resources
+	}
+
+	return resources
+}
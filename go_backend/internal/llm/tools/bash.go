@@ -4,6 +4,8 @@ import (
 	"context"
 	"encoding/json"
 	"fmt"
+	"os"
+	"path/filepath"
 	"strings"
 	"time"
 
@@ -140,7 +142,7 @@ func (b *bashTool) Run(ctx context.Context, call ToolCall) (ToolResponse, error)
 	}
 	startTime := time.Now()
 	shell := shell.GetPersistentShell(config.WorkingDirectory())
-	stdout, stderr, exitCode, interrupted, err := shell.Exec(ctx, params.Command, params.Timeout)
+	stdout, stderr, exitCode, interrupted, err := shell.Exec(ctx, withScratchDir(params.Command, sessionID), params.Timeout)
 	if err != nil {
 		return ToolResponse{}, fmt.Errorf("error executing command: %w", err)
 	}
@@ -181,6 +183,25 @@ func (b *bashTool) Run(ctx context.Context, call ToolCall) (ToolResponse, error)
 	return WithResponseMetadata(NewTextResponse(stdout), metadata), nil
 }
 
+// ScratchDirEnv is set by the desktop app to a managed directory; each session
+// gets its own subdirectory, exported to commands under the same name
+const ScratchDirEnv = "MIX_SCRATCH_DIR"
+
+// withScratchDir exports the session's scratch directory before the command.
+// The shell is shared between sessions, so it is re-exported on every call.
+func withScratchDir(command, sessionID string) string {
+	root := os.Getenv(ScratchDirEnv)
+	if root == "" {
+		return command
+	}
+	dir := filepath.Join(root, sessionID)
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return command
+	}
+	quoted := "'" + strings.ReplaceAll(dir, "'", `'\''`) + "'"
+	return fmt.Sprintf("export %s=%s\n%s", ScratchDirEnv, quoted, command)
+}
+
 func truncateOutput(content string) string {
 	if len(content) <= MaxOutputLength {
 		return content
@@ -0,0 +1,32 @@
+package logging
+
+import (
+	"context"
+	"log/slog"
+)
+
+type traceIDKey struct{}
+
+// WithTraceID returns a context carrying the app's per-prompt trace id, so
+// logs written while handling the request can be matched with the app's.
+func WithTraceID(ctx context.Context, traceID string) context.Context {
+	if traceID == "" {
+		return ctx
+	}
+	return context.WithValue(ctx, traceIDKey{}, traceID)
+}
+
+// TraceID returns the trace id stored by WithTraceID, or "".
+func TraceID(ctx context.Context) string {
+	traceID, _ := ctx.Value(traceIDKey{}).(string)
+	return traceID
+}
+
+// FromContext returns the default logger, tagged with the context's trace id
+// when it has one.
+func FromContext(ctx context.Context) *slog.Logger {
+	if traceID := TraceID(ctx); traceID != "" {
+		return slog.Default().With("trace_id", traceID)
+	}
+	return slog.Default()
+}
@@ -5,10 +5,12 @@ import (
 	"database/sql"
 	"encoding/json"
 	"fmt"
+	"sync"
 	"time"
 
 	"mix/internal/db"
 	"mix/internal/llm/models"
+	"mix/internal/logging"
 	"mix/internal/pubsub"
 
 	"github.com/google/uuid"
@@ -32,18 +34,55 @@ type Service interface {
 	ListPreviousSessionsUserMessages(ctx context.Context, excludeSessionID string, limit, offset int64) ([]Message, error)
 }
 
+// streamFlushInterval bounds how long a streamed chunk stays in memory only.
+// Providers emit one delta per token, and each used to be its own write.
+const streamFlushInterval = 250 * time.Millisecond
+
 type service struct {
 	*pubsub.Broker[Message]
-	q db.Querier
+	db *sql.DB
+	q  *db.Queries
+
+	// Latest parts of unfinished messages, keyed by message ID, waiting for
+	// the next flush
+	mu      sync.Mutex
+	pending map[string]db.UpdateMessageParams
 }
 
-func NewService(q db.Querier) Service {
+func NewService(q *db.Queries, conn *sql.DB) Service {
 	return &service{
-		Broker: pubsub.NewBroker[Message](),
-		q:      q,
+		Broker:  pubsub.NewBroker[Message](),
+		q:       q,
+		db:      conn,
+		pending: make(map[string]db.UpdateMessageParams),
 	}
 }
 
+// flush writes all pending updates in one transaction. Reads call it first so
+// they never see a message older than what subscribers were sent.
+func (s *service) flush(ctx context.Context) error {
+	s.mu.Lock()
+	pending := s.pending
+	s.pending = make(map[string]db.UpdateMessageParams)
+	s.mu.Unlock()
+	if len(pending) == 0 {
+		return nil
+	}
+
+	tx, err := s.db.BeginTx(ctx, nil)
+	if err != nil {
+		return fmt.Errorf("failed to begin transaction: %w", err)
+	}
+	qtx := s.q.WithTx(tx)
+	for _, params := range pending {
+		if err := qtx.UpdateMessage(ctx, params); err != nil {
+			tx.Rollback()
+			return fmt.Errorf("failed to update message %s: %w", params.ID, err)
+		}
+	}
+	return tx.Commit()
+}
+
 func (s *service) Delete(ctx context.Context, id string) error {
 	message, err := s.Get(ctx, id)
 	if err != nil {
@@ -111,13 +150,32 @@ func (s *service) Update(ctx context.Context, message Message) error {
 		finishedAt.Int64 = f.Time
 		finishedAt.Valid = true
 	}
-	err = s.q.UpdateMessage(ctx, db.UpdateMessageParams{
+	params := db.UpdateMessageParams{
 		ID:         message.ID,
 		Parts:      string(parts),
 		FinishedAt: finishedAt,
-	})
-	if err != nil {
-		return err
+	}
+
+	if finishedAt.Valid {
+		// Finished messages are written through, together with anything
+		// still pending, so the final state is durable when Update returns
+		s.mu.Lock()
+		s.pending[message.ID] = params
+		s.mu.Unlock()
+		if err := s.flush(ctx); err != nil {
+			return err
+		}
+	} else {
+		s.mu.Lock()
+		if len(s.pending) == 0 {
+			time.AfterFunc(streamFlushInterval, func() {
+				if err := s.flush(context.Background()); err != nil {
+					logging.Error("Failed to flush streamed messages", "error", err)
+				}
+			})
+		}
+		s.pending[message.ID] = params
+		s.mu.Unlock()
 	}
 	message.UpdatedAt = time.Now().Unix()
 	s.Publish(pubsub.UpdatedEvent, message)
@@ -125,6 +183,9 @@ func (s *service) Update(ctx context.Context, message Message) error {
 }
 
 func (s *service) Get(ctx context.Context, id string) (Message, error) {
+	if err := s.flush(ctx); err != nil {
+		return Message{}, err
+	}
 	dbMessage, err := s.q.GetMessage(ctx, id)
 	if err != nil {
 		return Message{}, err
@@ -133,6 +194,9 @@ func (s *service) Get(ctx context.Context, id string) (Message, error) {
 }
 
 func (s *service) List(ctx context.Context, sessionID string) ([]Message, error) {
+	if err := s.flush(ctx); err != nil {
+		return nil, err
+	}
 	dbMessages, err := s.q.ListMessagesBySession(ctx, sessionID)
 	if err != nil {
 		return nil, err
@@ -148,6 +212,9 @@ func (s *service) List(ctx context.Context, sessionID string) ([]Message, error)
 }
 
 func (s *service) ListUserMessageHistory(ctx context.Context, sessionID string, limit, offset int64) ([]Message, error) {
+	if err := s.flush(ctx); err != nil {
+		return nil, err
+	}
 	dbMessages, err := s.q.ListUserMessageHistory(ctx, db.ListUserMessageHistoryParams{
 		SessionID: sessionID,
 		Limit:     limit,
@@ -167,6 +234,9 @@ func (s *service) ListUserMessageHistory(ctx context.Context, sessionID string,
 }
 
 func (s *service) ListPreviousSessionsUserMessages(ctx context.Context, excludeSessionID string, limit, offset int64) ([]Message, error) {
+	if err := s.flush(ctx); err != nil {
+		return nil, err
+	}
 	dbMessages, err := s.q.ListPreviousSessionsUserHistory(ctx, db.ListPreviousSessionsUserHistoryParams{
 		SessionID: excludeSessionID,
 		Limit:     limit,
@@ -4,6 +4,7 @@ import (
 	"context"
 	"database/sql"
 
+	"mix/internal/config"
 	"mix/internal/db"
 	"mix/internal/pubsub"
 
@@ -18,6 +19,7 @@ type Session struct {
 	PromptTokens     int64
 	CompletionTokens int64
 	SummaryMessageID string
+	Workspace        string
 	Cost             float64
 	CreatedAt        int64
 	UpdatedAt        int64
@@ -29,6 +31,7 @@ type Service interface {
 	Create(ctx context.Context, title string) (Session, error)
 	Get(ctx context.Context, id string) (Session, error)
 	List(ctx context.Context) ([]Session, error)
+	ListByWorkspace(ctx context.Context, workspace string) ([]Session, error)
 	Save(ctx context.Context, session Session) (Session, error)
 	Delete(ctx context.Context, id string) error
 }
@@ -39,9 +42,11 @@ type service struct {
 }
 
 func (s *service) Create(ctx context.Context, title string) (Session, error) {
+	// Sessions belong to the project the sidecar was launched in
 	dbSession, err := s.q.CreateSession(ctx, db.CreateSessionParams{
-		ID:    uuid.New().String(),
-		Title: title,
+		ID:        uuid.New().String(),
+		Title:     title,
+		Workspace: config.WorkingDirectory(),
 	})
 	if err != nil {
 		return Session{}, err
@@ -86,6 +91,18 @@ func (s *service) List(ctx context.Context) ([]Session, error) {
 	return sessions, nil
 }
 
+func (s *service) ListByWorkspace(ctx context.Context, workspace string) ([]Session, error) {
+	dbSessions, err := s.q.ListSessionsByWorkspace(ctx, workspace)
+	if err != nil {
+		return nil, err
+	}
+	sessions := make([]Session, len(dbSessions))
+	for i, dbSession := range dbSessions {
+		sessions[i] = s.fromDBItem(dbSession)
+	}
+	return sessions, nil
+}
+
 func (s *service) Save(ctx context.Context, session Session) (Session, error) {
 	dbSession, err := s.q.UpdateSession(ctx, db.UpdateSessionParams{
 		ID:               session.ID,
@@ -117,6 +134,7 @@ func (s service) fromDBItem(item db.Session) Session {
 		PromptTokens:     item.PromptTokens,
 		CompletionTokens: item.CompletionTokens,
 		SummaryMessageID: item.SummaryMessageID.String,
+		Workspace:        item.Workspace,
 		Cost:             item.Cost,
 		CreatedAt:        item.CreatedAt,
 		UpdatedAt:        item.UpdatedAt,
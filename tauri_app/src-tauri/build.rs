@@ -1,3 +1,80 @@
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Written on every build, so a release never ships an empty manifest that
+// would refuse to start its own sidecar; see `integrity::verify_sidecar`
+const MANIFEST_PATH: &str = "resources/sidecar-manifest.json";
+const SIDECAR_NAME: &str = "mix";
+
+// Where a freshly built sidecar can be: Tauri's `binaries/<name>-<target>`
+// layout, or `make build` in the Go backend
+fn sidecar_candidates() -> Vec<PathBuf> {
+    let target = env::var("TARGET").unwrap_or_default();
+    let exe = if target.contains("windows") {
+        ".exe"
+    } else {
+        ""
+    };
+    vec![
+        PathBuf::from(format!("binaries/{}-{}{}", SIDECAR_NAME, target, exe)),
+        PathBuf::from(format!("../../go_backend/build/{}{}", SIDECAR_NAME, exe)),
+    ]
+}
+
+fn sha256(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn write_manifest() -> io::Result<()> {
+    let candidates = sidecar_candidates();
+    for candidate in &candidates {
+        println!("cargo:rerun-if-changed={}", candidate.display());
+    }
+    let manifest = match candidates.iter().find(|candidate| candidate.is_file()) {
+        Some(sidecar) => format!("{{\n  \"{}\": \"{}\"\n}}\n", SIDECAR_NAME, sha256(sidecar)?),
+        None => {
+            if env::var("PROFILE").as_deref() == Ok("release") {
+                println!(
+                    "cargo:warning=No sidecar binary found, this build won't start the sidecar"
+                );
+            }
+            "{}\n".to_string()
+        }
+    };
+    // Leaves the file alone when nothing changed, so it doesn't retrigger builds
+    if fs::read_to_string(MANIFEST_PATH).ok().as_deref() != Some(manifest.as_str()) {
+        fs::write(MANIFEST_PATH, manifest)?;
+    }
+    Ok(())
+}
+
+// Generates the gRPC client from the service definition the sidecar is built
+// from. protox parses it, so building needs no protoc install.
+#[cfg(feature = "grpc")]
+fn compile_protos() -> Result<(), Box<dyn std::error::Error>> {
+    const PROTO_ROOT: &str = "../../go_backend/proto";
+    const SIDECAR_PROTO: &str = "../../go_backend/proto/mix/sidecar/v1/sidecar.proto";
+
+    println!("cargo:rerun-if-changed={}", SIDECAR_PROTO);
+    let descriptors = protox::compile([SIDECAR_PROTO], [PROTO_ROOT])?;
+    tonic_build::configure()
+        .build_server(false)
+        .compile_fds(descriptors)?;
+    Ok(())
+}
+
 fn main() {
+    if let Err(e) = write_manifest() {
+        panic!("Failed to write {}: {}", MANIFEST_PATH, e);
+    }
+    #[cfg(feature = "grpc")]
+    if let Err(e) = compile_protos() {
+        panic!("Failed to generate the sidecar gRPC client: {}", e);
+    }
     tauri_build::build()
 }
@@ -0,0 +1,61 @@
+use crate::platform::{AccessibilityPrefs, Native, Platform};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow, WindowEvent};
+
+pub const ACCESSIBILITY_CHANGED_EVENT: &str = "accessibility://changed";
+
+// Last read, so only real changes are announced
+static PREFS: Mutex<Option<AccessibilityPrefs>> = Mutex::new(None);
+
+// Takes the window's own show/hide animation away while motion is reduced
+fn apply(app: &AppHandle, prefs: AccessibilityPrefs) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let target = window.clone();
+    let _ = window.run_on_main_thread(move || {
+        Native::set_window_animations(&target, !prefs.reduce_motion);
+    });
+}
+
+// Reads the settings again and tells the native side and every webview when
+// they changed
+async fn refresh(app: &AppHandle) -> AccessibilityPrefs {
+    let prefs = tauri::async_runtime::spawn_blocking(Native::accessibility_prefs)
+        .await
+        .unwrap_or_default();
+    let previous = PREFS.lock().unwrap().replace(prefs);
+    if previous != Some(prefs) {
+        log::info!("Accessibility settings: {:?}", prefs);
+        apply(app, prefs);
+        let _ = app.emit(ACCESSIBILITY_CHANGED_EVENT, prefs);
+    }
+    prefs
+}
+
+// Settings are changed in another app, so they're read again whenever a
+// window comes back to the front, and once at startup
+pub fn watch(window: &WebviewWindow) {
+    let app = window.app_handle().clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Focused(true) = event {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                refresh(&app).await;
+            });
+        }
+    });
+}
+
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        refresh(&app).await;
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_accessibility_prefs(app: AppHandle) -> AccessibilityPrefs {
+    refresh(&app).await
+}
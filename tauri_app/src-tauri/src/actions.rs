@@ -0,0 +1,212 @@
+use crate::error::AppError;
+use serde::Serialize;
+use specta::Type;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+// Carries the id of an action the webview carries out itself
+pub const ACTION_EVENT: &str = "actions://run";
+
+// Query characters that match right after the previous one, at the start of
+// a word, or at the very start score higher than scattered ones
+const CONSECUTIVE_BONUS: i64 = 15;
+const WORD_START_BONUS: i64 = 10;
+const PREFIX_BONUS: i64 = 20;
+const GAP_PENALTY: i64 = 1;
+// Matches against the group or keywords rank below title matches
+const SECONDARY_PENALTY: i64 = 50;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Action {
+    pub id: String,
+    pub title: String,
+    // Heading the palette lists the action under
+    pub group: String,
+    // Extra words the action is found by, e.g. "hide" for the window toggle
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct ActionMatch {
+    pub action: Action,
+    pub score: i64,
+    // Character indices of `title` that matched, for highlighting
+    pub positions: Vec<u32>,
+}
+
+pub type ActionFuture = Pin<Box<dyn Future<Output = Result<(), AppError>> + Send>>;
+pub type Handler = Arc<dyn Fn(AppHandle) -> ActionFuture + Send + Sync>;
+
+#[derive(Clone)]
+pub struct Entry {
+    pub action: Action,
+    pub handler: Handler,
+}
+
+// Lists actions that change while the app runs, such as one per template
+type Source = fn(&AppHandle) -> Vec<Entry>;
+
+static ACTIONS: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+static SOURCES: Mutex<Vec<Source>> = Mutex::new(Vec::new());
+
+impl Action {
+    pub fn new(id: &str, title: &str, group: &str, keywords: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+            group: group.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+}
+
+pub fn handler<F, Fut>(run: F) -> Handler
+where
+    F: Fn(AppHandle) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), AppError>> + Send + 'static,
+{
+    Arc::new(move |app| Box::pin(run(app)))
+}
+
+// Registering an id again replaces the earlier action
+pub fn register(action: Action, handler: Handler) {
+    let mut actions = ACTIONS.lock().unwrap();
+    actions.retain(|entry| entry.action.id != action.id);
+    actions.push(Entry { action, handler });
+}
+
+pub fn register_source(source: Source) {
+    SOURCES.lock().unwrap().push(source);
+}
+
+// Actions the webview carries out itself, e.g. starting a new session
+// through its own sidecar client; running one emits `ACTION_EVENT`
+pub fn register_webview(action: Action) {
+    let id = action.id.clone();
+    register(
+        action,
+        handler(move |app| {
+            let id = id.clone();
+            async move {
+                app.emit(ACTION_EVENT, id)
+                    .map_err(|e| format!("Failed to emit action: {}", e))?;
+                Ok(())
+            }
+        }),
+    )
+}
+
+pub fn register_builtin() {
+    register_webview(Action::new(
+        "session.new",
+        "New session",
+        "Session",
+        &["chat", "start", "clear"],
+    ));
+}
+
+fn entries(app: &AppHandle) -> Vec<Entry> {
+    let mut entries = ACTIONS.lock().unwrap().clone();
+    let sources = SOURCES.lock().unwrap().clone();
+    for source in sources {
+        entries.extend(source(app));
+    }
+    entries
+}
+
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    i == 0
+        || !chars[i - 1].is_alphanumeric()
+        || (chars[i].is_uppercase() && chars[i - 1].is_lowercase())
+}
+
+// Case-insensitive subsequence match, returning a score and the matched
+// character indices. Each query character takes its first occurrence after
+// the previous match, which is what palettes usually do and cheap enough for
+// a few hundred candidates. Also ranks the quick switcher.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<u32>)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut next = 0;
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let q = q.to_lowercase().next().unwrap_or(q);
+        let found = (next..chars.len()).find(|&i| chars[i].to_lowercase().next() == Some(q))?;
+        score -= (found - next) as i64 * GAP_PENALTY;
+        if found == 0 {
+            score += PREFIX_BONUS;
+        } else if is_word_start(&chars, found) {
+            score += WORD_START_BONUS;
+        }
+        if found > 0 && positions.last() == Some(&(found as u32 - 1)) {
+            score += CONSECUTIVE_BONUS;
+        }
+        positions.push(found as u32);
+        next = found + 1;
+    }
+    Some((score, positions))
+}
+
+fn score(query: &str, action: &Action) -> Option<(i64, Vec<u32>)> {
+    if let Some(matched) = fuzzy_match(query, &action.title) {
+        return Some(matched);
+    }
+    std::iter::once(&action.group)
+        .chain(&action.keywords)
+        .filter_map(|text| fuzzy_match(query, text))
+        .map(|(score, _)| score - SECONDARY_PENALTY)
+        .max()
+        .map(|score| (score, Vec::new()))
+}
+
+// Without a query every action is listed, grouped; with one only matches
+// are, best first
+#[tauri::command]
+#[specta::specta]
+pub fn list_actions(app: AppHandle, query: Option<String>) -> Vec<ActionMatch> {
+    let actions = entries(&app).into_iter().map(|entry| entry.action);
+    let query = query.unwrap_or_default();
+    if query.trim().is_empty() {
+        let mut matches: Vec<ActionMatch> = actions
+            .map(|action| ActionMatch {
+                action,
+                score: 0,
+                positions: Vec::new(),
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            (&a.action.group, &a.action.title).cmp(&(&b.action.group, &b.action.title))
+        });
+        return matches;
+    }
+
+    let mut matches: Vec<ActionMatch> = actions
+        .filter_map(|action| {
+            let (score, positions) = score(&query, &action)?;
+            Some(ActionMatch {
+                action,
+                score,
+                positions,
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.action.title.cmp(&b.action.title))
+    });
+    matches
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn run_action(app: AppHandle, id: String) -> Result<(), AppError> {
+    let entry = entries(&app)
+        .into_iter()
+        .find(|entry| entry.action.id == id)
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown action: {}", id)))?;
+    log::info!("Running action {}", id);
+    (entry.handler)(app).await
+}
@@ -0,0 +1,331 @@
+use crate::ring_buffer::RingBuffer;
+use crate::sidecar::SidecarManager;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::http::{header, Method, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, UriSchemeContext, UriSchemeResponder};
+use tokio::sync::Notify;
+
+// `agent://` proxies the sidecar's SSE endpoints so the webview never talks to
+// the sidecar port or holds its token:
+//   GET  agent://localhost/stream?sessionId=<id>   (EventSource)
+//   POST agent://localhost/stream/<id>/message
+// Custom protocols can't stream a response body, so the stream is served as a
+// series of short responses. Rust keeps one upstream connection per session and
+// buffers its events; each request returns what arrived after `Last-Event-ID`
+// and EventSource reconnects right away because of the `retry` field.
+pub const SCHEME: &str = "agent";
+const POLL_WAIT: Duration = Duration::from_secs(20);
+const RETRY_MS: u64 = 50;
+// Upstream connections nobody polls for this long are closed
+const IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+// Events kept per session, see `ring_buffer::BufferSizes`
+static BUFFERED_EVENTS: AtomicUsize = AtomicUsize::new(1000);
+
+static STREAMS: LazyLock<Mutex<HashMap<String, Arc<SessionStream>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+struct SessionStream {
+    // (id, raw event block without its trailing blank line)
+    events: Mutex<RingBuffer<(u64, String)>>,
+    next_id: Mutex<u64>,
+    last_polled: Mutex<Instant>,
+    notify: Notify,
+}
+
+impl SessionStream {
+    fn new() -> Self {
+        Self {
+            events: Mutex::new(RingBuffer::new(
+                "stream_events",
+                BUFFERED_EVENTS.load(Ordering::Relaxed),
+            )),
+            next_id: Mutex::new(1),
+            last_polled: Mutex::new(Instant::now()),
+            notify: Notify::new(),
+        }
+    }
+
+    fn push(&self, block: String) {
+        let mut next_id = self.next_id.lock().unwrap();
+        let mut events = self.events.lock().unwrap();
+        events.push((*next_id, block));
+        *next_id += 1;
+        self.notify.notify_waiters();
+    }
+
+    fn latest_id(&self) -> u64 {
+        *self.next_id.lock().unwrap() - 1
+    }
+
+    fn events_after(&self, id: u64) -> Vec<(u64, String)> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(event_id, _)| *event_id > id)
+            .cloned()
+            .collect()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.last_polled.lock().unwrap().elapsed() > IDLE_TIMEOUT
+    }
+}
+
+// Applies to streams opened from now on
+pub fn set_capacity(capacity: usize) {
+    BUFFERED_EVENTS.store(capacity, Ordering::Relaxed);
+}
+
+pub(crate) fn query_param(query: Option<&str>, name: &str) -> Option<String> {
+    query?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then(|| percent_decode(value))
+    })
+}
+
+pub(crate) fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => decoded.push(b' '),
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Reads the sidecar's event stream into `stream` until it ends or goes idle
+async fn pump_upstream(
+    manager: Arc<SidecarManager>,
+    session_id: String,
+    stream: Arc<SessionStream>,
+) {
+    let result = async {
+        let mut response = manager
+            .http()
+            .get(manager.url("/stream"))
+            .query(&[("sessionId", session_id.as_str())])
+            .bearer_auth(&manager.auth.token)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to open sidecar stream: {}", e))?;
+        manager.verify_identity(&response)?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Sidecar stream failed with status: {}",
+                response.status()
+            ));
+        }
+
+        let mut pending = String::new();
+        while let Some(bytes) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Sidecar stream interrupted: {}", e))?
+        {
+            if stream.is_idle() {
+                log::info!("Closing idle event stream for session {}", session_id);
+                return Ok(());
+            }
+            pending.push_str(&String::from_utf8_lossy(&bytes).replace("\r\n", "\n"));
+            while let Some(end) = pending.find("\n\n") {
+                let block: String = pending.drain(..end + 2).collect();
+                // Our own ids replace upstream ones, and `connected` is sent
+                // per proxied request instead, see `poll`
+                let block: Vec<&str> = block
+                    .lines()
+                    .filter(|line| !line.is_empty() && !line.starts_with("id:"))
+                    .collect();
+                if block.is_empty() || block.contains(&"event: connected") {
+                    continue;
+                }
+                stream.push(block.join("\n"));
+            }
+        }
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        log::warn!("Event stream for session {} ended: {}", session_id, e);
+        let data = serde_json::json!({ "error": e });
+        stream.push(format!("event: error\ndata: {}", data));
+    }
+    // The next request opens a fresh upstream connection
+    let mut streams = STREAMS.lock().unwrap();
+    if streams
+        .get(&session_id)
+        .is_some_and(|current| Arc::ptr_eq(current, &stream))
+    {
+        streams.remove(&session_id);
+    }
+}
+
+fn session_stream(app: &AppHandle, session_id: &str) -> Arc<SessionStream> {
+    let mut streams = STREAMS.lock().unwrap();
+    if let Some(stream) = streams.get(session_id) {
+        return stream.clone();
+    }
+    let stream = Arc::new(SessionStream::new());
+    streams.insert(session_id.to_string(), stream.clone());
+    let manager = app.state::<Arc<SidecarManager>>().inner().clone();
+    tauri::async_runtime::spawn(pump_upstream(
+        manager,
+        session_id.to_string(),
+        stream.clone(),
+    ));
+    stream
+}
+
+fn event_stream_response(body: String) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(body.into_bytes())
+        .unwrap_or_default()
+}
+
+async fn poll(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let Some(session_id) = query_param(request.uri().query(), "sessionId") else {
+        return error_response(StatusCode::BAD_REQUEST, "Missing sessionId parameter");
+    };
+    if !app.state::<Arc<SidecarManager>>().is_running() {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "Sidecar is not running");
+    }
+
+    let last_event_id = request
+        .headers()
+        .get("last-event-id")
+        .and_then(|id| id.to_str().ok()?.parse::<u64>().ok());
+    let stream = session_stream(app, &session_id);
+    *stream.last_polled.lock().unwrap() = Instant::now();
+
+    let mut body = format!("retry: {}\n\n", RETRY_MS);
+    let after = match last_event_id {
+        // Ids restart when the upstream connection was reopened
+        Some(id) if id > stream.latest_id() => 0,
+        Some(id) => id,
+        // A new EventSource: start from now and tell it it's connected
+        None => {
+            let data = serde_json::json!({ "sessionId": session_id });
+            body.push_str(&format!(
+                "id: {}\nevent: connected\ndata: {}\n\n",
+                stream.latest_id(),
+                data
+            ));
+            return event_stream_response(body);
+        }
+    };
+
+    let mut events = stream.events_after(after);
+    if events.is_empty() {
+        let notified = stream.notify.notified();
+        // Re-check in case an event landed before we started waiting
+        events = stream.events_after(after);
+        if events.is_empty() {
+            let _ = tokio::time::timeout(POLL_WAIT, notified).await;
+            events = stream.events_after(after);
+        }
+    }
+    // Keep the reconnect on the same id when nothing new arrived
+    if events.is_empty() {
+        body.push_str(&format!("id: {}\n: keep-alive\n\n", after));
+    }
+    for (id, block) in events {
+        body.push_str(&format!("id: {}\n{}\n\n", id, block));
+    }
+    event_stream_response(body)
+}
+
+async fn forward_message(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let manager = app.state::<Arc<SidecarManager>>().inner().clone();
+    let result = async {
+        let response = manager
+            .http()
+            .post(manager.url(request.uri().path()))
+            .bearer_auth(&manager.auth.token)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(request.body().clone())
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        manager.verify_identity(&response)?;
+        let status = response.status().as_u16();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+        Ok::<_, String>((status, body.to_vec()))
+    }
+    .await;
+
+    match result {
+        Ok((status, body)) => Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .body(body)
+            .unwrap_or_default(),
+        Err(e) => error_response(StatusCode::BAD_GATEWAY, &e),
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(message.as_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+async fn handle(app: AppHandle, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let path = request.uri().path().to_string();
+    match (request.method(), path.as_str()) {
+        // The app origin differs from agent://, so POSTs are preflighted
+        (&Method::OPTIONS, _) => Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .header(header::ACCESS_CONTROL_ALLOW_METHODS, "GET, POST, OPTIONS")
+            .header(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                "Content-Type, Last-Event-ID",
+            )
+            .body(Vec::new())
+            .unwrap_or_default(),
+        (&Method::GET, "/stream") => poll(&app, &request).await,
+        (&Method::POST, path) if path.starts_with("/stream/") && path.ends_with("/message") => {
+            forward_message(&app, &request).await
+        }
+        _ => error_response(StatusCode::NOT_FOUND, "Unknown agent endpoint"),
+    }
+}
+
+pub fn protocol(
+    context: UriSchemeContext<'_, tauri::Wry>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = context.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        responder.respond(handle(app, request).await);
+    });
+}
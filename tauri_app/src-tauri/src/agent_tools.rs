@@ -0,0 +1,354 @@
+use crate::{audit, local_http};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+// Tools the app runs for its own agent, served to the sidecar as an MCP
+// server. The sidecar's MCP client speaks the SSE transport: it holds a GET on
+// `/sse` open, posts requests to the endpoint announced there, and reads the
+// responses and notifications back from the stream. Other MCP clients are
+// served by `mcp_server` instead.
+pub const SERVER_NAME: &str = "mix";
+const SSE_PATH: &str = "/sse";
+const MESSAGES_PATH: &str = "/messages/";
+const PROTOCOL_VERSION: &str = "2024-11-05";
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const JSON: &str = "application/json";
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+// Longest a tool may run; the sidecar is told to wait a little longer
+pub const CALL_TIMEOUT: Duration = Duration::from_secs(330);
+// A failed call's message can be a whole command's output; the audit log
+// keeps the start of it
+const AUDIT_RESULT_CHARS: usize = 500;
+
+pub type ToolFuture = Pin<Box<dyn Future<Output = Result<Vec<Content>, String>> + Send>>;
+pub type Handler = Arc<dyn Fn(AppHandle, Value, Progress) -> ToolFuture + Send + Sync>;
+
+// A block of a tool's result. Most tools answer with one text block, images
+// are shown to the model alongside it.
+#[derive(Debug, Clone)]
+pub enum Content {
+    Text(String),
+    // Base64 data of the given MIME type
+    Image { data: String, mime_type: String },
+}
+
+#[derive(Clone)]
+pub struct Tool {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub input_schema: Value,
+    pub handler: Handler,
+}
+
+// Sends a tool's partial output to the sidecar while the call runs, as
+// progress notifications when the request asked for them and log messages
+// otherwise
+#[derive(Clone)]
+pub struct Progress {
+    session: mpsc::UnboundedSender<String>,
+    tool: &'static str,
+    token: Option<Value>,
+    sent: Arc<Mutex<u64>>,
+}
+
+impl Progress {
+    pub fn send(&self, message: &str) {
+        let notification = match &self.token {
+            Some(token) => {
+                let mut sent = self.sent.lock().unwrap();
+                *sent += 1;
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/progress",
+                    "params": { "progressToken": token, "progress": *sent, "message": message },
+                })
+            }
+            None => json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/message",
+                "params": { "level": "info", "logger": self.tool, "data": message },
+            }),
+        };
+        let _ = self.session.send(notification.to_string());
+    }
+}
+
+static TOOLS: Mutex<Vec<Tool>> = Mutex::new(Vec::new());
+// Open `/sse` streams by session id
+static SESSIONS: LazyLock<Mutex<HashMap<String, mpsc::UnboundedSender<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+// (port, token), bound on first use
+static SERVER: Mutex<Option<(u16, String)>> = Mutex::new(None);
+
+pub fn handler<F, Fut>(run: F) -> Handler
+where
+    F: Fn(AppHandle, Value, Progress) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<String, String>> + Send + 'static,
+{
+    Arc::new(move |app, arguments, progress| {
+        let result = run(app, arguments, progress);
+        Box::pin(async move { result.await.map(|text| vec![Content::Text(text)]) })
+    })
+}
+
+// For tools whose results carry more than text
+pub fn content_handler<F, Fut>(run: F) -> Handler
+where
+    F: Fn(AppHandle, Value, Progress) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Vec<Content>, String>> + Send + 'static,
+{
+    Arc::new(move |app, arguments, progress| Box::pin(run(app, arguments, progress)))
+}
+
+// Registering a name again replaces the earlier tool
+pub fn register(tool: Tool) {
+    let mut tools = TOOLS.lock().unwrap();
+    tools.retain(|existing| existing.name != tool.name);
+    tools.push(tool);
+}
+
+pub fn string_arg(arguments: &Value, key: &str) -> Result<String, String> {
+    arguments
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| format!("Missing argument {}", key))
+}
+
+fn tool_definitions() -> Value {
+    let tools = TOOLS.lock().unwrap();
+    Value::Array(
+        tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "inputSchema": tool.input_schema,
+                })
+            })
+            .collect(),
+    )
+}
+
+async fn call_tool(
+    app: &AppHandle,
+    session: &mpsc::UnboundedSender<String>,
+    params: Value,
+) -> Value {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+    let tool = TOOLS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|tool| tool.name == name)
+        .cloned();
+    let Some(tool) = tool else {
+        return json!({
+            "content": [{ "type": "text", "text": format!("Unknown tool {}", name) }],
+            "isError": true,
+        });
+    };
+    let progress = Progress {
+        session: session.clone(),
+        tool: tool.name,
+        token: params.pointer("/_meta/progressToken").cloned(),
+        sent: Arc::new(Mutex::new(0)),
+    };
+    let result = (tool.handler)(app.clone(), arguments.clone(), progress).await;
+    // Every call is audited here, whichever tool ran it
+    audit::record(
+        app,
+        "agent",
+        tool.name,
+        arguments,
+        result.is_ok(),
+        result
+            .as_ref()
+            .err()
+            .map(|e| e.chars().take(AUDIT_RESULT_CHARS).collect()),
+    );
+    // Tool failures are reported to the model, not as protocol errors
+    let (content, is_error) = match result {
+        Ok(content) => (content, false),
+        Err(e) => (vec![Content::Text(e)], true),
+    };
+    let content: Vec<Value> = content
+        .into_iter()
+        .map(|block| match block {
+            Content::Text(text) => json!({ "type": "text", "text": text }),
+            Content::Image { data, mime_type } => {
+                json!({ "type": "image", "data": data, "mimeType": mime_type })
+            }
+        })
+        .collect();
+    json!({
+        "content": content,
+        "isError": is_error,
+    })
+}
+
+async fn dispatch(
+    app: &AppHandle,
+    session: &mpsc::UnboundedSender<String>,
+    method: &str,
+    params: Value,
+) -> Result<Value, (i64, String)> {
+    match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {}, "logging": {} },
+            "serverInfo": {
+                "name": app.package_info().name,
+                "version": app.package_info().version.to_string(),
+            },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => Ok(call_tool(app, session, params).await),
+        "resources/list" => Ok(json!({ "resources": [] })),
+        _ => Err((-32601, format!("Method not found: {}", method))),
+    }
+}
+
+// Holds the stream open and writes whatever the session's requests answer
+async fn open_stream(mut stream: TcpStream, port: u16) {
+    let session_id = uuid::Uuid::new_v4().simple().to_string();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    SESSIONS.lock().unwrap().insert(session_id.clone(), tx);
+
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\nevent: endpoint\ndata: http://127.0.0.1:{}{}{}\n\n",
+        port, MESSAGES_PATH, session_id
+    );
+    if stream.write_all(head.as_bytes()).await.is_ok() {
+        let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+        loop {
+            let event = tokio::select! {
+                message = rx.recv() => match message {
+                    Some(message) => format!("event: message\ndata: {}\n\n", message),
+                    None => break,
+                },
+                _ = keepalive.tick() => ": keepalive\n\n".to_string(),
+            };
+            if stream.write_all(event.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    }
+    SESSIONS.lock().unwrap().remove(&session_id);
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream, port: u16, token: String) {
+    let request = match local_http::read_request(&mut stream, MAX_BODY_BYTES).await {
+        Ok(request) => request,
+        Err(Some(status)) => return local_http::respond(&mut stream, status, JSON, "").await,
+        Err(None) => return,
+    };
+    if !request.has_bearer_token(&token) || request.is_browser() {
+        return local_http::respond(&mut stream, "401 Unauthorized", JSON, "").await;
+    }
+    if request.method == "GET" && request.path == SSE_PATH {
+        return open_stream(stream, port).await;
+    }
+    let Some(session_id) = request.path.strip_prefix(MESSAGES_PATH) else {
+        return local_http::respond(&mut stream, "404 Not Found", JSON, "").await;
+    };
+    if request.method != "POST" {
+        return local_http::respond(&mut stream, "405 Method Not Allowed", JSON, "").await;
+    }
+    let Some(session) = SESSIONS.lock().unwrap().get(session_id).cloned() else {
+        return local_http::respond(&mut stream, "404 Not Found", JSON, "").await;
+    };
+    let Ok(message) = serde_json::from_slice::<Value>(&request.body) else {
+        return local_http::respond(&mut stream, "400 Bad Request", JSON, "").await;
+    };
+    local_http::respond(&mut stream, "202 Accepted", JSON, "").await;
+
+    // Notifications and responses need no answer
+    let Some(id) = message.get("id").cloned() else {
+        return;
+    };
+    let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(json!({}));
+    let response = match dispatch(&app, &session, method, params).await {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    };
+    let _ = session.send(response.to_string());
+}
+
+fn bind(app: &AppHandle) -> Result<(u16, String), String> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+        .map_err(|e| format!("Failed to listen for agent tools: {}", e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to listen for agent tools: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to listen for agent tools: {}", e))?
+        .port();
+    let token = uuid::Uuid::new_v4().simple().to_string();
+
+    let app = app.clone();
+    let server_token = token.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => return log::error!("Failed to serve agent tools: {}", e),
+        };
+        log::info!("Agent tools listening on 127.0.0.1:{}", port);
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tauri::async_runtime::spawn(handle_connection(
+                        app.clone(),
+                        stream,
+                        port,
+                        server_token.clone(),
+                    ));
+                }
+                Err(e) => log::warn!("Failed to accept agent tools connection: {}", e),
+            }
+        }
+    });
+    Ok((port, token))
+}
+
+// The entry `mcp::sidecar_env` hands the sidecar, starting the server the
+// first time. The port is random and the token new for every launch.
+pub fn sidecar_server(app: &AppHandle) -> Option<Value> {
+    let mut server = SERVER.lock().unwrap();
+    if server.is_none() {
+        match bind(app) {
+            Ok(bound) => *server = Some(bound),
+            Err(e) => {
+                log::error!("{}", e);
+                return None;
+            }
+        }
+    }
+    let (port, token) = server.clone()?;
+    Some(json!({
+        "type": "sse",
+        "url": format!("http://127.0.0.1:{}{}", port, SSE_PATH),
+        "headers": { "Authorization": format!("Bearer {}", token) },
+        "timeout": CALL_TIMEOUT.as_secs() + 30,
+    }))
+}
@@ -0,0 +1,390 @@
+use crate::error::AppError;
+use crate::panel;
+use crate::secrets;
+use crate::settings::SettingsManager;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand::RngCore;
+use serde::Serialize;
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow, WindowEvent};
+
+pub const LOCKED_EVENT: &str = "lock://locked";
+pub const UNLOCKED_EVENT: &str = "lock://unlocked";
+const PASSPHRASE_SECRET: &str = "APP_LOCK_PASSPHRASE";
+const AUTO_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const UNLOCK_REASON: &str = "unlock Mix";
+// Wrong passphrases allowed before each further attempt has to wait, twice
+// as long every time up to the cap
+const FREE_ATTEMPTS: u32 = 3;
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(300);
+// What the webview may still call while the app is locked
+const ALLOWED_WHILE_LOCKED: &[&str] = &[
+    "get_lock_status",
+    "lock_app",
+    "unlock_with_passphrase",
+    "unlock_with_biometrics",
+    "get_startup_progress",
+    "get_startup_warnings",
+];
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+// When the user last did something: a command from the webview or a focus
+// change. Set at start, so the app locks even if no window ever gets focus.
+static LAST_ACTIVITY: Mutex<Option<Instant>> = Mutex::new(None);
+// Passphrase attempts since the last success, and when the last one started
+static FAILED_ATTEMPTS: Mutex<(u32, Option<Instant>)> = Mutex::new((0, None));
+
+#[derive(Debug, Serialize, Type)]
+pub struct LockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub auto_lock_secs: u64,
+    pub biometrics_available: bool,
+}
+
+#[cfg(target_os = "macos")]
+mod biometrics {
+    use block2::RcBlock;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Bool};
+    use objc2_foundation::{NSError, NSString};
+    use std::sync::Mutex;
+
+    #[link(name = "LocalAuthentication", kind = "framework")]
+    extern "C" {}
+
+    // LAPolicyDeviceOwnerAuthenticationWithBiometrics
+    const POLICY: isize = 1;
+
+    fn context() -> Option<Retained<AnyObject>> {
+        let class = AnyClass::get(c"LAContext")?;
+        let context: Retained<AnyObject> = unsafe { msg_send![class, new] };
+        let available: Bool = unsafe {
+            msg_send![&*context, canEvaluatePolicy: POLICY, error: std::ptr::null_mut::<*mut NSError>()]
+        };
+        available.as_bool().then_some(context)
+    }
+
+    pub fn available() -> bool {
+        context().is_some()
+    }
+
+    pub async fn authenticate(reason: &str) -> bool {
+        let Some(context) = context() else {
+            return false;
+        };
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Mutex::new(Some(tx));
+        let reply = RcBlock::new(move |success: Bool, _error: *mut NSError| {
+            if let Some(tx) = tx.lock().unwrap().take() {
+                let _ = tx.send(success.as_bool());
+            }
+        });
+        let reason = NSString::from_str(reason);
+        unsafe {
+            let _: () = msg_send![
+                &*context,
+                evaluatePolicy: POLICY,
+                localizedReason: &*reason,
+                reply: &*reply
+            ];
+        }
+        rx.await.unwrap_or(false)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod biometrics {
+    pub fn available() -> bool {
+        false
+    }
+
+    pub async fn authenticate(_reason: &str) -> bool {
+        false
+    }
+}
+
+// A PHC string, "$argon2id$v=19$...", carrying its own salt and parameters
+fn hash_passphrase(passphrase: &str) -> Result<String, String> {
+    let mut salt = [0u8; 16];
+    rand::rng().fill_bytes(&mut salt);
+    let salt = SaltString::encode_b64(&salt).map_err(|e| format!("Failed to salt: {}", e))?;
+    Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Failed to hash passphrase: {}", e))
+}
+
+// The comparison inside `verify_password` is constant-time
+fn verify_hash(stored: &str, passphrase: &str) -> Result<bool, String> {
+    let hash =
+        PasswordHash::new(stored).map_err(|e| format!("Stored passphrase is malformed: {}", e))?;
+    Ok(Argon2::default()
+        .verify_password(passphrase.as_bytes(), &hash)
+        .is_ok())
+}
+
+fn verify_passphrase(passphrase: &str) -> Result<bool, String> {
+    match secrets::get_secret(PASSPHRASE_SECRET)? {
+        Some(stored) => verify_hash(&stored, passphrase),
+        None => Ok(false),
+    }
+}
+
+fn store_passphrase(passphrase: &str) -> Result<(), AppError> {
+    secrets::set_secret(PASSPHRASE_SECRET.to_string(), hash_passphrase(passphrase)?)
+}
+
+// How long to wait after `failures` wrong passphrases in a row
+fn backoff(failures: u32) -> Duration {
+    if failures < FREE_ATTEMPTS {
+        return Duration::ZERO;
+    }
+    let doublings = (failures - FREE_ATTEMPTS).min(16);
+    (BACKOFF_BASE * 2u32.pow(doublings)).min(BACKOFF_MAX)
+}
+
+// Counts an attempt as failed before it is verified, so concurrent attempts
+// can't all slip past the backoff; a correct passphrase resets the count
+fn reserve_attempt() -> Result<(), AppError> {
+    let mut attempts = FAILED_ATTEMPTS.lock().unwrap();
+    let (failures, last) = *attempts;
+    let wait = backoff(failures).saturating_sub(last.map_or(Duration::MAX, |at| at.elapsed()));
+    if !wait.is_zero() {
+        return Err(AppError::PermissionDenied(format!(
+            "Too many wrong passphrases, try again in {}s",
+            wait.as_secs().max(1)
+        )));
+    }
+    *attempts = (failures + 1, Some(Instant::now()));
+    Ok(())
+}
+
+// Commands the webview may not run while the app is locked
+pub fn blocks(command: &str) -> bool {
+    is_locked() && !ALLOWED_WHILE_LOCKED.contains(&command)
+}
+
+pub fn record_activity() {
+    *LAST_ACTIVITY.lock().unwrap() = Some(Instant::now());
+}
+
+pub fn is_locked() -> bool {
+    LOCKED.load(Ordering::Relaxed)
+}
+
+fn lock(app: &AppHandle) {
+    if !LOCKED.swap(true, Ordering::Relaxed) {
+        log::info!("App locked");
+        let _ = app.emit(LOCKED_EVENT, ());
+    }
+}
+
+fn unlock(app: &AppHandle) {
+    if LOCKED.swap(false, Ordering::Relaxed) {
+        log::info!("App unlocked");
+        let _ = app.emit(UNLOCKED_EVENT, ());
+    }
+}
+
+// Runs before the panel shows a locked window: Touch ID is tried first, and
+// if it fails the window opens on the passphrase screen.
+pub async fn unlock_for_show(app: &AppHandle) {
+    if biometrics::authenticate(UNLOCK_REASON).await {
+        unlock(app);
+    } else {
+        let _ = app.emit(LOCKED_EVENT, ());
+    }
+}
+
+// Counts focus changes as activity, alongside the commands the webview runs
+pub fn watch(window: &WebviewWindow) {
+    window.on_window_event(|event| {
+        if let WindowEvent::Focused(_) = event {
+            record_activity();
+        }
+    });
+}
+
+pub fn start(app: &AppHandle) {
+    let settings = app.state::<SettingsManager>().get();
+    LOCKED.store(settings.lock_enabled, Ordering::Relaxed);
+    record_activity();
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(AUTO_LOCK_CHECK_INTERVAL).await;
+
+            let settings = app.state::<SettingsManager>().get();
+            if !settings.lock_enabled || settings.auto_lock_secs == 0 || is_locked() {
+                continue;
+            }
+            let idle = LAST_ACTIVITY
+                .lock()
+                .unwrap()
+                .is_some_and(|at| at.elapsed() >= Duration::from_secs(settings.auto_lock_secs));
+            if idle {
+                lock(&app);
+                panel::hide(&app);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_lock_status(settings_manager: State<'_, SettingsManager>) -> LockStatus {
+    let settings = settings_manager.get();
+    LockStatus {
+        enabled: settings.lock_enabled,
+        locked: is_locked(),
+        auto_lock_secs: settings.auto_lock_secs,
+        biometrics_available: biometrics::available(),
+    }
+}
+
+// Enabling requires a passphrase, which also serves as the fallback when
+// Touch ID is unavailable. Changing settings requires the app to be unlocked.
+#[tauri::command]
+#[specta::specta]
+pub fn configure_app_lock(
+    settings_manager: State<'_, SettingsManager>,
+    enabled: bool,
+    passphrase: Option<String>,
+    auto_lock_secs: Option<u64>,
+) -> Result<(), AppError> {
+    if is_locked() {
+        return Err(AppError::PermissionDenied(
+            "Unlock the app before changing lock settings".to_string(),
+        ));
+    }
+
+    if enabled {
+        match passphrase.filter(|passphrase| !passphrase.is_empty()) {
+            Some(passphrase) => store_passphrase(&passphrase)?,
+            None if secrets::get_secret(PASSPHRASE_SECRET)?.is_some() => {}
+            None => {
+                return Err(AppError::InvalidInput(
+                    "A passphrase is required to enable the app lock".to_string(),
+                ))
+            }
+        }
+    } else {
+        secrets::delete_secret(PASSPHRASE_SECRET.to_string())?;
+    }
+
+    settings_manager.update(|settings| {
+        settings.lock_enabled = enabled;
+        if let Some(secs) = auto_lock_secs {
+            settings.auto_lock_secs = secs;
+        }
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn lock_app(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    if !settings_manager.get().lock_enabled {
+        return Err(AppError::InvalidInput(
+            "App lock is not enabled".to_string(),
+        ));
+    }
+    lock(&app);
+    Ok(())
+}
+
+// Hashing takes a moment on purpose, so it runs off the main thread
+#[tauri::command]
+#[specta::specta]
+pub async fn unlock_with_passphrase(app: AppHandle, passphrase: String) -> Result<bool, AppError> {
+    reserve_attempt()?;
+    let valid = tauri::async_runtime::spawn_blocking(move || verify_passphrase(&passphrase))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to verify passphrase: {}", e)))??;
+    if valid {
+        *FAILED_ATTEMPTS.lock().unwrap() = (0, None);
+        unlock(&app);
+    } else {
+        let failures = FAILED_ATTEMPTS.lock().unwrap().0;
+        log::warn!("Failed unlock attempt ({} in a row)", failures);
+    }
+    Ok(valid)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn unlock_with_biometrics(app: AppHandle) -> bool {
+    let valid = biometrics::authenticate(UNLOCK_REASON).await;
+    if valid {
+        unlock(&app);
+    }
+    valid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passphrase_round_trips() {
+        let stored = hash_passphrase("correct horse").unwrap();
+        assert!(stored.starts_with("$argon2id$"));
+        assert!(verify_hash(&stored, "correct horse").unwrap());
+        assert!(!verify_hash(&stored, "correct horse ").unwrap());
+        assert!(!verify_hash(&stored, "").unwrap());
+    }
+
+    #[test]
+    fn each_hash_is_salted() {
+        assert_ne!(
+            hash_passphrase("same").unwrap(),
+            hash_passphrase("same").unwrap()
+        );
+    }
+
+    #[test]
+    fn malformed_hash_is_an_error() {
+        assert!(verify_hash("c2FsdA==$aGFzaA==", "anything").is_err());
+    }
+
+    #[test]
+    fn backoff_starts_after_free_attempts_and_is_capped() {
+        assert_eq!(backoff(0), Duration::ZERO);
+        assert_eq!(backoff(FREE_ATTEMPTS - 1), Duration::ZERO);
+        assert_eq!(backoff(FREE_ATTEMPTS), BACKOFF_BASE);
+        assert_eq!(backoff(FREE_ATTEMPTS + 1), BACKOFF_BASE * 2);
+        assert_eq!(backoff(FREE_ATTEMPTS + 40), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn attempts_are_counted_before_verifying() {
+        *FAILED_ATTEMPTS.lock().unwrap() = (0, None);
+        for _ in 0..FREE_ATTEMPTS {
+            assert!(reserve_attempt().is_ok());
+        }
+        assert!(reserve_attempt().is_err());
+        *FAILED_ATTEMPTS.lock().unwrap() = (0, None);
+    }
+
+    #[test]
+    fn locked_app_allows_only_unlocking() {
+        LOCKED.store(true, Ordering::Relaxed);
+        assert!(blocks("read_file"));
+        assert!(blocks("send_prompt"));
+        assert!(!blocks("unlock_with_passphrase"));
+        LOCKED.store(false, Ordering::Relaxed);
+        assert!(!blocks("read_file"));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::error::AppError;
+use crate::settings::{Settings, SettingsManager};
+use crate::sidecar::{SidecarManager, SidecarStatus, SidecarTransport};
+use crate::validation;
+use crate::workspace::{self, WorkspaceInfo};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::watch;
+
+pub const STATE_PATCH_EVENT: &str = "state://patch";
+
+// Bumped by every patch. A webview that sees a gap refetches `get_app_state`.
+static VERSION: AtomicU64 = AtomicU64::new(0);
+
+// Session the webview last reported as open, so a reload can resume it
+static ACTIVE_SESSION: LazyLock<watch::Sender<Option<String>>> =
+    LazyLock::new(|| watch::Sender::new(None));
+
+// The settings the webview renders from, plus a hash of all of them; when the
+// hash moves, `get_settings` has something new
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct SettingsDigest {
+    pub hash: String,
+    pub sidecar_transport: SidecarTransport,
+    pub lock_enabled: bool,
+    pub telemetry_enabled: bool,
+    pub encrypt_history: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AppState {
+    pub version: u64,
+    pub sidecar: SidecarStatus,
+    pub active_session: Option<String>,
+    pub workspace: WorkspaceInfo,
+    pub settings: SettingsDigest,
+}
+
+// One changed part of `AppState`, replacing it whole
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "key", content = "value", rename_all = "snake_case")]
+pub enum StatePatch {
+    Sidecar(SidecarStatus),
+    ActiveSession(Option<String>),
+    Workspace(WorkspaceInfo),
+    Settings(SettingsDigest),
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct StatePatchEvent {
+    // The state's version once the changes are applied
+    pub version: u64,
+    pub changes: Vec<StatePatch>,
+}
+
+fn digest(settings: &Settings) -> SettingsDigest {
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(settings).unwrap_or_default());
+    SettingsDigest {
+        hash: format!("{:x}", hasher.finalize()),
+        sidecar_transport: settings.sidecar_transport,
+        lock_enabled: settings.lock_enabled,
+        telemetry_enabled: settings.telemetry_enabled,
+        encrypt_history: settings.encrypt_history,
+    }
+}
+
+fn emit(app: &AppHandle, changes: Vec<StatePatch>) {
+    if changes.is_empty() {
+        return;
+    }
+    let version = VERSION.fetch_add(1, Ordering::SeqCst) + 1;
+    let _ = app.emit(STATE_PATCH_EVENT, StatePatchEvent { version, changes });
+}
+
+// Follows the sidecar status, the settings and the active session, and emits
+// a patch for whichever part changed. The workspace lives in the settings, so
+// it is compared on every settings change.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut sidecar = app.state::<Arc<SidecarManager>>().subscribe();
+        let mut settings = app.state::<SettingsManager>().subscribe();
+        let mut session = ACTIVE_SESSION.subscribe();
+        let current = settings.borrow_and_update().clone();
+        let mut last_workspace = workspace::info(current.clone());
+        let mut last_digest = digest(&current);
+
+        loop {
+            let changes = tokio::select! {
+                Ok(()) = sidecar.changed() => {
+                    vec![StatePatch::Sidecar(sidecar.borrow_and_update().clone())]
+                }
+                Ok(()) = settings.changed() => {
+                    let current = settings.borrow_and_update().clone();
+                    let mut changes = Vec::new();
+                    let workspace = workspace::info(current.clone());
+                    if workspace != last_workspace {
+                        last_workspace = workspace.clone();
+                        changes.push(StatePatch::Workspace(workspace));
+                    }
+                    let digest = digest(&current);
+                    if digest != last_digest {
+                        last_digest = digest.clone();
+                        changes.push(StatePatch::Settings(digest));
+                    }
+                    changes
+                }
+                Ok(()) = session.changed() => {
+                    vec![StatePatch::ActiveSession(session.borrow_and_update().clone())]
+                }
+                else => break,
+            };
+            emit(&app, changes);
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_app_state(app: AppHandle) -> AppState {
+    let settings = app.state::<SettingsManager>().get();
+    AppState {
+        version: VERSION.load(Ordering::SeqCst),
+        sidecar: app.state::<Arc<SidecarManager>>().status(),
+        active_session: ACTIVE_SESSION.borrow().clone(),
+        workspace: workspace::info(settings.clone()),
+        settings: digest(&settings),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_active_session(id: Option<String>) -> Result<(), AppError> {
+    if let Some(id) = &id {
+        validation::uuid("id", id)?;
+    }
+    ACTIVE_SESSION.send_if_modified(|active| {
+        if *active == id {
+            return false;
+        }
+        *active = id;
+        true
+    });
+    Ok(())
+}
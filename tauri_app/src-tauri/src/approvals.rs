@@ -0,0 +1,161 @@
+use crate::audit;
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::sounds::{self, SoundEvent};
+use crate::validation;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::oneshot;
+
+pub const APPROVAL_REQUEST_EVENT: &str = "approval://request";
+// Unanswered requests are treated as denied
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+
+// Pending request id -> (what "always allow" stores, channel back to the
+// waiting command)
+type PendingApprovals = HashMap<String, (String, oneshot::Sender<bool>)>;
+
+static PENDING: LazyLock<Mutex<PendingApprovals>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ApprovalRequest {
+    pub id: String,
+    pub action: String,
+    pub detail: String,
+}
+
+// Sensitive operations (clipboard read, screenshots, ...) call this first. It
+// asks the webview and waits for the user's decision, unless the action was
+// previously marked "always allow".
+pub async fn require_approval(app: &AppHandle, action: &str, detail: &str) -> Result<(), String> {
+    ask(app, action, action, detail).await
+}
+
+// Like `require_approval`, but "always allow" only covers this `scope` of the
+// action, e.g. one shell command rather than every command the agent may run
+pub async fn require_scoped_approval(
+    app: &AppHandle,
+    action: &str,
+    scope: &str,
+    detail: &str,
+) -> Result<(), String> {
+    ask(app, action, &format!("{}:{}", action, scope), detail).await
+}
+
+async fn ask(app: &AppHandle, action: &str, key: &str, detail: &str) -> Result<(), String> {
+    let always_allowed = app
+        .state::<SettingsManager>()
+        .get()
+        .always_allowed_actions
+        .iter()
+        .any(|allowed| allowed == key);
+    if always_allowed {
+        return Ok(());
+    }
+
+    let request = ApprovalRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        action: action.to_string(),
+        detail: detail.to_string(),
+    };
+    let (tx, rx) = oneshot::channel();
+    PENDING
+        .lock()
+        .unwrap()
+        .insert(request.id.clone(), (key.to_string(), tx));
+
+    if let Err(e) = app.emit(APPROVAL_REQUEST_EVENT, &request) {
+        PENDING.lock().unwrap().remove(&request.id);
+        return Err(format!("Failed to request approval: {}", e));
+    }
+    sounds::play(app, SoundEvent::ApprovalNeeded);
+
+    let approved = match tokio::time::timeout(APPROVAL_TIMEOUT, rx).await {
+        Ok(Ok(approved)) => approved,
+        _ => {
+            PENDING.lock().unwrap().remove(&request.id);
+            false
+        }
+    };
+
+    log::info!(
+        "{} {}: {}",
+        if approved { "Approved" } else { "Denied" },
+        action,
+        detail
+    );
+    audit::record(
+        app,
+        "user",
+        &format!("approval:{}", action),
+        serde_json::json!({ "detail": detail }),
+        approved,
+        None,
+    );
+    if approved {
+        Ok(())
+    } else {
+        Err(format!("Permission denied for {}", action))
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn request_permission(
+    app: AppHandle,
+    action: String,
+    detail: String,
+) -> Result<bool, AppError> {
+    Ok(require_approval(&app, &action, &detail).await.is_ok())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn respond_permission_request(
+    settings_manager: State<'_, SettingsManager>,
+    id: String,
+    approved: bool,
+    always: bool,
+) -> Result<(), AppError> {
+    validation::uuid("id", &id)?;
+    let Some((key, tx)) = PENDING.lock().unwrap().remove(&id) else {
+        return Err(AppError::InvalidInput(format!(
+            "No pending permission request {}",
+            id
+        )));
+    };
+
+    if approved && always {
+        settings_manager.update(|settings| {
+            if !settings.always_allowed_actions.contains(&key) {
+                settings.always_allowed_actions.push(key.clone());
+            }
+        })?;
+    }
+    let _ = tx.send(approved);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_always_allowed(settings_manager: State<'_, SettingsManager>) -> Vec<String> {
+    settings_manager.get().always_allowed_actions
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn revoke_always_allowed(
+    settings_manager: State<'_, SettingsManager>,
+    action: String,
+) -> Result<(), AppError> {
+    settings_manager.update(|settings| {
+        settings
+            .always_allowed_actions
+            .retain(|allowed| *allowed != action)
+    })?;
+    Ok(())
+}
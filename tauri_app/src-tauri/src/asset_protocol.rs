@@ -0,0 +1,250 @@
+use crate::agent_protocol::{percent_decode, query_param};
+use crate::files::FsError;
+use crate::previews;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tauri::http::{header, Method, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, UriSchemeContext, UriSchemeResponder};
+use tauri_plugin_fs::FsExt;
+
+// `asset-agent://` moves attachment bytes without base64 JSON through invoke:
+//   GET  asset-agent://localhost/<encoded path>               (file, Range aware)
+//   GET  asset-agent://localhost/<encoded path>?preview=<px>  (PNG thumbnail)
+//   POST asset-agent://localhost/upload?name=<file name>      (raw body)
+// File URLs are what `convertFileSrc(path, "asset-agent")` produces. Only
+// paths inside granted folders are served, like the `asset:` protocol.
+pub const SCHEME: &str = "asset-agent";
+const UPLOAD_DIR_NAME: &str = "uploads";
+const MAX_UPLOAD_BYTES: usize = 200 * 1024 * 1024;
+// Most bytes read for one response. Longer ranges, and whole files past it, are
+// answered in part, and media elements request the rest as they play.
+const MAX_RESPONSE_BYTES: u64 = 8 * 1024 * 1024;
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "heic" => "image/heic",
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "m4a" => "audio/mp4",
+        "aac" => "audio/aac",
+        "flac" => "audio/flac",
+        "ogg" => "audio/ogg",
+        "mp4" | "m4v" => "video/mp4",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "txt" | "md" => "text/plain; charset=utf-8",
+        "json" => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+// Only single ranges are supported, which is all media elements ask for
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = value.strip_prefix("bytes=")?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (len.saturating_sub(suffix), len.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, len.checked_sub(1)?),
+        (start, end) => (
+            start.parse().ok()?,
+            end.parse::<u64>().ok()?.min(len.checked_sub(1)?),
+        ),
+    };
+    (start <= end && end < len).then_some((start, end))
+}
+
+fn resolve(app: &AppHandle, request: &Request<Vec<u8>>) -> Result<PathBuf, FsError> {
+    let raw = percent_decode(request.uri().path().trim_start_matches('/'));
+    let path = Path::new(&raw)
+        .canonicalize()
+        .map_err(|e| FsError::io(&raw, e))?;
+    if !app.fs_scope().is_allowed(&path) {
+        return Err(FsError::OutsideScope { path: raw });
+    }
+    Ok(path)
+}
+
+fn read_file(path: &Path, range: Option<&str>) -> Result<Response<Vec<u8>>, FsError> {
+    let display = path.to_string_lossy();
+    let mut file = File::open(path).map_err(|e| FsError::io(&display, e))?;
+    let len = file.metadata().map_err(|e| FsError::io(&display, e))?.len();
+
+    let response = Response::builder()
+        .header(header::CONTENT_TYPE, content_type(path))
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+    let range = match range {
+        None if len <= MAX_RESPONSE_BYTES => {
+            let mut body = Vec::with_capacity(len as usize);
+            file.read_to_end(&mut body)
+                .map_err(|e| FsError::io(&display, e))?;
+            return Ok(response
+                .status(StatusCode::OK)
+                .body(body)
+                .unwrap_or_default());
+        }
+        None => Some((0, len - 1)),
+        Some(range) => parse_range(range, len),
+    };
+
+    let Some((start, end)) = range else {
+        return Ok(response
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new())
+            .unwrap_or_default());
+    };
+    let end = end.min(start + MAX_RESPONSE_BYTES - 1);
+    let mut body = vec![0; (end - start + 1) as usize];
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.read_exact(&mut body))
+        .map_err(|e| FsError::io(&display, e))?;
+    Ok(response
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(
+            header::CONTENT_RANGE,
+            format!("bytes {}-{}/{}", start, end, len),
+        )
+        .body(body)
+        .unwrap_or_default())
+}
+
+async fn serve(app: &AppHandle, request: &Request<Vec<u8>>) -> Result<Response<Vec<u8>>, FsError> {
+    let path = resolve(app, request)?;
+
+    if let Some(max_px) = query_param(request.uri().query(), "preview") {
+        let preview = previews::preview(
+            app,
+            path.to_string_lossy().into_owned(),
+            max_px.parse().ok(),
+        )
+        .await?;
+        return read_file(Path::new(&preview.path), None);
+    }
+
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|range| range.to_str().ok())
+        .map(str::to_string);
+    tauri::async_runtime::spawn_blocking(move || read_file(&path, range.as_deref()))
+        .await
+        .map_err(|e| FsError::Invalid {
+            reason: format!("Failed to read asset: {}", e),
+        })?
+}
+
+// Stores the body under the cache dir and grants access to it, so the
+// returned path can be attached like any picked file
+fn upload(app: &AppHandle, request: &Request<Vec<u8>>) -> Result<String, FsError> {
+    let name = query_param(request.uri().query(), "name").unwrap_or_default();
+    let name = Path::new(&name)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "upload".to_string());
+    let size = request.body().len();
+    if size > MAX_UPLOAD_BYTES {
+        return Err(FsError::TooLarge {
+            path: name,
+            size: size as u64,
+            limit: MAX_UPLOAD_BYTES as u64,
+        });
+    }
+
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| FsError::Io {
+            path: UPLOAD_DIR_NAME.to_string(),
+            reason: e.to_string(),
+        })?
+        .join(UPLOAD_DIR_NAME)
+        .join(uuid::Uuid::new_v4().simple().to_string());
+    fs::create_dir_all(&dir).map_err(|e| FsError::io(&dir.to_string_lossy(), e))?;
+    let path = dir.join(&name);
+    fs::write(&path, request.body()).map_err(|e| FsError::io(&name, e))?;
+    app.fs_scope().allow_file(&path).map_err(|e| FsError::Io {
+        path: name,
+        reason: e.to_string(),
+    })?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+fn error_response(error: FsError) -> Response<Vec<u8>> {
+    let status = match error {
+        FsError::NotFound { .. } => StatusCode::NOT_FOUND,
+        FsError::PermissionDenied { .. }
+        | FsError::OutsideScope { .. }
+        | FsError::SymlinkEscapesScope { .. } => StatusCode::FORBIDDEN,
+        FsError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        FsError::Binary { .. } | FsError::Invalid { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        FsError::NoWorkspace | FsError::Io { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(error.to_string().into_bytes())
+        .unwrap_or_default()
+}
+
+async fn upload_response(
+    app: AppHandle,
+    request: Request<Vec<u8>>,
+) -> Result<Response<Vec<u8>>, FsError> {
+    let path = tauri::async_runtime::spawn_blocking(move || upload(&app, &request))
+        .await
+        .map_err(|e| FsError::Invalid {
+            reason: format!("Upload failed: {}", e),
+        })??;
+    Ok(Response::builder()
+        .status(StatusCode::CREATED)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(serde_json::json!({ "path": path }).to_string().into_bytes())
+        .unwrap_or_default())
+}
+
+async fn handle(app: AppHandle, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let method = request.method().clone();
+    let result = match (method, request.uri().path()) {
+        // The app origin differs from asset-agent://, so uploads are preflighted
+        (Method::OPTIONS, _) => Ok(Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .header(header::ACCESS_CONTROL_ALLOW_METHODS, "GET, POST, OPTIONS")
+            .header(header::ACCESS_CONTROL_ALLOW_HEADERS, "Content-Type, Range")
+            .body(Vec::new())
+            .unwrap_or_default()),
+        (Method::POST, "/upload") => upload_response(app, request).await,
+        (Method::GET, _) => serve(&app, &request).await,
+        _ => Err(FsError::Invalid {
+            reason: "Unsupported asset request".to_string(),
+        }),
+    };
+    result.unwrap_or_else(error_response)
+}
+
+pub fn protocol(
+    context: UriSchemeContext<'_, tauri::Wry>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = context.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        responder.respond(handle(app, request).await);
+    });
+}
@@ -0,0 +1,357 @@
+use crate::error::AppError;
+use crate::secrets;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const AUDIT_FILE_NAME: &str = "audit.jsonl";
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+// The last entry's (seq, hash), read from the log on the first append. Held
+// for the whole append so sequence numbers and the hash chain stay consistent.
+static CHAIN_TIP: Mutex<Option<(u64, String)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub timestamp: String,
+    pub actor: String,
+    pub action: String,
+    pub args: Value,
+    pub ok: bool,
+    pub result: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Default, Deserialize, Type)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Type)]
+pub struct AgentAction {
+    pub action: String,
+    pub args: Value,
+    pub ok: bool,
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct AuditLog {
+    pub entries: Vec<AuditEntry>,
+    // False when any entry was edited, removed or reordered
+    pub verified: bool,
+}
+
+fn audit_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?;
+    Ok(dir.join(AUDIT_FILE_NAME))
+}
+
+// The entries that parse, and how many lines didn't. One damaged line, say
+// from a crash mid-write, shouldn't hide the rest of the log.
+fn parse_entries(contents: &str) -> (Vec<AuditEntry>, usize) {
+    let mut entries = Vec::new();
+    let mut invalid = 0;
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => {
+                log::warn!("Skipping invalid audit entry: {}", e);
+                invalid += 1;
+            }
+        }
+    }
+    (entries, invalid)
+}
+
+fn read_entries(app: &AppHandle) -> Result<(Vec<AuditEntry>, usize), String> {
+    let contents = fs::read_to_string(audit_path(app)?).unwrap_or_default();
+    Ok(parse_entries(&contents))
+}
+
+// Objects with their keys sorted, so the hash doesn't depend on how a map
+// happened to be ordered when it was written
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|key| {
+                    format!(
+                        "{}:{}",
+                        Value::from(key.as_str()),
+                        canonical_json(&map[key])
+                    )
+                })
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+// Each hash covers the previous hash plus every field of the entry, so an edit
+// anywhere breaks the chain from that point on. Fields are length-prefixed,
+// so moving text from one field into the next changes the hash too.
+fn entry_hash(entry: &AuditEntry) -> String {
+    let mut hasher = Sha256::new();
+    let mut field = |bytes: &[u8]| {
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    };
+    field(entry.prev_hash.as_bytes());
+    field(&entry.seq.to_le_bytes());
+    field(entry.timestamp.as_bytes());
+    field(entry.actor.as_bytes());
+    field(entry.action.as_bytes());
+    field(canonical_json(&entry.args).as_bytes());
+    field(&[entry.ok as u8]);
+    // So None and Some("") hash differently
+    field(&[entry.result.is_some() as u8]);
+    field(entry.result.as_deref().unwrap_or_default().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn verify_chain(entries: &[AuditEntry]) -> bool {
+    let mut prev_hash = GENESIS_HASH;
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.seq != index as u64
+            || entry.prev_hash != prev_hash
+            || entry_hash(entry) != entry.hash
+        {
+            return false;
+        }
+        prev_hash = &entry.hash;
+    }
+    true
+}
+
+pub fn record(
+    app: &AppHandle,
+    actor: &str,
+    action: &str,
+    args: Value,
+    ok: bool,
+    result: Option<String>,
+) {
+    let mut tip = CHAIN_TIP.lock().unwrap();
+
+    let outcome = (|| {
+        let path = audit_path(app)?;
+        if tip.is_none() {
+            let (entries, _) = read_entries(app)?;
+            *tip = entries.last().map(|entry| (entry.seq, entry.hash.clone()));
+        }
+
+        // Args and results can carry credentials, scrub them before they hit disk
+        let args = serde_json::from_str(&secrets::redact(&args.to_string())).unwrap_or(args);
+        let mut entry = AuditEntry {
+            seq: tip.as_ref().map(|(seq, _)| seq + 1).unwrap_or(0),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            actor: actor.to_string(),
+            action: action.to_string(),
+            args,
+            ok,
+            result: result.map(|result| secrets::redact(&result)),
+            prev_hash: tip
+                .as_ref()
+                .map(|(_, hash)| hash.clone())
+                .unwrap_or_else(|| GENESIS_HASH.to_string()),
+            hash: String::new(),
+        };
+        entry.hash = entry_hash(&entry);
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+        }
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open audit log: {}", e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit log: {}", e))?;
+        *tip = Some((entry.seq, entry.hash));
+        Ok::<_, String>(())
+    })();
+
+    if let Err(e) = outcome {
+        log::error!("Failed to record audit entry for {}: {}", action, e);
+    }
+}
+
+// The agent's tool calls reach the app through the webview's event stream,
+// which reports each finished call here
+#[tauri::command]
+#[specta::specta]
+pub fn record_agent_action(app: AppHandle, payload: AgentAction) {
+    record(
+        &app,
+        "agent",
+        &payload.action,
+        payload.args,
+        payload.ok,
+        payload.result,
+    );
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_audit_log(app: AppHandle, filter: Option<AuditFilter>) -> Result<AuditLog, AppError> {
+    let filter = filter.unwrap_or_default();
+    let (entries, invalid) = read_entries(&app)?;
+    let verified = invalid == 0 && verify_chain(&entries);
+
+    let mut matching: Vec<AuditEntry> = entries
+        .into_iter()
+        .filter(|entry| {
+            filter
+                .actor
+                .as_ref()
+                .is_none_or(|actor| &entry.actor == actor)
+        })
+        .filter(|entry| {
+            filter
+                .action
+                .as_ref()
+                .is_none_or(|action| entry.action.starts_with(action.as_str()))
+        })
+        .filter(|entry| {
+            filter
+                .since
+                .as_ref()
+                .is_none_or(|since| entry.timestamp.as_str() >= since.as_str())
+        })
+        .filter(|entry| {
+            filter
+                .until
+                .as_ref()
+                .is_none_or(|until| entry.timestamp.as_str() <= until.as_str())
+        })
+        .collect();
+
+    if let Some(limit) = filter.limit {
+        matching = matching.split_off(matching.len().saturating_sub(limit));
+    }
+
+    Ok(AuditLog {
+        entries: matching,
+        verified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn chain(count: u64) -> Vec<AuditEntry> {
+        let mut entries: Vec<AuditEntry> = Vec::new();
+        for seq in 0..count {
+            let mut entry = AuditEntry {
+                seq,
+                timestamp: format!("2024-01-01T00:00:0{}Z", seq),
+                actor: "agent".to_string(),
+                action: "run_shell".to_string(),
+                args: json!({ "command": "ls", "cwd": "/tmp" }),
+                ok: true,
+                result: Some("exit code 0".to_string()),
+                prev_hash: entries
+                    .last()
+                    .map(|entry| entry.hash.clone())
+                    .unwrap_or_else(|| GENESIS_HASH.to_string()),
+                hash: String::new(),
+            };
+            entry.hash = entry_hash(&entry);
+            entries.push(entry);
+        }
+        entries
+    }
+
+    #[test]
+    fn untouched_chain_verifies() {
+        assert!(verify_chain(&chain(3)));
+        assert!(verify_chain(&[]));
+    }
+
+    #[test]
+    fn edited_entry_breaks_the_chain() {
+        let mut entries = chain(3);
+        entries[1].args = json!({ "command": "rm -rf /", "cwd": "/tmp" });
+        assert!(!verify_chain(&entries));
+    }
+
+    #[test]
+    fn removed_or_reordered_entries_break_the_chain() {
+        let mut removed = chain(3);
+        removed.remove(1);
+        assert!(!verify_chain(&removed));
+
+        let mut reordered = chain(3);
+        reordered.swap(1, 2);
+        assert!(!verify_chain(&reordered));
+    }
+
+    #[test]
+    fn shifting_text_between_fields_changes_the_hash() {
+        let mut entry = chain(1).remove(0);
+        entry.actor = "agen".to_string();
+        entry.action = "trun_shell".to_string();
+        assert_ne!(entry_hash(&entry), chain(1)[0].hash);
+    }
+
+    #[test]
+    fn missing_and_empty_results_hash_differently() {
+        let mut empty = chain(1).remove(0);
+        empty.result = Some(String::new());
+        let mut missing = empty.clone();
+        missing.result = None;
+        assert_ne!(entry_hash(&empty), entry_hash(&missing));
+    }
+
+    #[test]
+    fn key_order_does_not_change_the_hash() {
+        assert_eq!(
+            canonical_json(&json!({ "b": [1, { "d": 2, "c": 3 }], "a": "x" })),
+            r#"{"a":"x","b":[1,{"c":3,"d":2}]}"#
+        );
+    }
+
+    #[test]
+    fn invalid_lines_are_skipped_and_counted() {
+        let entries = chain(2);
+        let contents = format!(
+            "{}\n{{\"seq\": 1, \"trunc\n\n{}\n",
+            serde_json::to_string(&entries[0]).unwrap(),
+            serde_json::to_string(&entries[1]).unwrap()
+        );
+
+        let (parsed, invalid) = parse_entries(&contents);
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(invalid, 1);
+        assert!(verify_chain(&parsed));
+    }
+}
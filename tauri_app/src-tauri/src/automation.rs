@@ -0,0 +1,163 @@
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+
+// AppleScript commands from resources/Mix.sdef. Shortcuts.app reaches them
+// through its "Run AppleScript" action:
+//   tell application "Mix" to run prompt "Summarize my notes" in session "abc"
+//   tell application "Mix" to toggle window
+//   tell application "Mix" to last response
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static LAST_RESPONSE: Mutex<Option<String>> = Mutex::new(None);
+
+// Called with every reply shown in the app, for `last response`
+pub fn record_response(text: &str) {
+    *LAST_RESPONSE.lock().unwrap() = Some(text.to_string());
+}
+
+pub fn register(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    #[cfg(target_os = "macos")]
+    scripting::register_classes();
+}
+
+#[cfg(target_os = "macos")]
+mod scripting {
+    use super::{record_response, APP, LAST_RESPONSE};
+    use crate::settings::SettingsManager;
+    use crate::{panel, providers};
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::{define_class, msg_send, ClassType, Message};
+    use objc2_foundation::{NSObject, NSScriptCommand, NSString};
+    use tauri::Manager;
+
+    // errOSAGeneralError, shown as "Mix got an error: ..."
+    const SCRIPT_ERROR: isize = -2700;
+
+    // Cocoa scripting finds the classes named in the sdef by name, so they
+    // have to be registered before the first Apple event arrives
+    pub fn register_classes() {
+        RunPromptCommand::class();
+        ToggleWindowCommand::class();
+        LastResponseCommand::class();
+    }
+
+    define_class!(
+        #[unsafe(super(NSScriptCommand, NSObject))]
+        #[name = "MixRunPromptCommand"]
+        struct RunPromptCommand;
+
+        impl RunPromptCommand {
+            #[unsafe(method(performDefaultImplementation))]
+            fn perform(&self) -> Option<Retained<AnyObject>> {
+                run_prompt(self);
+                None
+            }
+        }
+    );
+
+    define_class!(
+        #[unsafe(super(NSScriptCommand, NSObject))]
+        #[name = "MixToggleWindowCommand"]
+        struct ToggleWindowCommand;
+
+        impl ToggleWindowCommand {
+            #[unsafe(method(performDefaultImplementation))]
+            fn perform(&self) -> Option<Retained<AnyObject>> {
+                if let Some(app) = APP.get() {
+                    panel::toggle(app);
+                }
+                None
+            }
+        }
+    );
+
+    define_class!(
+        #[unsafe(super(NSScriptCommand, NSObject))]
+        #[name = "MixLastResponseCommand"]
+        struct LastResponseCommand;
+
+        impl LastResponseCommand {
+            #[unsafe(method(performDefaultImplementation))]
+            fn perform(&self) -> Option<Retained<AnyObject>> {
+                let text = LAST_RESPONSE.lock().unwrap().clone().unwrap_or_default();
+                Some(to_object(&text))
+            }
+        }
+    );
+
+    // A suspended `run prompt`, resumed on the main thread once the reply is in
+    struct PendingCommand(Retained<RunPromptCommand>);
+
+    // The command is only touched again from `run_on_main_thread`
+    unsafe impl Send for PendingCommand {}
+
+    impl PendingCommand {
+        fn resume(self, result: Result<String, String>) {
+            let reply = match result {
+                Ok(text) => Some(to_object(&text)),
+                Err(e) => {
+                    fail(&self.0, &e);
+                    None
+                }
+            };
+            unsafe {
+                let _: () = msg_send![&*self.0, resumeExecutionWithResult: reply.as_deref()];
+            }
+        }
+    }
+
+    fn to_object(text: &str) -> Retained<AnyObject> {
+        Retained::into_super(Retained::into_super(NSString::from_str(text)))
+    }
+
+    fn to_string(object: Option<Retained<AnyObject>>) -> Option<String> {
+        let string = object?.downcast::<NSString>().ok()?;
+        Some(string.to_string())
+    }
+
+    fn fail(command: &NSScriptCommand, message: &str) {
+        let message = NSString::from_str(message);
+        unsafe {
+            let _: () = msg_send![command, setScriptErrorNumber: SCRIPT_ERROR];
+            let _: () = msg_send![command, setScriptErrorString: &*message];
+        }
+    }
+
+    fn run_prompt(command: &RunPromptCommand) {
+        let Some(app) = APP.get().cloned() else {
+            return fail(command, "Mix is still starting");
+        };
+        let prompt = to_string(unsafe { msg_send![command, directParameter] });
+        let Some(prompt) = prompt.filter(|prompt| !prompt.trim().is_empty()) else {
+            return fail(command, "run prompt needs some text");
+        };
+        let session_id = unsafe {
+            let arguments: Option<Retained<AnyObject>> = msg_send![command, evaluatedArguments];
+            arguments.and_then(|arguments| {
+                to_string(msg_send![&*arguments, objectForKey: &*NSString::from_str("session")])
+            })
+        }
+        .unwrap_or_default();
+
+        // Prompts take a while, so the script waits without blocking the app
+        unsafe {
+            let _: () = msg_send![command, suspendExecution];
+        }
+        let pending = PendingCommand(command.retain());
+        tauri::async_runtime::spawn(async move {
+            let trace_id = uuid::Uuid::new_v4().to_string();
+            log::info!("[trace {}] AppleScript prompt", trace_id);
+            let provider = providers::provider(&app.state::<SettingsManager>(), &session_id);
+            let result = providers::send(&app, &session_id, provider, &prompt, &trace_id)
+                .await
+                .map_err(String::from);
+            if let Ok(text) = &result {
+                record_response(text);
+            }
+            if let Err(e) = app.run_on_main_thread(move || pending.resume(result)) {
+                log::error!("Failed to finish AppleScript prompt: {}", e);
+            }
+        });
+    }
+}
@@ -0,0 +1,250 @@
+use crate::{
+    accessibility, actions, app_lock, app_state, approvals, audit, calendar, code_blocks,
+    code_eval, companion_api, databases, debug, delivery, diagnostics, editors, edits, files,
+    fs_access, generated_images, git, health, highlight, history, i18n, image_edit, integrity,
+    link_preview, logging, mcp, mcp_server, metrics, native_messaging, oauth, ollama, onboarding,
+    openai_api, panel, pdf_export, previews, prompt_cache, providers, quick_switch, recents,
+    recording, ring_buffer, scratch, search, secrets, self_test, settings, share, shell_tool,
+    sidecar, snapshot, sounds, startup, telemetry, vault, warmup, watcher, web_search, webhooks,
+    webview_language, workspace,
+};
+use tauri_specta::{collect_commands, Builder};
+
+// Every command and event payload, so `src/bindings.ts` describes the whole
+// contract between the Rust side and the webview. Debug builds regenerate the
+// file on launch; commit it with the change that altered a signature.
+#[cfg(debug_assertions)]
+const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/bindings.ts");
+
+pub fn builder() -> Builder<tauri::Wry> {
+    Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            crate::list_apps_with_icons,
+            crate::start_sidecar,
+            crate::stop_sidecar,
+            crate::sidecar_status,
+            crate::sidecar_health,
+            crate::sidecar_error,
+            crate::get_sidecar_auth,
+            crate::get_sidecar_port,
+            crate::send_prompt,
+            crate::stream_prompt,
+            crate::cancel_prompt,
+            crate::set_sidecar_transport,
+            crate::set_sidecar_port_fallback,
+            app_lock::get_lock_status,
+            app_lock::configure_app_lock,
+            app_lock::lock_app,
+            app_lock::unlock_with_passphrase,
+            app_lock::unlock_with_biometrics,
+            approvals::request_permission,
+            approvals::respond_permission_request,
+            approvals::list_always_allowed,
+            approvals::revoke_always_allowed,
+            audit::record_agent_action,
+            audit::get_audit_log,
+            debug::set_debug_mode,
+            debug::get_debug_mode,
+            diagnostics::export_diagnostics,
+            fs_access::request_fs_access,
+            fs_access::list_fs_grants,
+            fs_access::revoke_fs_grant,
+            health::get_health_report,
+            history::enable_history_encryption,
+            history::get_history_encryption,
+            logging::get_logs,
+            logging::open_log_viewer,
+            logging::report_frontend_error,
+            metrics::get_metrics,
+            oauth::oauth_login,
+            oauth::oauth_logout,
+            oauth::list_oauth_providers,
+            onboarding::get_onboarding,
+            onboarding::request_os_permission,
+            onboarding::open_permission_settings,
+            onboarding::complete_onboarding,
+            app_state::get_app_state,
+            app_state::set_active_session,
+            actions::list_actions,
+            actions::run_action,
+            panel::show_panel,
+            panel::hide_panel,
+            panel::toggle_panel,
+            recording::start_recording,
+            recording::start_replay,
+            recording::stop_recording,
+            recording::get_recording_status,
+            secrets::set_secret,
+            secrets::get_secret_exists,
+            secrets::delete_secret,
+            self_test::run_self_test,
+            settings::get_settings,
+            telemetry::preview_telemetry,
+            telemetry::set_telemetry_consent,
+            workspace::set_workspace,
+            workspace::get_workspace,
+            files::list_workspace_tree,
+            files::read_file,
+            files::read_file_chunk,
+            files::write_file,
+            files::diff_file,
+            files::apply_patch,
+            git::git_status,
+            git::git_current_branch,
+            git::git_diff,
+            git::git_commit,
+            search::search_workspace,
+            recents::get_recent_projects,
+            recents::pin_project,
+            recents::record_recent_file,
+            recents::get_recent_files,
+            quick_switch::quick_switch_candidates,
+            scratch::get_scratch_dir,
+            scratch::open_scratch_dir,
+            scratch::clear_scratch_dir,
+            snapshot::snapshot_workspace,
+            previews::generate_preview,
+            edits::list_applied_edits,
+            edits::undo_last_edit,
+            mcp::list_mcp_servers,
+            mcp::save_mcp_server,
+            mcp::remove_mcp_server,
+            mcp::list_mcp_tools,
+            mcp::list_mcp_resources,
+            mcp_server::get_mcp_server_info,
+            mcp_server::set_mcp_server_enabled,
+            ollama::detect_ollama,
+            ollama::list_ollama_models,
+            ollama::clear_ollama_history,
+            providers::get_session_provider,
+            providers::set_session_provider,
+            providers::send_session_prompt,
+            openai_api::get_openai_api_info,
+            openai_api::set_openai_api_enabled,
+            companion_api::get_companion_api_info,
+            companion_api::set_companion_api_enabled,
+            companion_api::rotate_companion_api_token,
+            code_blocks::get_code_blocks,
+            code_blocks::copy_code_block,
+            code_blocks::save_code_block,
+            highlight::highlight_code,
+            highlight::set_highlight_theme,
+            generated_images::save_generated_image,
+            generated_images::get_message_images,
+            generated_images::reveal_image,
+            generated_images::set_wallpaper,
+            editors::list_editors,
+            editors::set_preferred_editor,
+            editors::open_in_editor,
+            link_preview::fetch_link_preview,
+            share::share_content,
+            pdf_export::export_session_pdf,
+            webview_language::get_webview_language,
+            webview_language::set_webview_language,
+            i18n::get_locale,
+            i18n::set_locale,
+            i18n::get_translations,
+            accessibility::get_accessibility_prefs,
+            sounds::play_sound,
+            sounds::preview_sound,
+            sounds::set_sound_settings,
+            shell_tool::get_shell_tool_settings,
+            shell_tool::set_shell_tool_settings,
+            web_search::list_search_providers,
+            web_search::get_web_search_settings,
+            web_search::set_web_search_settings,
+            web_search::search_web,
+            code_eval::get_code_eval_status,
+            databases::list_database_connections,
+            databases::save_database_connection,
+            databases::delete_database_connection,
+            databases::test_database_connection,
+            image_edit::edit_image,
+            webhooks::list_webhooks,
+            webhooks::create_webhook,
+            webhooks::delete_webhook,
+            webhooks::set_webhooks_enabled,
+            delivery::list_delivery_targets,
+            delivery::save_delivery_target,
+            delivery::delete_delivery_target,
+            delivery::test_delivery_target,
+            native_messaging::install_native_messaging_host,
+            vault::set_vault_path,
+            vault::export_to_vault,
+            calendar::calendar_access_status,
+            calendar::preview_calendar_items,
+            calendar::create_calendar_items,
+            startup::get_startup_progress,
+            startup::get_startup_warnings,
+            startup::get_startup_profile,
+            ring_buffer::set_buffer_sizes,
+            prompt_cache::set_prompt_cache,
+            prompt_cache::clear_prompt_cache,
+            warmup::set_warmup
+        ])
+        // Events keep their `scheme://name` names, so payloads and names are
+        // exported side by side rather than as tauri-specta events
+        .typ::<app_state::StatePatchEvent>()
+        .typ::<approvals::ApprovalRequest>()
+        .typ::<debug::SidecarOutput>()
+        .typ::<debug::PromptTiming<'static>>()
+        .typ::<highlight::CodeHighlighted>()
+        .typ::<integrity::IntegrityError>()
+        .typ::<logging::LogEntry>()
+        .typ::<ollama::ChatChunk>()
+        .typ::<shell_tool::ShellEvent>()
+        .typ::<sidecar::PromptChunk>()
+        .typ::<startup::StartupStep>()
+        .typ::<startup::StartupWarning>()
+        .typ::<watcher::FileChange>()
+        .typ::<webhooks::WebhookResult>()
+        .typ::<workspace::WorkspaceInfo>()
+        .constant(
+            "ACCESSIBILITY_CHANGED_EVENT",
+            accessibility::ACCESSIBILITY_CHANGED_EVENT,
+        )
+        .constant("ACTION_EVENT", actions::ACTION_EVENT)
+        .constant("LOCKED_EVENT", app_lock::LOCKED_EVENT)
+        .constant("UNLOCKED_EVENT", app_lock::UNLOCKED_EVENT)
+        .constant("STATE_PATCH_EVENT", app_state::STATE_PATCH_EVENT)
+        .constant("APPROVAL_REQUEST_EVENT", approvals::APPROVAL_REQUEST_EVENT)
+        .constant("SIDECAR_OUTPUT_EVENT", debug::SIDECAR_OUTPUT_EVENT)
+        .constant("PROMPT_TIMING_EVENT", debug::PROMPT_TIMING_EVENT)
+        .constant("CODE_HIGHLIGHTED_EVENT", highlight::CODE_HIGHLIGHTED_EVENT)
+        .constant("LOCALE_CHANGED_EVENT", i18n::LOCALE_CHANGED_EVENT)
+        .constant("INTEGRITY_ERROR_EVENT", integrity::INTEGRITY_ERROR_EVENT)
+        .constant("LOG_ENTRY_EVENT", logging::LOG_ENTRY_EVENT)
+        .constant("OLLAMA_CHUNK_EVENT", ollama::OLLAMA_CHUNK_EVENT)
+        .constant("SHELL_EVENT", shell_tool::SHELL_EVENT)
+        .constant("SIDECAR_CHUNK_EVENT", sidecar::SIDECAR_CHUNK_EVENT)
+        .constant("STARTUP_PROGRESS_EVENT", startup::STARTUP_PROGRESS_EVENT)
+        .constant("STARTUP_WARNINGS_EVENT", startup::STARTUP_WARNINGS_EVENT)
+        .constant("FILES_CHANGED_EVENT", watcher::FILES_CHANGED_EVENT)
+        .constant("WEBHOOK_RESULT_EVENT", webhooks::WEBHOOK_RESULT_EVENT)
+        .constant(
+            "WEBVIEW_LANGUAGE_EVENT",
+            webview_language::WEBVIEW_LANGUAGE_EVENT,
+        )
+        .constant(
+            "WORKSPACE_SWITCHED_EVENT",
+            workspace::WORKSPACE_SWITCHED_EVENT,
+        )
+}
+
+// Sizes and timestamps are u64 on the Rust side but stay well below 2^53.
+// The generated helpers trip `noUnusedLocals`, so the file opts out of checks.
+#[cfg(debug_assertions)]
+fn language() -> specta_typescript::Typescript {
+    use specta_typescript::{BigIntExportBehavior, Typescript};
+
+    Typescript::default()
+        .bigint(BigIntExportBehavior::Number)
+        .header("// @ts-nocheck")
+}
+
+#[cfg(debug_assertions)]
+pub fn export(builder: &Builder<tauri::Wry>) {
+    if let Err(e) = builder.export(language(), BINDINGS_PATH) {
+        log::warn!("Failed to export TypeScript bindings: {}", e);
+    }
+}
@@ -0,0 +1,724 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::approvals;
+use crate::error::AppError;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use tauri::AppHandle;
+
+// Turns structured agent output ("schedule these three tasks") into calendar
+// events and reminders. The agent is asked for a JSON array like
+//   [{"kind": "event", "title": "Review", "start": "2026-10-20T09:00", "duration_minutes": 30},
+//    {"kind": "reminder", "title": "Send invoice", "due": "2026-10-21"}]
+// which is previewed with `preview_calendar_items` before anything is written.
+const DEFAULT_EVENT_MINUTES: i64 = 30;
+const MAX_ITEMS: usize = 50;
+// The agent reads events through `get_calendar_events`, confirmed by the user
+// under this action
+const READ_APPROVAL_ACTION: &str = "calendar_read";
+const MAX_RANGE_DAYS: i64 = 62;
+const MAX_EVENTS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Event,
+    Reminder,
+}
+
+fn event_by_default() -> ItemKind {
+    ItemKind::Event
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct ScheduleItem {
+    #[serde(default = "event_by_default")]
+    pub kind: ItemKind,
+    pub title: String,
+    // A date ("2026-10-20") makes an all-day item; reminders call it `due`
+    #[serde(default, alias = "due")]
+    pub start: Option<String>,
+    #[serde(default)]
+    pub end: Option<String>,
+    #[serde(default)]
+    pub duration_minutes: Option<i64>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+// What will be (or was) created, with dates resolved to local time
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PlannedItem {
+    pub kind: ItemKind,
+    pub title: String,
+    // RFC 3339; a reminder's due date is its `start`
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub all_day: bool,
+    pub notes: Option<String>,
+    // EventKit's identifier once the item is saved
+    pub id: Option<String>,
+}
+
+// An event as the agent reads it. Only title, times and location are filled
+// unless more fields are asked for.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct CalendarEvent {
+    pub title: String,
+    // RFC 3339, or a date for all-day events
+    pub start: String,
+    pub end: String,
+    pub all_day: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attendees: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+// Fields beyond title, times and location
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct ExtraFields {
+    pub calendar: bool,
+    pub notes: bool,
+    pub attendees: bool,
+    pub url: bool,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct CalendarAccess {
+    pub events: &'static str,
+    pub reminders: &'static str,
+}
+
+// Returns the time and whether only a date was given
+fn parse_time(value: &str) -> Option<(DateTime<Local>, bool)> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some((time.with_timezone(&Local), false));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, format) {
+            return Some((Local.from_local_datetime(&time).earliest()?, false));
+        }
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    Some((
+        Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()?,
+        true,
+    ))
+}
+
+fn plan_item(index: usize, item: ScheduleItem) -> Result<PlannedItem, String> {
+    let invalid = |reason: String| format!("Item {} ({}): {}", index + 1, item.title, reason);
+    if item.title.trim().is_empty() {
+        return Err(format!("Item {} has no title", index + 1));
+    }
+    let start = match item.start.as_deref() {
+        Some(start) => Some(
+            parse_time(start).ok_or_else(|| invalid(format!("can't read the date {}", start)))?,
+        ),
+        None => None,
+    };
+
+    let (start, end, all_day) = match (item.kind, start) {
+        (ItemKind::Event, None) => return Err(invalid("events need a start".to_string())),
+        (ItemKind::Event, Some((start, all_day))) => {
+            let end = match item.end.as_deref() {
+                Some(end) => {
+                    parse_time(end)
+                        .ok_or_else(|| invalid(format!("can't read the date {}", end)))?
+                        .0
+                }
+                None if all_day => start + Duration::days(1),
+                None => {
+                    let minutes = item.duration_minutes.unwrap_or(DEFAULT_EVENT_MINUTES);
+                    start + Duration::minutes(minutes)
+                }
+            };
+            if end <= start {
+                return Err(invalid("ends before it starts".to_string()));
+            }
+            (Some(start), Some(end), all_day)
+        }
+        // Reminders without a due date just land in the list
+        (ItemKind::Reminder, None) => (None, None, false),
+        (ItemKind::Reminder, Some((due, all_day))) => (Some(due), None, all_day),
+    };
+
+    Ok(PlannedItem {
+        kind: item.kind,
+        title: item.title.trim().to_string(),
+        start: start.map(|start| start.to_rfc3339()),
+        end: end.map(|end| end.to_rfc3339()),
+        all_day,
+        notes: item.notes.filter(|notes| !notes.trim().is_empty()),
+        id: None,
+    })
+}
+
+fn plan(items: Vec<ScheduleItem>) -> Result<Vec<PlannedItem>, String> {
+    if items.is_empty() {
+        return Err("There is nothing to schedule".to_string());
+    }
+    if items.len() > MAX_ITEMS {
+        return Err(format!("Refusing to create more than {} items", MAX_ITEMS));
+    }
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| plan_item(index, item))
+        .collect()
+}
+
+// Agents tend to wrap the JSON in prose or a code fence, and sometimes in an
+// object with an `items` field
+fn parse_output(output: &str) -> Result<Vec<ScheduleItem>, String> {
+    let start = output
+        .find(['[', '{'])
+        .ok_or_else(|| "The reply contains no schedule".to_string())?;
+    let end = output
+        .rfind([']', '}'])
+        .filter(|end| *end > start)
+        .ok_or_else(|| "The reply contains no schedule".to_string())?;
+    let value: serde_json::Value = serde_json::from_str(&output[start..=end])
+        .map_err(|e| format!("Failed to parse schedule: {}", e))?;
+    let items = match value {
+        serde_json::Value::Object(mut object) => object
+            .remove("items")
+            .ok_or_else(|| "The schedule has no items".to_string())?,
+        value => value,
+    };
+    serde_json::from_value(items).map_err(|e| format!("Failed to parse schedule: {}", e))
+}
+
+fn summary(items: &[PlannedItem]) -> String {
+    let events = items
+        .iter()
+        .filter(|item| item.kind == ItemKind::Event)
+        .count();
+    let reminders = items.len() - events;
+    let titles: Vec<&str> = items.iter().map(|item| item.title.as_str()).collect();
+    format!(
+        "{} event(s) and {} reminder(s): {}",
+        events,
+        reminders,
+        titles.join(", ")
+    )
+}
+
+#[cfg(target_os = "macos")]
+mod eventkit {
+    use super::{CalendarAccess, CalendarEvent, ExtraFields, ItemKind, PlannedItem};
+    use block2::RcBlock;
+    use chrono::{DateTime, Local, TimeZone};
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Bool};
+    use objc2_foundation::{NSArray, NSCalendar, NSCalendarUnit, NSDate, NSError, NSString};
+    use std::sync::Mutex;
+
+    #[link(name = "EventKit", kind = "framework")]
+    extern "C" {}
+
+    // EKEntityType, EKAuthorizationStatus and EKSpan values
+    const ENTITY_EVENT: usize = 0;
+    const ENTITY_REMINDER: usize = 1;
+    const SPAN_THIS_EVENT: isize = 0;
+
+    fn entity(kind: ItemKind) -> usize {
+        match kind {
+            ItemKind::Event => ENTITY_EVENT,
+            ItemKind::Reminder => ENTITY_REMINDER,
+        }
+    }
+
+    fn class(name: &std::ffi::CStr) -> Result<&'static AnyClass, String> {
+        AnyClass::get(name).ok_or_else(|| "EventKit is not available".to_string())
+    }
+
+    fn status_of(kind: ItemKind) -> &'static str {
+        let Ok(store_class) = class(c"EKEventStore") else {
+            return "unsupported";
+        };
+        let status: isize =
+            unsafe { msg_send![store_class, authorizationStatusForEntityType: entity(kind)] };
+        match status {
+            0 => "not_determined",
+            1 => "restricted",
+            2 => "denied",
+            4 => "write_only",
+            _ => "granted",
+        }
+    }
+
+    pub fn status() -> CalendarAccess {
+        CalendarAccess {
+            events: status_of(ItemKind::Event),
+            reminders: status_of(ItemKind::Reminder),
+        }
+    }
+
+    // Shows the system prompt the first time. Adding events only needs write
+    // access; reading them needs `full_access`.
+    fn request_access(store: &AnyObject, kind: ItemKind, full_access: bool) -> Result<(), String> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Mutex::new(Some(tx));
+        let completion = RcBlock::new(move |granted: Bool, _error: *mut NSError| {
+            if let Some(tx) = tx.lock().unwrap().take() {
+                let _ = tx.send(granted.as_bool());
+            }
+        });
+        unsafe {
+            // The per-kind requests are macOS 14+, older systems only grant full access
+            match kind {
+                ItemKind::Event
+                    if full_access
+                        && msg_send![store, respondsToSelector: objc2::sel!(requestFullAccessToEventsWithCompletion:)] =>
+                {
+                    let _: () =
+                        msg_send![store, requestFullAccessToEventsWithCompletion: &*completion];
+                }
+                ItemKind::Event
+                    if !full_access
+                        && msg_send![store, respondsToSelector: objc2::sel!(requestWriteOnlyAccessToEventsWithCompletion:)] =>
+                {
+                    let _: () = msg_send![store, requestWriteOnlyAccessToEventsWithCompletion: &*completion];
+                }
+                ItemKind::Reminder if msg_send![store, respondsToSelector: objc2::sel!(requestFullAccessToRemindersWithCompletion:)] =>
+                {
+                    let _: () =
+                        msg_send![store, requestFullAccessToRemindersWithCompletion: &*completion];
+                }
+                _ => {
+                    let _: () = msg_send![
+                        store,
+                        requestAccessToEntityType: entity(kind),
+                        completion: &*completion
+                    ];
+                }
+            }
+        }
+        if rx.blocking_recv().unwrap_or(false) {
+            return Ok(());
+        }
+        Err(match kind {
+            ItemKind::Event if full_access => "Mix isn't allowed to read your calendar. Allow full access in System Settings > Privacy & Security > Calendars",
+            ItemKind::Event => "Mix isn't allowed to add calendar events. Allow it in System Settings > Privacy & Security > Calendars",
+            ItemKind::Reminder => "Mix isn't allowed to add reminders. Allow it in System Settings > Privacy & Security > Reminders",
+        }
+        .to_string())
+    }
+
+    fn date(time: &str) -> Result<Retained<NSDate>, String> {
+        let time =
+            DateTime::parse_from_rfc3339(time).map_err(|e| format!("Invalid date: {}", e))?;
+        Ok(unsafe { NSDate::dateWithTimeIntervalSince1970(time.timestamp() as f64) })
+    }
+
+    fn set_common(item: &AnyObject, planned: &PlannedItem) {
+        let title = NSString::from_str(&planned.title);
+        unsafe {
+            let _: () = msg_send![item, setTitle: &*title];
+            if let Some(notes) = &planned.notes {
+                let notes = NSString::from_str(notes);
+                let _: () = msg_send![item, setNotes: &*notes];
+            }
+        }
+    }
+
+    fn default_calendar(store: &AnyObject, kind: ItemKind) -> Result<Retained<AnyObject>, String> {
+        let calendar: Option<Retained<AnyObject>> = unsafe {
+            match kind {
+                ItemKind::Event => msg_send![store, defaultCalendarForNewEvents],
+                ItemKind::Reminder => msg_send![store, defaultCalendarForNewReminders],
+            }
+        };
+        calendar.ok_or_else(|| "No default calendar is set up".to_string())
+    }
+
+    fn identifier(item: &AnyObject) -> Option<String> {
+        let id: Option<Retained<NSString>> = unsafe { msg_send![item, calendarItemIdentifier] };
+        id.map(|id| id.to_string())
+    }
+
+    fn error_text(error: Retained<NSError>) -> String {
+        error.localizedDescription().to_string()
+    }
+
+    fn save_event(store: &AnyObject, planned: &PlannedItem) -> Result<Option<String>, String> {
+        let calendar = default_calendar(store, ItemKind::Event)?;
+        let event: Retained<AnyObject> =
+            unsafe { msg_send![class(c"EKEvent")?, eventWithEventStore: store] };
+        set_common(&event, planned);
+        let (Some(start), Some(end)) = (&planned.start, &planned.end) else {
+            return Err(format!("{} has no start", planned.title));
+        };
+        let (start, end) = (date(start)?, date(end)?);
+        let saved: Result<(), Retained<NSError>> = unsafe {
+            let _: () = msg_send![&*event, setCalendar: &*calendar];
+            let _: () = msg_send![&*event, setStartDate: &*start];
+            let _: () = msg_send![&*event, setEndDate: &*end];
+            let _: () = msg_send![&*event, setAllDay: planned.all_day];
+            msg_send![store, saveEvent: &*event, span: SPAN_THIS_EVENT, commit: false, error: _]
+        };
+        saved.map_err(|e| format!("Failed to add {}: {}", planned.title, error_text(e)))?;
+        Ok(identifier(&event))
+    }
+
+    fn save_reminder(store: &AnyObject, planned: &PlannedItem) -> Result<Option<String>, String> {
+        let calendar = default_calendar(store, ItemKind::Reminder)?;
+        let reminder: Retained<AnyObject> =
+            unsafe { msg_send![class(c"EKReminder")?, reminderWithEventStore: store] };
+        set_common(&reminder, planned);
+        unsafe {
+            let _: () = msg_send![&*reminder, setCalendar: &*calendar];
+        }
+        if let Some(due) = &planned.start {
+            let due = date(due)?;
+            // Without a time component the reminder is due that day
+            let mut units = NSCalendarUnit::Year | NSCalendarUnit::Month | NSCalendarUnit::Day;
+            if !planned.all_day {
+                units |= NSCalendarUnit::Hour | NSCalendarUnit::Minute;
+            }
+            unsafe {
+                let components = NSCalendar::currentCalendar().components_fromDate(units, &due);
+                let _: () = msg_send![&*reminder, setDueDateComponents: &*components];
+            }
+        }
+        let saved: Result<(), Retained<NSError>> =
+            unsafe { msg_send![store, saveReminder: &*reminder, commit: false, error: _] };
+        saved.map_err(|e| format!("Failed to add {}: {}", planned.title, error_text(e)))?;
+        Ok(identifier(&reminder))
+    }
+
+    // Saves everything in one commit, so a failing item leaves nothing behind
+    pub fn create(items: &mut [PlannedItem]) -> Result<(), String> {
+        let store: Retained<AnyObject> = unsafe { msg_send![class(c"EKEventStore")?, new] };
+        for kind in [ItemKind::Event, ItemKind::Reminder] {
+            if items.iter().any(|item| item.kind == kind) {
+                request_access(&store, kind, false)?;
+            }
+        }
+        for item in items.iter_mut() {
+            let saved = match item.kind {
+                ItemKind::Event => save_event(&store, item),
+                ItemKind::Reminder => save_reminder(&store, item),
+            };
+            if let Err(e) = saved.as_ref() {
+                unsafe {
+                    let _: () = msg_send![&*store, reset];
+                }
+                return Err(e.clone());
+            }
+            item.id = saved?;
+        }
+        let committed: Result<(), Retained<NSError>> = unsafe { msg_send![&*store, commit: _] };
+        committed.map_err(|e| format!("Failed to save to the calendar: {}", error_text(e)))
+    }
+
+    fn string(value: Option<Retained<NSString>>) -> Option<String> {
+        value
+            .map(|value| value.to_string())
+            .filter(|value| !value.trim().is_empty())
+    }
+
+    fn local_time(date: &NSDate, all_day: bool) -> String {
+        let seconds: f64 = unsafe { msg_send![date, timeIntervalSince1970] };
+        match Local.timestamp_opt(seconds.floor() as i64, 0).single() {
+            Some(time) if all_day => time.format("%Y-%m-%d").to_string(),
+            Some(time) => time.to_rfc3339(),
+            None => String::new(),
+        }
+    }
+
+    fn read_event(event: &AnyObject, extra: &ExtraFields) -> (f64, CalendarEvent) {
+        unsafe {
+            let start: Retained<NSDate> = msg_send![event, startDate];
+            let end: Retained<NSDate> = msg_send![event, endDate];
+            let all_day: bool = msg_send![event, isAllDay];
+            let calendar = if extra.calendar {
+                let calendar: Option<Retained<AnyObject>> = msg_send![event, calendar];
+                calendar.and_then(|calendar| string(msg_send![&*calendar, title]))
+            } else {
+                None
+            };
+            let attendees = if extra.attendees {
+                let attendees: Option<Retained<NSArray<AnyObject>>> = msg_send![event, attendees];
+                attendees.map(|attendees| {
+                    attendees
+                        .iter()
+                        .filter_map(|attendee| string(msg_send![&*attendee, name]))
+                        .collect()
+                })
+            } else {
+                None
+            };
+            let url = if extra.url {
+                let url: Option<Retained<AnyObject>> = msg_send![event, URL];
+                url.and_then(|url| string(msg_send![&*url, absoluteString]))
+            } else {
+                None
+            };
+            let read = CalendarEvent {
+                title: string(msg_send![event, title]).unwrap_or_default(),
+                start: local_time(&start, all_day),
+                end: local_time(&end, all_day),
+                all_day,
+                location: string(msg_send![event, location]),
+                calendar,
+                notes: if extra.notes {
+                    string(msg_send![event, notes])
+                } else {
+                    None
+                },
+                attendees,
+                url,
+            };
+            (msg_send![&*start, timeIntervalSince1970], read)
+        }
+    }
+
+    // Events overlapping `start..end` in every calendar, in start order
+    pub fn events(
+        start: &str,
+        end: &str,
+        extra: &ExtraFields,
+    ) -> Result<Vec<CalendarEvent>, String> {
+        let store: Retained<AnyObject> = unsafe { msg_send![class(c"EKEventStore")?, new] };
+        request_access(&store, ItemKind::Event, true)?;
+        let (start, end) = (date(start)?, date(end)?);
+        let found: Retained<NSArray<AnyObject>> = unsafe {
+            let predicate: Retained<AnyObject> = msg_send![
+                &*store,
+                predicateForEventsWithStartDate: &*start,
+                endDate: &*end,
+                calendars: None::<&NSArray<AnyObject>>
+            ];
+            msg_send![&*store, eventsMatchingPredicate: &*predicate]
+        };
+        let mut events: Vec<(f64, CalendarEvent)> = found
+            .iter()
+            .map(|event| read_event(&event, extra))
+            .collect();
+        events.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(events.into_iter().map(|(_, event)| event).collect())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod eventkit {
+    use super::{CalendarAccess, CalendarEvent, ExtraFields, PlannedItem};
+
+    pub fn status() -> CalendarAccess {
+        CalendarAccess {
+            events: "unsupported",
+            reminders: "unsupported",
+        }
+    }
+
+    pub fn create(_items: &mut [PlannedItem]) -> Result<(), String> {
+        Err("Calendar integration is only available on macOS".to_string())
+    }
+
+    pub fn events(
+        _start: &str,
+        _end: &str,
+        _extra: &ExtraFields,
+    ) -> Result<Vec<CalendarEvent>, String> {
+        Err("Calendar integration is only available on macOS".to_string())
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn calendar_access_status() -> CalendarAccess {
+    eventkit::status()
+}
+
+// Dry run: resolves `items`, or the schedule in an agent reply, without
+// touching the calendar
+#[tauri::command]
+#[specta::specta]
+pub fn preview_calendar_items(
+    output: Option<String>,
+    items: Option<Vec<ScheduleItem>>,
+) -> Result<Vec<PlannedItem>, AppError> {
+    let items = match (items, output) {
+        (Some(items), _) => items,
+        (None, Some(output)) => parse_output(&output)?,
+        (None, None) => {
+            return Err(AppError::InvalidInput(
+                "There is nothing to schedule".to_string(),
+            ))
+        }
+    };
+    Ok(plan(items)?)
+}
+
+// Creates the items after the user approves them; with `dry_run` this is the
+// same as `preview_calendar_items`
+#[tauri::command]
+#[specta::specta]
+pub async fn create_calendar_items(
+    app: AppHandle,
+    items: Vec<ScheduleItem>,
+    dry_run: Option<bool>,
+) -> Result<Vec<PlannedItem>, AppError> {
+    let mut planned = plan(items)?;
+    if dry_run.unwrap_or(false) {
+        return Ok(planned);
+    }
+    approvals::require_approval(&app, "calendar_write", &summary(&planned)).await?;
+
+    // EventKit blocks while the permission prompt is up
+    let planned = tauri::async_runtime::spawn_blocking(move || {
+        eventkit::create(&mut planned).map(|_| planned)
+    })
+    .await
+    .map_err(|e| format!("Failed to create calendar items: {}", e))??;
+    log::info!("Created {}", summary(&planned));
+    Ok(planned)
+}
+
+fn local_midnight(date: NaiveDate) -> Result<DateTime<Local>, String> {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+        .ok_or_else(|| format!("Can't resolve midnight on {}", date))
+}
+
+// `start`/`end` when given, else the named `range`. A date-only `end`
+// includes that whole day.
+fn event_range(arguments: &Value) -> Result<(DateTime<Local>, DateTime<Local>), String> {
+    let time = |key: &str| -> Result<Option<(DateTime<Local>, bool)>, String> {
+        match arguments.get(key).and_then(Value::as_str) {
+            Some(value) => parse_time(value)
+                .map(Some)
+                .ok_or_else(|| format!("Can't read the date {}", value)),
+            None => Ok(None),
+        }
+    };
+    let (start, end) = match (time("start")?, time("end")?) {
+        (Some((start, _)), Some((end, true))) => (start, end + Duration::days(1)),
+        (Some((start, _)), Some((end, false))) => (start, end),
+        (Some((start, _)), None) => (start, start + Duration::days(1)),
+        (None, Some(_)) => return Err("end needs a start".to_string()),
+        (None, None) => {
+            let today = local_midnight(Local::now().date_naive())?;
+            match arguments
+                .get("range")
+                .and_then(Value::as_str)
+                .unwrap_or("today")
+            {
+                "today" => (today, today + Duration::days(1)),
+                "tomorrow" => (today + Duration::days(1), today + Duration::days(2)),
+                "week" => (today, today + Duration::days(7)),
+                other => return Err(format!("Unknown range {}", other)),
+            }
+        }
+    };
+    if end <= start {
+        return Err("end must be after start".to_string());
+    }
+    if end - start > Duration::days(MAX_RANGE_DAYS) {
+        return Err(format!(
+            "The range can span at most {} days",
+            MAX_RANGE_DAYS
+        ));
+    }
+    Ok((start, end))
+}
+
+fn extra_fields(arguments: &Value) -> ExtraFields {
+    let mut extra = ExtraFields::default();
+    let fields = arguments
+        .get("include")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    for field in fields {
+        match field {
+            "calendar" => extra.calendar = true,
+            "notes" => extra.notes = true,
+            "attendees" => extra.attendees = true,
+            "url" => extra.url = true,
+            _ => {}
+        }
+    }
+    extra
+}
+
+async fn get_calendar_events(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let (start, end) = event_range(&arguments)?;
+    let extra = extra_fields(&arguments);
+    let detail = format!(
+        "Read calendar events from {} to {}",
+        start.format("%Y-%m-%d %H:%M"),
+        end.format("%Y-%m-%d %H:%M")
+    );
+    approvals::require_approval(&app, READ_APPROVAL_ACTION, &detail)
+        .await
+        .map_err(|_| "The user declined access to the calendar".to_string())?;
+
+    progress.send("Reading calendar events");
+    let (start, end) = (start.to_rfc3339(), end.to_rfc3339());
+    // EventKit blocks while the permission prompt is up
+    let mut events =
+        tauri::async_runtime::spawn_blocking(move || eventkit::events(&start, &end, &extra))
+            .await
+            .map_err(|e| format!("Failed to read the calendar: {}", e))??;
+    log::info!("Agent read {} calendar event(s)", events.len());
+
+    if events.is_empty() {
+        return Ok("No events in that range".to_string());
+    }
+    let total = events.len();
+    events.truncate(MAX_EVENTS);
+    let mut result = serde_json::to_string_pretty(&events)
+        .map_err(|e| format!("Failed to serialize events: {}", e))?;
+    if total > MAX_EVENTS {
+        result.push_str(&format!(
+            "\n[only the first {} of {} events; ask for a shorter range]",
+            MAX_EVENTS, total
+        ));
+    }
+    Ok(result)
+}
+
+pub fn register_tools() {
+    agent_tools::register(Tool {
+        name: "get_calendar_events",
+        description: "Read the user's calendar events (macOS) for a range: `range` is today, \
+            tomorrow or week (the next 7 days), or give `start` and `end` as dates or local \
+            date-times. Each event has its title, start, end, all-day flag and location; \
+            `include` adds the calendar name, notes, attendees or URL. The user is asked first.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "range": { "type": "string", "enum": ["today", "tomorrow", "week"] },
+                "start": { "type": "string", "description": "e.g. 2026-10-20 or 2026-10-20T09:00" },
+                "end": { "type": "string" },
+                "include": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["calendar", "notes", "attendees", "url"] }
+                }
+            }
+        }),
+        handler: agent_tools::handler(get_calendar_events),
+    });
+}
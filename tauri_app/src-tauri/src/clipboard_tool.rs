@@ -0,0 +1,89 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::platform::{Native, Platform};
+use crate::settings::SettingsManager;
+use crate::{approvals, i18n};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+// Lets the agent leave a ready-to-paste result on the clipboard. Every write
+// is confirmed by the user under this action, since it replaces whatever the
+// user had copied.
+const APPROVAL_ACTION: &str = "clipboard_write";
+const MAX_TEXT_BYTES: usize = 1024 * 1024;
+// How much of the text the approval and the notification show
+const PREVIEW_CHARS: usize = 200;
+
+fn preview(text: &str) -> String {
+    let mut preview: String = text.chars().take(PREVIEW_CHARS).collect();
+    if preview.len() < text.len() {
+        preview.push('…');
+    }
+    preview
+}
+
+fn notify(app: &AppHandle, text: &str) {
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(i18n::t(app, "clipboard.written"))
+        .body(preview(text))
+        .show()
+    {
+        log::warn!("Failed to show clipboard notification: {}", e);
+    }
+}
+
+async fn write_clipboard(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let text = agent_tools::string_arg(&arguments, "text")?;
+    if text.is_empty() {
+        return Err("There is no text to copy".to_string());
+    }
+    if text.len() > MAX_TEXT_BYTES {
+        return Err(format!(
+            "The text is {} bytes, over the limit of {}",
+            text.len(),
+            MAX_TEXT_BYTES
+        ));
+    }
+    let detail = format!("Copy to the clipboard:\n{}", preview(&text));
+    approvals::require_approval(&app, APPROVAL_ACTION, &detail)
+        .await
+        .map_err(|_| "The user declined to replace the clipboard".to_string())?;
+
+    progress.send("Copying to the clipboard");
+    let copied = text.clone();
+    tauri::async_runtime::spawn_blocking(move || Native::copy_text(&copied))
+        .await
+        .map_err(|e| format!("Failed to copy: {}", e))??;
+    log::info!("Agent copied {} characters", text.chars().count());
+
+    if app.state::<SettingsManager>().get().clipboard_notifications {
+        notify(&app, &text);
+    }
+    Ok(format!(
+        "Copied {} characters to the clipboard",
+        text.chars().count()
+    ))
+}
+
+pub fn register_tools() {
+    agent_tools::register(Tool {
+        name: "write_clipboard",
+        description: "Put text on the user's clipboard, replacing what's there, so they can \
+            paste a finished result (a reply, a command, a snippet) wherever they need it. \
+            The user is asked first.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "text": { "type": "string" }
+            },
+            "required": ["text"]
+        }),
+        handler: agent_tools::handler(write_clipboard),
+    });
+}
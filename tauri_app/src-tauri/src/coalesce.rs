@@ -0,0 +1,90 @@
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+// Streams produce one event per token or output line, which floods the IPC
+// bridge during long generations. A coalescer merges what arrives within one
+// frame into as few events as possible. Payloads keep their shape, so
+// listeners just see larger chunks.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(16);
+
+pub trait Coalesce: Serialize + Clone + Send + 'static {
+    // Appends `next` to `self` if they belong to the same stream
+    fn merge(&mut self, next: &Self) -> bool;
+
+    // Completion markers go out right away, with anything still pending
+    fn is_final(&self) -> bool {
+        false
+    }
+}
+
+struct Shared<T> {
+    app: AppHandle,
+    event: &'static str,
+    pending: Mutex<Vec<T>>,
+    wake: Notify,
+}
+
+impl<T: Coalesce> Shared<T> {
+    // Emits under the lock, so a flush on completion can't overtake the ticker
+    fn flush(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        for item in pending.drain(..) {
+            let _ = self.app.emit(self.event, item);
+        }
+    }
+}
+
+pub struct Coalescer<T: Coalesce> {
+    shared: Arc<Shared<T>>,
+    ticker: JoinHandle<()>,
+}
+
+impl<T: Coalesce> Coalescer<T> {
+    pub fn new(app: &AppHandle, event: &'static str) -> Self {
+        let shared = Arc::new(Shared {
+            app: app.clone(),
+            event,
+            pending: Mutex::new(Vec::new()),
+            wake: Notify::new(),
+        });
+        // Sleeps until something is pushed, then flushes a frame later
+        let ticker = tauri::async_runtime::spawn({
+            let shared = shared.clone();
+            async move {
+                loop {
+                    shared.wake.notified().await;
+                    tokio::time::sleep(FLUSH_INTERVAL).await;
+                    shared.flush();
+                }
+            }
+        });
+        Self { shared, ticker }
+    }
+
+    pub fn push(&self, item: T) {
+        let is_final = item.is_final();
+        {
+            let mut pending = self.shared.pending.lock().unwrap();
+            let merged = pending.last_mut().is_some_and(|last| last.merge(&item));
+            if !merged {
+                pending.push(item);
+            }
+        }
+        if is_final {
+            self.shared.flush();
+        } else {
+            self.shared.wake.notify_one();
+        }
+    }
+}
+
+impl<T: Coalesce> Drop for Coalescer<T> {
+    fn drop(&mut self) {
+        self.ticker.abort();
+        self.shared.flush();
+    }
+}
@@ -0,0 +1,175 @@
+use crate::error::AppError;
+use crate::platform::{Native, Platform};
+use crate::ring_buffer::RingBuffer;
+use crate::sidecar::SidecarManager;
+use crate::validation;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+// Messages whose blocks are kept parsed; older ones are fetched again
+const CACHED_MESSAGES: usize = 100;
+
+static CACHE: Mutex<RingBuffer<(String, Vec<CodeBlock>)>> =
+    Mutex::new(RingBuffer::new("code_blocks", CACHED_MESSAGES));
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CodeBlock {
+    // Position among the message's blocks, from 0
+    pub index: u32,
+    // First word of the fence's info string, e.g. "rust" for ```rust
+    pub language: Option<String>,
+    pub code: String,
+}
+
+// The fields of a `messages.get` result this module reads
+#[derive(Deserialize)]
+struct MessageContent {
+    content: String,
+}
+
+// A fence opens with three or more backticks or tildes, indented by up to
+// three spaces
+fn fence(line: &str) -> Option<(char, usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let marker = rest.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = rest.len() - rest.trim_start_matches(marker).len();
+    if length < 3 {
+        return None;
+    }
+    Some((marker, length, rest[length..].trim()))
+}
+
+// Fenced code blocks in Markdown order. A block left open runs to the end of
+// the message, as it does when rendered.
+pub fn extract(markdown: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(char, usize, Option<String>, Vec<&str>)> = None;
+
+    for line in markdown.lines() {
+        match open.take() {
+            None => {
+                // Backtick fences can't have backticks in their info string
+                if let Some((marker, length, info)) = fence(line) {
+                    if marker == '`' && info.contains('`') {
+                        continue;
+                    }
+                    let language = info.split_whitespace().next().map(str::to_string);
+                    open = Some((marker, length, language, Vec::new()));
+                }
+            }
+            Some((marker, length, language, lines)) => {
+                let closes = fence(line).is_some_and(|(closing, closing_length, info)| {
+                    closing == marker && closing_length >= length && info.is_empty()
+                });
+                if closes {
+                    blocks.push(CodeBlock {
+                        index: blocks.len() as u32,
+                        language,
+                        code: lines.join("\n"),
+                    });
+                } else {
+                    let mut lines = lines;
+                    lines.push(line);
+                    open = Some((marker, length, language, lines));
+                }
+            }
+        }
+    }
+    if let Some((_, _, language, lines)) = open {
+        blocks.push(CodeBlock {
+            index: blocks.len() as u32,
+            language,
+            code: lines.join("\n"),
+        });
+    }
+    blocks
+}
+
+// Parsed once per message; the sidecar is only asked on a cache miss
+async fn blocks(sidecar: &SidecarManager, message_id: &str) -> Result<Vec<CodeBlock>, AppError> {
+    validation::uuid("messageId", message_id)?;
+    let cached = CACHE
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|(id, _)| id == message_id)
+        .map(|(_, blocks)| blocks.clone());
+    if let Some(blocks) = cached {
+        return Ok(blocks);
+    }
+
+    let message: MessageContent = serde_json::from_value(
+        sidecar
+            .rpc("messages.get", json!({ "id": message_id }))
+            .await?,
+    )
+    .map_err(|e| format!("Failed to parse message: {}", e))?;
+    let blocks = extract(&message.content);
+    CACHE
+        .lock()
+        .unwrap()
+        .push((message_id.to_string(), blocks.clone()));
+    Ok(blocks)
+}
+
+async fn block(
+    sidecar: &SidecarManager,
+    message_id: &str,
+    index: u32,
+) -> Result<CodeBlock, AppError> {
+    let blocks = blocks(sidecar, message_id).await?;
+    let count = blocks.len();
+    blocks
+        .into_iter()
+        .nth(index as usize)
+        .ok_or_else(|| AppError::InvalidField {
+            field: "index".to_string(),
+            message: format!("is out of range, the message has {} code blocks", count),
+        })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_code_blocks(
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+    message_id: String,
+) -> Result<Vec<CodeBlock>, AppError> {
+    blocks(&sidecar_manager, &message_id).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn copy_code_block(
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+    message_id: String,
+    index: u32,
+) -> Result<(), AppError> {
+    let block = block(&sidecar_manager, &message_id, index).await?;
+    Ok(Native::copy_text(&block.code)?)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn save_code_block(
+    app: AppHandle,
+    message_id: String,
+    index: u32,
+    path: String,
+) -> Result<(), AppError> {
+    let path = validation::output_path(&app, "path", &path)?;
+    let sidecar_manager = app.state::<Arc<SidecarManager>>();
+    let block = block(&sidecar_manager, &message_id, index).await?;
+    let mut code = block.code;
+    code.push('\n');
+    fs::write(&path, code)?;
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::agent_tools::{self, Progress, Tool};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::time::Duration;
+use tauri::AppHandle;
+
+// Code runs in a fresh QuickJS runtime per call. Only the language's own
+// built-ins exist there: rquickjs links none of QuickJS's `std`/`os` modules,
+// so there's no filesystem, network, process or timer access to take away.
+const MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+const MAX_STACK_BYTES: usize = 512 * 1024;
+const MAX_CODE_BYTES: usize = 100 * 1024;
+const MAX_OUTPUT_CHARS: usize = 20_000;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Serialize, Type)]
+pub struct CodeEvalStatus {
+    // False where the sandbox isn't built, so `eval_code` isn't offered
+    pub available: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Outcome {
+    output: Vec<String>,
+    value: Option<String>,
+    error: Option<String>,
+}
+
+// rquickjs ships QuickJS bindings for the desktop platforms only
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod sandbox {
+    use super::{Outcome, MAX_STACK_BYTES, MEMORY_LIMIT};
+    use rquickjs::convert::Coerced;
+    use rquickjs::{Context, Runtime};
+    use std::time::{Duration, Instant};
+
+    pub const AVAILABLE: bool = true;
+
+    // Runs the code given in `__code` with a console that collects its lines,
+    // and answers with JSON the Rust side reads back
+    const DRIVER: &str = r#"(() => {
+        const output = [];
+        const show = (value) => {
+            if (typeof value === "string") return value;
+            if (typeof value === "bigint") return `${value}n`;
+            if (typeof value === "function" || typeof value === "symbol") return String(value);
+            try {
+                const json = JSON.stringify(value, (_, v) => typeof v === "bigint" ? `${v}n` : v, 2);
+                return json === undefined ? String(value) : json;
+            } catch {
+                return String(value);
+            }
+        };
+        const log = (...args) => { output.push(args.map(show).join(" ")); };
+        globalThis.console = { log, info: log, warn: log, error: log, debug: log };
+        const code = globalThis.__code;
+        delete globalThis.__code;
+        try {
+            return JSON.stringify({ output, value: show((0, eval)(code)) });
+        } catch (e) {
+            if (!(e instanceof Error)) return JSON.stringify({ output, error: show(e) });
+            // Only the frames in the agent's code, and not all of a deep recursion
+            const frames = (e.stack ?? "").split("\n").filter((line) => line.includes("<input>"));
+            const error = [`${e.name}: ${e.message}`, ...frames.slice(0, 10)].join("\n");
+            return JSON.stringify({ output, error });
+        }
+    })()"#;
+
+    // Evaluates `code` on the calling thread, which is blocked until it
+    // finishes or `timeout` passes
+    pub fn evaluate(code: &str, timeout: Duration) -> Result<Outcome, String> {
+        let failed_to_start = |e| format!("Failed to start the JavaScript runtime: {}", e);
+        let runtime = Runtime::new().map_err(failed_to_start)?;
+        runtime.set_memory_limit(MEMORY_LIMIT);
+        runtime.set_max_stack_size(MAX_STACK_BYTES);
+        // Asked by QuickJS every so often whether to stop; stopping throws an
+        // error the script can't catch
+        let deadline = Instant::now() + timeout;
+        runtime.set_interrupt_handler(Some(Box::new(move || Instant::now() >= deadline)));
+        let context = Context::full(&runtime).map_err(failed_to_start)?;
+
+        context.with(|ctx| {
+            ctx.globals()
+                .set("__code", code)
+                .map_err(|e| format!("Failed to pass the code in: {}", e))?;
+            let json: String = match ctx.eval(DRIVER) {
+                Ok(json) => json,
+                // Out of memory, too deep or interrupted; the driver couldn't
+                // report it itself
+                Err(rquickjs::Error::Exception) => {
+                    let message = ctx
+                        .catch()
+                        .get::<Coerced<String>>()
+                        .map(|message| message.0)
+                        .unwrap_or_default();
+                    return Err(if Instant::now() >= deadline {
+                        format!("Timed out after {}s", timeout.as_secs_f32())
+                    } else {
+                        format!("Evaluation failed: {}", message)
+                    });
+                }
+                Err(e) => return Err(format!("Evaluation failed: {}", e)),
+            };
+            serde_json::from_str(&json)
+                .map_err(|e| format!("Evaluation produced an unreadable result: {}", e))
+        })
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+mod sandbox {
+    use super::Outcome;
+    use std::time::Duration;
+
+    pub const AVAILABLE: bool = false;
+
+    pub fn evaluate(_code: &str, _timeout: Duration) -> Result<Outcome, String> {
+        Err("JavaScript evaluation isn't available on this platform".to_string())
+    }
+}
+
+fn truncate(text: String) -> String {
+    match text.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((end, _)) => format!("{}\n[output truncated]", &text[..end]),
+        None => text,
+    }
+}
+
+async fn eval_code(
+    _app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let code = agent_tools::string_arg(&arguments, "code")?;
+    if code.len() > MAX_CODE_BYTES {
+        return Err(format!("The code must be under {} bytes", MAX_CODE_BYTES));
+    }
+    let timeout = arguments
+        .get("timeout_ms")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_TIMEOUT, Duration::from_millis)
+        .clamp(Duration::from_millis(1), MAX_TIMEOUT);
+
+    progress.send("Evaluating");
+    let outcome = tauri::async_runtime::spawn_blocking(move || sandbox::evaluate(&code, timeout))
+        .await
+        .map_err(|e| format!("Evaluation failed: {}", e))??;
+
+    let mut result = String::new();
+    if !outcome.output.is_empty() {
+        result.push_str(&format!("Console:\n{}\n\n", outcome.output.join("\n")));
+    }
+    match (outcome.value, outcome.error) {
+        (_, Some(error)) => Err(truncate(format!("{}Error: {}", result, error))),
+        (value, None) => Ok(truncate(format!(
+            "{}Result: {}",
+            result,
+            value.unwrap_or_else(|| "undefined".to_string())
+        ))),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_code_eval_status() -> CodeEvalStatus {
+    CodeEvalStatus {
+        available: sandbox::AVAILABLE,
+    }
+}
+
+pub fn register_tools() {
+    if !sandbox::AVAILABLE {
+        return;
+    }
+    agent_tools::register(Tool {
+        name: "eval_code",
+        description: "Evaluate JavaScript (ES2023) in an isolated sandbox to check a computation \
+            or transformation. The last expression's value and any console.log output are \
+            returned. There is no filesystem, network, timer or module access; memory is capped \
+            at 64MB and time at 5s unless timeout_ms asks for up to 30s.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "code": { "type": "string" },
+                "timeout_ms": { "type": "integer", "minimum": 1, "maximum": MAX_TIMEOUT.as_millis() as u64 }
+            },
+            "required": ["code"]
+        }),
+        handler: agent_tools::handler(eval_code),
+    });
+}
@@ -0,0 +1,183 @@
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::sidecar::SidecarManager;
+use crate::{automation, local_http, panel, providers};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::net::TcpStream;
+
+// Small API for launcher extensions (Raycast, Alfred):
+//   POST /prompt    {"prompt": "...", "session_id": "..."} -> {"trace_id", "text"}
+//   GET  /sessions  the most recently created sessions
+//   POST /show      brings the main window to the front
+const DEFAULT_API_PORT: u16 = 7333;
+const TOKEN_SECRET: &str = "companion_api_token";
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const RECENT_SESSIONS: usize = 20;
+const JSON: &str = "application/json";
+
+static LISTENER: local_http::Listener = local_http::Listener::new("Companion API");
+
+#[derive(Debug, Serialize, Type)]
+pub struct CompanionApiInfo {
+    pub enabled: bool,
+    pub base_url: String,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptRequest {
+    prompt: String,
+    #[serde(default)]
+    session_id: Option<String>,
+}
+
+fn port(app: &AppHandle) -> u16 {
+    app.state::<SettingsManager>()
+        .get()
+        .companion_api_port
+        .unwrap_or(DEFAULT_API_PORT)
+}
+
+fn error_body(message: &str) -> String {
+    json!({ "error": message }).to_string()
+}
+
+async fn prompt(app: &AppHandle, body: &[u8]) -> Result<Value, (&'static str, String)> {
+    let request: PromptRequest = serde_json::from_slice(body)
+        .map_err(|e| ("400 Bad Request", format!("Invalid request: {}", e)))?;
+    if request.prompt.trim().is_empty() {
+        return Err(("400 Bad Request", "prompt is empty".to_string()));
+    }
+    let session_id = request.session_id.unwrap_or_default();
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    log::info!("[trace {}] Companion API prompt", trace_id);
+
+    let provider = providers::provider(&app.state::<SettingsManager>(), &session_id);
+    let text = providers::send(app, &session_id, provider, &request.prompt, &trace_id)
+        .await
+        .map_err(|e| ("502 Bad Gateway", e.to_string()))?;
+    automation::record_response(&text);
+    Ok(json!({ "trace_id": trace_id, "text": text }))
+}
+
+async fn recent_sessions(app: &AppHandle) -> Result<Value, String> {
+    let mut sessions = app
+        .state::<Arc<SidecarManager>>()
+        .rpc("sessions.list", json!({}))
+        .await?;
+    if let Some(sessions) = sessions.as_array_mut() {
+        sessions.truncate(RECENT_SESSIONS);
+    }
+    Ok(sessions)
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream, token: String) {
+    let request = match local_http::read_request(&mut stream, MAX_BODY_BYTES).await {
+        Ok(request) => request,
+        Err(Some(status)) => return local_http::respond(&mut stream, status, JSON, "").await,
+        Err(None) => return,
+    };
+    if !request.has_bearer_token(&token) || request.is_browser() {
+        let body = error_body("Invalid token");
+        return local_http::respond(&mut stream, "401 Unauthorized", JSON, &body).await;
+    }
+
+    let (status, body) = match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/prompt") => match prompt(&app, &request.body).await {
+            Ok(reply) => ("200 OK", reply.to_string()),
+            Err((status, e)) => (status, error_body(&e)),
+        },
+        ("GET", "/sessions") => match recent_sessions(&app).await {
+            Ok(sessions) => ("200 OK", json!({ "sessions": sessions }).to_string()),
+            Err(e) => ("502 Bad Gateway", error_body(&e)),
+        },
+        ("POST", "/show") => {
+            let handle = app.clone();
+            match app.run_on_main_thread(move || panel::show(&handle)) {
+                Ok(()) => ("200 OK", json!({ "ok": true }).to_string()),
+                Err(e) => ("500 Internal Server Error", error_body(&e.to_string())),
+            }
+        }
+        _ => (
+            "404 Not Found",
+            error_body(&format!("Unknown endpoint {}", request.path)),
+        ),
+    };
+    local_http::respond(&mut stream, status, JSON, &body).await
+}
+
+// Starts the API if it is enabled in settings, replacing a running one
+pub fn start(app: &AppHandle) {
+    if !app.state::<SettingsManager>().get().companion_api_enabled {
+        return LISTENER.stop();
+    }
+    let token = match local_http::token(TOKEN_SECRET) {
+        Ok(token) => token,
+        Err(e) => {
+            log::error!("Companion API failed: {}", e);
+            return LISTENER.stop();
+        }
+    };
+    let app = app.clone();
+    LISTENER.start(port(&app), move |stream| {
+        handle_connection(app.clone(), stream, token.clone())
+    });
+}
+
+// Base URL and token for other local clients (e.g. `native_messaging`), None
+// while the API is disabled
+pub fn client_config(app: &AppHandle) -> Result<Option<(String, String)>, String> {
+    if !app.state::<SettingsManager>().get().companion_api_enabled {
+        return Ok(None);
+    }
+    Ok(Some((
+        format!("http://127.0.0.1:{}", port(app)),
+        local_http::token(TOKEN_SECRET)?,
+    )))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_companion_api_info(app: AppHandle) -> Result<CompanionApiInfo, AppError> {
+    let enabled = app.state::<SettingsManager>().get().companion_api_enabled;
+    Ok(CompanionApiInfo {
+        enabled,
+        base_url: format!("http://127.0.0.1:{}", port(&app)),
+        token: if enabled {
+            Some(local_http::token(TOKEN_SECRET)?)
+        } else {
+            None
+        },
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_companion_api_enabled(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<CompanionApiInfo, AppError> {
+    settings_manager.update(|settings| {
+        settings.companion_api_enabled = enabled;
+        if port.is_some() {
+            settings.companion_api_port = port;
+        }
+    })?;
+    start(&app);
+    get_companion_api_info(app)
+}
+
+// Invalidates the token extensions were set up with
+#[tauri::command]
+#[specta::specta]
+pub fn rotate_companion_api_token(app: AppHandle) -> Result<CompanionApiInfo, AppError> {
+    local_http::new_token(TOKEN_SECRET)?;
+    start(&app);
+    get_companion_api_info(app)
+}
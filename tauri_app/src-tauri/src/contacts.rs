@@ -0,0 +1,278 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::approvals;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+// Looks people up in the system address book for the agent, e.g. to address
+// an email it drafts. Each lookup is confirmed by the user under this action.
+const APPROVAL_ACTION: &str = "contacts_read";
+const MAX_NAME_CHARS: usize = 200;
+const MAX_CONTACTS: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct LabeledValue {
+    // "home", "work", "mobile", ... when the contact has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct Contact {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    pub emails: Vec<LabeledValue>,
+    pub phones: Vec<LabeledValue>,
+}
+
+// Trimmed and lowercased
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+// Digits only, keeping a leading `+`, so "(555) 010-2030" reads 5550102030
+fn normalize_phone(phone: &str) -> String {
+    let phone = phone.trim();
+    let digits: String = phone.chars().filter(char::is_ascii_digit).collect();
+    if phone.starts_with('+') {
+        format!("+{}", digits)
+    } else {
+        digits
+    }
+}
+
+// Contacts stores built-in labels as `_$!<Mobile>!$_`
+fn normalize_label(label: &str) -> Option<String> {
+    let label = label
+        .trim()
+        .trim_start_matches("_$!<")
+        .trim_end_matches(">!$_")
+        .trim()
+        .to_lowercase();
+    (!label.is_empty()).then_some(label)
+}
+
+fn normalize(contact: &mut Contact) {
+    for email in &mut contact.emails {
+        email.value = normalize_email(&email.value);
+    }
+    for phone in &mut contact.phones {
+        phone.value = normalize_phone(&phone.value);
+    }
+    for value in contact.emails.iter_mut().chain(&mut contact.phones) {
+        value.label = value.label.as_deref().and_then(normalize_label);
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod cncontacts {
+    use super::{Contact, LabeledValue};
+    use block2::RcBlock;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Bool};
+    use objc2_foundation::{NSArray, NSError, NSString};
+    use std::sync::Mutex;
+
+    #[link(name = "Contacts", kind = "framework")]
+    extern "C" {}
+
+    // CNEntityType and CNAuthorizationStatus values
+    const ENTITY_CONTACTS: isize = 0;
+    const STATUS_AUTHORIZED: isize = 3;
+    const STATUS_LIMITED: isize = 4;
+    // The values of the CNContact*Key constants
+    const KEYS: [&str; 6] = [
+        "givenName",
+        "middleName",
+        "familyName",
+        "organizationName",
+        "emailAddresses",
+        "phoneNumbers",
+    ];
+
+    fn class(name: &std::ffi::CStr) -> Result<&'static AnyClass, String> {
+        AnyClass::get(name).ok_or_else(|| "The Contacts framework is not available".to_string())
+    }
+
+    // Shows the system prompt the first time
+    fn request_access(store: &AnyObject) -> Result<(), String> {
+        let status: isize = unsafe {
+            msg_send![class(c"CNContactStore")?, authorizationStatusForEntityType: ENTITY_CONTACTS]
+        };
+        if matches!(status, STATUS_AUTHORIZED | STATUS_LIMITED) {
+            return Ok(());
+        }
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Mutex::new(Some(tx));
+        let completion = RcBlock::new(move |granted: Bool, _error: *mut NSError| {
+            if let Some(tx) = tx.lock().unwrap().take() {
+                let _ = tx.send(granted.as_bool());
+            }
+        });
+        unsafe {
+            let _: () = msg_send![
+                store,
+                requestAccessForEntityType: ENTITY_CONTACTS,
+                completionHandler: &*completion
+            ];
+        }
+        if rx.blocking_recv().unwrap_or(false) {
+            return Ok(());
+        }
+        Err("Mix isn't allowed to read your contacts. Allow it in System Settings > Privacy & Security > Contacts".to_string())
+    }
+
+    fn string(value: Option<Retained<NSString>>) -> Option<String> {
+        value
+            .map(|value| value.to_string().trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    // `CNLabeledValue`s, read with `value` turning each value into text
+    fn labeled(
+        values: Option<Retained<NSArray<AnyObject>>>,
+        value: impl Fn(Retained<AnyObject>) -> Option<String>,
+    ) -> Vec<LabeledValue> {
+        let Some(values) = values else {
+            return Vec::new();
+        };
+        values
+            .iter()
+            .filter_map(|labeled| {
+                let inner: Retained<AnyObject> = unsafe { msg_send![&*labeled, value] };
+                let label: Option<Retained<NSString>> = unsafe { msg_send![&*labeled, label] };
+                Some(LabeledValue {
+                    label: string(label),
+                    value: value(inner)?,
+                })
+            })
+            .collect()
+    }
+
+    fn read_contact(contact: &AnyObject) -> Contact {
+        unsafe {
+            let organization = string(msg_send![contact, organizationName]);
+            let parts: Vec<String> = [
+                string(msg_send![contact, givenName]),
+                string(msg_send![contact, middleName]),
+                string(msg_send![contact, familyName]),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            let name = if parts.is_empty() {
+                organization.clone().unwrap_or_default()
+            } else {
+                parts.join(" ")
+            };
+            Contact {
+                name,
+                organization,
+                emails: labeled(msg_send![contact, emailAddresses], |email| {
+                    string(email.downcast::<NSString>().ok())
+                }),
+                phones: labeled(msg_send![contact, phoneNumbers], |phone| {
+                    string(msg_send![&*phone, stringValue])
+                }),
+            }
+        }
+    }
+
+    pub fn lookup(name: &str) -> Result<Vec<Contact>, String> {
+        let store: Retained<AnyObject> = unsafe { msg_send![class(c"CNContactStore")?, new] };
+        request_access(&store)?;
+        let name = NSString::from_str(name);
+        let keys: Vec<Retained<NSString>> =
+            KEYS.iter().map(|key| NSString::from_str(key)).collect();
+        let keys = NSArray::from_retained_slice(&keys);
+        let found: Result<Retained<NSArray<AnyObject>>, Retained<NSError>> = unsafe {
+            let predicate: Retained<AnyObject> =
+                msg_send![class(c"CNContact")?, predicateForContactsMatchingName: &*name];
+            msg_send![
+                &*store,
+                unifiedContactsMatchingPredicate: &*predicate,
+                keysToFetch: &*keys,
+                error: _
+            ]
+        };
+        let found = found
+            .map_err(|e| format!("Failed to search contacts: {}", e.localizedDescription()))?;
+        Ok(found.iter().map(|contact| read_contact(&contact)).collect())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod cncontacts {
+    use super::Contact;
+
+    pub fn lookup(_name: &str) -> Result<Vec<Contact>, String> {
+        Err("Contacts lookup is only available on macOS".to_string())
+    }
+}
+
+async fn lookup_contact(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let name = agent_tools::string_arg(&arguments, "name")?;
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return Err(format!(
+            "name must be between 1 and {} characters",
+            MAX_NAME_CHARS
+        ));
+    }
+    let detail = format!("Look up \"{}\" in your contacts", name);
+    approvals::require_approval(&app, APPROVAL_ACTION, &detail)
+        .await
+        .map_err(|_| "The user declined access to their contacts".to_string())?;
+
+    progress.send(&format!("Looking up {}", name));
+    let lookup_name = name.clone();
+    // The Contacts framework blocks while the permission prompt is up
+    let mut contacts =
+        tauri::async_runtime::spawn_blocking(move || cncontacts::lookup(&lookup_name))
+            .await
+            .map_err(|e| format!("Failed to search contacts: {}", e))??;
+    log::info!("Agent looked up {} contact(s)", contacts.len());
+
+    if contacts.is_empty() {
+        return Ok(format!("No contacts match {}", name));
+    }
+    let total = contacts.len();
+    contacts.truncate(MAX_CONTACTS);
+    contacts.iter_mut().for_each(normalize);
+    let mut result = serde_json::to_string_pretty(&contacts)
+        .map_err(|e| format!("Failed to serialize contacts: {}", e))?;
+    if total > MAX_CONTACTS {
+        result.push_str(&format!(
+            "\n[only the first {} of {} matches; use a fuller name]",
+            MAX_CONTACTS, total
+        ));
+    }
+    Ok(result)
+}
+
+pub fn register_tools() {
+    agent_tools::register(Tool {
+        name: "lookup_contact",
+        description: "Find people in the user's contacts (macOS) by name, e.g. to address an \
+            email or message. Returns each match's name, organization, email addresses and \
+            phone numbers (digits only, with a leading + when international). The user is \
+            asked first.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "description": "Full or partial name" }
+            },
+            "required": ["name"]
+        }),
+        handler: agent_tools::handler(lookup_contact),
+    });
+}
@@ -0,0 +1,452 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::{secrets, validation};
+use reqwest::Url;
+use rusqlite::types::ValueRef;
+use rusqlite::{limits::Limit, OpenFlags};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+// Databases the user registered for the agent's `query_database` tool. Every
+// query runs read-only: SQLite files are opened read-only, and Postgres
+// queries run in a READ ONLY transaction that is rolled back. A Postgres
+// password lives in the keychain, never in the settings file.
+const PASSWORD_SECRET_PREFIX: &str = "database_password_";
+const DEFAULT_MAX_ROWS: usize = 100;
+const MAX_ROWS: usize = 1000;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(15);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_CELL_CHARS: usize = 500;
+const MAX_RESULT_CHARS: usize = 40_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseKind {
+    Sqlite,
+    Postgres,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DatabaseConnection {
+    pub id: String,
+    // What the agent calls it by
+    pub name: String,
+    pub kind: DatabaseKind,
+    // The SQLite file, or the Postgres URL without its password
+    pub location: String,
+}
+
+// Column names and rows as display text, plus whether more rows were left out
+struct QueryResult {
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+    more: bool,
+}
+
+fn password_secret(id: &str) -> String {
+    format!("{}{}", PASSWORD_SECRET_PREFIX, id)
+}
+
+fn cell(text: String) -> String {
+    match text.char_indices().nth(MAX_CELL_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+fn query_sqlite(path: &str, sql: &str, max_rows: usize) -> Result<QueryResult, String> {
+    let conn = rusqlite::Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    // No reaching other files through ATTACH
+    conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0)
+        .map_err(|e| e.to_string())?;
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    conn.progress_handler(10_000, Some(move || Instant::now() >= deadline));
+
+    // One statement only; `prepare` refuses any that follow
+    let mut statement = conn.prepare(sql).map_err(|e| e.to_string())?;
+    if !statement.readonly() {
+        return Err("Only statements that read are allowed".to_string());
+    }
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let failed = |e: rusqlite::Error| match e {
+        rusqlite::Error::SqliteFailure(error, _)
+            if error.code == rusqlite::ErrorCode::OperationInterrupted =>
+        {
+            format!("The query took longer than {}s", QUERY_TIMEOUT.as_secs())
+        }
+        e => e.to_string(),
+    };
+    let mut rows = statement.query([]).map_err(failed)?;
+    let mut result = QueryResult {
+        columns,
+        rows: Vec::new(),
+        more: false,
+    };
+    while let Some(row) = rows.next().map_err(failed)? {
+        if result.rows.len() == max_rows {
+            result.more = true;
+            break;
+        }
+        let values = (0..result.columns.len())
+            .map(|i| {
+                let text = match row.get_ref(i).map_err(|e| e.to_string())? {
+                    ValueRef::Null => "NULL".to_string(),
+                    ValueRef::Integer(value) => value.to_string(),
+                    ValueRef::Real(value) => value.to_string(),
+                    ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned(),
+                    ValueRef::Blob(blob) => format!("<{} byte blob>", blob.len()),
+                };
+                Ok(cell(text))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        result.rows.push(values);
+    }
+    Ok(result)
+}
+
+async fn query_postgres(
+    connection: &DatabaseConnection,
+    sql: &str,
+    max_rows: usize,
+) -> Result<QueryResult, String> {
+    let mut config = tokio_postgres::Config::from_str(&connection.location)
+        .map_err(|e| format!("Invalid Postgres URL: {}", e))?;
+    if let Some(password) = secrets::get_secret(&password_secret(&connection.id))? {
+        config.password(password);
+    }
+    config.connect_timeout(CONNECT_TIMEOUT);
+    let tls =
+        native_tls::TlsConnector::new().map_err(|e| format!("Failed to set up TLS: {}", e))?;
+    let (client, conn) = config
+        .connect(postgres_native_tls::MakeTlsConnector::new(tls))
+        .await
+        .map_err(|e| {
+            secrets::redact(&format!("Failed to connect to {}: {}", connection.name, e))
+        })?;
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = conn.await {
+            log::warn!("Postgres connection closed: {}", e);
+        }
+    });
+
+    client
+        .batch_execute(&format!(
+            "BEGIN READ ONLY; SET LOCAL statement_timeout = {}",
+            QUERY_TIMEOUT.as_millis()
+        ))
+        .await
+        .map_err(|e| e.to_string())?;
+    // Preparing refuses more than one statement and names the columns. The
+    // rows come back as JSON so every type reads as text.
+    let statement = client.prepare(sql).await.map_err(|e| e.to_string())?;
+    let columns: Vec<String> = statement
+        .columns()
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect();
+    let wrapped = format!(
+        "SELECT row_to_json(q)::text FROM (\n{}\n) q LIMIT {}",
+        sql.trim().trim_end_matches(';'),
+        max_rows + 1
+    );
+    let rows = client.query(&wrapped, &[]).await.map_err(|e| e.to_string());
+    let _ = client.batch_execute("ROLLBACK").await;
+
+    let mut rows = rows?;
+    let more = rows.len() > max_rows;
+    rows.truncate(max_rows);
+    let rows = rows
+        .iter()
+        .map(|row| {
+            let object: Value = serde_json::from_str(row.get::<_, &str>(0)).unwrap_or_default();
+            columns
+                .iter()
+                .map(|column| {
+                    cell(match object.get(column) {
+                        None | Some(Value::Null) => "NULL".to_string(),
+                        Some(Value::String(text)) => text.clone(),
+                        Some(value) => value.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    Ok(QueryResult {
+        columns,
+        rows,
+        more,
+    })
+}
+
+async fn query(
+    connection: &DatabaseConnection,
+    sql: &str,
+    max_rows: usize,
+) -> Result<QueryResult, String> {
+    match connection.kind {
+        DatabaseKind::Sqlite => {
+            let path = connection.location.clone();
+            let sql = sql.to_string();
+            tauri::async_runtime::spawn_blocking(move || query_sqlite(&path, &sql, max_rows))
+                .await
+                .map_err(|e| format!("Query failed: {}", e))?
+        }
+        DatabaseKind::Postgres => {
+            // The statement timeout should fire first; this catches a
+            // server that stops answering
+            tokio::time::timeout(
+                QUERY_TIMEOUT + CONNECT_TIMEOUT,
+                query_postgres(connection, sql, max_rows),
+            )
+            .await
+            .map_err(|_| format!("{} stopped responding", connection.name))?
+        }
+    }
+}
+
+// A Markdown table, cut short before it gets too long for the model
+fn render(result: &QueryResult) -> String {
+    let escape = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+    let line = |cells: &[String]| {
+        format!(
+            "| {} |",
+            cells
+                .iter()
+                .map(|cell| escape(cell))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        )
+    };
+    let mut table = format!(
+        "{}\n|{}\n",
+        line(&result.columns),
+        " --- |".repeat(result.columns.len())
+    );
+    let mut shown = 0;
+    for row in &result.rows {
+        let row = line(row);
+        if table.len() + row.len() > MAX_RESULT_CHARS {
+            break;
+        }
+        table.push_str(&row);
+        table.push('\n');
+        shown += 1;
+    }
+    if shown < result.rows.len() || result.more {
+        table.push_str(&format!(
+            "\n[{} rows shown; more were left out, narrow the query or aggregate]",
+            shown
+        ));
+    } else {
+        table.push_str(&format!("\n[{} rows]", shown));
+    }
+    table
+}
+
+fn find(app: &AppHandle, name: &str) -> Result<DatabaseConnection, String> {
+    let connections = app.state::<SettingsManager>().get().database_connections;
+    connections
+        .iter()
+        .find(|connection| connection.name.eq_ignore_ascii_case(name.trim()))
+        .cloned()
+        .ok_or_else(|| match connections.is_empty() {
+            true => "No databases are set up; the user can add them in settings".to_string(),
+            false => format!(
+                "No database named {:?}; there are {}",
+                name,
+                connections
+                    .iter()
+                    .map(|connection| connection.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })
+}
+
+async fn query_database(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let connection = find(&app, &agent_tools::string_arg(&arguments, "connection")?)?;
+    let sql = agent_tools::string_arg(&arguments, "sql")?;
+    let max_rows = arguments
+        .get("max_rows")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_MAX_ROWS, |rows| rows as usize)
+        .clamp(1, MAX_ROWS);
+
+    progress.send(&format!("Querying {}", connection.name));
+    let result = query(&connection, &sql, max_rows).await?;
+    Ok(render(&result))
+}
+
+async fn list_databases(
+    app: AppHandle,
+    _arguments: Value,
+    _progress: Progress,
+) -> Result<String, String> {
+    let connections = app.state::<SettingsManager>().get().database_connections;
+    if connections.is_empty() {
+        return Ok("No databases are set up".to_string());
+    }
+    Ok(connections
+        .iter()
+        .map(|connection| format!("- {} ({:?})", connection.name, connection.kind))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+pub fn register_tools() {
+    agent_tools::register(Tool {
+        name: "list_databases",
+        description: "List the databases the user has connected for query_database.",
+        input_schema: json!({ "type": "object", "properties": {} }),
+        handler: agent_tools::handler(list_databases),
+    });
+    agent_tools::register(Tool {
+        name: "query_database",
+        description: "Run one read-only SQL statement against a database the user connected \
+            (SQLite or Postgres) and get the rows back as a table. Writes are refused; results \
+            are limited to max_rows (100 by default, at most 1000) and queries to 15s.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "connection": { "type": "string", "description": "Name from list_databases" },
+                "sql": { "type": "string" },
+                "max_rows": { "type": "integer", "minimum": 1, "maximum": MAX_ROWS }
+            },
+            "required": ["connection", "sql"]
+        }),
+        handler: agent_tools::handler(query_database),
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_database_connections(
+    settings_manager: State<'_, SettingsManager>,
+) -> Vec<DatabaseConnection> {
+    settings_manager.get().database_connections
+}
+
+// Creates or updates a connection. A password in a Postgres URL is moved to
+// the keychain, as is `password`; leaving both out when editing keeps the
+// stored one.
+#[tauri::command]
+#[specta::specta]
+pub fn save_database_connection(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    mut connection: DatabaseConnection,
+    password: Option<String>,
+) -> Result<DatabaseConnection, AppError> {
+    connection.name = connection.name.trim().to_string();
+    if connection.name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Database connections need a name".to_string(),
+        ));
+    }
+    if connection.id.is_empty() {
+        connection.id = uuid::Uuid::new_v4().simple().to_string();
+    }
+    let taken = settings_manager
+        .get()
+        .database_connections
+        .iter()
+        .any(|existing| {
+            existing.id != connection.id && existing.name.eq_ignore_ascii_case(&connection.name)
+        });
+    if taken {
+        return Err(AppError::InvalidField {
+            field: "name".to_string(),
+            message: format!(
+                "{:?} is already used by another connection",
+                connection.name
+            ),
+        });
+    }
+
+    let mut password = password;
+    match connection.kind {
+        DatabaseKind::Sqlite => {
+            let path = validation::existing_path(&app, "location", &connection.location)?;
+            connection.location = path.to_string_lossy().into_owned();
+        }
+        DatabaseKind::Postgres => {
+            let mut url = Url::parse(connection.location.trim())
+                .ok()
+                .filter(|url| matches!(url.scheme(), "postgres" | "postgresql"))
+                .ok_or_else(|| AppError::InvalidField {
+                    field: "location".to_string(),
+                    message: "must be a postgres:// URL".to_string(),
+                })?;
+            if let Some(in_url) = url.password() {
+                password = password.or_else(|| Some(in_url.to_string()));
+                let _ = url.set_password(None);
+            }
+            connection.location = url.to_string();
+        }
+    }
+    if let Some(password) = password.filter(|password| !password.is_empty()) {
+        secrets::set_secret(password_secret(&connection.id), password)?;
+    }
+
+    settings_manager.update(|settings| {
+        match settings
+            .database_connections
+            .iter_mut()
+            .find(|existing| existing.id == connection.id)
+        {
+            Some(existing) => *existing = connection.clone(),
+            None => settings.database_connections.push(connection.clone()),
+        }
+    })?;
+    Ok(connection)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_database_connection(
+    settings_manager: State<'_, SettingsManager>,
+    id: String,
+) -> Result<(), AppError> {
+    settings_manager.update(|settings| {
+        settings
+            .database_connections
+            .retain(|connection| connection.id != id)
+    })?;
+    secrets::delete_secret(password_secret(&id))
+}
+
+// Connects and runs a trivial query, so settings can show whether it works
+#[tauri::command]
+#[specta::specta]
+pub async fn test_database_connection(
+    settings_manager: State<'_, SettingsManager>,
+    id: String,
+) -> Result<(), AppError> {
+    let connection = settings_manager
+        .get()
+        .database_connections
+        .into_iter()
+        .find(|connection| connection.id == id)
+        .ok_or_else(|| AppError::InvalidInput(format!("No database connection {}", id)))?;
+    query(&connection, "SELECT 1", 1)
+        .await
+        .map_err(AppError::InvalidInput)?;
+    Ok(())
+}
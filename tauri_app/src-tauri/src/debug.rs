@@ -0,0 +1,92 @@
+use crate::coalesce::{Coalesce, Coalescer};
+use crate::{logging, secrets};
+use log::LevelFilter;
+use serde::Serialize;
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+
+pub const SIDECAR_OUTPUT_EVENT: &str = "sidecar://output";
+pub const PROMPT_TIMING_EVENT: &str = "prompt://timing";
+
+static DEBUG_MODE: AtomicBool = AtomicBool::new(false);
+
+// Batches output lines for the webview, see `coalesce`
+static SIDECAR_OUTPUT: OnceLock<Coalescer<SidecarOutput>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SidecarOutput {
+    stream: &'static str,
+    data: String,
+}
+
+impl Coalesce for SidecarOutput {
+    fn merge(&mut self, next: &Self) -> bool {
+        if self.stream != next.stream {
+            return false;
+        }
+        if !self.data.ends_with('\n') {
+            self.data.push('\n');
+        }
+        self.data.push_str(&next.data);
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PromptTiming<'a> {
+    trace_id: &'a str,
+    duration_ms: u128,
+    ok: bool,
+}
+
+pub fn is_enabled() -> bool {
+    DEBUG_MODE.load(Ordering::Relaxed)
+}
+
+// Raw sidecar output only reaches the webview while debug mode is on
+pub fn forward_sidecar_output(app: &AppHandle, stream: &'static str, data: &[u8]) {
+    if is_enabled() {
+        SIDECAR_OUTPUT
+            .get_or_init(|| Coalescer::new(app, SIDECAR_OUTPUT_EVENT))
+            .push(SidecarOutput {
+                stream,
+                data: secrets::redact(&String::from_utf8_lossy(data)),
+            });
+    }
+}
+
+pub fn annotate_prompt(app: &AppHandle, trace_id: &str, duration_ms: u128, ok: bool) {
+    if is_enabled() {
+        let _ = app.emit(
+            PROMPT_TIMING_EVENT,
+            PromptTiming {
+                trace_id,
+                duration_ms,
+                ok,
+            },
+        );
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_debug_mode(enabled: bool) {
+    DEBUG_MODE.store(enabled, Ordering::Relaxed);
+    log::set_max_level(if enabled {
+        LevelFilter::Trace
+    } else {
+        logging::default_level()
+    });
+    log::info!(
+        "Debug mode {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_debug_mode() -> bool {
+    is_enabled()
+}
@@ -0,0 +1,272 @@
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::validation;
+use crate::{http_client, secrets};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+// Posts results (e.g. from `webhooks`) to Slack or Discord incoming webhooks.
+// The webhook URL is a credential, so it lives in the keychain and settings
+// only keep the target's id, kind and message template.
+const URL_SECRET_PREFIX: &str = "delivery_url_";
+const MAX_ATTEMPTS: u32 = 4;
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+// `{{title}}`, `{{text}}`, `{{status}}` and `{{trace_id}}` are filled in
+const DEFAULT_TEMPLATE: &str = "*{{title}}* ({{status}})\n{{text}}";
+const SLACK_MAX_CHARS: usize = 40_000;
+const DISCORD_MAX_CHARS: usize = 2_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryKind {
+    Slack,
+    Discord,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeliveryTarget {
+    pub id: String,
+    pub name: String,
+    pub kind: DeliveryKind,
+    #[serde(default)]
+    pub message_template: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryMessage {
+    pub title: String,
+    pub trace_id: String,
+    // The reply, or the error when the prompt failed
+    pub text: String,
+    pub ok: bool,
+}
+
+fn url_secret(id: &str) -> String {
+    format!("{}{}", URL_SECRET_PREFIX, id)
+}
+
+fn render(target: &DeliveryTarget, message: &DeliveryMessage) -> String {
+    let template = target
+        .message_template
+        .as_deref()
+        .filter(|template| !template.trim().is_empty())
+        .unwrap_or(DEFAULT_TEMPLATE);
+    let status = if message.ok { "done" } else { "failed" };
+    let rendered = template
+        .replace("{{title}}", &message.title)
+        .replace("{{status}}", status)
+        .replace("{{trace_id}}", &message.trace_id)
+        .replace("{{text}}", &message.text);
+
+    let limit = match target.kind {
+        DeliveryKind::Slack => SLACK_MAX_CHARS,
+        DeliveryKind::Discord => DISCORD_MAX_CHARS,
+    };
+    if rendered.chars().count() <= limit {
+        return rendered;
+    }
+    let mut truncated: String = rendered.chars().take(limit - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+// Retry-After in seconds, as both services send it on 429
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let seconds: f64 = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs_f64(seconds.max(0.0)).min(MAX_RETRY_DELAY))
+}
+
+async fn post(target: &DeliveryTarget, url: &str, text: &str) -> Result<(), String> {
+    let payload = match target.kind {
+        DeliveryKind::Slack => json!({ "text": text }),
+        DeliveryKind::Discord => json!({ "content": text }),
+    };
+    let client = http_client::shared();
+    let mut delay = FIRST_RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        let result = client
+            .post(url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&payload)
+            .send()
+            .await;
+        // Client errors other than rate limiting won't succeed on a retry
+        let error = match result {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response)
+                if response.status().is_client_error()
+                    && response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS =>
+            {
+                return Err(format!(
+                    "{} rejected the message: {}",
+                    target.name,
+                    response.status()
+                ));
+            }
+            Ok(response) => {
+                if let Some(wait) = retry_after(&response) {
+                    delay = wait;
+                }
+                format!("status {}", response.status())
+            }
+            Err(e) => e.to_string(),
+        };
+
+        if attempt >= MAX_ATTEMPTS {
+            return Err(format!(
+                "Failed to deliver to {} after {} attempts: {}",
+                target.name, attempt, error
+            ));
+        }
+        log::warn!(
+            "Delivery to {} failed ({}), retrying in {:?}",
+            target.name,
+            error,
+            delay
+        );
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+        attempt += 1;
+    }
+}
+
+pub async fn deliver(
+    app: &AppHandle,
+    target_id: &str,
+    message: &DeliveryMessage,
+) -> Result<(), String> {
+    let target = app
+        .state::<SettingsManager>()
+        .get()
+        .delivery_targets
+        .into_iter()
+        .find(|target| target.id == target_id)
+        .ok_or_else(|| format!("Unknown delivery target {}", target_id))?;
+    let url = secrets::get_secret(&url_secret(&target.id))?
+        .ok_or_else(|| format!("No webhook URL stored for {}", target.name))?;
+    post(&target, &url, &render(&target, message)).await?;
+    log::info!(
+        "[trace {}] Delivered result to {}",
+        message.trace_id,
+        target.name
+    );
+    Ok(())
+}
+
+// Delivers in the background to every target, logging failures
+pub fn deliver_all(app: &AppHandle, target_ids: &[String], message: DeliveryMessage) {
+    for target_id in target_ids {
+        let app = app.clone();
+        let target_id = target_id.clone();
+        let message = message.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = deliver(&app, &target_id, &message).await {
+                log::error!("[trace {}] {}", message.trace_id, e);
+            }
+        });
+    }
+}
+
+fn validate_url(kind: DeliveryKind, url: &str) -> Result<(), String> {
+    let prefixes: &[&str] = match kind {
+        DeliveryKind::Slack => &["https://hooks.slack.com/"],
+        DeliveryKind::Discord => &[
+            "https://discord.com/api/webhooks/",
+            "https://discordapp.com/api/webhooks/",
+        ],
+    };
+    if prefixes.iter().any(|prefix| url.starts_with(prefix)) {
+        Ok(())
+    } else {
+        Err(format!("Not a {:?} webhook URL", kind))
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_delivery_targets(settings_manager: State<'_, SettingsManager>) -> Vec<DeliveryTarget> {
+    settings_manager.get().delivery_targets
+}
+
+// Creates or updates a target; `url` may be omitted when editing to keep the
+// stored one
+#[tauri::command]
+#[specta::specta]
+pub fn save_delivery_target(
+    settings_manager: State<'_, SettingsManager>,
+    mut target: DeliveryTarget,
+    url: Option<String>,
+) -> Result<DeliveryTarget, AppError> {
+    if target.name.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Delivery targets need a name".to_string(),
+        ));
+    }
+    if target.id.is_empty() {
+        target.id = uuid::Uuid::new_v4().simple().to_string();
+    }
+    match url {
+        Some(url) => {
+            validate_url(target.kind, &url)?;
+            secrets::set_secret(url_secret(&target.id), url)?;
+        }
+        None if secrets::get_secret(&url_secret(&target.id))?.is_none() => {
+            return Err(AppError::InvalidInput(
+                "A webhook URL is required".to_string(),
+            ));
+        }
+        None => {}
+    }
+    settings_manager.update(|settings| {
+        match settings
+            .delivery_targets
+            .iter_mut()
+            .find(|existing| existing.id == target.id)
+        {
+            Some(existing) => *existing = target.clone(),
+            None => settings.delivery_targets.push(target.clone()),
+        }
+    })?;
+    Ok(target)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_delivery_target(
+    settings_manager: State<'_, SettingsManager>,
+    id: String,
+) -> Result<(), AppError> {
+    validation::uuid("id", &id)?;
+    settings_manager.update(|settings| {
+        settings.delivery_targets.retain(|target| target.id != id);
+        for webhook in settings.webhooks.iter_mut() {
+            webhook.deliver_to.retain(|target_id| *target_id != id);
+        }
+    })?;
+    secrets::delete_secret(url_secret(&id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn test_delivery_target(app: AppHandle, id: String) -> Result<(), AppError> {
+    validation::uuid("id", &id)?;
+    let message = DeliveryMessage {
+        title: "Mix".to_string(),
+        trace_id: uuid::Uuid::new_v4().to_string(),
+        text: "Test message from Mix".to_string(),
+        ok: true,
+    };
+    Ok(deliver(&app, &id, &message).await?)
+}
@@ -0,0 +1,140 @@
+use crate::error::AppError;
+use crate::settings::{SettingsManager, SETTINGS_FILE_NAME};
+use crate::sidecar::SidecarManager;
+use crate::{logging, secrets, validation};
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use sysinfo::System;
+use tauri::{AppHandle, Manager, State};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+const SENSITIVE_KEY_PARTS: [&str; 4] = ["key", "token", "secret", "password"];
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SENSITIVE_KEY_PARTS.iter().any(|part| key.contains(part)) {
+                    *value = Value::String("[REDACTED]".to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn redacted_settings(app: &AppHandle) -> Value {
+    let settings = app.state::<SettingsManager>().get();
+    let mut settings = serde_json::to_value(settings).unwrap_or(Value::Null);
+    redact(&mut settings);
+    settings
+}
+
+async fn sidecar_snapshot(app: &AppHandle, sidecar_manager: &SidecarManager) -> Value {
+    json!({
+        "running": sidecar_manager.is_running(),
+        "version": sidecar_manager.version(app).await.unwrap_or_else(|e| e.to_string()),
+        "health": sidecar_manager.health_check().await.unwrap_or_else(|e| e.to_string()),
+        "error": sidecar_manager.get_error(),
+    })
+}
+
+fn system_info(app: &AppHandle) -> Value {
+    let mut system = System::new();
+    system.refresh_memory();
+
+    json!({
+        "app_version": app.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "os_version": System::long_os_version(),
+        "arch": std::env::consts::ARCH,
+        "total_memory_bytes": system.total_memory(),
+        "used_memory_bytes": system.used_memory(),
+    })
+}
+
+fn write_json(zip: &mut ZipWriter<File>, name: &str, value: &Value) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+    let contents = secrets::redact(&contents);
+    zip.start_file(name, SimpleFileOptions::default())
+        .map_err(|e| format!("Failed to add {}: {}", name, e))?;
+    zip.write_all(contents.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", name, e))
+}
+
+fn write_logs(zip: &mut ZipWriter<File>, log_dir: &Path) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(log_dir) else {
+        return Ok(());
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        // Older log files may predate redaction, so scrub them again
+        let contents =
+            fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let contents = secrets::redact(&String::from_utf8_lossy(&contents));
+        let name = format!("logs/{}", entry.file_name().to_string_lossy());
+        zip.start_file(name.as_str(), SimpleFileOptions::default())
+            .map_err(|e| format!("Failed to add {}: {}", name, e))?;
+        zip.write_all(contents.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+
+    Ok(())
+}
+
+async fn write_bundle(
+    app: &AppHandle,
+    sidecar_manager: &SidecarManager,
+    path: &str,
+) -> Result<(), String> {
+    log::info!("Exporting diagnostics bundle to {}", path);
+
+    let sidecar = sidecar_snapshot(app, sidecar_manager).await;
+
+    // Reading the logs and zipping them is blocking file IO
+    let app = app.clone();
+    let path = path.to_string();
+    tauri::async_runtime::spawn_blocking(move || write_zip(&app, &sidecar, &path))
+        .await
+        .map_err(|e| format!("Failed to write diagnostics bundle: {}", e))?
+}
+
+fn write_zip(app: &AppHandle, sidecar: &Value, path: &str) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path, e))?;
+    let mut zip = ZipWriter::new(file);
+
+    write_json(&mut zip, "system.json", &system_info(app))?;
+    write_json(&mut zip, "sidecar.json", sidecar)?;
+    write_json(&mut zip, SETTINGS_FILE_NAME, &redacted_settings(app))?;
+    write_logs(&mut zip, &logging::log_dir(app)?)?;
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize diagnostics bundle: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn export_diagnostics(
+    app: AppHandle,
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+    path: String,
+) -> Result<(), AppError> {
+    let path = validation::output_path(&app, "path", &path)?;
+    Ok(write_bundle(&app, &sidecar_manager, &path.to_string_lossy()).await?)
+}
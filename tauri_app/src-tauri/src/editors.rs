@@ -0,0 +1,290 @@
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::validation;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_opener::OpenerExt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Editor {
+    VsCode,
+    Cursor,
+    Zed,
+    Idea,
+    WebStorm,
+    PyCharm,
+    GoLand,
+    RustRover,
+}
+
+// How an editor is found and told to jump to a line
+struct Spec {
+    editor: Editor,
+    name: &'static str,
+    // Launchers looked up on PATH, in order
+    commands: &'static [&'static str],
+    // macOS bundle in /Applications and the launcher inside it, for installs
+    // whose shell command was never added to PATH
+    app_bundle: &'static str,
+    bundled_command: &'static str,
+    // `<scheme>://file/<path>:<line>:<column>`, used when no launcher is found
+    uri_scheme: Option<&'static str>,
+}
+
+// Detection order, which is also the pick when no editor is preferred
+const EDITORS: [Spec; 8] = [
+    Spec {
+        editor: Editor::VsCode,
+        name: "Visual Studio Code",
+        commands: &["code"],
+        app_bundle: "Visual Studio Code.app",
+        bundled_command: "Contents/Resources/app/bin/code",
+        uri_scheme: Some("vscode"),
+    },
+    Spec {
+        editor: Editor::Cursor,
+        name: "Cursor",
+        commands: &["cursor"],
+        app_bundle: "Cursor.app",
+        bundled_command: "Contents/Resources/app/bin/cursor",
+        uri_scheme: Some("cursor"),
+    },
+    Spec {
+        editor: Editor::Zed,
+        name: "Zed",
+        commands: &["zed", "zeditor"],
+        app_bundle: "Zed.app",
+        bundled_command: "Contents/MacOS/cli",
+        uri_scheme: Some("zed"),
+    },
+    Spec {
+        editor: Editor::Idea,
+        name: "IntelliJ IDEA",
+        commands: &["idea"],
+        app_bundle: "IntelliJ IDEA.app",
+        bundled_command: "Contents/MacOS/idea",
+        uri_scheme: None,
+    },
+    Spec {
+        editor: Editor::WebStorm,
+        name: "WebStorm",
+        commands: &["webstorm"],
+        app_bundle: "WebStorm.app",
+        bundled_command: "Contents/MacOS/webstorm",
+        uri_scheme: None,
+    },
+    Spec {
+        editor: Editor::PyCharm,
+        name: "PyCharm",
+        commands: &["pycharm", "charm"],
+        app_bundle: "PyCharm.app",
+        bundled_command: "Contents/MacOS/pycharm",
+        uri_scheme: None,
+    },
+    Spec {
+        editor: Editor::GoLand,
+        name: "GoLand",
+        commands: &["goland"],
+        app_bundle: "GoLand.app",
+        bundled_command: "Contents/MacOS/goland",
+        uri_scheme: None,
+    },
+    Spec {
+        editor: Editor::RustRover,
+        name: "RustRover",
+        commands: &["rustrover"],
+        app_bundle: "RustRover.app",
+        bundled_command: "Contents/MacOS/rustrover",
+        uri_scheme: None,
+    },
+];
+
+#[derive(Debug, Serialize, Type)]
+pub struct EditorInfo {
+    pub editor: Editor,
+    pub name: &'static str,
+    pub preferred: bool,
+}
+
+enum Launch {
+    Command(PathBuf),
+    Uri(&'static str),
+}
+
+// Apps started from Finder or a desktop launcher get a minimal PATH, so the
+// usual install locations of editor launchers are searched as well
+fn search_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    if cfg!(unix) {
+        dirs.push(PathBuf::from("/usr/local/bin"));
+        dirs.push(PathBuf::from("/opt/homebrew/bin"));
+    }
+    if let Ok(home) = app.path().home_dir() {
+        dirs.push(home.join(".local/bin"));
+        if cfg!(target_os = "macos") {
+            dirs.push(home.join("Library/Application Support/JetBrains/Toolbox/scripts"));
+        } else if cfg!(target_os = "linux") {
+            dirs.push(home.join(".local/share/JetBrains/Toolbox/scripts"));
+        }
+    }
+    dirs
+}
+
+fn find_command(dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(windows) {
+        &["exe", "cmd", "bat"]
+    } else {
+        &[""]
+    };
+    dirs.iter().find_map(|dir| {
+        extensions
+            .iter()
+            .map(|extension| dir.join(name).with_extension(extension))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+fn detect(spec: &Spec, dirs: &[PathBuf]) -> Option<Launch> {
+    if let Some(command) = spec
+        .commands
+        .iter()
+        .find_map(|name| find_command(dirs, name))
+    {
+        return Some(Launch::Command(command));
+    }
+    if cfg!(target_os = "macos") {
+        let bundle = Path::new("/Applications").join(spec.app_bundle);
+        let command = bundle.join(spec.bundled_command);
+        if command.is_file() {
+            return Some(Launch::Command(command));
+        }
+        if let (true, Some(scheme)) = (bundle.is_dir(), spec.uri_scheme) {
+            return Some(Launch::Uri(scheme));
+        }
+    }
+    None
+}
+
+// The preferred editor when it is installed, otherwise the first one found
+fn pick(app: &AppHandle, preferred: Option<Editor>) -> Option<(&'static Spec, Launch)> {
+    let dirs = search_dirs(app);
+    let preferred = preferred
+        .and_then(|editor| EDITORS.iter().find(|spec| spec.editor == editor))
+        .and_then(|spec| Some((spec, detect(spec, &dirs)?)));
+    preferred.or_else(|| {
+        EDITORS
+            .iter()
+            .find_map(|spec| Some((spec, detect(spec, &dirs)?)))
+    })
+}
+
+fn args(editor: Editor, path: &Path, line: u32, column: u32) -> Vec<String> {
+    let path = path.to_string_lossy();
+    match editor {
+        Editor::VsCode | Editor::Cursor => {
+            vec!["-g".to_string(), format!("{}:{}:{}", path, line, column)]
+        }
+        Editor::Zed => vec![format!("{}:{}:{}", path, line, column)],
+        _ => vec![
+            "--line".to_string(),
+            line.to_string(),
+            "--column".to_string(),
+            column.to_string(),
+            path.into_owned(),
+        ],
+    }
+}
+
+// Agent answers mention files relative to the workspace, or under `~`
+fn resolve(app: &AppHandle, path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Ok(home) = app.path().home_dir() {
+            return home.join(rest);
+        }
+    }
+    let path = Path::new(path);
+    match app.state::<SettingsManager>().get().workspace {
+        Some(workspace) if path.is_relative() => Path::new(&workspace).join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_editors(app: AppHandle) -> Vec<EditorInfo> {
+    let dirs = search_dirs(&app);
+    let preferred = app.state::<SettingsManager>().get().preferred_editor;
+    EDITORS
+        .iter()
+        .filter(|spec| detect(spec, &dirs).is_some())
+        .map(|spec| EditorInfo {
+            editor: spec.editor,
+            name: spec.name,
+            preferred: preferred == Some(spec.editor),
+        })
+        .collect()
+}
+
+// None goes back to the first editor found
+#[tauri::command]
+#[specta::specta]
+pub fn set_preferred_editor(
+    settings_manager: State<'_, SettingsManager>,
+    editor: Option<Editor>,
+) -> Result<(), AppError> {
+    settings_manager.update(|settings| settings.preferred_editor = editor)?;
+    Ok(())
+}
+
+// Opens `path` at the 1-based `line` and `column`. Without an editor the file
+// opens in its default app, at the top.
+#[tauri::command]
+#[specta::specta]
+pub fn open_in_editor(
+    app: AppHandle,
+    path: String,
+    line: Option<u32>,
+    column: Option<u32>,
+) -> Result<(), AppError> {
+    let resolved = resolve(&app, &path);
+    let path = validation::existing_path(&app, "path", &resolved.to_string_lossy())?;
+    let line = line.unwrap_or(1).max(1);
+    let column = column.unwrap_or(1).max(1);
+    let preferred = app.state::<SettingsManager>().get().preferred_editor;
+
+    match pick(&app, preferred) {
+        Some((spec, Launch::Command(command))) => {
+            let mut child = Command::new(&command)
+                .args(args(spec.editor, &path, line, column))
+                .spawn()
+                .map_err(|e| format!("Failed to start {}: {}", spec.name, e))?;
+            // Launchers hand off to a running editor and exit; reap them
+            tauri::async_runtime::spawn_blocking(move || child.wait());
+            log::info!("Opened {} in {}", path.display(), spec.name);
+        }
+        Some((spec, Launch::Uri(scheme))) => {
+            let url = format!(
+                "{}://file{}:{}:{}",
+                scheme,
+                path.to_string_lossy(),
+                line,
+                column
+            );
+            app.opener()
+                .open_url(&url, None::<&str>)
+                .map_err(|e| format!("Failed to open {}: {}", spec.name, e))?;
+        }
+        None => {
+            app.opener()
+                .open_path(path.to_string_lossy(), None::<&str>)
+                .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use crate::files::{self, FsError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+// Kept across restarts, so an edit can still be undone after relaunching
+const HISTORY_FILE_NAME: &str = "edit-history.json";
+// Older edits drop off the bottom of a session's stack, and the sessions
+// edited least recently drop off entirely
+const MAX_EDITS_PER_SESSION: usize = 100;
+const MAX_SESSIONS: usize = 50;
+
+// Loaded from disk on first use; see `with_history`
+static EDITS: Mutex<Option<HashMap<String, Vec<StoredEdit>>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum EditKind {
+    Write,
+    Patch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AppliedEdit {
+    pub kind: EditKind,
+    // As given by the agent, relative to the workspace it was made in
+    pub path: String,
+    // Copy of the previous content, None when the edit created the file
+    pub backup: Option<String>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredEdit {
+    #[serde(flatten)]
+    edit: AppliedEdit,
+    resolved: PathBuf,
+    // SHA-256 of what the edit wrote, so an undo can tell whether the file
+    // changed again since
+    after_hash: String,
+}
+
+fn history_path(app: &AppHandle) -> Option<PathBuf> {
+    let dir = app.path().app_data_dir().ok()?;
+    Some(dir.join(HISTORY_FILE_NAME))
+}
+
+fn sha256(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+fn remove_backup(edit: &StoredEdit) {
+    if let Some(backup) = &edit.edit.backup {
+        if let Err(e) = fs::remove_file(backup) {
+            log::debug!("Failed to remove backup {}: {}", backup, e);
+        }
+    }
+}
+
+// Backups no edit refers to any more, e.g. from edits that dropped off while
+// the history wasn't saved
+fn prune_backups(app: &AppHandle, history: &HashMap<String, Vec<StoredEdit>>) {
+    let Ok(entries) = files::backup_dir(app)
+        .and_then(|dir| fs::read_dir(&dir).map_err(|e| FsError::io(&dir.to_string_lossy(), e)))
+    else {
+        return;
+    };
+    let referenced: HashSet<&Path> = history
+        .values()
+        .flatten()
+        .filter_map(|edit| edit.edit.backup.as_deref().map(Path::new))
+        .collect();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !referenced.contains(path.as_path()) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+fn load(app: &AppHandle) -> HashMap<String, Vec<StoredEdit>> {
+    let history: HashMap<String, Vec<StoredEdit>> = history_path(app)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| {
+            serde_json::from_str(&contents)
+                .inspect_err(|e| log::warn!("Discarding unreadable edit history: {}", e))
+                .ok()
+        })
+        .unwrap_or_default();
+    prune_backups(app, &history);
+    history
+}
+
+fn save(app: &AppHandle, history: &HashMap<String, Vec<StoredEdit>>) {
+    let Some(path) = history_path(app) else {
+        return;
+    };
+    let result = serde_json::to_vec(history)
+        .map_err(|e| e.to_string())
+        .and_then(|json| files::write_atomic(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to save edit history: {}", e);
+    }
+}
+
+// Runs `f` on the history and saves it when `f` reports a change
+fn with_history<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut HashMap<String, Vec<StoredEdit>>) -> (T, bool),
+) -> T {
+    let mut edits = EDITS.lock().unwrap();
+    let history = edits.get_or_insert_with(|| load(app));
+    let (result, changed) = f(history);
+    if changed {
+        save(app, history);
+    }
+    result
+}
+
+pub fn record(
+    app: &AppHandle,
+    session_id: &str,
+    kind: EditKind,
+    path: &str,
+    resolved: PathBuf,
+    backup: Option<PathBuf>,
+    content: &[u8],
+) {
+    let edit = StoredEdit {
+        edit: AppliedEdit {
+            kind,
+            path: path.to_string(),
+            backup: backup.map(|backup| backup.to_string_lossy().into_owned()),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        },
+        resolved,
+        after_hash: sha256(content),
+    };
+    with_history(app, |history| {
+        let stack = history.entry(session_id.to_string()).or_default();
+        stack.push(edit);
+        if stack.len() > MAX_EDITS_PER_SESSION {
+            remove_backup(&stack.remove(0));
+        }
+
+        while history.len() > MAX_SESSIONS {
+            let oldest = history
+                .iter()
+                .min_by(|(_, a), (_, b)| {
+                    let last = |stack: &Vec<StoredEdit>| {
+                        stack.last().map(|edit| edit.edit.timestamp.clone())
+                    };
+                    last(a).cmp(&last(b))
+                })
+                .map(|(session, _)| session.clone());
+            let Some(oldest) = oldest else {
+                break;
+            };
+            for edit in history.remove(&oldest).unwrap_or_default() {
+                remove_backup(&edit);
+            }
+        }
+        ((), true)
+    })
+}
+
+// Oldest first
+#[tauri::command]
+#[specta::specta]
+pub fn list_applied_edits(app: AppHandle, session_id: String) -> Vec<AppliedEdit> {
+    with_history(&app, |history| {
+        let edits = history
+            .get(&session_id)
+            .map(|stack| stack.iter().map(|edit| edit.edit.clone()).collect())
+            .unwrap_or_default();
+        (edits, false)
+    })
+}
+
+// Restores the file touched by the session's most recent edit, returning that
+// edit, or None when there is nothing left to undo. Refuses when the file
+// changed since the edit, as restoring would discard those changes.
+#[tauri::command]
+#[specta::specta]
+pub async fn undo_last_edit(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Option<AppliedEdit>, FsError> {
+    files::blocking(HISTORY_FILE_NAME, move || undo(app, session_id)).await
+}
+
+fn undo(app: AppHandle, session_id: String) -> Result<Option<AppliedEdit>, FsError> {
+    with_history(&app, |history| {
+        let Some(edit) = history.get(&session_id).and_then(|stack| stack.last()) else {
+            return (Ok(None), false);
+        };
+
+        let current = fs::read(&edit.resolved).map_err(|e| FsError::io(&edit.edit.path, e));
+        match current {
+            Ok(current) if sha256(&current) == edit.after_hash => {}
+            Ok(_) => {
+                let reason = format!(
+                    "{} changed since the edit, undoing it would lose those changes",
+                    edit.edit.path
+                );
+                return (Err(FsError::Invalid { reason }), false);
+            }
+            Err(e) => return (Err(e), false),
+        }
+
+        let restored = match &edit.edit.backup {
+            Some(backup) => {
+                fs::read(backup).and_then(|content| files::write_atomic(&edit.resolved, content))
+            }
+            // The edit created the file, so undoing it removes the file again
+            None => fs::remove_file(&edit.resolved),
+        };
+        // The edit stays on the stack so the undo can be retried
+        if let Err(e) = restored {
+            return (Err(FsError::io(&edit.edit.path, e)), false);
+        }
+
+        let Some(edit) = history.get_mut(&session_id).and_then(Vec::pop) else {
+            return (Ok(None), false);
+        };
+        remove_backup(&edit);
+        log::info!("Undid {:?} of {}", edit.edit.kind, edit.resolved.display());
+        (Ok(Some(edit.edit)), true)
+    })
+}
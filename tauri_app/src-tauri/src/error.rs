@@ -0,0 +1,161 @@
+use crate::files::FsError;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use specta::Type;
+use std::io;
+
+// Error type of the sidecar manager and the commands. The frontend receives
+// `{ code, message }`, so it can branch on `code` instead of matching text.
+// Argument checks from `validation` also carry the offending `field`.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum AppError {
+    #[error("Sidecar is not running")]
+    SidecarUnavailable,
+    // The sidecar answered, but with an error or something unexpected
+    #[error("{0}")]
+    Sidecar(String),
+    #[error("{0} timed out")]
+    Timeout(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    // The request conflicts with a transition already under way
+    #[error("{0}")]
+    InvalidState(String),
+    // `field` is the argument name as the webview passes it
+    #[error("{field} {message}")]
+    InvalidField { field: String, message: String },
+    #[error("{0}")]
+    Io(String),
+    // `holder` describes the process listening there, when it could be found
+    #[error("Port {port} is already in use by {}", holder.as_deref().unwrap_or("another process"))]
+    PortInUse { port: u16, holder: Option<String> },
+    #[error("{0}")]
+    PermissionDenied(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    SidecarUnavailable,
+    Sidecar,
+    Timeout,
+    InvalidInput,
+    InvalidState,
+    Io,
+    PortInUse,
+    PermissionDenied,
+    Internal,
+}
+
+// What `AppError` serializes to, for the generated bindings
+#[derive(Type)]
+#[specta(remote = AppError, rename = "AppError")]
+#[allow(dead_code)]
+struct AppErrorPayload {
+    code: ErrorCode,
+    message: String,
+    #[specta(optional)]
+    field: Option<String>,
+}
+
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::SidecarUnavailable => ErrorCode::SidecarUnavailable,
+            AppError::Sidecar(_) => ErrorCode::Sidecar,
+            AppError::Timeout(_) => ErrorCode::Timeout,
+            AppError::InvalidInput(_) | AppError::InvalidField { .. } => ErrorCode::InvalidInput,
+            AppError::InvalidState(_) => ErrorCode::InvalidState,
+            AppError::Io(_) => ErrorCode::Io,
+            AppError::PortInUse { .. } => ErrorCode::PortInUse,
+            AppError::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            AppError::Internal(_) => ErrorCode::Internal,
+        }
+    }
+
+    // Keeps the code, so the frontend can still tell a timeout from a refusal
+    pub fn with_trace_id(self, trace_id: &str) -> Self {
+        let note = |message: String| format!("{} (trace id: {})", message, trace_id);
+        match self {
+            AppError::SidecarUnavailable => AppError::SidecarUnavailable,
+            AppError::Sidecar(message) => AppError::Sidecar(note(message)),
+            AppError::Timeout(message) => AppError::Timeout(note(message)),
+            AppError::InvalidInput(message) => AppError::InvalidInput(note(message)),
+            AppError::InvalidField { field, message } => AppError::InvalidField {
+                field,
+                message: note(message),
+            },
+            AppError::InvalidState(message) => AppError::InvalidState(note(message)),
+            AppError::Io(message) => AppError::Io(note(message)),
+            AppError::PortInUse { port, holder } => AppError::PortInUse { port, holder },
+            AppError::PermissionDenied(message) => AppError::PermissionDenied(note(message)),
+            AppError::Internal(message) => AppError::Internal(note(message)),
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("AppError", 3)?;
+        error.serialize_field("code", &self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        match self {
+            AppError::InvalidField { field, .. } => error.serialize_field("field", field)?,
+            _ => error.skip_field("field")?,
+        }
+        error.end()
+    }
+}
+
+// Failures the app can't start without. `run` reports them in a native dialog
+// instead of returning them from `setup`, where Tauri would panic.
+#[derive(Debug, thiserror::Error)]
+pub enum SetupError {
+    #[error("Failed to set up logging: {0}")]
+    Logging(String),
+    #[error("Failed to load settings: {0}")]
+    Settings(String),
+    #[error("Failed to create the main window: {0}")]
+    Window(tauri::Error),
+}
+
+// Helpers that still report plain strings convert with `?`
+impl From<String> for AppError {
+    fn from(e: String) -> Self {
+        AppError::Internal(e)
+    }
+}
+
+impl From<AppError> for String {
+    fn from(e: AppError) -> Self {
+        e.to_string()
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => AppError::PermissionDenied(e.to_string()),
+            io::ErrorKind::TimedOut => AppError::Timeout(e.to_string()),
+            _ => AppError::Io(e.to_string()),
+        }
+    }
+}
+
+impl From<FsError> for AppError {
+    fn from(e: FsError) -> Self {
+        let message = e.to_string();
+        match e {
+            FsError::PermissionDenied { .. }
+            | FsError::OutsideScope { .. }
+            | FsError::SymlinkEscapesScope { .. } => AppError::PermissionDenied(message),
+            FsError::NotFound { .. } | FsError::Io { .. } => AppError::Io(message),
+            FsError::NoWorkspace
+            | FsError::TooLarge { .. }
+            | FsError::Binary { .. }
+            | FsError::Invalid { .. } => AppError::InvalidInput(message),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::link_preview::{self, decode_entities};
+use regex::{Captures, Regex};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// Bytes read off the wire; larger bodies are cut here
+const MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+// Characters handed back per call; the agent pages on with `offset`
+const MAX_TEXT_CHARS: usize = 40_000;
+const USER_AGENT: &str = concat!("Mix/", env!("CARGO_PKG_VERSION"), " (agent fetch)");
+const ACCEPT: &str =
+    "text/html,application/xhtml+xml,text/plain;q=0.9,application/json;q=0.9,*/*;q=0.5";
+
+// Parts of a page that are never text a reader sees
+static HIDDEN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)<!--.*?-->|<head\b.*?</head>|<script\b.*?</script>|<style\b.*?</style>|<noscript\b.*?</noscript>|<svg\b.*?</svg>|<template\b.*?</template>",
+    )
+    .unwrap()
+});
+static TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+static PRE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<pre\b[^>]*>(.*?)</pre>").unwrap());
+static LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<a\s[^>]*?href\s*=\s*(?:"([^"]*)"|'([^']*)')[^>]*>(.*?)</a>"#).unwrap()
+});
+static TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<(/?)([a-zA-Z][a-zA-Z0-9]*)\b[^>]*>").unwrap());
+static WHITESPACE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
+
+fn strip_tags(html: &str) -> String {
+    TAG.replace_all(html, "").into_owned()
+}
+
+// The line breaks a tag stands for in plain text
+fn tag_text(captures: &Captures) -> String {
+    let closing = !captures[1].is_empty();
+    let name = captures[2].to_ascii_lowercase();
+    match name.as_str() {
+        "br" | "tr" | "dt" | "dd" => "\n".to_string(),
+        "li" if !closing => "\n- ".to_string(),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" if !closing => {
+            format!("\n\n{} ", "#".repeat(name[1..].parse().unwrap_or(1)))
+        }
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "blockquote" | "table" => {
+            "\n\n".to_string()
+        }
+        "div" | "section" | "article" | "main" | "header" | "footer" | "nav" | "aside" | "ul"
+        | "ol" | "dl" | "figure" | "form" => "\n".to_string(),
+        "td" | "th" => " ".to_string(),
+        _ => String::new(),
+    }
+}
+
+// Running text with markup turned into Markdown-ish line structure and links
+// kept as `[text](url)`
+fn flow_text(base: &Url, html: &str) -> String {
+    let html = WHITESPACE.replace_all(html, " ");
+    let html = LINK.replace_all(&html, |captures: &Captures| {
+        let text = strip_tags(&captures[3]).trim().to_string();
+        let href = captures
+            .get(1)
+            .or_else(|| captures.get(2))
+            .unwrap()
+            .as_str();
+        match base.join(&decode_entities(href.trim())) {
+            Ok(url) if !text.is_empty() && matches!(url.scheme(), "http" | "https") => {
+                format!("[{}]({})", text, url)
+            }
+            _ => text,
+        }
+    });
+    let text = decode_entities(&TAG.replace_all(&html, tag_text));
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        // One blank line at most between blocks
+        if !line.is_empty() || lines.last().is_some_and(|last| !last.is_empty()) {
+            lines.push(line);
+        }
+    }
+    lines.join("\n").trim().to_string()
+}
+
+// Readable text of a page. Preformatted blocks keep their layout as fenced
+// code, since documentation is mostly read for them.
+fn html_to_text(base: &Url, html: &str) -> (Option<String>, String) {
+    let title = TITLE
+        .captures(html)
+        .map(|captures| {
+            WHITESPACE
+                .replace_all(&decode_entities(&captures[1]), " ")
+                .trim()
+                .to_string()
+        })
+        .filter(|title| !title.is_empty());
+    let html = HIDDEN.replace_all(html, "");
+
+    let mut blocks = Vec::new();
+    let mut last = 0;
+    for pre in PRE.captures_iter(&html) {
+        let whole = pre.get(0).unwrap();
+        blocks.push(flow_text(base, &html[last..whole.start()]));
+        let code = decode_entities(&strip_tags(&pre[1]));
+        blocks.push(format!("```\n{}\n```", code.trim_matches('\n')));
+        last = whole.end();
+    }
+    blocks.push(flow_text(base, &html[last..]));
+    let text = blocks
+        .into_iter()
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (title, text)
+}
+
+fn is_text(content_type: &str) -> bool {
+    content_type.starts_with("text/")
+        || ["json", "xml", "javascript", "yaml", "toml", "csv"]
+            .iter()
+            .any(|kind| content_type.contains(kind))
+}
+
+// `offset`..`offset + MAX_TEXT_CHARS` in characters, with a note on how to
+// read on when more is left
+pub fn page(text: &str, offset: usize) -> String {
+    let total = text.chars().count();
+    let window: String = text.chars().skip(offset).take(MAX_TEXT_CHARS).collect();
+    let end = offset + window.chars().count();
+    if end < total {
+        format!(
+            "{}\n\n[showing characters {}-{} of {}; call again with offset {} for more]",
+            window, offset, end, total, end
+        )
+    } else {
+        window
+    }
+}
+
+async fn fetch_url(
+    _app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let url = agent_tools::string_arg(&arguments, "url")?;
+    let url = Url::parse(url.trim()).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    let offset = arguments.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let raw = arguments
+        .get("raw")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let (url, mut response) = link_preview::get(url, ACCEPT, USER_AGENT, REQUEST_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .unwrap_or("text/html")
+        .to_ascii_lowercase();
+    let html = content_type.contains("html");
+    if !html && !is_text(&content_type) {
+        return Err(format!(
+            "{} is {}, which fetch_url can't read as text",
+            url, content_type
+        ));
+    }
+
+    let mut body = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| link_preview::request_error(&url, e).to_string())?
+    {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_BODY_BYTES {
+            truncated = true;
+            break;
+        }
+    }
+    body.truncate(MAX_BODY_BYTES);
+    progress.send(&format!("Read {} bytes from {}", body.len(), url));
+    log::info!("Agent fetched {} ({} bytes)", url, body.len());
+
+    let source = String::from_utf8_lossy(&body);
+    let (title, text) = if html && !raw {
+        html_to_text(&url, &source)
+    } else {
+        (None, source.into_owned())
+    };
+    let mut result = String::new();
+    if let Some(title) = title {
+        result.push_str(&format!("# {}\n", title));
+    }
+    result.push_str(&format!("URL: {}\nContent-Type: {}\n", url, content_type));
+    if truncated {
+        result.push_str(&format!(
+            "[only the first {} bytes were read]\n",
+            MAX_BODY_BYTES
+        ));
+    }
+    result.push('\n');
+    result.push_str(&page(&text, offset));
+    Ok(result)
+}
+
+pub fn register_tools() {
+    agent_tools::register(Tool {
+        name: "fetch_url",
+        description: "Fetch a public http(s) URL and return its text. HTML pages are \
+            converted to readable text with links kept; long results are paged with `offset`. \
+            Set `raw` to get the HTML source instead.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "url": { "type": "string" },
+                "offset": { "type": "integer", "minimum": 0 },
+                "raw": { "type": "boolean" }
+            },
+            "required": ["url"]
+        }),
+        handler: agent_tools::handler(fetch_url),
+    });
+}
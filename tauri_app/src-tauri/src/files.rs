@@ -0,0 +1,743 @@
+use crate::edits::{self, EditKind};
+use crate::workspace;
+use diffy::{Line, Patch};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+use specta::Type;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+const DEFAULT_TREE_DEPTH: usize = 3;
+const MAX_TREE_ENTRIES: usize = 5000;
+const MAX_READ_BYTES: u64 = 10 * 1024 * 1024;
+const DIFF_CONTEXT_LINES: usize = 3;
+const DEFAULT_CHUNK_BYTES: u64 = 256 * 1024;
+const MAX_CHUNK_BYTES: u64 = 4 * 1024 * 1024;
+// Backups the edit history refers to; it prunes the ones it no longer does
+const BACKUP_DIR_NAME: &str = "backups";
+// Backups of patches applied outside a session, which no edit refers to, so
+// they are kept until the user removes them
+const KEPT_BACKUP_DIR_NAME: &str = "kept_backups";
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FsError {
+    NoWorkspace,
+    NotFound { path: String },
+    PermissionDenied { path: String },
+    // Escapes the workspace lexically, e.g. through `..` or an absolute path
+    OutsideScope { path: String },
+    // Inside the workspace by name, but a symlink leads out of it
+    SymlinkEscapesScope { path: String, target: String },
+    TooLarge { path: String, size: u64, limit: u64 },
+    Binary { path: String },
+    Invalid { reason: String },
+    Io { path: String, reason: String },
+}
+
+impl FsError {
+    pub fn io(path: &str, e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::NotFound => FsError::NotFound {
+                path: path.to_string(),
+            },
+            io::ErrorKind::PermissionDenied => FsError::PermissionDenied {
+                path: path.to_string(),
+            },
+            _ => FsError::Io {
+                path: path.to_string(),
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for FsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsError::NoWorkspace => write!(f, "No workspace selected"),
+            FsError::NotFound { path } => write!(f, "{} does not exist", path),
+            FsError::PermissionDenied { path } => write!(f, "Permission denied for {}", path),
+            FsError::OutsideScope { path } => write!(f, "{} is outside the workspace", path),
+            FsError::SymlinkEscapesScope { path, target } => write!(
+                f,
+                "{} is a symlink to {}, which is outside the workspace",
+                path, target
+            ),
+            FsError::TooLarge { path, size, limit } => write!(
+                f,
+                "{} is too large ({} bytes, limit {} bytes)",
+                path, size, limit
+            ),
+            FsError::Binary { path } => write!(f, "{} is not a UTF-8 text file", path),
+            FsError::Invalid { reason } => write!(f, "{}", reason),
+            FsError::Io { path, reason } => write!(f, "Failed to access {}: {}", path, reason),
+        }
+    }
+}
+
+// Lets commands that still report plain strings use `?` on file helpers
+impl From<FsError> for String {
+    fn from(e: FsError) -> Self {
+        e.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    File,
+    Dir,
+    Symlink,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct TreeNode {
+    pub name: String,
+    // Relative to the workspace root, always with forward slashes
+    pub path: String,
+    pub kind: EntryKind,
+    pub size: u64,
+    pub mtime: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNode>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct WorkspaceTree {
+    pub root: String,
+    pub entries: Vec<TreeNode>,
+    // Set when the entry cap was hit and the listing is incomplete
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LineKind {
+    Context,
+    Add,
+    Remove,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub content: String,
+}
+
+// Line numbers are 1-based, matching unified diff headers
+#[derive(Debug, Serialize, Type)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct FileDiff {
+    pub path: String,
+    pub hunks: Vec<DiffHunk>,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+// A hunk whose original lines no longer appear in the file
+#[derive(Debug, Serialize, Type)]
+pub struct PatchConflict {
+    pub hunk: usize,
+    pub old_start: usize,
+    pub expected: Vec<String>,
+    pub actual: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct PatchResult {
+    pub applied: bool,
+    pub backup: Option<String>,
+    pub conflicts: Vec<PatchConflict>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct FileChunk {
+    pub content: String,
+    pub offset: u64,
+    // Pass back as `offset` to read the following chunk
+    pub next_offset: u64,
+    pub size: u64,
+    pub eof: bool,
+}
+
+// Resolves `..` and `.` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+// Resolves a workspace-relative (or absolute) path and refuses anything that
+// ends up outside the workspace, whether by name or through a symlink. The path
+// itself may not exist yet, but its parent directory must.
+pub fn resolve(app: &AppHandle, path: &str) -> Result<PathBuf, FsError> {
+    let root = workspace::root(app)?;
+    let joined = normalize(&root.join(path));
+    if !joined.starts_with(&root) {
+        return Err(FsError::OutsideScope {
+            path: path.to_string(),
+        });
+    }
+
+    let resolved = match joined.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let (Some(parent), Some(name)) = (joined.parent(), joined.file_name()) else {
+                return Err(FsError::Invalid {
+                    reason: format!("Invalid path: {}", path),
+                });
+            };
+            let parent = parent.canonicalize().map_err(|e| FsError::io(path, e))?;
+            // A dangling symlink would be followed by a later write
+            match fs::read_link(parent.join(name)) {
+                Ok(target) => normalize(&parent.join(target)),
+                Err(_) => parent.join(name),
+            }
+        }
+        Err(e) => return Err(FsError::io(path, e)),
+    };
+
+    if !resolved.starts_with(&root) {
+        return Err(FsError::SymlinkEscapesScope {
+            path: path.to_string(),
+            target: resolved.to_string_lossy().into_owned(),
+        });
+    }
+    Ok(resolved)
+}
+
+// Gitignore-aware walker over `root`, sorted by name. `globs` whitelist
+// matching files, directories are always walked.
+pub fn walker(root: &Path, globs: Option<Vec<String>>) -> Result<WalkBuilder, FsError> {
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in globs.unwrap_or_default() {
+        overrides.add(&glob).map_err(|e| FsError::Invalid {
+            reason: format!("Invalid glob {}: {}", glob, e),
+        })?;
+    }
+    let overrides = overrides.build().map_err(|e| FsError::Invalid {
+        reason: format!("Invalid globs: {}", e),
+    })?;
+
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .overrides(overrides)
+        .sort_by_file_name(|a, b| a.cmp(b));
+    Ok(builder)
+}
+
+// Walks the workspace honoring .gitignore (and .ignore / global excludes). The
+// walk runs off the main thread, as large repos take a while.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_workspace_tree(
+    app: AppHandle,
+    depth: Option<usize>,
+    globs: Option<Vec<String>>,
+) -> Result<WorkspaceTree, FsError> {
+    let root = workspace::root(&app)?;
+    let path = root.to_string_lossy().into_owned();
+    tauri::async_runtime::spawn_blocking(move || walk_tree(root, depth, globs))
+        .await
+        .map_err(|e| FsError::Io {
+            path,
+            reason: e.to_string(),
+        })?
+}
+
+fn walk_tree(
+    root: PathBuf,
+    depth: Option<usize>,
+    globs: Option<Vec<String>>,
+) -> Result<WorkspaceTree, FsError> {
+    let walker = walker(&root, globs)?
+        .max_depth(Some(depth.unwrap_or(DEFAULT_TREE_DEPTH)))
+        .build();
+
+    // Nodes are collected flat, then attached to their parents deepest first
+    let mut nodes: Vec<(usize, PathBuf, TreeNode)> = Vec::new();
+    let mut truncated = false;
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::debug!("Skipping unreadable entry: {}", e);
+                continue;
+            }
+        };
+        if entry.depth() == 0 {
+            continue;
+        }
+        if nodes.len() == MAX_TREE_ENTRIES {
+            truncated = true;
+            break;
+        }
+
+        let metadata = entry.path().symlink_metadata().ok();
+        let kind = match entry.file_type() {
+            Some(file_type) if file_type.is_symlink() => EntryKind::Symlink,
+            Some(file_type) if file_type.is_dir() => EntryKind::Dir,
+            _ => EntryKind::File,
+        };
+        let relative = entry
+            .path()
+            .strip_prefix(&root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        nodes.push((
+            entry.depth(),
+            entry.path().to_path_buf(),
+            TreeNode {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                path: relative,
+                kind,
+                size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+                mtime: metadata
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_secs()),
+                children: vec![],
+            },
+        ));
+    }
+
+    // Stable sort keeps the walker's name order among siblings
+    nodes.sort_by_key(|node| std::cmp::Reverse(node.0));
+    let mut children: HashMap<PathBuf, Vec<TreeNode>> = HashMap::new();
+    for (_, path, mut node) in nodes {
+        if let Some(mut own) = children.remove(&path) {
+            own.reverse();
+            node.children = own;
+        }
+        let parent = path.parent().map(PathBuf::from).unwrap_or_default();
+        children.entry(parent).or_default().push(node);
+    }
+    let mut entries = children.remove(&root).unwrap_or_default();
+    entries.reverse();
+
+    Ok(WorkspaceTree {
+        root: root.to_string_lossy().into_owned(),
+        entries,
+        truncated,
+    })
+}
+
+fn read_text(path: &Path, display: &str) -> Result<String, FsError> {
+    let metadata = fs::metadata(path).map_err(|e| FsError::io(display, e))?;
+    if metadata.len() > MAX_READ_BYTES {
+        return Err(FsError::TooLarge {
+            path: display.to_string(),
+            size: metadata.len(),
+            limit: MAX_READ_BYTES,
+        });
+    }
+    let bytes = fs::read(path).map_err(|e| FsError::io(display, e))?;
+    if bytes.contains(&0) {
+        return Err(FsError::Binary {
+            path: display.to_string(),
+        });
+    }
+    String::from_utf8(bytes).map_err(|_| FsError::Binary {
+        path: display.to_string(),
+    })
+}
+
+// Writes next to the target and renames over it, so readers never see a
+// half-written file
+pub(crate) fn write_atomic(path: &Path, content: impl AsRef<[u8]>) -> io::Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".{}.mix-tmp", name));
+    fs::write(&temp, content)?;
+    if let Ok(metadata) = fs::metadata(path) {
+        fs::set_permissions(&temp, metadata.permissions())?;
+    }
+    fs::rename(&temp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp);
+    })
+}
+
+fn app_data_subdir(app: &AppHandle, name: &str) -> Result<PathBuf, FsError> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| FsError::Io {
+            path: name.to_string(),
+            reason: e.to_string(),
+        })?
+        .join(name))
+}
+
+pub(crate) fn backup_dir(app: &AppHandle) -> Result<PathBuf, FsError> {
+    app_data_subdir(app, BACKUP_DIR_NAME)
+}
+
+// Copies the file into `dir`, keyed by its workspace path and time
+fn backup(dir: &Path, path: &Path, relative: &str) -> Result<PathBuf, FsError> {
+    fs::create_dir_all(&dir).map_err(|e| FsError::io(&dir.to_string_lossy(), e))?;
+
+    let name = format!(
+        "{}.{}",
+        relative.replace(['/', '\\'], "__"),
+        chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+    );
+    let backup = dir.join(name);
+    fs::copy(path, &backup).map_err(|e| FsError::io(relative, e))?;
+    Ok(backup)
+}
+
+fn find_conflicts(content: &str, patch: &Patch<'_, str>) -> Vec<PatchConflict> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    patch
+        .hunks()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, hunk)| {
+            let expected: Vec<&str> = hunk
+                .lines()
+                .iter()
+                .filter_map(|line| match line {
+                    Line::Context(text) | Line::Delete(text) => Some(*text),
+                    Line::Insert(_) => None,
+                })
+                .collect();
+            if expected.is_empty() || lines.windows(expected.len()).any(|w| w == expected) {
+                return None;
+            }
+
+            let start = hunk.old_range().start().saturating_sub(1).min(lines.len());
+            let end = (start + expected.len()).min(lines.len());
+            Some(PatchConflict {
+                hunk: index + 1,
+                old_start: hunk.old_range().start(),
+                expected: expected.iter().map(|line| line.to_string()).collect(),
+                actual: lines[start..end]
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+// Tauri runs sync commands on the main thread, so file IO runs on a blocking
+// thread; `path` names the file in the error should that thread fail
+pub(crate) async fn blocking<T: Send + 'static>(
+    path: &str,
+    work: impl FnOnce() -> Result<T, FsError> + Send + 'static,
+) -> Result<T, FsError> {
+    let path = path.to_string();
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| FsError::Io {
+            path,
+            reason: e.to_string(),
+        })?
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn read_file(app: AppHandle, path: String) -> Result<String, FsError> {
+    blocking(&path.clone(), move || {
+        let resolved = resolve(&app, &path)?;
+        read_text(&resolved, &path)
+    })
+    .await
+}
+
+// Pages through files too large for read_file. Chunks end on a line break where
+// possible, and never split a UTF-8 character.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_file_chunk(
+    app: AppHandle,
+    path: String,
+    offset: Option<u64>,
+    max_bytes: Option<u64>,
+) -> Result<FileChunk, FsError> {
+    blocking(&path.clone(), move || {
+        read_chunk(app, path, offset, max_bytes)
+    })
+    .await
+}
+
+fn read_chunk(
+    app: AppHandle,
+    path: String,
+    offset: Option<u64>,
+    max_bytes: Option<u64>,
+) -> Result<FileChunk, FsError> {
+    let resolved = resolve(&app, &path)?;
+    let offset = offset.unwrap_or(0);
+    let max_bytes = max_bytes
+        .unwrap_or(DEFAULT_CHUNK_BYTES)
+        .clamp(1, MAX_CHUNK_BYTES);
+
+    let mut file = File::open(&resolved).map_err(|e| FsError::io(&path, e))?;
+    let size = file.metadata().map_err(|e| FsError::io(&path, e))?.len();
+    if offset > size {
+        return Err(FsError::Invalid {
+            reason: format!(
+                "Offset {} is past the end of {} ({} bytes)",
+                offset, path, size
+            ),
+        });
+    }
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| FsError::io(&path, e))?;
+    let mut bytes = Vec::new();
+    file.take(max_bytes)
+        .read_to_end(&mut bytes)
+        .map_err(|e| FsError::io(&path, e))?;
+    if bytes.contains(&0) {
+        return Err(FsError::Binary { path });
+    }
+
+    let eof = offset + bytes.len() as u64 >= size;
+    if !eof {
+        if let Some(newline) = bytes.iter().rposition(|&b| b == b'\n') {
+            bytes.truncate(newline + 1);
+        }
+    }
+    let content = match String::from_utf8(bytes) {
+        Ok(content) => content,
+        Err(e) => {
+            // Only a character cut off at the end of the chunk is acceptable
+            let error = e.utf8_error();
+            if error.error_len().is_some() || error.valid_up_to() == 0 || eof {
+                return Err(FsError::Binary { path });
+            }
+            let mut bytes = e.into_bytes();
+            bytes.truncate(error.valid_up_to());
+            String::from_utf8(bytes).map_err(|_| FsError::Binary { path: path.clone() })?
+        }
+    };
+
+    let next_offset = offset + content.len() as u64;
+    Ok(FileChunk {
+        content,
+        offset,
+        next_offset,
+        size,
+        eof: next_offset >= size,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn write_file(
+    app: AppHandle,
+    path: String,
+    content: String,
+    session_id: Option<String>,
+) -> Result<(), FsError> {
+    blocking(&path.clone(), move || write(app, path, content, session_id)).await
+}
+
+fn write(
+    app: AppHandle,
+    path: String,
+    content: String,
+    session_id: Option<String>,
+) -> Result<(), FsError> {
+    let resolved = resolve(&app, &path)?;
+    // Agent edits keep the previous content so they can be undone
+    let backup = match &session_id {
+        Some(_) if resolved.exists() => Some(backup(&backup_dir(&app)?, &resolved, &path)?),
+        _ => None,
+    };
+    write_atomic(&resolved, &content).map_err(|e| FsError::io(&path, e))?;
+    log::info!("Wrote {}", resolved.display());
+    if let Some(session_id) = session_id {
+        edits::record(
+            &app,
+            &session_id,
+            EditKind::Write,
+            &path,
+            resolved,
+            backup,
+            content.as_bytes(),
+        );
+    }
+    Ok(())
+}
+
+// Previews an edit against what is on disk, a missing file diffs as empty
+#[tauri::command]
+#[specta::specta]
+pub async fn diff_file(
+    app: AppHandle,
+    path: String,
+    new_content: String,
+) -> Result<FileDiff, FsError> {
+    blocking(&path.clone(), move || diff(app, path, new_content)).await
+}
+
+fn diff(app: AppHandle, path: String, new_content: String) -> Result<FileDiff, FsError> {
+    let resolved = resolve(&app, &path)?;
+    let old_content = if resolved.exists() {
+        read_text(&resolved, &path)?
+    } else {
+        String::new()
+    };
+
+    let diff = TextDiff::from_lines(&old_content, &new_content);
+    let mut additions = 0;
+    let mut deletions = 0;
+    let hunks = diff
+        .grouped_ops(DIFF_CONTEXT_LINES)
+        .iter()
+        .filter_map(|group| {
+            let (first, last) = (group.first()?, group.last()?);
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+
+            let lines = group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| {
+                    let kind = match change.tag() {
+                        ChangeTag::Equal => LineKind::Context,
+                        ChangeTag::Insert => {
+                            additions += 1;
+                            LineKind::Add
+                        }
+                        ChangeTag::Delete => {
+                            deletions += 1;
+                            LineKind::Remove
+                        }
+                    };
+                    DiffLine {
+                        kind,
+                        content: change.value().to_string(),
+                    }
+                })
+                .collect();
+
+            Some(DiffHunk {
+                old_start: old_range.start + 1,
+                old_lines: old_range.len(),
+                new_start: new_range.start + 1,
+                new_lines: new_range.len(),
+                lines,
+            })
+        })
+        .collect();
+
+    Ok(FileDiff {
+        path,
+        hunks,
+        additions,
+        deletions,
+    })
+}
+
+// Applies a unified diff only if every hunk still matches the file, otherwise
+// the file is left untouched and the drifted hunks are reported
+#[tauri::command]
+#[specta::specta]
+pub async fn apply_patch(
+    app: AppHandle,
+    path: String,
+    unified_diff: String,
+    session_id: Option<String>,
+) -> Result<PatchResult, FsError> {
+    blocking(&path.clone(), move || {
+        patch(app, path, unified_diff, session_id)
+    })
+    .await
+}
+
+fn patch(
+    app: AppHandle,
+    path: String,
+    unified_diff: String,
+    session_id: Option<String>,
+) -> Result<PatchResult, FsError> {
+    let resolved = resolve(&app, &path)?;
+    let exists = resolved.exists();
+    let current = if exists {
+        read_text(&resolved, &path)?
+    } else {
+        String::new()
+    };
+
+    let patch = Patch::from_str(&unified_diff).map_err(|e| FsError::Invalid {
+        reason: format!("Invalid patch: {}", e),
+    })?;
+    let patched = match diffy::apply(&current, &patch) {
+        Ok(patched) => patched,
+        Err(e) => {
+            log::warn!("Patch for {} does not apply: {}", path, e);
+            let conflicts = find_conflicts(&current, &patch);
+            if conflicts.is_empty() {
+                // Every hunk matches on its own but they overlap or are out of order
+                return Err(FsError::Invalid {
+                    reason: format!("Failed to apply patch to {}: {}", path, e),
+                });
+            }
+            return Ok(PatchResult {
+                applied: false,
+                backup: None,
+                conflicts,
+            });
+        }
+    };
+
+    let backup = if exists {
+        let dir = match session_id {
+            Some(_) => backup_dir(&app)?,
+            None => app_data_subdir(&app, KEPT_BACKUP_DIR_NAME)?,
+        };
+        Some(backup(&dir, &resolved, &path)?)
+    } else {
+        None
+    };
+    write_atomic(&resolved, &patched).map_err(|e| FsError::io(&path, e))?;
+    log::info!("Applied patch to {}", resolved.display());
+
+    let backup_path = backup
+        .as_ref()
+        .map(|backup| backup.to_string_lossy().into_owned());
+    if let Some(session_id) = session_id {
+        edits::record(
+            &app,
+            &session_id,
+            EditKind::Patch,
+            &path,
+            resolved,
+            backup,
+            patched.as_bytes(),
+        );
+    }
+    Ok(PatchResult {
+        applied: true,
+        backup: backup_path,
+        conflicts: vec![],
+    })
+}
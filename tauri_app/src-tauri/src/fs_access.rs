@@ -0,0 +1,113 @@
+use crate::error::AppError;
+use crate::i18n;
+use crate::settings::SettingsManager;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_fs::FsExt;
+
+fn normalize(path: &str) -> Result<PathBuf, String> {
+    let path = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", path, e))?;
+    if !path.is_dir() {
+        return Err(format!("{} is not a directory", path.display()));
+    }
+    Ok(path)
+}
+
+// Re-applies the directories the user approved in earlier sessions, along with
+// the active workspace
+pub fn restore_grants(app: &AppHandle) {
+    let settings = app.state::<SettingsManager>().get();
+    for grant in settings.fs_grants.into_iter().chain(settings.workspace) {
+        if let Err(e) = app.fs_scope().allow_directory(&grant, true) {
+            log::warn!("Failed to restore fs grant for {}: {}", grant, e);
+        }
+    }
+}
+
+async fn confirm(app: &AppHandle, path: &Path) -> bool {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(i18n::t_args(
+            app,
+            "fs_access.message",
+            &[("path", &path.display().to_string())],
+        ))
+        .title(i18n::t(app, "fs_access.title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::t(app, "fs_access.allow"),
+            i18n::t(app, "fs_access.deny"),
+        ))
+        .show(move |allowed| {
+            let _ = tx.send(allowed);
+        });
+    rx.await.unwrap_or(false)
+}
+
+// Adds `path` to the fs scope once the user confirmed it, now or in an earlier
+// session. None when they declined. Every runtime grant goes through here,
+// including switching workspaces.
+pub async fn grant(app: &AppHandle, path: &str) -> Result<Option<PathBuf>, String> {
+    let settings_manager = app.state::<SettingsManager>();
+    let path = normalize(path)?;
+    let grant = path.to_string_lossy().into_owned();
+
+    if !settings_manager.get().fs_grants.contains(&grant) {
+        if !confirm(app, &path).await {
+            log::info!("Folder access denied for {}", grant);
+            return Ok(None);
+        }
+        settings_manager.update(|settings| settings.fs_grants.push(grant.clone()))?;
+    }
+
+    app.fs_scope()
+        .allow_directory(&path, true)
+        .map_err(|e| format!("Failed to grant access to {}: {}", grant, e))?;
+    log::info!("Folder access granted for {}", grant);
+    Ok(Some(path))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn request_fs_access(app: AppHandle, path: String) -> Result<bool, AppError> {
+    Ok(grant(&app, &path).await?.is_some())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_fs_grants(settings_manager: State<'_, SettingsManager>) -> Vec<String> {
+    settings_manager.get().fs_grants
+}
+
+// The runtime scope can't drop an allowed pattern, so revoking forbids the
+// directory for the rest of the session and the grant is gone after restart
+#[tauri::command]
+#[specta::specta]
+pub fn revoke_fs_grant(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    path: String,
+) -> Result<(), AppError> {
+    // Grants are stored canonicalized; a folder that is gone since is matched
+    // as given, without a trailing separator
+    let grant = Path::new(&path)
+        .canonicalize()
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.trim_end_matches(['/', '\\']).to_string());
+    if !settings_manager.get().fs_grants.contains(&grant) {
+        return Err(AppError::InvalidInput(format!(
+            "No folder access was granted for {}",
+            path
+        )));
+    }
+
+    settings_manager.update(|settings| settings.fs_grants.retain(|stored| *stored != grant))?;
+    app.fs_scope()
+        .forbid_directory(&grant, true)
+        .map_err(|e| format!("Failed to revoke access to {}: {}", grant, e))?;
+    log::info!("Folder access revoked for {}", grant);
+    Ok(())
+}
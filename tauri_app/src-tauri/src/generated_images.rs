@@ -0,0 +1,209 @@
+use crate::error::AppError;
+use crate::platform::{Native, Platform};
+use crate::{scratch, validation};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+// Saved images go to `<Pictures>/Mix/<date>/`
+const OUTPUT_DIR_NAME: &str = "Mix";
+// Message id -> the images saved from it, in the app data dir
+const INDEX_FILE_NAME: &str = "generated_images.json";
+const MAX_IMAGE_BYTES: usize = 100 * 1024 * 1024;
+
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+// Where an image the agent returned comes from
+#[derive(Debug, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImageSource {
+    // A file a tool wrote, in a scratch dir or a granted folder
+    Path { path: String },
+    // Base64, bare or as a `data:image/...;base64,` URL
+    Data { data: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SavedImage {
+    pub message_id: String,
+    pub path: String,
+    pub saved_at: String,
+}
+
+fn output_root(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .picture_dir()
+        .or_else(|_| app.path().app_data_dir())
+        .map_err(|e| format!("Failed to resolve the pictures dir: {}", e))?;
+    Ok(dir.join(OUTPUT_DIR_NAME))
+}
+
+fn index_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?;
+    Ok(dir.join(INDEX_FILE_NAME))
+}
+
+fn read_index(app: &AppHandle) -> Result<HashMap<String, Vec<SavedImage>>, String> {
+    match fs::read_to_string(index_path(app)?) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", INDEX_FILE_NAME, e)),
+        Err(_) => Ok(HashMap::new()),
+    }
+}
+
+fn record(app: &AppHandle, image: &SavedImage) -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let mut index = read_index(app)?;
+    index
+        .entry(image.message_id.clone())
+        .or_default()
+        .push(image.clone());
+    let path = index_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(&index)
+        .map_err(|e| format!("Failed to serialize {}: {}", INDEX_FILE_NAME, e))?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to save {}: {}", INDEX_FILE_NAME, e))
+}
+
+fn read_source(app: &AppHandle, source: &ImageSource) -> Result<Vec<u8>, AppError> {
+    let bytes = match source {
+        ImageSource::Path { path } => {
+            let resolved = Path::new(path)
+                .canonicalize()
+                .map_err(|e| AppError::InvalidField {
+                    field: "source.path".to_string(),
+                    message: format!("{}: {}", path, e),
+                })?;
+            if !scratch::contains(app, &resolved) {
+                validation::existing_path(app, "source.path", path)?;
+            }
+            fs::read(&resolved)?
+        }
+        ImageSource::Data { data } => {
+            let encoded = match data.split_once(";base64,") {
+                Some((_, encoded)) => encoded,
+                None => data.as_str(),
+            };
+            STANDARD
+                .decode(encoded.trim())
+                .map_err(|e| AppError::InvalidField {
+                    field: "source.data".to_string(),
+                    message: format!("is not valid base64: {}", e),
+                })?
+        }
+    };
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(AppError::InvalidInput(format!(
+            "Images over {} MB can't be saved",
+            MAX_IMAGE_BYTES / (1024 * 1024)
+        )));
+    }
+    Ok(bytes)
+}
+
+// Only files under the output folder, which this module wrote, are revealed
+// or set as wallpaper
+fn saved_path(app: &AppHandle, path: &str) -> Result<PathBuf, AppError> {
+    let root = output_root(app)?;
+    let resolved = Path::new(path)
+        .canonicalize()
+        .map_err(|e| AppError::InvalidField {
+            field: "path".to_string(),
+            message: format!("{}: {}", path, e),
+        })?;
+    match root.canonicalize() {
+        Ok(root) if resolved.starts_with(&root) => Ok(resolved),
+        _ => Err(AppError::PermissionDenied(format!(
+            "{} is not a saved image",
+            resolved.display()
+        ))),
+    }
+}
+
+// Copies or decodes the image into today's output folder and records it on
+// the message
+#[tauri::command]
+#[specta::specta]
+pub fn save_generated_image(
+    app: AppHandle,
+    message_id: String,
+    source: ImageSource,
+) -> Result<SavedImage, AppError> {
+    validation::uuid("messageId", &message_id)?;
+    let bytes = read_source(&app, &source)?;
+    let format = image::guess_format(&bytes).map_err(|_| {
+        AppError::InvalidInput("The source is not a known image format".to_string())
+    })?;
+    let extension = format.extensions_str().first().copied().unwrap_or("img");
+
+    let now = chrono::Local::now();
+    let dir = output_root(&app)?.join(now.format("%Y-%m-%d").to_string());
+    fs::create_dir_all(&dir)?;
+    let stem = format!("{}-{}", now.format("%H%M%S"), &message_id[..8]);
+    let mut path = dir.join(format!("{}.{}", stem, extension));
+    let mut copy = 1;
+    while path.exists() {
+        copy += 1;
+        path = dir.join(format!("{}-{}.{}", stem, copy, extension));
+    }
+    fs::write(&path, bytes)?;
+
+    let image = SavedImage {
+        message_id,
+        path: path.to_string_lossy().into_owned(),
+        saved_at: now.to_rfc3339(),
+    };
+    record(&app, &image)?;
+    log::info!("Saved generated image to {}", image.path);
+    Ok(image)
+}
+
+// Images saved from `message_id`, oldest first; none if the index can't be read
+pub fn message_images(app: &AppHandle, message_id: &str) -> Vec<SavedImage> {
+    let _guard = INDEX_LOCK.lock().unwrap();
+    read_index(app)
+        .ok()
+        .and_then(|mut index| index.remove(message_id))
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_message_images(app: AppHandle, message_id: String) -> Result<Vec<SavedImage>, AppError> {
+    validation::uuid("messageId", &message_id)?;
+    let _guard = INDEX_LOCK.lock().unwrap();
+    Ok(read_index(&app)?.remove(&message_id).unwrap_or_default())
+}
+
+// Opens Finder or Explorer with the image selected
+#[tauri::command]
+#[specta::specta]
+pub fn reveal_image(app: AppHandle, path: String) -> Result<(), AppError> {
+    let path = saved_path(&app, &path)?;
+    app.opener()
+        .reveal_item_in_dir(&path)
+        .map_err(|e| AppError::Io(format!("Failed to reveal {}: {}", path.display(), e)))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_wallpaper(app: AppHandle, path: String) -> Result<(), AppError> {
+    let path = saved_path(&app, &path)?;
+    tauri::async_runtime::spawn_blocking(move || Native::set_wallpaper(&path))
+        .await
+        .map_err(|e| format!("Failed to set wallpaper: {}", e))??;
+    Ok(())
+}
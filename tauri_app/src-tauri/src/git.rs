@@ -0,0 +1,233 @@
+use crate::error::AppError;
+use crate::{files, workspace};
+use git2::{DiffFormat, DiffOptions, ErrorCode, Repository, Status, StatusOptions};
+use serde::Serialize;
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+#[derive(Debug, Serialize, Type)]
+pub struct GitFileStatus {
+    // Relative to the repository root
+    pub path: String,
+    // Change staged in the index, if any
+    pub index: Option<&'static str>,
+    // Unstaged change in the working tree, if any
+    pub worktree: Option<&'static str>,
+}
+
+fn open_repo(app: &AppHandle) -> Result<Repository, String> {
+    let root = workspace::root(app)?;
+    Repository::discover(&root)
+        .map_err(|e| format!("Failed to open git repository at {}: {}", root.display(), e))
+}
+
+fn workdir(repo: &Repository) -> Result<PathBuf, String> {
+    repo.workdir()
+        .ok_or_else(|| "Bare repositories are not supported".to_string())?
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve repository root: {}", e))
+}
+
+// Maps a workspace path onto the repository-relative path git2 expects
+fn repo_path(app: &AppHandle, workdir: &Path, path: &str) -> Result<PathBuf, String> {
+    let resolved = files::resolve(app, path)?;
+    resolved
+        .strip_prefix(workdir)
+        .map(Path::to_path_buf)
+        .map_err(|_| format!("{} is outside the repository", path))
+}
+
+fn index_change(status: Status) -> Option<&'static str> {
+    if status.is_index_new() {
+        Some("new")
+    } else if status.is_index_modified() {
+        Some("modified")
+    } else if status.is_index_deleted() {
+        Some("deleted")
+    } else if status.is_index_renamed() {
+        Some("renamed")
+    } else if status.is_index_typechange() {
+        Some("typechange")
+    } else {
+        None
+    }
+}
+
+fn worktree_change(status: Status) -> Option<&'static str> {
+    if status.is_conflicted() {
+        Some("conflicted")
+    } else if status.is_wt_new() {
+        Some("untracked")
+    } else if status.is_wt_modified() {
+        Some("modified")
+    } else if status.is_wt_deleted() {
+        Some("deleted")
+    } else if status.is_wt_renamed() {
+        Some("renamed")
+    } else if status.is_wt_typechange() {
+        Some("typechange")
+    } else {
+        None
+    }
+}
+
+// Runs off the main thread, as scanning a large worktree takes a while
+#[tauri::command]
+#[specta::specta]
+pub async fn git_status(app: AppHandle) -> Result<Vec<GitFileStatus>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || status(&app))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to read git status: {}", e)))?
+}
+
+fn status(app: &AppHandle) -> Result<Vec<GitFileStatus>, AppError> {
+    let repo = open_repo(app)?;
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true);
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(|e| format!("Failed to read git status: {}", e))?;
+
+    Ok(statuses
+        .iter()
+        .filter_map(|entry| {
+            let status = entry.status();
+            let (index, worktree) = (index_change(status), worktree_change(status));
+            if index.is_none() && worktree.is_none() {
+                return None;
+            }
+            Some(GitFileStatus {
+                path: entry.path()?.to_string(),
+                index,
+                worktree,
+            })
+        })
+        .collect())
+}
+
+// None when HEAD is detached
+#[tauri::command]
+#[specta::specta]
+pub fn git_current_branch(app: AppHandle) -> Result<Option<String>, AppError> {
+    let repo = open_repo(&app)?;
+    let head = match repo.head() {
+        Ok(head) => head,
+        // A fresh repository has no commits yet, but HEAD still names a branch
+        Err(e) if e.code() == ErrorCode::UnbornBranch => {
+            let head = repo
+                .find_reference("HEAD")
+                .map_err(|e| format!("Failed to read HEAD: {}", e))?;
+            return Ok(head
+                .symbolic_target()
+                .map(|target| target.trim_start_matches("refs/heads/").to_string()));
+        }
+        Err(e) => return Err(format!("Failed to read HEAD: {}", e).into()),
+    };
+    if !head.is_branch() {
+        return Ok(None);
+    }
+    Ok(head.shorthand().map(str::to_string))
+}
+
+// Staged and unstaged changes against HEAD as a unified diff
+#[tauri::command]
+#[specta::specta]
+pub fn git_diff(app: AppHandle, path: Option<String>) -> Result<String, AppError> {
+    let repo = open_repo(&app)?;
+    let mut options = DiffOptions::new();
+    options.include_untracked(true).show_untracked_content(true);
+    if let Some(path) = path {
+        options.pathspec(repo_path(&app, &workdir(&repo)?, &path)?);
+    }
+
+    let head_tree = match repo.head() {
+        Ok(head) => Some(
+            head.peel_to_tree()
+                .map_err(|e| format!("Failed to read HEAD tree: {}", e))?,
+        ),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(format!("Failed to read HEAD: {}", e).into()),
+    };
+    let diff = repo
+        .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))
+        .map_err(|e| format!("Failed to compute diff: {}", e))?;
+
+    let mut patch = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })
+    .map_err(|e| format!("Failed to format diff: {}", e))?;
+    Ok(patch)
+}
+
+// Stages exactly `paths` (deleted files are removed from the index) and commits
+// them on the current branch, returning the new commit id
+#[tauri::command]
+#[specta::specta]
+pub fn git_commit(app: AppHandle, message: String, paths: Vec<String>) -> Result<String, AppError> {
+    if message.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Commit message is empty".to_string(),
+        ));
+    }
+    if paths.is_empty() {
+        return Err(AppError::InvalidInput("No paths to commit".to_string()));
+    }
+
+    let repo = open_repo(&app)?;
+    let workdir = workdir(&repo)?;
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to open git index: {}", e))?;
+    for path in &paths {
+        let relative = repo_path(&app, &workdir, path)?;
+        let staged = if workdir.join(&relative).exists() {
+            index.add_path(&relative)
+        } else {
+            index.remove_path(&relative)
+        };
+        staged.map_err(|e| format!("Failed to stage {}: {}", path, e))?;
+    }
+    index
+        .write()
+        .map_err(|e| format!("Failed to write git index: {}", e))?;
+
+    let tree_id = index
+        .write_tree()
+        .map_err(|e| format!("Failed to write tree: {}", e))?;
+    let tree = repo
+        .find_tree(tree_id)
+        .map_err(|e| format!("Failed to read tree: {}", e))?;
+    let signature = repo
+        .signature()
+        .map_err(|e| format!("Failed to read git identity (user.name/user.email): {}", e))?;
+    let parent = match repo.head() {
+        Ok(head) => Some(
+            head.peel_to_commit()
+                .map_err(|e| format!("Failed to read HEAD commit: {}", e))?,
+        ),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(format!("Failed to read HEAD: {}", e).into()),
+    };
+
+    let commit_id = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            parent.iter().collect::<Vec<_>>().as_slice(),
+        )
+        .map_err(|e| format!("Failed to commit: {}", e))?;
+    log::info!("Committed {} file(s) as {}", paths.len(), commit_id);
+    Ok(commit_id.to_string())
+}
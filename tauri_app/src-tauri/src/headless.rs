@@ -0,0 +1,214 @@
+use crate::providers::{self, Provider};
+use crate::settings::SettingsManager;
+use crate::sidecar::{PromptChunk, SidecarManager, SidecarState, SIDECAR_CHUNK_EVENT};
+use crate::{native_messaging, warmup};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+
+// Launch modes without a window:
+//   mix --prompt "text" [--session <id>] [--json]
+//     One-shot for scripts and cron, no window or tray. Tool calls go to
+//     stderr and the reply to stdout; `--json` prints every chunk and the
+//     result as JSON lines instead.
+//   mix --daemon [--no-tray]
+//     Keeps the sidecar and the local APIs running, e.g. on a headless Mac.
+pub const EXIT_PROMPT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_SIDECAR_FAILED: i32 = 3;
+
+const USAGE: &str =
+    "Usage: mix --prompt <text> [--session <id>] [--json]\n       mix --daemon [--no-tray]";
+// How often the daemon checks that the sidecar is still up
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub enum LaunchMode {
+    Window,
+    Prompt(HeadlessArgs),
+    Daemon { tray: bool },
+    // Started by a browser for the extension, see `native_messaging`
+    NativeHost,
+}
+
+#[derive(Debug, Clone)]
+pub struct HeadlessArgs {
+    pub prompt: String,
+    pub session: Option<String>,
+    pub json: bool,
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<LaunchMode, String> {
+    let args: Vec<String> = args.into_iter().collect();
+    if native_messaging::is_host_launch(&args) {
+        return Ok(LaunchMode::NativeHost);
+    }
+    let mut args = args.into_iter().skip(1);
+    let mut prompt = None;
+    let mut session = None;
+    let mut json = false;
+    let mut one_shot = false;
+    let mut daemon = false;
+    let mut tray = true;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--prompt" => {
+                one_shot = true;
+                prompt = args.next();
+            }
+            "--session" => session = args.next(),
+            "--json" => json = true,
+            "--daemon" => daemon = true,
+            "--no-tray" => tray = false,
+            // Anything else belongs to the platform (e.g. macOS -psn_ args)
+            _ => {}
+        }
+    }
+
+    match (one_shot, daemon) {
+        (true, true) => Err(format!(
+            "--prompt and --daemon can't be combined\n{}",
+            USAGE
+        )),
+        (true, false) => match prompt {
+            Some(prompt) if !prompt.trim().is_empty() => Ok(LaunchMode::Prompt(HeadlessArgs {
+                prompt,
+                session,
+                json,
+            })),
+            _ => Err(format!("--prompt needs a non-empty value\n{}", USAGE)),
+        },
+        (false, true) => Ok(LaunchMode::Daemon { tray }),
+        (false, false) => Ok(LaunchMode::Window),
+    }
+}
+
+fn print_chunk(args: &HeadlessArgs, chunk: &PromptChunk) {
+    if args.json {
+        println!("{}", json!({ "type": "chunk", "chunk": chunk }));
+    } else if chunk.kind == "tool" {
+        eprintln!("[tool] {}", chunk.content);
+    }
+}
+
+async fn prompt(app: &AppHandle, args: &HeadlessArgs) -> Result<String, (i32, String)> {
+    let session_id = args.session.clone().unwrap_or_default();
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    log::info!("[trace {}] Headless prompt", trace_id);
+
+    // Sessions routed to Ollama never need the sidecar
+    if let Provider::Ollama { model } =
+        providers::provider(&app.state::<SettingsManager>(), &session_id)
+    {
+        return providers::send(
+            app,
+            &session_id,
+            Provider::Ollama { model },
+            &args.prompt,
+            &trace_id,
+        )
+        .await
+        .map_err(|e| (EXIT_PROMPT_FAILED, e.to_string()));
+    }
+
+    let manager = app.state::<Arc<SidecarManager>>().inner().clone();
+    manager.start_sidecar(app).await.map_err(|e| {
+        (
+            EXIT_SIDECAR_FAILED,
+            format!("Failed to start sidecar: {}", e),
+        )
+    })?;
+
+    let printer = args.clone();
+    let listener = app.listen(SIDECAR_CHUNK_EVENT, move |event| {
+        if let Ok(chunk) = serde_json::from_str::<PromptChunk>(event.payload()) {
+            print_chunk(&printer, &chunk);
+        }
+    });
+    let result = manager
+        .stream_prompt(app, &session_id, &args.prompt, &trace_id)
+        .await;
+    app.unlisten(listener);
+    if let Err(e) = manager.stop_sidecar().await {
+        log::warn!("Failed to stop sidecar after headless prompt: {}", e);
+    }
+    result.map_err(|e| (EXIT_PROMPT_FAILED, e.to_string()))
+}
+
+// Runs the prompt and exits the app with its outcome
+pub fn start(app: &AppHandle, args: HeadlessArgs) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let code = match prompt(&app, &args).await {
+            Ok(text) => {
+                if args.json {
+                    println!("{}", json!({ "type": "result", "ok": true, "text": text }));
+                } else {
+                    println!("{}", text);
+                }
+                0
+            }
+            Err((code, e)) => {
+                if args.json {
+                    println!("{}", json!({ "type": "result", "ok": false, "error": e }));
+                } else {
+                    eprintln!("{}", e);
+                }
+                code
+            }
+        };
+        app.exit(code);
+    });
+}
+
+// Starts the sidecar and restarts it whenever it goes down. The local APIs are
+// started by the regular setup, as configured in settings.
+pub fn start_daemon(app: &AppHandle) {
+    let settings = app.state::<SettingsManager>().get();
+    if !settings.openai_api_enabled
+        && !settings.mcp_server_enabled
+        && !settings.companion_api_enabled
+        && !settings.webhooks_enabled
+    {
+        log::warn!("Daemon started with all local APIs disabled");
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let manager = app.state::<Arc<SidecarManager>>().inner().clone();
+        let mut status = manager.subscribe();
+        loop {
+            if !manager.is_running() {
+                match manager.start_sidecar(&app).await {
+                    Ok(()) => {
+                        log::info!("Daemon started the sidecar");
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            warmup::run(&app, warmup::Profile::Daemon).await;
+                        });
+                    }
+                    Err(e) => log::error!("Daemon failed to start the sidecar: {}", e),
+                }
+            }
+            // Restarts right away when a running sidecar exits; failed starts
+            // wait for the next interval
+            let running = manager.is_running();
+            tokio::select! {
+                _ = tokio::time::sleep(SUPERVISE_INTERVAL) => {}
+                _ = async {
+                    let _ = status.wait_for(|status| status.state != SidecarState::Running).await;
+                }, if running => {}
+                _ = tokio::signal::ctrl_c() => {
+                    log::info!("Daemon shutting down");
+                    if let Err(e) = manager.stop_sidecar().await {
+                        log::warn!("Failed to stop sidecar on shutdown: {}", e);
+                    }
+                    app.exit(0);
+                    return;
+                }
+            }
+        }
+    });
+}
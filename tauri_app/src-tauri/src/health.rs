@@ -0,0 +1,88 @@
+use crate::error::AppError;
+use crate::sidecar::{HealthCheck, SidecarManager, SidecarState};
+use serde::Serialize;
+use specta::Type;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::Disks;
+use tauri::{AppHandle, Manager, State};
+use tokio::net::TcpStream;
+
+const REACHABILITY_ADDR: &str = "1.1.1.1:443";
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Type)]
+pub struct SidecarHealth {
+    pub running: bool,
+    pub error: Option<String>,
+    pub last_health_check: Option<HealthCheck>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct DiskHealth {
+    pub data_dir: String,
+    pub available_bytes: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct HealthReport {
+    pub sidecar: SidecarHealth,
+    pub disk: Option<DiskHealth>,
+    pub network_reachable: bool,
+    // Prompts waiting for the sidecar to finish starting
+    pub pending_queue_depth: usize,
+}
+
+// Picks the disk whose mount point is the longest prefix of the data dir
+fn disk_health(data_dir: &Path) -> Option<DiskHealth> {
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .iter()
+        .filter(|disk| data_dir.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| DiskHealth {
+            data_dir: data_dir.to_string_lossy().into_owned(),
+            available_bytes: disk.available_space(),
+            total_bytes: disk.total_space(),
+        })
+}
+
+async fn network_reachable() -> bool {
+    matches!(
+        tokio::time::timeout(REACHABILITY_TIMEOUT, TcpStream::connect(REACHABILITY_ADDR)).await,
+        Ok(Ok(_))
+    )
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_health_report(
+    app: AppHandle,
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+) -> Result<HealthReport, AppError> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?;
+
+    // Listing disks blocks
+    let disk = tauri::async_runtime::spawn_blocking(move || disk_health(&data_dir))
+        .await
+        .map_err(|e| format!("Failed to check disk health: {}", e))?;
+
+    // One snapshot, so the fields agree with each other
+    let status = sidecar_manager.status();
+    Ok(HealthReport {
+        sidecar: SidecarHealth {
+            // Also catches a sidecar killed from outside the app
+            running: status.state == SidecarState::Running && sidecar_manager.is_running(),
+            error: status.error,
+            last_health_check: status.last_health_check,
+        },
+        disk,
+        network_reachable: network_reachable().await,
+        pending_queue_depth: sidecar_manager.pending_prompts(),
+    })
+}
@@ -0,0 +1,223 @@
+use crate::code_blocks;
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::LazyLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+// Carries the highlighted code blocks of a finished streamed reply
+pub const CODE_HIGHLIGHTED_EVENT: &str = "code://highlighted";
+
+// Larger inputs are returned escaped but unstyled; highlighting is roughly
+// linear but slow enough per byte to stall a reply
+const MAX_HIGHLIGHT_BYTES: usize = 512 * 1024;
+// What the app's light and dark themes map to
+const LIGHT_THEME: &str = "InspiredGitHub";
+const DARK_THEME: &str = "base16-ocean.dark";
+
+// Loading the bundled syntaxes and themes takes a moment, so it happens on
+// first use
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEMES: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightFormat {
+    // `<span style="...">` runs, to be placed inside a `<pre>`
+    #[default]
+    Html,
+    // 24-bit terminal escapes, for output shown in a terminal
+    Ansi,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct HighlightedCode {
+    // Syntax used, "Plain Text" when the language wasn't recognised
+    pub language: String,
+    pub theme: String,
+    pub output: String,
+    // The theme's background as #rrggbb, for the surrounding block
+    pub background: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct HighlightedBlock {
+    // Index of the block in the reply, as `get_code_blocks` numbers them
+    pub index: u32,
+    pub code: HighlightedCode,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CodeHighlighted {
+    pub trace_id: String,
+    pub blocks: Vec<HighlightedBlock>,
+}
+
+// "light" and "dark" follow the app theme; any bundled theme name works too
+fn theme(name: Option<&str>) -> Result<(&'static str, &'static Theme), AppError> {
+    let name = match name.unwrap_or("dark") {
+        "light" => LIGHT_THEME,
+        "dark" => DARK_THEME,
+        name => name,
+    };
+    THEMES
+        .themes
+        .get_key_value(name)
+        .map(|(name, theme)| (name.as_str(), theme))
+        .ok_or_else(|| AppError::InvalidField {
+            field: "theme".to_string(),
+            message: format!(
+                "must be light, dark or one of {}",
+                THEMES.themes.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        })
+}
+
+// Matches a language name or file extension, e.g. "Rust", "rs" or "tsx"
+fn syntax(language: Option<&str>) -> &'static SyntaxReference {
+    language
+        .and_then(|language| SYNTAXES.find_syntax_by_token(language))
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text())
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn highlight(
+    code: &str,
+    language: Option<&str>,
+    theme_name: Option<&str>,
+    format: HighlightFormat,
+) -> Result<HighlightedCode, AppError> {
+    let (theme_name, theme) = theme(theme_name)?;
+    let syntax = syntax(language);
+    let background = theme
+        .settings
+        .background
+        .map(|color| format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b));
+
+    let output = if code.len() > MAX_HIGHLIGHT_BYTES {
+        match format {
+            HighlightFormat::Html => escape_html(code),
+            HighlightFormat::Ansi => code.to_string(),
+        }
+    } else {
+        let mut highlighter = HighlightLines::new(syntax, theme);
+        let mut output = String::with_capacity(code.len() * 2);
+        for line in LinesWithEndings::from(code) {
+            let ranges = highlighter
+                .highlight_line(line, &SYNTAXES)
+                .map_err(|e| format!("Failed to highlight: {}", e))?;
+            match format {
+                HighlightFormat::Html => output.push_str(
+                    &styled_line_to_highlighted_html(&ranges, IncludeBackground::No)
+                        .map_err(|e| format!("Failed to render highlighting: {}", e))?,
+                ),
+                HighlightFormat::Ansi => {
+                    output.push_str(&as_24_bit_terminal_escaped(&ranges, false))
+                }
+            }
+        }
+        if let HighlightFormat::Ansi = format {
+            output.push_str("\x1b[0m");
+        }
+        output
+    };
+
+    Ok(HighlightedCode {
+        language: syntax.name.clone(),
+        theme: theme_name.to_string(),
+        output,
+        background,
+    })
+}
+
+// Highlights the code blocks of a finished reply off the async runtime and
+// emits them, so the webview can swap them in without highlighting itself
+pub fn prehighlight(app: &AppHandle, trace_id: &str, text: &str) {
+    let blocks = code_blocks::extract(text);
+    if blocks.is_empty() {
+        return;
+    }
+    let theme = app.state::<SettingsManager>().get().highlight_theme;
+    let app = app.clone();
+    let trace_id = trace_id.to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        let blocks = blocks
+            .into_iter()
+            .filter_map(|block| {
+                match highlight(
+                    &block.code,
+                    block.language.as_deref(),
+                    theme.as_deref(),
+                    HighlightFormat::Html,
+                ) {
+                    Ok(code) => Some(HighlightedBlock {
+                        index: block.index,
+                        code,
+                    }),
+                    Err(e) => {
+                        log::warn!(
+                            "[trace {}] Failed to highlight a code block: {}",
+                            trace_id,
+                            e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+        let _ = app.emit(CODE_HIGHLIGHTED_EVENT, CodeHighlighted { trace_id, blocks });
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn highlight_code(
+    code: String,
+    lang: Option<String>,
+    theme: Option<String>,
+    format: Option<HighlightFormat>,
+) -> Result<HighlightedCode, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        highlight(
+            &code,
+            lang.as_deref(),
+            theme.as_deref(),
+            format.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to highlight: {}", e))?
+}
+
+// The webview passes its own theme, "light" or "dark", whenever it changes,
+// so pre-highlighted replies match it
+#[tauri::command]
+#[specta::specta]
+pub fn set_highlight_theme(
+    settings_manager: State<'_, SettingsManager>,
+    theme: String,
+) -> Result<(), AppError> {
+    self::theme(Some(&theme))?;
+    settings_manager.update(|settings| settings.highlight_theme = Some(theme))?;
+    Ok(())
+}
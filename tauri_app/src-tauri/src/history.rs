@@ -0,0 +1,69 @@
+use crate::error::AppError;
+use crate::secrets;
+use crate::settings::SettingsManager;
+use crate::sidecar::SidecarManager;
+use rand::RngCore;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+// Keychain entry and sidecar environment variable holding the conversation
+// database key. The sidecar migrates a plaintext database on first use.
+const DB_KEY_SECRET: &str = "MIX_DB_KEY";
+// AES-256-XTS takes two 256-bit keys
+const DB_KEY_LEN: usize = 64;
+
+fn generate_key() -> String {
+    let mut key = [0u8; DB_KEY_LEN];
+    rand::rng().fill_bytes(&mut key);
+    key.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn sidecar_env(app: &AppHandle) -> Vec<(String, String)> {
+    if !app.state::<SettingsManager>().get().encrypt_history {
+        return vec![];
+    }
+
+    match secrets::get_secret(DB_KEY_SECRET) {
+        Ok(Some(key)) => vec![(DB_KEY_SECRET.to_string(), key)],
+        Ok(None) => {
+            log::error!("History encryption is enabled but no key is stored in the keychain");
+            vec![]
+        }
+        Err(e) => {
+            log::error!("{}", e);
+            vec![]
+        }
+    }
+}
+
+// Encryption can't be turned off again: without the key the database is unreadable
+#[tauri::command]
+#[specta::specta]
+pub async fn enable_history_encryption(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+) -> Result<(), AppError> {
+    if settings_manager.get().encrypt_history {
+        return Ok(());
+    }
+
+    if secrets::get_secret(DB_KEY_SECRET)?.is_none() {
+        secrets::set_secret(DB_KEY_SECRET.to_string(), generate_key())?;
+    }
+    settings_manager.update(|settings| settings.encrypt_history = true)?;
+    log::info!("History encryption enabled");
+
+    // The sidecar only picks up the key at launch, where it encrypts the existing database
+    if sidecar_manager.is_running() {
+        sidecar_manager.stop_sidecar().await?;
+        sidecar_manager.start_sidecar(&app).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_history_encryption(settings_manager: State<'_, SettingsManager>) -> bool {
+    settings_manager.get().encrypt_history
+}
@@ -0,0 +1,21 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Pooled like the sidecar's client (see `SidecarManager::http`), but for
+// outside services, so it goes through the system proxy. Callers set their
+// own per-request timeout.
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .unwrap_or_else(|e| {
+            log::error!("Failed to build HTTP client: {}", e);
+            reqwest::Client::new()
+        })
+});
+
+pub fn shared() -> &'static reqwest::Client {
+    &CLIENT
+}
@@ -0,0 +1,184 @@
+use crate::error::AppError;
+use crate::logging::LOG_VIEWER_LABEL;
+use crate::platform::{Native, Platform};
+use crate::recents;
+use crate::settings::SettingsManager;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+pub const LOCALE_CHANGED_EVENT: &str = "locale://changed";
+
+// Strings are looked up here and then in English, so a bundle that lags
+// behind still shows every label
+const FALLBACK: &str = "en";
+
+// Flat key -> string maps; `{name}` marks where an argument goes
+const SOURCES: [(&str, &str); 5] = [
+    ("en", include_str!("../resources/locales/en.json")),
+    ("de", include_str!("../resources/locales/de.json")),
+    ("es", include_str!("../resources/locales/es.json")),
+    ("fr", include_str!("../resources/locales/fr.json")),
+    ("ja", include_str!("../resources/locales/ja.json")),
+];
+
+static BUNDLES: LazyLock<Vec<(&str, HashMap<String, String>)>> = LazyLock::new(|| {
+    SOURCES
+        .iter()
+        .map(|(locale, source)| (*locale, serde_json::from_str(source).unwrap()))
+        .collect()
+});
+
+// Read once, as the OS only applies a new language to apps started after it
+static SYSTEM_LANGUAGES: LazyLock<Vec<String>> = LazyLock::new(Native::preferred_languages);
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LocaleOption {
+    pub locale: String,
+    // The language's name in itself, e.g. "Deutsch"
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LocaleInfo {
+    // What native surfaces are shown in right now
+    pub active: String,
+    // The user's choice; following the OS when unset
+    pub selected: Option<String>,
+    pub system: Vec<String>,
+    pub available: Vec<LocaleOption>,
+}
+
+fn bundle(locale: &str) -> Option<&'static HashMap<String, String>> {
+    BUNDLES
+        .iter()
+        .find(|(known, _)| *known == locale)
+        .map(|(_, strings)| strings)
+}
+
+// The bundle for a BCP 47 tag, by its language alone, so "de-AT" and "fr_CA"
+// find "de" and "fr"
+fn supported(tag: &str) -> Option<&'static str> {
+    let language = tag.split(['-', '_']).next().unwrap_or_default();
+    SOURCES
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| locale.eq_ignore_ascii_case(language))
+}
+
+fn resolve(selected: Option<&str>) -> &'static str {
+    selected
+        .into_iter()
+        .chain(SYSTEM_LANGUAGES.iter().map(String::as_str))
+        .find_map(supported)
+        .unwrap_or(FALLBACK)
+}
+
+// The locale native strings are shown in. Early in setup, before the settings
+// are loaded, that's the OS's.
+pub fn locale(app: &AppHandle) -> &'static str {
+    let selected = app
+        .try_state::<SettingsManager>()
+        .and_then(|settings| settings.get().locale);
+    resolve(selected.as_deref())
+}
+
+// `key` in the active locale, or the key itself when no bundle has it
+pub fn t(app: &AppHandle, key: &str) -> String {
+    t_args(app, key, &[])
+}
+
+pub fn t_args(app: &AppHandle, key: &str, args: &[(&str, &str)]) -> String {
+    let template = [locale(app), FALLBACK]
+        .into_iter()
+        .find_map(|locale| bundle(locale)?.get(key))
+        .map_or(key, String::as_str);
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+// Native surfaces that stay open get their labels again
+fn relabel(app: &AppHandle) {
+    recents::refresh_tray(app);
+    if let Some(window) = app.get_webview_window(LOG_VIEWER_LABEL) {
+        let _ = window.set_title(&t(app, "window.logs"));
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_locale(settings_manager: State<'_, SettingsManager>) -> LocaleInfo {
+    let selected = settings_manager.get().locale;
+    LocaleInfo {
+        active: resolve(selected.as_deref()).to_string(),
+        selected,
+        system: SYSTEM_LANGUAGES.clone(),
+        available: BUNDLES
+            .iter()
+            .map(|(locale, strings)| LocaleOption {
+                locale: locale.to_string(),
+                name: strings
+                    .get("language.name")
+                    .cloned()
+                    .unwrap_or_else(|| locale.to_string()),
+            })
+            .collect(),
+    }
+}
+
+// Picks the language of tray menus, dialogs and notifications, or goes back
+// to following the OS with `None`. Returns the locale now active.
+#[tauri::command]
+#[specta::specta]
+pub fn set_locale(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    locale: Option<String>,
+) -> Result<String, AppError> {
+    let locale = match locale {
+        Some(tag) => Some(
+            supported(tag.trim())
+                .ok_or_else(|| AppError::InvalidField {
+                    field: "locale".to_string(),
+                    message: format!(
+                        "must be one of {}",
+                        SOURCES.map(|(locale, _)| locale).join(", ")
+                    ),
+                })?
+                .to_string(),
+        ),
+        None => None,
+    };
+    settings_manager.update(|settings| settings.locale = locale.clone())?;
+
+    let active = resolve(locale.as_deref()).to_string();
+    relabel(&app);
+    let _ = app.emit(LOCALE_CHANGED_EVENT, &active);
+    Ok(active)
+}
+
+// A whole bundle, with English filling its gaps, so the webview can show the
+// same strings as the native side. The active locale's when none is given.
+#[tauri::command]
+#[specta::specta]
+pub fn get_translations(
+    app: AppHandle,
+    locale: Option<String>,
+) -> Result<HashMap<String, String>, AppError> {
+    let locale = match locale {
+        Some(tag) => supported(tag.trim()).ok_or_else(|| AppError::InvalidField {
+            field: "locale".to_string(),
+            message: format!("{:?} has no translations", tag),
+        })?,
+        None => self::locale(&app),
+    };
+    let mut strings = bundle(FALLBACK).cloned().unwrap_or_default();
+    if let Some(translated) = bundle(locale) {
+        strings.extend(translated.clone());
+    }
+    Ok(strings)
+}
@@ -0,0 +1,371 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::error::AppError;
+use crate::{scratch, validation};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// Edits write a new file into the session's scratch dir and leave the source
+// alone, so an attachment or generated image is never overwritten
+const MAX_SOURCE_BYTES: u64 = 100 * 1024 * 1024;
+const MAX_SIDE_PX: u32 = 16_384;
+const DEFAULT_JPEG_QUALITY: u8 = 85;
+// Compressing to `max_bytes` lowers JPEG quality to this, then scales down
+const MIN_JPEG_QUALITY: u8 = 40;
+const MAX_SHRINK_STEPS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Png,
+    Jpeg,
+    Webp,
+    Gif,
+    Bmp,
+    Tiff,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum Fit {
+    // Within the box, keeping the aspect ratio
+    #[default]
+    Contain,
+    // Filling the box, keeping the aspect ratio and cropping the overflow
+    Cover,
+    // Exactly the box, stretching if need be
+    Fill,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Type)]
+pub struct Crop {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+// Either side may be left out to follow the other's scale
+#[derive(Debug, Clone, Copy, Deserialize, Type)]
+pub struct Resize {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub fit: Fit,
+}
+
+// Applied in order: crop, resize, then encode
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct ImageEdit {
+    pub source: String,
+    pub crop: Option<Crop>,
+    pub resize: Option<Resize>,
+    // The source's format when left out
+    pub format: Option<OutputFormat>,
+    // JPEG quality, 1-100
+    pub quality: Option<u8>,
+    // Shrinks the output until it fits, lowering JPEG quality first
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct EditedImage {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub format: OutputFormat,
+    pub bytes: u64,
+}
+
+impl OutputFormat {
+    fn image_format(self) -> ImageFormat {
+        match self {
+            OutputFormat::Png => ImageFormat::Png,
+            OutputFormat::Jpeg => ImageFormat::Jpeg,
+            OutputFormat::Webp => ImageFormat::WebP,
+            OutputFormat::Gif => ImageFormat::Gif,
+            OutputFormat::Bmp => ImageFormat::Bmp,
+            OutputFormat::Tiff => ImageFormat::Tiff,
+        }
+    }
+
+    fn from_image_format(format: ImageFormat) -> Option<Self> {
+        match format {
+            ImageFormat::Png => Some(OutputFormat::Png),
+            ImageFormat::Jpeg => Some(OutputFormat::Jpeg),
+            ImageFormat::WebP => Some(OutputFormat::Webp),
+            ImageFormat::Gif => Some(OutputFormat::Gif),
+            ImageFormat::Bmp => Some(OutputFormat::Bmp),
+            ImageFormat::Tiff => Some(OutputFormat::Tiff),
+            _ => None,
+        }
+    }
+}
+
+fn invalid(field: &str, message: impl Into<String>) -> AppError {
+    AppError::InvalidField {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+// A file in a scratch dir or a folder the user granted, like the sources
+// `generated_images` saves from
+pub fn source_path(app: &AppHandle, path: &str) -> Result<PathBuf, AppError> {
+    let resolved = Path::new(path)
+        .canonicalize()
+        .map_err(|e| invalid("source", format!("{}: {}", path, e)))?;
+    if !scratch::contains(app, &resolved) {
+        validation::existing_path(app, "source", path)?;
+    }
+    Ok(resolved)
+}
+
+pub fn crop(image: DynamicImage, crop: Crop) -> Result<DynamicImage, AppError> {
+    let fits = crop.width > 0
+        && crop.height > 0
+        && crop.x.saturating_add(crop.width) <= image.width()
+        && crop.y.saturating_add(crop.height) <= image.height();
+    if !fits {
+        return Err(invalid(
+            "crop",
+            format!(
+                "must lie within the {}x{} image",
+                image.width(),
+                image.height()
+            ),
+        ));
+    }
+    Ok(image.crop_imm(crop.x, crop.y, crop.width, crop.height))
+}
+
+fn resize(image: DynamicImage, resize: Resize) -> Result<DynamicImage, AppError> {
+    let (width, height) = match (resize.width, resize.height) {
+        (Some(width), Some(height)) => (width, height),
+        (Some(width), None) => (
+            width,
+            (image.height() as u64 * width as u64 / image.width().max(1) as u64) as u32,
+        ),
+        (None, Some(height)) => (
+            (image.width() as u64 * height as u64 / image.height().max(1) as u64) as u32,
+            height,
+        ),
+        (None, None) => return Err(invalid("resize", "needs a width or a height")),
+    };
+    if !(1..=MAX_SIDE_PX).contains(&width) || !(1..=MAX_SIDE_PX).contains(&height) {
+        return Err(invalid(
+            "resize",
+            format!("sides must be between 1 and {} pixels", MAX_SIDE_PX),
+        ));
+    }
+    Ok(match resize.fit {
+        Fit::Contain => image.resize(width, height, FilterType::Lanczos3),
+        Fit::Cover => image.resize_to_fill(width, height, FilterType::Lanczos3),
+        Fit::Fill => image.resize_exact(width, height, FilterType::Lanczos3),
+    })
+}
+
+fn encode(image: &DynamicImage, format: OutputFormat, quality: u8) -> Result<Vec<u8>, AppError> {
+    let mut bytes = Vec::new();
+    let encoded = match format {
+        // JPEG has no alpha channel
+        OutputFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, quality)),
+        _ => image.write_to(&mut Cursor::new(&mut bytes), format.image_format()),
+    };
+    encoded.map_err(|e| AppError::InvalidInput(format!("Failed to encode the image: {}", e)))?;
+    Ok(bytes)
+}
+
+// Encodes, and when over `max_bytes` tries lower JPEG quality and then
+// smaller sizes until it fits
+fn compress(
+    mut image: DynamicImage,
+    format: OutputFormat,
+    quality: u8,
+    max_bytes: Option<u64>,
+) -> Result<(DynamicImage, Vec<u8>), AppError> {
+    let mut bytes = encode(&image, format, quality)?;
+    let Some(max_bytes) = max_bytes else {
+        return Ok((image, bytes));
+    };
+    let mut quality = quality;
+    for _ in 0..MAX_SHRINK_STEPS {
+        if bytes.len() as u64 <= max_bytes {
+            return Ok((image, bytes));
+        }
+        if format == OutputFormat::Jpeg && quality > MIN_JPEG_QUALITY {
+            quality = quality.saturating_sub(15).max(MIN_JPEG_QUALITY);
+        } else {
+            let (width, height) = (image.width() * 4 / 5, image.height() * 4 / 5);
+            if width == 0 || height == 0 {
+                break;
+            }
+            image = image.resize_exact(width, height, FilterType::Lanczos3);
+        }
+        bytes = encode(&image, format, quality)?;
+    }
+    if bytes.len() as u64 > max_bytes {
+        return Err(AppError::InvalidInput(format!(
+            "Couldn't get the image under {} bytes; the smallest was {}",
+            max_bytes,
+            bytes.len()
+        )));
+    }
+    Ok((image, bytes))
+}
+
+// `<stem>-<suffix>.<ext>`, numbered when taken
+pub fn output_path(dir: &Path, source: &Path, suffix: &str, format: OutputFormat) -> PathBuf {
+    let stem = source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "image".to_string());
+    let extension = format
+        .image_format()
+        .extensions_str()
+        .first()
+        .copied()
+        .unwrap_or("img");
+    let mut path = dir.join(format!("{}-{}.{}", stem, suffix, extension));
+    let mut copy = 1;
+    while path.exists() {
+        copy += 1;
+        path = dir.join(format!("{}-{}-{}.{}", stem, suffix, copy, extension));
+    }
+    path
+}
+
+fn apply(source: &Path, dir: &Path, edit: &ImageEdit) -> Result<EditedImage, AppError> {
+    if fs::metadata(source)?.len() > MAX_SOURCE_BYTES {
+        return Err(AppError::InvalidInput(format!(
+            "Images over {} MB can't be edited",
+            MAX_SOURCE_BYTES / (1024 * 1024)
+        )));
+    }
+    let reader = ImageReader::open(source)?
+        .with_guessed_format()
+        .map_err(|e| AppError::Io(format!("Failed to read {}: {}", source.display(), e)))?;
+    let source_format = reader.format().and_then(OutputFormat::from_image_format);
+    let mut image = reader.decode().map_err(|e| {
+        AppError::InvalidInput(format!("Failed to decode {}: {}", source.display(), e))
+    })?;
+
+    if let Some(area) = edit.crop {
+        image = crop(image, area)?;
+    }
+    if let Some(size) = edit.resize {
+        image = resize(image, size)?;
+    }
+    let format = edit.format.or(source_format).unwrap_or(OutputFormat::Png);
+    let quality = edit.quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(1, 100);
+    let (image, bytes) = compress(image, format, quality, edit.max_bytes)?;
+
+    let path = output_path(dir, source, "edited", format);
+    fs::write(&path, &bytes)?;
+    Ok(EditedImage {
+        path: path.to_string_lossy().into_owned(),
+        width: image.width(),
+        height: image.height(),
+        format,
+        bytes: bytes.len() as u64,
+    })
+}
+
+pub async fn edit(
+    app: &AppHandle,
+    session_id: Option<&str>,
+    edit: ImageEdit,
+) -> Result<EditedImage, AppError> {
+    let source = source_path(app, &edit.source)?;
+    let dir = scratch::dir(app, session_id)?;
+    let edited = tauri::async_runtime::spawn_blocking(move || apply(&source, &dir, &edit))
+        .await
+        .map_err(|e| AppError::Internal(format!("Image edit failed: {}", e)))??;
+    log::info!("Edited image written to {}", edited.path);
+    Ok(edited)
+}
+
+async fn edit_image_tool(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let session_id = arguments
+        .get("session_id")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let request: ImageEdit =
+        serde_json::from_value(arguments).map_err(|e| format!("Invalid arguments: {}", e))?;
+    progress.send(&format!("Editing {}", request.source));
+    let edited = edit(&app, session_id.as_deref(), request)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(format!(
+        "Wrote {} ({}x{}, {:?}, {} bytes)",
+        edited.path, edited.width, edited.height, edited.format, edited.bytes
+    ))
+}
+
+pub fn register_tools() {
+    let offset = json!({ "type": "integer", "minimum": 0 });
+    let side = json!({ "type": "integer", "minimum": 1, "maximum": MAX_SIDE_PX });
+    agent_tools::register(Tool {
+        name: "edit_image",
+        description: "Crop, resize, convert or compress an image file (an attachment, a \
+            generated image or one in the workspace) without sending it through a model. \
+            Steps run in the order crop, resize, encode. The result is written as a new file \
+            in the session's scratch folder and its path returned.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "source": { "type": "string", "description": "Absolute path of the image" },
+                "session_id": { "type": "string" },
+                "crop": {
+                    "type": "object",
+                    "properties": {
+                        "x": offset,
+                        "y": offset,
+                        "width": side,
+                        "height": side
+                    },
+                    "required": ["x", "y", "width", "height"]
+                },
+                "resize": {
+                    "type": "object",
+                    "properties": {
+                        "width": side,
+                        "height": side,
+                        "fit": { "type": "string", "enum": ["contain", "cover", "fill"] }
+                    }
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["png", "jpeg", "webp", "gif", "bmp", "tiff"]
+                },
+                "quality": { "type": "integer", "minimum": 1, "maximum": 100 },
+                "max_bytes": { "type": "integer", "minimum": 1 }
+            },
+            "required": ["source"]
+        }),
+        handler: agent_tools::handler(edit_image_tool),
+    });
+}
+
+// The same edits for the webview, e.g. to shrink an attachment before sending
+#[tauri::command]
+#[specta::specta]
+pub async fn edit_image(
+    app: AppHandle,
+    session_id: String,
+    edit: ImageEdit,
+) -> Result<EditedImage, AppError> {
+    self::edit(&app, Some(&session_id), edit).await
+}
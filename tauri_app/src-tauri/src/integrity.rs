@@ -0,0 +1,148 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const MANIFEST_PATH: &str = "resources/sidecar-manifest.json";
+pub const INTEGRITY_ERROR_EVENT: &str = "sidecar://integrity-error";
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IntegrityError {
+    ManifestMissing {
+        path: String,
+    },
+    ManifestInvalid {
+        reason: String,
+    },
+    NotInManifest {
+        binary: String,
+    },
+    Unreadable {
+        path: String,
+        reason: String,
+    },
+    Mismatch {
+        binary: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::ManifestMissing { path } => {
+                write!(f, "Sidecar manifest not found at {}", path)
+            }
+            IntegrityError::ManifestInvalid { reason } => {
+                write!(f, "Sidecar manifest is invalid: {}", reason)
+            }
+            IntegrityError::NotInManifest { binary } => {
+                write!(f, "Sidecar {} is not listed in the manifest", binary)
+            }
+            IntegrityError::Unreadable { path, reason } => {
+                write!(f, "Failed to read sidecar at {}: {}", path, reason)
+            }
+            IntegrityError::Mismatch {
+                binary,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Sidecar {} failed integrity check (expected {}, got {})",
+                binary, expected, actual
+            ),
+        }
+    }
+}
+
+// Tauri resolves sidecars next to the app executable
+pub fn sidecar_path() -> Result<PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to resolve app path: {}", e))?;
+    let dir = exe
+        .parent()
+        .ok_or_else(|| "App executable has no parent directory".to_string())?;
+    Ok(dir.join(if cfg!(windows) { "mix.exe" } else { "mix" }))
+}
+
+fn sha256(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// The manifest maps the sidecar file name (without extension) to its SHA-256,
+// it is written by build.rs from the binary the app is built with
+fn load_manifest(app: &AppHandle) -> Result<HashMap<String, String>, IntegrityError> {
+    let path = app
+        .path()
+        .resolve(MANIFEST_PATH, BaseDirectory::Resource)
+        .map_err(|e| IntegrityError::ManifestMissing {
+            path: format!("{} ({})", MANIFEST_PATH, e),
+        })?;
+    let contents = fs::read_to_string(&path).map_err(|_| IntegrityError::ManifestMissing {
+        path: path.to_string_lossy().into_owned(),
+    })?;
+    serde_json::from_str(&contents).map_err(|e| IntegrityError::ManifestInvalid {
+        reason: e.to_string(),
+    })
+}
+
+fn check(app: &AppHandle) -> Result<(), IntegrityError> {
+    let manifest = load_manifest(app)?;
+
+    let path = sidecar_path().map_err(|reason| IntegrityError::Unreadable {
+        path: String::new(),
+        reason,
+    })?;
+    let binary = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let expected = manifest
+        .get(&binary)
+        .ok_or_else(|| IntegrityError::NotInManifest {
+            binary: binary.clone(),
+        })?;
+
+    let actual = sha256(&path).map_err(|e| IntegrityError::Unreadable {
+        path: path.to_string_lossy().into_owned(),
+        reason: e.to_string(),
+    })?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(IntegrityError::Mismatch {
+            binary,
+            expected: expected.clone(),
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+// Verifies the sidecar against the bundled manifest before it is spawned.
+// Dev builds run a freshly compiled sidecar that usually isn't in the manifest,
+// so a missing entry is only fatal in release builds.
+pub fn verify_sidecar(app: &AppHandle) -> Result<(), String> {
+    match check(app) {
+        Ok(()) => Ok(()),
+        Err(
+            e @ (IntegrityError::ManifestMissing { .. } | IntegrityError::NotInManifest { .. }),
+        ) if cfg!(debug_assertions) => {
+            log::warn!("Skipping sidecar integrity check: {}", e);
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("{}", e);
+            let _ = app.emit(INTEGRITY_ERROR_EVENT, &e);
+            Err(e.to_string())
+        }
+    }
+}
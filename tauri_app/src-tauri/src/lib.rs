@@ -1,351 +1,577 @@
-// mod sidecar;
-// use sidecar::SidecarManager;
-
-use objc2_app_kit::{NSColor, NSWindow};
-use objc2::ffi::nil;
-use objc2::runtime::AnyObject;
-
-#[cfg(target_os = "macos")]
-use objc2_app_kit::{NSWorkspace, NSBitmapImageRep};
-#[cfg(target_os = "macos")]
-#[cfg(target_os = "macos")]
-use objc2::{msg_send, ClassType};
-#[cfg(target_os = "macos")]
-use std::ffi::CStr;
-#[cfg(target_os = "macos")]
-use base64::engine::general_purpose;
-#[cfg(target_os = "macos")]
-use base64::Engine;
+mod accessibility;
+mod actions;
+mod agent_protocol;
+mod agent_tools;
+mod app_lock;
+mod app_state;
+mod approvals;
+mod asset_protocol;
+mod audit;
+mod automation;
+mod bindings;
+mod calendar;
+mod clipboard_tool;
+mod coalesce;
+mod code_blocks;
+mod code_eval;
+mod companion_api;
+mod contacts;
+mod databases;
+mod debug;
+mod delivery;
+mod diagnostics;
+mod editors;
+mod edits;
+mod error;
+mod fetch_tool;
+mod files;
+mod fs_access;
+mod generated_images;
+mod git;
+mod headless;
+mod health;
+mod highlight;
+mod history;
+mod http_client;
+mod i18n;
+mod image_edit;
+mod integrity;
+mod link_preview;
+mod local_http;
+mod logging;
+mod mcp;
+mod mcp_server;
+mod metrics;
+mod native_messaging;
+mod oauth;
+mod ollama;
+mod onboarding;
+mod openai_api;
+mod panel;
+mod pdf_export;
+mod platform;
+mod previews;
+mod prompt_cache;
+mod providers;
+mod quick_switch;
+mod recents;
+mod recording;
+mod ring_buffer;
+mod scratch;
+mod screenshot;
+mod search;
+mod secrets;
+mod self_test;
+mod settings;
+mod share;
+mod shell_tool;
+mod snapshot;
+mod sounds;
+mod sidecar;
+#[cfg(feature = "grpc")]
+mod sidecar_grpc;
+mod startup;
+mod telemetry;
+mod text_extract;
+mod transcript;
+mod validation;
+mod vault;
+mod warmup;
+mod watcher;
+mod webhooks;
+mod web_search;
+mod webview_language;
+mod workspace;
+use error::{AppError, SetupError};
+use headless::LaunchMode;
+use platform::{AppInfo, Native, Platform};
+use settings::SettingsManager;
+use sidecar::{SidecarAuth, SidecarManager, SidecarTransport};
+use sounds::SoundEvent;
+use std::sync::Arc;
 
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
-use tauri::{Manager, TitleBarStyle, WebviewUrl, WebviewWindowBuilder};
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 
 #[cfg(desktop)]
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 
-#[cfg(target_os = "macos")]
-#[derive(serde::Serialize)]
-struct AppInfo {
-    name: String,
-    icon_png_base64: String,
+// Encoding every icon as PNG takes a while, so it runs off the async runtime
+#[tauri::command]
+#[specta::specta]
+async fn list_apps_with_icons() -> Result<Vec<AppInfo>, AppError> {
+    tauri::async_runtime::spawn_blocking(Native::running_apps)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to list apps: {}", e)))
+}
+
+#[derive(serde::Serialize, specta::Type)]
+struct PromptResponse {
+    trace_id: String,
+    text: String,
 }
 
-#[cfg(target_os = "macos")]
 #[tauri::command]
-async fn list_apps_with_icons() -> Result<Vec<AppInfo>, String> {
-    unsafe {
-        let workspace = NSWorkspace::sharedWorkspace();
-        let apps = workspace.runningApplications();
-        let mut result = Vec::with_capacity(apps.len());
-
-        for app in apps.iter() {
-            
-            // Check if app is visible (not background-only)
-            let is_hidden: bool = msg_send![&*app, isHidden];
-            let activation_policy: i64 = msg_send![&*app, activationPolicy];
-            
-            // Only include regular GUI apps (activation policy 0 = NSApplicationActivationPolicyRegular)
-            // Skip accessory apps (1) like browser plugins and prohibited apps (2) like background processes
-            if is_hidden || activation_policy != 0 {
-                continue;
-            }
+#[specta::specta]
+async fn start_sidecar(
+    app: AppHandle,
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+) -> Result<(), AppError> {
+    sidecar_manager.start_sidecar(&app).await
+}
 
-            // Get app name
-            let name_ns: *mut AnyObject = msg_send![&*app, localizedName];
-            if name_ns == nil {
-                continue;
-            }
-            let utf8_ptr: *const std::os::raw::c_char = msg_send![name_ns, UTF8String];
-            let cname = CStr::from_ptr(utf8_ptr)
-                .to_string_lossy()
-                .into_owned();
-
-            // Skip empty names
-            if cname.is_empty() {
-                continue;
-            }
+#[tauri::command]
+#[specta::specta]
+async fn stop_sidecar(sidecar_manager: State<'_, Arc<SidecarManager>>) -> Result<(), AppError> {
+    sidecar_manager.stop_sidecar().await
+}
 
-            // Get app icon
-            let icon: *mut AnyObject = msg_send![&*app, icon];
-            if icon == nil {
-                // Skip apps without icons
-                continue;
-            }
+#[tauri::command]
+#[specta::specta]
+fn sidecar_status(sidecar_manager: State<'_, Arc<SidecarManager>>) -> bool {
+    sidecar_manager.is_running()
+}
 
-            // Convert icon to PNG data
-            let tiff_data: *mut AnyObject = msg_send![icon, TIFFRepresentation];
-            if tiff_data == nil {
-                continue;
-            }
+#[tauri::command]
+#[specta::specta]
+async fn sidecar_health(sidecar_manager: State<'_, Arc<SidecarManager>>) -> Result<String, AppError> {
+    sidecar_manager.health_check().await
+}
 
-            // Create bitmap representation from TIFF data
-            let bitmap_rep: *mut AnyObject = msg_send![NSBitmapImageRep::class(), alloc];
-            let bitmap_rep: *mut AnyObject = msg_send![bitmap_rep, initWithData: tiff_data];
-            if bitmap_rep == nil {
-                continue;
-            }
+#[tauri::command]
+#[specta::specta]
+fn sidecar_error(sidecar_manager: State<'_, Arc<SidecarManager>>) -> Option<String> {
+    sidecar_manager.get_error()
+}
 
-            // Convert to PNG data (NSBitmapImageFileTypePNG = 4)
-            let png_data: *mut AnyObject = msg_send![bitmap_rep, representationUsingType: 4u64, properties: nil];
-            if png_data == nil {
-                continue;
-            }
+// Only handed out while we manage the sidecar; a standalone dev server has its own token
+#[tauri::command]
+#[specta::specta]
+fn get_sidecar_auth(sidecar_manager: State<'_, Arc<SidecarManager>>) -> Option<SidecarAuth> {
+    sidecar_manager
+        .is_running()
+        .then(|| sidecar_manager.auth.clone())
+}
 
-            // Extract bytes and base64-encode
-            let bytes: *const u8 = msg_send![png_data, bytes];
-            let len: usize = msg_send![png_data, length];
-            let slice = std::slice::from_raw_parts(bytes, len);
-            let b64 = general_purpose::STANDARD.encode(slice);
+// The webview calls the sidecar on this port, as it may have fallen back from
+// SIDECAR_PORT; None while it isn't running
+#[tauri::command]
+#[specta::specta]
+fn get_sidecar_port(sidecar_manager: State<'_, Arc<SidecarManager>>) -> Option<u16> {
+    sidecar_manager
+        .is_running()
+        .then(|| sidecar_manager.port())
+}
 
-            result.push(AppInfo {
-                name: cname,
-                icon_png_base64: b64,
-            });
+#[tauri::command]
+#[specta::specta]
+async fn send_prompt(app: AppHandle, prompt: String) -> Result<PromptResponse, AppError> {
+    validation::prompt("prompt", &prompt)?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let start = std::time::Instant::now();
+    let result = prompt_cache::send_prompt(&app, "", &prompt, &trace_id).await;
+    let elapsed_ms = start.elapsed().as_millis();
+    debug::annotate_prompt(&app, &trace_id, elapsed_ms, result.is_ok());
+
+    match result {
+        Ok(text) => {
+            log::info!("[trace {}] Prompt completed in {}ms", trace_id, elapsed_ms);
+            automation::record_response(&text);
+            sounds::play(&app, SoundEvent::PromptComplete);
+            Ok(PromptResponse { trace_id, text })
         }
+        Err(e) => {
+            log::error!("[trace {}] Prompt failed after {}ms: {}", trace_id, elapsed_ms, e);
+            sounds::play(&app, SoundEvent::PromptFailed);
+            Err(e.with_trace_id(&trace_id))
+        }
+    }
+}
 
-        Ok(result)
+#[tauri::command]
+#[specta::specta]
+async fn stream_prompt(
+    app: AppHandle,
+    session_id: String,
+    prompt: String,
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+) -> Result<PromptResponse, AppError> {
+    validation::uuid("sessionId", &session_id)?;
+    validation::prompt("prompt", &prompt)?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let start = std::time::Instant::now();
+    let result = sidecar_manager
+        .stream_prompt(&app, &session_id, &prompt, &trace_id)
+        .await;
+    let elapsed_ms = start.elapsed().as_millis();
+    debug::annotate_prompt(&app, &trace_id, elapsed_ms, result.is_ok());
+
+    match result {
+        Ok(text) => {
+            automation::record_response(&text);
+            highlight::prehighlight(&app, &trace_id, &text);
+            sounds::play(&app, SoundEvent::PromptComplete);
+            Ok(PromptResponse { trace_id, text })
+        }
+        Err(e) => {
+            log::error!("[trace {}] Streamed prompt failed after {}ms: {}", trace_id, elapsed_ms, e);
+            sounds::play(&app, SoundEvent::PromptFailed);
+            Err(e.with_trace_id(&trace_id))
+        }
     }
 }
 
-#[cfg(not(target_os = "macos"))]
-#[derive(serde::Serialize)]
-struct AppInfo {
-    name: String,
-    icon_png_base64: String,
+// Applies the next time the sidecar starts
+#[tauri::command]
+#[specta::specta]
+fn set_sidecar_transport(
+    settings_manager: State<'_, SettingsManager>,
+    transport: SidecarTransport,
+) -> Result<(), AppError> {
+    settings_manager.update(|settings| settings.sidecar_transport = transport)?;
+    Ok(())
+}
+
+// Applies the next time the sidecar starts
+#[tauri::command]
+#[specta::specta]
+fn set_sidecar_port_fallback(
+    settings_manager: State<'_, SettingsManager>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    settings_manager.update(|settings| settings.sidecar_port_fallback = enabled)?;
+    Ok(())
 }
 
-#[cfg(not(target_os = "macos"))]
 #[tauri::command]
-async fn list_apps_with_icons() -> Result<Vec<AppInfo>, String> {
-    // Return empty result on non-macOS platforms 
-    Ok(vec![])
+#[specta::specta]
+async fn cancel_prompt(
+    session_id: String,
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+) -> Result<(), AppError> {
+    validation::uuid("sessionId", &session_id)?;
+    sidecar_manager.cancel(&session_id).await
+}
+
+// Rebuilt whenever the recent projects change, see `recents::refresh_tray`
+pub(crate) fn tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let recent_submenu = recents::tray_submenu(app)?;
+    let quit_item =
+        MenuItem::with_id(app, "quit", i18n::t(app, "tray.quit"), true, None::<&str>)?;
+    let show_item =
+        MenuItem::with_id(app, "show", i18n::t(app, "tray.show"), true, None::<&str>)?;
+    let hide_item =
+        MenuItem::with_id(app, "hide", i18n::t(app, "tray.hide"), true, None::<&str>)?;
+    // let sidecar_status_item =
+    //     MenuItem::with_id(app, "sidecar_status", "Sidecar Status", true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[&recent_submenu, &show_item, &hide_item, &quit_item],
+    )
+}
+
+fn create_tray(app: &tauri::App) -> tauri::Result<()> {
+    let tray_menu = tray_menu(app.handle())?;
+
+    let builder = Native::style_tray(TrayIconBuilder::with_id(recents::TRAY_ID));
+    // Bundles always carry an icon; without one the tray item is still usable
+    let builder = match app.default_window_icon() {
+        Some(icon) => builder.icon(icon.clone()),
+        None => {
+            log::warn!("No default window icon, creating the tray icon without one");
+            builder
+        }
+    };
+    // The tooltip is also what VoiceOver and Narrator read out for the icon
+    builder
+        .tooltip("Mix")
+        .menu(&tray_menu)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "quit" => {
+                log::info!("Quit menu item clicked");
+                app.exit(0);
+            }
+            "show" => {
+                log::info!("Show menu item clicked");
+                panel::show(app);
+            }
+            "hide" => {
+                log::info!("Hide menu item clicked");
+                panel::hide(app);
+            }
+            id if id.starts_with(recents::RECENT_MENU_PREFIX) => {
+                recents::open_from_tray(app, id);
+            }
+            _ => {
+                log::info!("Unhandled menu item: {:?}", event.id);
+            }
+        })
+        .on_tray_icon_event(|tray, event| match event {
+            TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } => {
+                log::info!("Left click on tray icon");
+                panel::toggle(tray.app_handle());
+            }
+            TrayIconEvent::DoubleClick {
+                button: MouseButton::Left,
+                ..
+            } => {
+                log::info!("Double click on tray icon");
+                let app = tray.app_handle();
+                panel::show(app);
+            }
+            _ => {
+                log::debug!("Unhandled tray event: {:?}", event);
+            }
+        })
+        .build(app)?;
+    Ok(())
+}
+
+#[cfg(desktop)]
+fn register_toggle_shortcut(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    // Cmd+Shift+T on macOS, Ctrl+Shift+T on Windows/Linux
+    let toggle_shortcut = Native::toggle_shortcut();
+
+    app.handle().plugin(
+        tauri_plugin_global_shortcut::Builder::new().with_handler(move |_app, shortcut, event| {
+            if shortcut == &toggle_shortcut {
+                match event.state() {
+                    ShortcutState::Pressed => {
+                        log::info!("Global shortcut pressed - toggling window visibility");
+                        panel::toggle(_app);
+                    }
+                    ShortcutState::Released => {
+                        // Handle release if needed
+                    }
+                }
+            }
+        })
+        .build(),
+    )?;
+
+    app.global_shortcut().register(toggle_shortcut)?;
+    Ok(())
+}
+
+// Everything `run` sets up once logging is in place. Errors here are the ones
+// the app can't start without; the rest are logged and skipped.
+fn setup(app: &mut tauri::App, launch_mode: LaunchMode) -> Result<(), SetupError> {
+    let setup_start = std::time::Instant::now();
+
+    let phase_start = std::time::Instant::now();
+    app.manage(settings::SettingsManager::load(app.handle()).map_err(SetupError::Settings)?);
+    ring_buffer::apply(app.state::<settings::SettingsManager>().get().buffer_sizes);
+    automation::register(app.handle());
+    startup::record_phase("settings", phase_start);
+
+    // One-shot prompts skip the window, tray and background services
+    if let LaunchMode::Prompt(args) = launch_mode.clone() {
+        Native::run_in_background(app);
+        headless::start(app.handle(), args);
+        return Ok(());
+    }
+    if let LaunchMode::NativeHost = launch_mode {
+        Native::run_in_background(app);
+        native_messaging::start(app.handle());
+        return Ok(());
+    }
+
+    fs_access::restore_grants(app.handle());
+
+    // The window comes first so it shows while the rest starts up
+    let phase_start = std::time::Instant::now();
+    if !matches!(launch_mode, LaunchMode::Daemon { .. }) {
+        // Create the main window programmatically
+        let win_builder = WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
+            .title("")
+            .inner_size(500.0, 600.0)
+            .max_inner_size(500.0, 700.0)
+            .min_inner_size(500.0, 600.0);
+
+        let win_builder = webview_language::configure(app.handle(), win_builder);
+
+        let window = Native::style_window_builder(win_builder)
+            .build()
+            .map_err(SetupError::Window)?;
+        app_lock::watch(&window);
+        accessibility::watch(&window);
+        Native::style_window(&window);
+        webview_language::apply(&window);
+    }
+    startup::record_phase("window", phase_start);
+
+    // Command palette entries, see `actions`
+    actions::register_builtin();
+    panel::register_actions();
+    pdf_export::register_actions();
+    sidecar::register_actions();
+    webhooks::register_actions();
+
+    // Tools the sidecar's agent calls back into the app for, see `agent_tools`
+    shell_tool::register_tools();
+    fetch_tool::register_tools();
+    web_search::register_tools();
+    code_eval::register_tools();
+    databases::register_tools();
+    image_edit::register_tools();
+    text_extract::register_tools();
+    calendar::register_tools();
+    contacts::register_tools();
+    screenshot::register_tools();
+    clipboard_tool::register_tools();
+    vault::register_tools();
+
+    let phase_start = std::time::Instant::now();
+    metrics::start_snapshots();
+    telemetry::start(app.handle());
+    oauth::start_refresh(app.handle());
+    app_lock::start(app.handle());
+    accessibility::start(app.handle());
+    app_state::start(app.handle());
+    scratch::start(app.handle());
+    mcp_server::start(app.handle());
+    openai_api::start(app.handle());
+    companion_api::start(app.handle());
+    webhooks::start(app.handle());
+    startup::record_phase("services", phase_start);
+
+    if let LaunchMode::Daemon { tray } = launch_mode {
+        Native::run_in_background(app);
+        watcher::watch(app.handle());
+        headless::start_daemon(app.handle());
+        if !tray {
+            return Ok(());
+        }
+    } else {
+        startup::start(app.handle());
+    }
+
+    let _app_handle = app.handle().clone();
+    // let manager = sidecar_manager.clone();
+
+    // Set up cleanup handler for app shutdown
+    // let cleanup_manager = manager.clone();
+    // let cleanup_handle = app_handle.clone();
+    // app.listen("tauri://close-requested", move |_| {
+    //     let manager = cleanup_manager.clone();
+    //     let handle = cleanup_handle.clone();
+    //     tauri::async_runtime::spawn(async move {
+    //         if let Err(e) = manager.stop_sidecar(&handle).await {
+    //             log::error!("Failed to stop sidecar during cleanup: {}", e);
+    //         }
+    //     });
+    // });
+
+    // The window still works without a tray icon, so this isn't fatal
+    let phase_start = std::time::Instant::now();
+    if let Err(e) = create_tray(app) {
+        startup::warn(app.handle(), "tray", format!("Failed to create the tray icon: {}", e));
+    }
+    startup::record_phase("tray", phase_start);
+
+    // Another app may already own the shortcut; the tray still works
+    #[cfg(desktop)]
+    {
+        let phase_start = std::time::Instant::now();
+        match register_toggle_shortcut(app) {
+            Ok(()) => log::info!("Global shortcut registered: Cmd+Shift+T (macOS) / Ctrl+Shift+T (Windows/Linux)"),
+            Err(e) => startup::warn(app.handle(), "shortcut", format!("Failed to register the global shortcut: {}", e)),
+        }
+        startup::record_phase("shortcuts", phase_start);
+    }
+
+    startup::record_phase("setup", setup_start);
+    Ok(())
 }
 
-// #[tauri::command]
-// async fn start_sidecar(
-//     app: AppHandle,
-//     sidecar_manager: State<'_, Arc<SidecarManager>>,
-// ) -> Result<(), String> {
-//     sidecar_manager.start_sidecar(&app).await
-// }
-
-// #[tauri::command]
-// async fn stop_sidecar(
-//     app: AppHandle,
-//     sidecar_manager: State<'_, Arc<SidecarManager>>,
-// ) -> Result<(), String> {
-//     sidecar_manager.stop_sidecar(&app).await
-// }
-
-// #[tauri::command]
-// fn sidecar_status(sidecar_manager: State<'_, Arc<SidecarManager>>) -> bool {
-//     sidecar_manager.is_running()
-// }
-
-// #[tauri::command]
-// async fn sidecar_health(sidecar_manager: State<'_, Arc<SidecarManager>>) -> Result<String, String> {
-//     sidecar_manager.health_check().await
-// }
-
-// #[tauri::command]
-// fn sidecar_error(sidecar_manager: State<'_, Arc<SidecarManager>>) -> Option<String> {
-//     sidecar_manager.get_error()
-// }
-
-// #[tauri::command]
-// async fn send_prompt(
-//     prompt: String,
-//     sidecar_manager: State<'_, Arc<SidecarManager>>,
-// ) -> Result<String, String> {
-//     sidecar_manager.send_prompt(Option 1 is already implemented, but it does not work. Let's try option 2. But how to ensure that the list of apps can be fetched again after initialization? &prompt).await
-// }
+// One-shot prompts and the native messaging host have no UI to show a dialog
+// in, so they print the error; the app quits once the dialog is dismissed
+fn report_setup_error(app: &AppHandle, error: &SetupError, headless: bool) {
+    if headless {
+        eprintln!("{}", error);
+        app.exit(1);
+        return;
+    }
+    let handle = app.clone();
+    app.dialog()
+        .message(error.to_string())
+        .title(i18n::t(app, "setup_error.title"))
+        .kind(MessageDialogKind::Error)
+        .show(move |_| handle.exit(1));
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // let sidecar_manager = Arc::new(SidecarManager::new());
+    let launch_mode = match headless::parse_args(std::env::args()) {
+        Ok(mode) => mode,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(headless::EXIT_USAGE);
+        }
+    };
+    startup::mark_launch();
+    metrics::time_commands();
+    let phase_start = std::time::Instant::now();
+    let sidecar_manager = Arc::new(SidecarManager::new());
+    let bindings = bindings::builder();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_macos_permissions::init())
-        // .manage(sidecar_manager.clone())
+        .manage(sidecar_manager.clone())
+        .manage(panel::Panel::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![
-            list_apps_with_icons,
-            // start_sidecar,
-            // stop_sidecar,
-            // sidecar_status,
-            // sidecar_health,
-            // sidecar_error,
-            // send_prompt
-        ])
-        .setup(move |app| {
-            // Create the main window programmatically
-            let win_builder = WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
-                .title("")
-                .inner_size(500.0, 600.0)
-                .max_inner_size(500.0, 700.0)
-                .min_inner_size(500.0, 600.0);
-
-            // set transparent title bar only when building for macOS
-            #[cfg(target_os = "macos")]
-            let win_builder = win_builder.title_bar_style(TitleBarStyle::Transparent);
-
-            let window = win_builder.build().unwrap();
-
-            // set background color only when building for macOS
-            #[cfg(target_os = "macos")]
-            {
-                let ns_window = window.ns_window().unwrap();
-                unsafe {
-                    let bg_color = NSColor::colorWithRed_green_blue_alpha(23.0/ 255.0, 23.0/ 255.0, 23.0/ 255.0, 1.0);
-                    let ns_window_ref = &*(ns_window as *const NSWindow);
-                    ns_window_ref.setBackgroundColor(Some(&bg_color));
+        .plugin(tauri_plugin_notification::init())
+        .register_asynchronous_uri_scheme_protocol(
+            agent_protocol::SCHEME,
+            agent_protocol::protocol,
+        )
+        .register_asynchronous_uri_scheme_protocol(
+            asset_protocol::SCHEME,
+            asset_protocol::protocol,
+        )
+        .invoke_handler({
+            let handler = bindings.invoke_handler();
+            move |invoke| {
+                if app_lock::blocks(invoke.message.command()) {
+                    invoke
+                        .resolver
+                        .reject(AppError::PermissionDenied("The app is locked".to_string()));
+                    return true;
                 }
+                app_lock::record_activity();
+                handler(invoke)
             }
-
-            let _app_handle = app.handle().clone();
-            // let manager = sidecar_manager.clone();
-
-            // Clone for auto-start
-            // let startup_manager = manager.clone();
-
-            // Auto-start sidecar on app launch
-            // tauri::async_runtime::spawn(async move {
-            //     if let Err(e) = startup_manager.start_sidecar(&startup_handle).await {
-            //         eprintln!("Failed to auto-start sidecar: {}", e);
-            //     }
-            // });
-
-            // Set up cleanup handler for app shutdown
-            // let cleanup_manager = manager.clone();
-            // let cleanup_handle = app_handle.clone();
-            // app.listen("tauri://close-requested", move |_| {
-            //     let manager = cleanup_manager.clone();
-            //     let handle = cleanup_handle.clone();
-            //     tauri::async_runtime::spawn(async move {
-            //         if let Err(e) = manager.stop_sidecar(&handle).await {
-            //             eprintln!("Failed to stop sidecar during cleanup: {}", e);
-            //         }
-            //     });
-            // });
-
-            // Create system tray
-            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let show_item = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
-            let hide_item = MenuItem::with_id(app, "hide", "Hide", true, None::<&str>)?;
-            // let sidecar_status_item =
-            //     MenuItem::with_id(app, "sidecar_status", "Sidecar Status", true, None::<&str>)?;
-
-            let tray_menu = Menu::with_items(
-                app,
-                &[&show_item, &hide_item, &quit_item],
-            )?;
-
-            let _tray = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
-                .menu(&tray_menu)
-                .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "quit" => {
-                        println!("Quit menu item clicked");
-                        app.exit(0);
-                    }
-                    "show" => {
-                        println!("Show menu item clicked");
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    "hide" => {
-                        println!("Hide menu item clicked");
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.hide();
-                        }
-                    }
-                    _ => {
-                        println!("Unhandled menu item: {:?}", event.id);
-                    }
-                })
-                .on_tray_icon_event(|tray, event| match event {
-                    TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        button_state: MouseButtonState::Up,
-                        ..
-                    } => {
-                        println!("Left click on tray icon");
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            if window.is_visible().unwrap_or(false) {
-                                let _ = window.hide();
-                            } else {
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                            }
-                        }
-                    }
-                    TrayIconEvent::DoubleClick {
-                        button: MouseButton::Left,
-                        ..
-                    } => {
-                        println!("Double click on tray icon");
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    _ => {
-                        println!("Unhandled tray event: {:?}", event);
-                    }
-                })
-                .build(app)?;
-
-            // Register global shortcut for window toggle
-            #[cfg(desktop)]
-            {
-                // Use Cmd+Shift+T on macOS, Ctrl+Shift+T on Windows/Linux
-                #[cfg(target_os = "macos")]
-                let toggle_shortcut = Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyT);
-                
-                #[cfg(not(target_os = "macos"))]
-                let toggle_shortcut = Shortcut::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyT);
-
-                app.handle().plugin(
-                    tauri_plugin_global_shortcut::Builder::new().with_handler(move |_app, shortcut, event| {
-                        if shortcut == &toggle_shortcut {
-                            match event.state() {
-                                ShortcutState::Pressed => {
-                                    println!("Global shortcut pressed - toggling window visibility");
-                                    if let Some(window) = _app.get_webview_window("main") {
-                                        if window.is_visible().unwrap_or(false) {
-                                            let _ = window.hide();
-                                        } else {
-                                            let _ = window.show();
-                                            let _ = window.set_focus();
-                                        }
-                                    }
-                                }
-                                ShortcutState::Released => {
-                                    // Handle release if needed
-                                }
-                            }
-                        }
-                    })
-                    .build(),
-                )?;
-
-                app.global_shortcut().register(toggle_shortcut)?;
-                println!("Global shortcut registered: Cmd+Shift+T (macOS) / Ctrl+Shift+T (Windows/Linux)");
+        })
+        .setup(move |app| {
+            // Builder and plugin initialization, up to our own setup
+            startup::record_phase("plugins", phase_start);
+            let headless = matches!(launch_mode, LaunchMode::Prompt(_) | LaunchMode::NativeHost);
+            let result = logging::init(app.handle()).map_err(SetupError::Logging);
+            #[cfg(debug_assertions)]
+            bindings::export(&bindings);
+            // Returning the error would make Tauri panic, so report it instead
+            if let Err(e) = result.and_then(|()| setup(app, launch_mode)) {
+                log::error!("{}", e);
+                report_setup_error(app.handle(), &e, headless);
             }
-
             Ok(())
         })
         .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .unwrap_or_else(|e| {
+            eprintln!("Error while running the app: {}", e);
+            std::process::exit(1);
+        });
 }
@@ -0,0 +1,390 @@
+use crate::error::AppError;
+use crate::ring_buffer::RingBuffer;
+use regex::{Captures, Regex};
+use reqwest::header::{ACCEPT, CONTENT_TYPE, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use serde::Serialize;
+use specta::Type;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(8);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(4);
+const MAX_REDIRECTS: usize = 5;
+// Metadata lives in the head, so the rest of a large page is never read
+const MAX_HTML_BYTES: usize = 512 * 1024;
+const MAX_TITLE_CHARS: usize = 200;
+const MAX_DESCRIPTION_CHARS: usize = 300;
+const CACHED_PREVIEWS: usize = 200;
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+const USER_AGENT: &str = concat!("Mix/", env!("CARGO_PKG_VERSION"), " (link preview)");
+
+static CACHE: Mutex<RingBuffer<(String, Instant, LinkPreview)>> =
+    Mutex::new(RingBuffer::new("link_previews", CACHED_PREVIEWS));
+
+static TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<(meta|link)\s[^>]*>").unwrap());
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)([a-zA-Z_:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+});
+static ENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#[xX][0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap());
+
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct LinkPreview {
+    // Where the redirects ended, which the card links to
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    // Absolute og:image URL
+    pub image: Option<String>,
+    pub site_name: Option<String>,
+    pub favicon: Option<String>,
+}
+
+// Only addresses on the public internet are fetched, so a link in a reply
+// can't make the app probe the local network or cloud metadata endpoints
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || a == 0
+                || a >= 240
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && b & 0xc0 == 64)
+                // Benchmarking, 198.18.0.0/15
+                || (a == 198 && b & 0xfe == 18))
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            let embedded =
+                |high: u16, low: u16| Ipv4Addr::from((u32::from(high) << 16) | u32::from(low));
+            // Addresses that reach an IPv4 host are judged by that host: mapped,
+            // NAT64 (64:ff9b::/96) and 6to4 (2002::/16)
+            let ipv4 = match segments {
+                [0x64, 0xff9b, 0, 0, 0, 0, high, low] => Some(embedded(high, low)),
+                [0x2002, high, low, ..] => Some(embedded(high, low)),
+                _ => ip.to_ipv4_mapped(),
+            };
+            match ipv4 {
+                Some(ipv4) => is_public(IpAddr::V4(ipv4)),
+                None => {
+                    !(ip.is_loopback()
+                        || ip.is_unspecified()
+                        || ip.is_multicast()
+                        // Unique local, fc00::/7, and link-local, fe80::/10
+                        || segments[0] & 0xfe00 == 0xfc00
+                        || segments[0] & 0xffc0 == 0xfe80)
+                }
+            }
+        }
+    }
+}
+
+fn blocked(url: &Url) -> AppError {
+    AppError::PermissionDenied(format!("{} is not a public address", url))
+}
+
+// Resolves the host once and checks every address it has. The request is
+// pinned to the checked address, so a second lookup can't rebind it.
+async fn public_addr(url: &Url) -> Result<(String, SocketAddr), AppError> {
+    if !matches!(url.scheme(), "http" | "https") || !url.username().is_empty() {
+        return Err(AppError::InvalidField {
+            field: "url".to_string(),
+            message: "must be an http or https URL without credentials".to_string(),
+        });
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| AppError::InvalidField {
+            field: "url".to_string(),
+            message: "has no host".to_string(),
+        })?
+        .trim_matches(|c| c == '[' || c == ']')
+        .to_string();
+    let port = url.port_or_known_default().unwrap_or(80);
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|e| AppError::InvalidInput(format!("Failed to resolve {}: {}", host, e)))?
+        .collect();
+    match addrs.first() {
+        Some(addr) if addrs.iter().all(|addr| is_public(addr.ip())) => Ok((host, *addr)),
+        _ => Err(blocked(url)),
+    }
+}
+
+pub fn request_error(url: &Url, e: reqwest::Error) -> AppError {
+    if e.is_timeout() {
+        AppError::Timeout(format!("{} took too long to respond", url))
+    } else {
+        AppError::InvalidInput(format!("Failed to fetch {}: {}", url, e))
+    }
+}
+
+pub fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |captures: &Captures| {
+            let entity = &captures[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+// Decoded, whitespace collapsed and cut to `max` characters
+fn clean(text: &str, max: usize) -> Option<String> {
+    let text = decode_entities(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    Some(match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text,
+    })
+}
+
+fn absolute(base: &Url, href: &str) -> Option<String> {
+    let url = base.join(decode_entities(href.trim()).as_str()).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+// Open Graph first, then Twitter cards, then plain HTML
+fn parse(url: &Url, html: &str) -> LinkPreview {
+    let mut meta: Vec<(String, String)> = Vec::new();
+    let mut favicon = None;
+    for tag in TAG.captures_iter(html) {
+        let attributes: Vec<(String, &str)> = ATTRIBUTE
+            .captures_iter(&tag[0])
+            .filter_map(|captures| {
+                let value = captures
+                    .get(2)
+                    .or_else(|| captures.get(3))
+                    .or_else(|| captures.get(4))?;
+                Some((captures[1].to_ascii_lowercase(), value.as_str()))
+            })
+            .collect();
+        let attribute = |name: &str| {
+            attributes
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| *value)
+        };
+        if tag[1].eq_ignore_ascii_case("link") {
+            let rel = attribute("rel").unwrap_or_default().to_ascii_lowercase();
+            if favicon.is_none() && rel.split_whitespace().any(|rel| rel == "icon") {
+                favicon = attribute("href").and_then(|href| absolute(url, href));
+            }
+        } else if let (Some(key), Some(content)) = (
+            attribute("property").or_else(|| attribute("name")),
+            attribute("content"),
+        ) {
+            meta.push((key.to_ascii_lowercase(), content.to_string()));
+        }
+    }
+    let first = |keys: &[&str]| {
+        keys.iter().find_map(|key| {
+            meta.iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, content)| content.as_str())
+        })
+    };
+
+    let title = first(&["og:title", "twitter:title"])
+        .map(str::to_string)
+        .or_else(|| TITLE.captures(html).map(|captures| captures[1].to_string()));
+    LinkPreview {
+        url: url.to_string(),
+        title: title.and_then(|title| clean(&title, MAX_TITLE_CHARS)),
+        description: first(&["og:description", "twitter:description", "description"])
+            .and_then(|description| clean(description, MAX_DESCRIPTION_CHARS)),
+        image: first(&["og:image", "og:image:url", "twitter:image"])
+            .and_then(|image| absolute(url, image)),
+        site_name: first(&["og:site_name"]).and_then(|name| clean(name, MAX_TITLE_CHARS)),
+        favicon: favicon.or_else(|| absolute(url, "/favicon.ico")),
+    }
+}
+
+// GETs `url`, following up to `MAX_REDIRECTS` redirects with every hop
+// checked by `public_addr`. Returns the final URL and its successful response
+// with the body unread. `fetch_tool` reads pages for the agent through it too.
+pub async fn get(
+    mut url: Url,
+    accept: &str,
+    user_agent: &str,
+    timeout: Duration,
+) -> Result<(Url, reqwest::Response), AppError> {
+    for _ in 0..=MAX_REDIRECTS {
+        let (host, addr) = public_addr(&url).await?;
+        let client = reqwest::Client::builder()
+            .redirect(Policy::none())
+            .timeout(timeout)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .user_agent(user_agent)
+            .resolve(&host, addr)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        let response = client
+            .get(url.clone())
+            .header(ACCEPT, accept)
+            .send()
+            .await
+            .map_err(|e| request_error(&url, e))?;
+
+        // Each hop is checked again before it is followed
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .ok_or_else(|| AppError::InvalidInput(format!("{} redirects nowhere", url)))?;
+            url = url.join(location).map_err(|e| {
+                AppError::InvalidInput(format!("{} redirects to a bad URL: {}", url, e))
+            })?;
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(AppError::InvalidInput(format!(
+                "{} returned {}",
+                url,
+                response.status()
+            )));
+        }
+        return Ok((url, response));
+    }
+    Err(AppError::InvalidInput(format!(
+        "{} redirects more than {} times",
+        url, MAX_REDIRECTS
+    )))
+}
+
+async fn fetch(url: Url) -> Result<LinkPreview, AppError> {
+    let (url, mut response) = get(
+        url,
+        "text/html,application/xhtml+xml;q=0.9,*/*;q=0.5",
+        USER_AGENT,
+        REQUEST_TIMEOUT,
+    )
+    .await?;
+
+    // Images and other files get a card with just the link
+    let html = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.contains("html"));
+    if !html {
+        return Ok(LinkPreview {
+            url: url.to_string(),
+            ..Default::default()
+        });
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| request_error(&url, e))? {
+        body.extend_from_slice(&chunk);
+        let end_of_head = chunk
+            .windows(7)
+            .any(|window| window.eq_ignore_ascii_case(b"</head>"));
+        if end_of_head || body.len() >= MAX_HTML_BYTES {
+            break;
+        }
+    }
+    body.truncate(MAX_HTML_BYTES);
+    Ok(parse(&url, &String::from_utf8_lossy(&body)))
+}
+
+// Title, description and image of a page, fetched here rather than in the
+// webview so pages can't track the viewer and CORS doesn't apply. Results are
+// cached for an hour.
+#[tauri::command]
+#[specta::specta]
+pub async fn fetch_link_preview(url: String) -> Result<LinkPreview, AppError> {
+    let cached = CACHE
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|(cached_url, fetched_at, _)| *cached_url == url && fetched_at.elapsed() < CACHE_TTL)
+        .map(|(_, _, preview)| preview.clone());
+    if let Some(preview) = cached {
+        return Ok(preview);
+    }
+
+    let parsed = Url::parse(&url).map_err(|e| AppError::InvalidField {
+        field: "url".to_string(),
+        message: e.to_string(),
+    })?;
+    let preview = fetch(parsed).await?;
+    CACHE
+        .lock()
+        .unwrap()
+        .push((url, Instant::now(), preview.clone()));
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_public_addresses_are_fetched() {
+        let cases = [
+            ("93.184.216.34", true),
+            ("2606:2800:220:1:248:1893:25c8:1946", true),
+            ("127.0.0.1", false),
+            ("::1", false),
+            ("10.0.0.1", false),
+            ("192.168.1.1", false),
+            ("0.0.0.0", false),
+            // Link-local, including cloud metadata endpoints
+            ("169.254.169.254", false),
+            ("fe80::1", false),
+            ("fd00::1", false),
+            // Carrier-grade NAT and benchmarking
+            ("100.64.0.1", false),
+            ("100.127.255.255", false),
+            ("100.128.0.1", true),
+            ("198.18.0.1", false),
+            ("198.19.255.255", false),
+            ("198.20.0.1", true),
+            // IPv4 hosts behind IPv6 addresses
+            ("::ffff:127.0.0.1", false),
+            ("::ffff:169.254.169.254", false),
+            ("::ffff:93.184.216.34", true),
+            ("64:ff9b::a9fe:a9fe", false),
+            ("64:ff9b::5db8:d822", true),
+            ("2002:7f00:1::", false),
+            ("2002:c0a8:101::1", false),
+            ("2002:5db8:d822::1", true),
+        ];
+        for (ip, public) in cases {
+            assert_eq!(is_public(ip.parse().unwrap()), public, "{}", ip);
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use crate::secrets;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+// Minimal HTTP/1.1 handling for the localhost APIs (`mcp_server`, `openai_api`,
+// `companion_api`, `webhooks`).
+// One request per connection, always answered with `Connection: close`.
+
+// A client gets this long to send its whole request, so a stalled connection
+// can't hold its task forever
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+// Per line, and for the request line and headers together
+const MAX_LINE_BYTES: usize = 8 * 1024;
+const MAX_HEAD_BYTES: usize = 32 * 1024;
+const HEADERS_TOO_LARGE: &str = "431 Request Header Fields Too Large";
+
+// A loopback listener that one of the APIs runs at most once
+pub struct Listener {
+    // For logs, e.g. "Companion API"
+    name: &'static str,
+    server: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Listener {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            server: Mutex::new(None),
+        }
+    }
+
+    // Listens on 127.0.0.1:`port`, replacing a running listener, and hands
+    // every connection to `handle` on a task of its own
+    pub fn start<F, Fut>(&self, port: u16, handle: F)
+    where
+        F: Fn(TcpStream) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.stop();
+        let name = self.name;
+        let server = tauri::async_runtime::spawn(async move {
+            if let Err(e) = serve(name, port, handle).await {
+                log::error!("{} failed: {}", name, e);
+            }
+        });
+        *self.server.lock().unwrap() = Some(server);
+    }
+
+    pub fn stop(&self) {
+        if let Some(server) = self.server.lock().unwrap().take() {
+            server.abort();
+            log::info!("{} stopped", self.name);
+        }
+    }
+}
+
+async fn serve<F, Fut>(name: &str, port: u16, handle: F) -> Result<(), String>
+where
+    F: Fn(TcpStream) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+    log::info!("{} listening on 127.0.0.1:{}", name, port);
+
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("Failed to accept connection: {}", e))?;
+        tauri::async_runtime::spawn(handle(stream));
+    }
+}
+
+// The bearer token kept under the secret `name`, made on first use
+pub fn token(name: &str) -> Result<String, String> {
+    if let Some(token) = secrets::get_secret(name)? {
+        return Ok(token);
+    }
+    new_token(name)
+}
+
+// Replaces the token under `name`, so clients holding the old one are refused
+pub fn new_token(name: &str) -> Result<String, String> {
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    secrets::set_secret(name.to_string(), token.clone())?;
+    Ok(token)
+}
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    // Header names are lowercased
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn bearer_token(&self) -> Option<&str> {
+        self.headers.get("authorization")?.strip_prefix("Bearer ")
+    }
+
+    pub fn has_bearer_token(&self, token: &str) -> bool {
+        self.bearer_token()
+            .is_some_and(|sent| constant_time_eq(sent.as_bytes(), token.as_bytes()))
+    }
+
+    // Browsers always send an Origin, local tools and scripts don't. Refusing
+    // those keeps web pages from reaching the API through DNS rebinding.
+    pub fn is_browser(&self) -> bool {
+        self.headers.contains_key("origin")
+    }
+}
+
+// Takes as long whatever the inputs share, so comparing secrets doesn't leak
+// how much of one a caller guessed right
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+// Reads up to and including the next newline, refusing lines longer than
+// `limit` bytes
+async fn read_line(
+    reader: &mut BufReader<&mut TcpStream>,
+    limit: usize,
+) -> Result<String, Option<&'static str>> {
+    let mut line = String::new();
+    (&mut *reader)
+        .take(limit as u64)
+        .read_line(&mut line)
+        .await
+        .map_err(|_| None)?;
+    if line.ends_with('\n') {
+        Ok(line)
+    } else if line.len() >= limit {
+        Err(Some(HEADERS_TOO_LARGE))
+    } else {
+        // The client closed the connection mid-line
+        Err(None)
+    }
+}
+
+// Err carries the status line to answer with, None when the connection
+// should just be dropped
+pub async fn read_request(
+    stream: &mut TcpStream,
+    max_body_bytes: usize,
+) -> Result<Request, Option<&'static str>> {
+    tokio::time::timeout(READ_TIMEOUT, read(stream, max_body_bytes))
+        .await
+        .unwrap_or(Err(Some("408 Request Timeout")))
+}
+
+async fn read(
+    stream: &mut TcpStream,
+    max_body_bytes: usize,
+) -> Result<Request, Option<&'static str>> {
+    let mut reader = BufReader::new(stream);
+    let request_line = read_line(&mut reader, MAX_LINE_BYTES)
+        .await
+        .map_err(|status| status.map(|_| "414 URI Too Long"))?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(Some("400 Bad Request"));
+    };
+
+    let mut headers = HashMap::new();
+    let mut head_bytes = request_line.len();
+    loop {
+        let limit = MAX_LINE_BYTES.min(MAX_HEAD_BYTES.saturating_sub(head_bytes));
+        if limit == 0 {
+            return Err(Some(HEADERS_TOO_LARGE));
+        }
+        let line = read_line(&mut reader, limit).await?;
+        head_bytes += line.len();
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let content_length: usize = headers
+        .get("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    if content_length > max_body_bytes {
+        return Err(Some("413 Payload Too Large"));
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).await.map_err(|_| None)?;
+
+    Ok(Request {
+        method: method.to_string(),
+        // Query strings aren't used by either API
+        path: path.split('?').next().unwrap_or_default().to_string(),
+        headers,
+        body,
+    })
+}
+
+pub async fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    // Sends `raw` from a client and parses it on the server side
+    async fn parse(raw: Vec<u8>) -> Result<Request, Option<&'static str>> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(address).await.unwrap();
+            // The server may stop reading early, which fails the write
+            let _ = stream.write_all(&raw).await;
+            stream
+        });
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let request = read_request(&mut stream, 1024).await;
+        drop(client.await);
+        request
+    }
+
+    #[tokio::test]
+    async fn parses_a_request() {
+        let raw =
+            b"POST /v1/x?y=1 HTTP/1.1\r\nAuthorization: Bearer t\r\nContent-Length: 2\r\n\r\n{}";
+        let request = parse(raw.to_vec()).await.ok().unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/v1/x");
+        assert_eq!(request.bearer_token(), Some("t"));
+        assert_eq!(request.body, b"{}");
+    }
+
+    #[test]
+    fn checks_the_bearer_token() {
+        let request = |authorization: &str| Request {
+            method: "POST".to_string(),
+            path: "/".to_string(),
+            headers: HashMap::from([("authorization".to_string(), authorization.to_string())]),
+            body: Vec::new(),
+        };
+
+        assert!(request("Bearer secret").has_bearer_token("secret"));
+        for authorization in ["Bearer secreT", "Bearer secret2", "Bearer ", "secret"] {
+            assert!(!request(authorization).has_bearer_token("secret"));
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_an_overlong_request_line() {
+        let raw = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE_BYTES));
+
+        let status = parse(raw.into_bytes()).await.err().unwrap();
+
+        assert_eq!(status, Some("414 URI Too Long"));
+    }
+
+    #[tokio::test]
+    async fn refuses_an_overlong_header() {
+        let raw = format!(
+            "GET / HTTP/1.1\r\nX: {}\r\n\r\n",
+            "a".repeat(MAX_LINE_BYTES)
+        );
+
+        let status = parse(raw.into_bytes()).await.err().unwrap();
+
+        assert_eq!(status, Some(HEADERS_TOO_LARGE));
+    }
+
+    #[tokio::test]
+    async fn refuses_too_many_headers() {
+        let header = format!("X: {}\r\n", "a".repeat(1000));
+        let raw = format!("GET / HTTP/1.1\r\n{}\r\n", header.repeat(40));
+
+        let status = parse(raw.into_bytes()).await.err().unwrap();
+
+        assert_eq!(status, Some(HEADERS_TOO_LARGE));
+    }
+
+    #[tokio::test]
+    async fn refuses_a_large_body() {
+        let raw = b"POST / HTTP/1.1\r\nContent-Length: 4096\r\n\r\n";
+
+        let status = parse(raw.to_vec()).await.err().unwrap();
+
+        assert_eq!(status, Some("413 Payload Too Large"));
+    }
+}
@@ -0,0 +1,332 @@
+use crate::error::AppError;
+use crate::i18n;
+use crate::ring_buffer::RingBuffer;
+use crate::secrets;
+use crate::webview_language;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::cell::Cell;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+pub const LOG_FILE_NAME: &str = "mix.log";
+pub const LOG_ENTRY_EVENT: &str = "log://entry";
+pub const SIDECAR_TARGET: &str = "sidecar";
+pub const FRONTEND_TARGET: &str = "frontend";
+pub const LOG_VIEWER_LABEL: &str = "log-viewer";
+// Until settings are loaded, see `ring_buffer::BufferSizes`
+const DEFAULT_RECENT_ENTRIES: usize = 2000;
+// The log file is rotated at this size into `mix.log.1`, `mix.log.2`, ...,
+// keeping this many old files next to it
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+const ROTATED_LOG_FILES: usize = 4;
+
+static RECENT_ENTRIES: Mutex<RingBuffer<LogEntry>> =
+    Mutex::new(RingBuffer::new("log_entries", DEFAULT_RECENT_ENTRIES));
+
+thread_local! {
+    // Emitting can itself log (tauri uses `log` internally), so don't recurse into it
+    static EMITTING: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub source: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Deserialize, Type)]
+pub struct LogFilter {
+    pub level: Option<String>,
+    pub source: Option<String>,
+    pub search: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Type)]
+pub struct FrontendError {
+    pub kind: String,
+    pub message: String,
+    #[specta(optional)]
+    pub stack: Option<String>,
+    #[specta(optional)]
+    pub url: Option<String>,
+    #[specta(optional)]
+    pub line: Option<u32>,
+    #[specta(optional)]
+    pub column: Option<u32>,
+    #[specta(optional)]
+    pub session_id: Option<String>,
+    #[specta(optional)]
+    pub trace_id: Option<String>,
+}
+
+// Writes every record to stderr, the log file in the app log dir, the in-memory
+// buffer backing the log viewer, and emits it to the webview. Messages are
+// redacted first so secrets never reach any of them.
+struct AppLogger {
+    app: AppHandle,
+    file: Mutex<LogFile>,
+}
+
+struct LogFile {
+    dir: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl LogFile {
+    fn open(dir: PathBuf) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LOG_FILE_NAME))?;
+        let len = file.metadata()?.len();
+        Ok(Self { dir, file, len })
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.len >= MAX_LOG_BYTES {
+            // Logging the failure would come straight back here
+            if let Err(e) = self.rotate() {
+                eprintln!("Failed to rotate log file: {}", e);
+            }
+        }
+        if writeln!(self.file, "{}", line).is_ok() {
+            self.len += line.len() as u64 + 1;
+        }
+    }
+
+    // Shifts every old file up by one, dropping the oldest, and starts a new
+    // log file
+    fn rotate(&mut self) -> io::Result<()> {
+        let path = |index: usize| match index {
+            0 => self.dir.join(LOG_FILE_NAME),
+            index => self.dir.join(format!("{}.{}", LOG_FILE_NAME, index)),
+        };
+        self.file.flush()?;
+        for index in (0..ROTATED_LOG_FILES).rev() {
+            let from = path(index);
+            if from.exists() {
+                fs::rename(&from, path(index + 1))?;
+            }
+        }
+        *self = Self::open(self.dir.clone())?;
+        Ok(())
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Keep dependency crates to warnings so they don't drown out our own logs
+        let own = metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+            || metadata.target() == SIDECAR_TARGET
+            || metadata.target() == FRONTEND_TARGET;
+        metadata.level() <= log::max_level() && (own || metadata.level() <= Level::Warn)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let entry = LogEntry {
+            timestamp: chrono::Local::now()
+                .format("%Y-%m-%dT%H:%M:%S%.3f")
+                .to_string(),
+            level: record.level().to_string(),
+            source: match record.target() {
+                SIDECAR_TARGET | FRONTEND_TARGET => record.target().to_string(),
+                _ => "app".to_string(),
+            },
+            message: secrets::redact(&record.args().to_string()),
+        };
+
+        let line = format!(
+            "{} {:<5} [{}] {}",
+            entry.timestamp,
+            entry.level,
+            record.target(),
+            entry.message
+        );
+
+        eprintln!("{}", line);
+        if let Ok(mut file) = self.file.lock() {
+            file.write_line(&line);
+        }
+
+        if let Ok(mut entries) = RECENT_ENTRIES.lock() {
+            entries.push(entry.clone());
+        }
+
+        if !EMITTING.with(|emitting| emitting.replace(true)) {
+            let _ = self.app.emit(LOG_ENTRY_EVENT, &entry);
+            EMITTING.with(|emitting| emitting.set(false));
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+pub fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve log dir: {}", e))
+}
+
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    let dir = log_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create log dir: {}", e))?;
+
+    let file = LogFile::open(dir).map_err(|e| format!("Failed to open log file: {}", e))?;
+
+    log::set_boxed_logger(Box::new(AppLogger {
+        app: app.clone(),
+        file: Mutex::new(file),
+    }))
+    .map_err(|e| format!("Failed to install logger: {}", e))?;
+
+    log::set_max_level(default_level());
+
+    Ok(())
+}
+
+pub fn default_level() -> LevelFilter {
+    if cfg!(debug_assertions) {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    }
+}
+
+pub fn set_capacity(capacity: usize) {
+    if let Ok(mut entries) = RECENT_ENTRIES.lock() {
+        entries.set_capacity(capacity);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_logs(filter: Option<LogFilter>) -> Result<Vec<LogEntry>, AppError> {
+    let filter = filter.unwrap_or_default();
+    let min_level = match &filter.level {
+        Some(level) => {
+            Some(Level::from_str(level).map_err(|_| format!("Invalid log level: {}", level))?)
+        }
+        None => None,
+    };
+    let search = filter.search.as_ref().map(|s| s.to_lowercase());
+
+    let entries = RECENT_ENTRIES
+        .lock()
+        .map_err(|e| format!("Failed to read logs: {}", e))?;
+
+    let mut matching: Vec<LogEntry> = entries
+        .iter()
+        .filter(|entry| match min_level {
+            Some(min_level) => Level::from_str(&entry.level).is_ok_and(|level| level <= min_level),
+            None => true,
+        })
+        .filter(|entry| {
+            filter
+                .source
+                .as_ref()
+                .is_none_or(|source| &entry.source == source)
+        })
+        .filter(|entry| {
+            search
+                .as_ref()
+                .is_none_or(|search| entry.message.to_lowercase().contains(search))
+        })
+        .cloned()
+        .collect();
+
+    if let Some(limit) = filter.limit {
+        matching = matching.split_off(matching.len().saturating_sub(limit));
+    }
+
+    Ok(matching)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn open_log_viewer(app: AppHandle) -> Result<(), AppError> {
+    if let Some(window) = app.get_webview_window(LOG_VIEWER_LABEL) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    let builder = WebviewWindowBuilder::new(
+        &app,
+        LOG_VIEWER_LABEL,
+        WebviewUrl::App("index.html#/logs".into()),
+    )
+    .title(i18n::t(&app, "window.logs"))
+    .inner_size(900.0, 600.0);
+    let window = webview_language::configure(&app, builder)
+        .build()
+        .map_err(|e| format!("Failed to open log viewer: {}", e))?;
+    webview_language::apply(&window);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn report_frontend_error(payload: FrontendError) {
+    let mut line = String::new();
+    if let Some(session_id) = &payload.session_id {
+        line.push_str(&format!("[session {}] ", session_id));
+    }
+    if let Some(trace_id) = &payload.trace_id {
+        line.push_str(&format!("[trace {}] ", trace_id));
+    }
+    line.push_str(&format!("Uncaught {}: {}", payload.kind, payload.message));
+    if let Some(url) = &payload.url {
+        line.push_str(&format!(
+            " ({}:{}:{})",
+            url,
+            payload.line.unwrap_or(0),
+            payload.column.unwrap_or(0)
+        ));
+    }
+    if let Some(stack) = &payload.stack {
+        line.push_str(&format!("\n{}", stack));
+    }
+
+    log::error!(target: FRONTEND_TARGET, "{}", line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_the_log_file_and_keeps_a_few_old_ones() {
+        let dir = std::env::temp_dir().join(format!("mix-logging-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut file = LogFile::open(dir.clone()).unwrap();
+
+        for round in 0..ROTATED_LOG_FILES + 2 {
+            file.len = MAX_LOG_BYTES;
+            file.write_line(&format!("round {}", round));
+        }
+
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), ROTATED_LOG_FILES + 1);
+        let current = fs::read_to_string(dir.join(LOG_FILE_NAME)).unwrap();
+        assert_eq!(current, format!("round {}\n", ROTATED_LOG_FILES + 1));
+        let _ = fs::remove_dir_all(dir);
+    }
+}
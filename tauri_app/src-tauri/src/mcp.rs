@@ -0,0 +1,227 @@
+use crate::agent_tools;
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::sidecar::SidecarManager;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+// The sidecar merges these over the servers in its own config files
+pub const MCP_SERVERS_ENV: &str = "MIX_MCP_SERVERS";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransport {
+    // Spawned by the sidecar, talking over stdin/stdout
+    #[default]
+    Stdio,
+    // Already running, attached to over HTTP
+    Sse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct McpServerConfig {
+    pub name: String,
+    pub transport: McpTransport,
+    pub command: String,
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    // Only these tools are offered to the agent when non-empty
+    pub allowed_tools: Vec<String>,
+    pub denied_tools: Vec<String>,
+    pub enabled: bool,
+}
+
+impl Default for McpServerConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            transport: McpTransport::default(),
+            command: String::new(),
+            args: vec![],
+            env: BTreeMap::new(),
+            url: String::new(),
+            headers: BTreeMap::new(),
+            allowed_tools: vec![],
+            denied_tools: vec![],
+            enabled: true,
+        }
+    }
+}
+
+fn validate(config: &McpServerConfig) -> Result<(), String> {
+    // The sidecar names tools `<server>_<tool>` and splits on the first underscore
+    if config.name.is_empty()
+        || !config
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(format!(
+            "Invalid MCP server name {:?}, use letters, digits and dashes",
+            config.name
+        ));
+    }
+    match config.transport {
+        McpTransport::Stdio if config.command.trim().is_empty() => {
+            Err(format!("MCP server {} needs a command", config.name))
+        }
+        McpTransport::Sse if config.url.trim().is_empty() => {
+            Err(format!("MCP server {} needs a URL", config.name))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Enabled servers in the sidecar's `mcpServers` config format
+pub fn sidecar_env(app: &AppHandle) -> Vec<(String, String)> {
+    let servers: serde_json::Map<String, serde_json::Value> = app
+        .state::<SettingsManager>()
+        .get()
+        .mcp_servers
+        .into_iter()
+        .filter(|server| server.enabled)
+        .map(|server| {
+            let env: Vec<String> = server
+                .env
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            let value = json!({
+                "type": server.transport,
+                "command": server.command,
+                "args": server.args,
+                "env": env,
+                "url": server.url,
+                "headers": server.headers,
+                "allowedTools": server.allowed_tools,
+                "deniedTools": server.denied_tools,
+            });
+            (server.name, value)
+        })
+        .collect();
+    let mut servers = servers;
+    if let Some(server) = agent_tools::sidecar_server(app) {
+        servers.insert(agent_tools::SERVER_NAME.to_string(), server);
+    }
+    vec![(
+        MCP_SERVERS_ENV.to_string(),
+        serde_json::Value::Object(servers).to_string(),
+    )]
+}
+
+// A server's status as the sidecar's `mcp.list` RPC method reports it
+#[derive(Debug, Deserialize)]
+struct McpServerStatus {
+    name: String,
+    connected: bool,
+    tools: Vec<McpTool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct McpTool {
+    // Without the `<server>_` prefix the agent sees
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpResource {
+    pub server: String,
+    pub uri: String,
+    pub name: String,
+    pub description: String,
+    pub mime_type: String,
+}
+
+// The sidecar connects to MCP servers at launch, so changes need a restart
+async fn restart_sidecar(app: &AppHandle) -> Result<(), AppError> {
+    app.state::<Arc<SidecarManager>>()
+        .restart_sidecar(app)
+        .await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_mcp_servers(settings_manager: State<'_, SettingsManager>) -> Vec<McpServerConfig> {
+    settings_manager.get().mcp_servers
+}
+
+// Adds the server, or replaces the one with the same name
+#[tauri::command]
+#[specta::specta]
+pub async fn save_mcp_server(app: AppHandle, config: McpServerConfig) -> Result<(), AppError> {
+    validate(&config)?;
+    app.state::<SettingsManager>().update(|settings| {
+        match settings
+            .mcp_servers
+            .iter_mut()
+            .find(|existing| existing.name == config.name)
+        {
+            Some(existing) => *existing = config.clone(),
+            None => settings.mcp_servers.push(config.clone()),
+        }
+    })?;
+    log::info!("Saved MCP server {}", config.name);
+    restart_sidecar(&app).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_mcp_server(app: AppHandle, name: String) -> Result<(), AppError> {
+    app.state::<SettingsManager>().update(|settings| {
+        settings
+            .mcp_servers
+            .retain(|existing| existing.name != name)
+    })?;
+    log::info!("Removed MCP server {}", name);
+    restart_sidecar(&app).await
+}
+
+// Tools the named server offered when the sidecar connected to it
+#[tauri::command]
+#[specta::specta]
+pub async fn list_mcp_tools(app: AppHandle, server: String) -> Result<Vec<McpTool>, AppError> {
+    let servers: Vec<McpServerStatus> = serde_json::from_value(
+        app.state::<Arc<SidecarManager>>()
+            .rpc("mcp.list", json!({}))
+            .await?,
+    )
+    .map_err(|e| format!("Failed to parse MCP servers: {}", e))?;
+    match servers.into_iter().find(|status| status.name == server) {
+        Some(status) if status.connected => Ok(status.tools),
+        Some(_) => Err(AppError::InvalidInput(format!(
+            "MCP server {} isn't connected",
+            server
+        ))),
+        None => Err(AppError::InvalidInput(format!(
+            "No MCP server named {}",
+            server
+        ))),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_mcp_resources(
+    app: AppHandle,
+    server: String,
+) -> Result<Vec<McpResource>, AppError> {
+    let resources: Vec<McpResource> = serde_json::from_value(
+        app.state::<Arc<SidecarManager>>()
+            .rpc("mcp.resources", json!({}))
+            .await?,
+    )
+    .map_err(|e| format!("Failed to parse MCP resources: {}", e))?;
+    Ok(resources
+        .into_iter()
+        .filter(|resource| resource.server == server)
+        .collect())
+}
@@ -0,0 +1,283 @@
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::sidecar::SidecarManager;
+use crate::{approvals, files, git, local_http, recents, search};
+use serde::Serialize;
+use serde_json::{json, Value};
+use specta::Type;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::net::TcpStream;
+
+// Other agent frontends connect with MCP's streamable HTTP transport, e.g.
+// `http://127.0.0.1:7331/mcp` with the token as a bearer header
+const MCP_PATH: &str = "/mcp";
+const DEFAULT_MCP_PORT: u16 = 7331;
+const PROTOCOL_VERSION: &str = "2025-03-26";
+const TOKEN_SECRET: &str = "mcp_server_token";
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const JSON: &str = "application/json";
+// Every tool call is confirmed by the user under this approval action
+const APPROVAL_ACTION: &str = "mcp_server";
+
+static LISTENER: local_http::Listener = local_http::Listener::new("MCP server");
+
+#[derive(Debug, Serialize, Type)]
+pub struct McpServerInfo {
+    pub enabled: bool,
+    pub url: String,
+    pub token: Option<String>,
+}
+
+fn port(app: &AppHandle) -> u16 {
+    app.state::<SettingsManager>()
+        .get()
+        .mcp_server_port
+        .unwrap_or(DEFAULT_MCP_PORT)
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "search_workspace",
+            "description": "Search the files of the open workspace for text or a regex",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "regex": { "type": "boolean" },
+                    "case_sensitive": { "type": "boolean" },
+                    "globs": { "type": "array", "items": { "type": "string" } },
+                    "max_results": { "type": "integer" }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "list_workspace_tree",
+            "description": "List the files and directories of the open workspace",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "depth": { "type": "integer" } }
+            }
+        },
+        {
+            "name": "read_file",
+            "description": "Read a text file from the open workspace",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "path": { "type": "string" } },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "git_status",
+            "description": "List changed files in the workspace's git repository",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "recent_files",
+            "description": "Files recently opened in the app",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "list_sessions",
+            "description": "Chat sessions of the open workspace",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "session_history",
+            "description": "Messages of a chat session",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "session_id": { "type": "string" },
+                    "limit": { "type": "integer" }
+                },
+                "required": ["session_id"]
+            }
+        }
+    ])
+}
+
+fn to_value(result: impl Serialize) -> Result<Value, String> {
+    serde_json::to_value(result).map_err(|e| format!("Failed to serialize result: {}", e))
+}
+
+async fn call_tool(app: &AppHandle, name: &str, arguments: Value) -> Result<Value, String> {
+    let string_arg = |key: &str| -> Result<String, String> {
+        arguments
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| format!("Missing argument {}", key))
+    };
+    match name {
+        "search_workspace" => {
+            let opts = serde_json::from_value(arguments.clone())
+                .map_err(|e| format!("Invalid arguments: {}", e))?;
+            to_value(search::search_workspace(app.clone(), string_arg("query")?, Some(opts)).await?)
+        }
+        "list_workspace_tree" => {
+            let depth = arguments
+                .get("depth")
+                .and_then(Value::as_u64)
+                .map(|depth| depth as usize);
+            to_value(files::list_workspace_tree(app.clone(), depth, None).await?)
+        }
+        "read_file" => to_value(files::read_file(app.clone(), string_arg("path")?).await?),
+        "git_status" => to_value(git::git_status(app.clone()).await?),
+        "recent_files" => to_value(recents::get_recent_files(app.state())),
+        "list_sessions" => app
+            .state::<Arc<SidecarManager>>()
+            .rpc("sessions.list", json!({}))
+            .await
+            .map_err(String::from),
+        "session_history" => {
+            let params = json!({
+                "sessionId": string_arg("session_id")?,
+                "limit": arguments.get("limit").and_then(Value::as_i64).unwrap_or(0),
+            });
+            app.state::<Arc<SidecarManager>>()
+                .rpc("messages.history", params)
+                .await
+                .map_err(String::from)
+        }
+        _ => Err(format!("Unknown tool {}", name)),
+    }
+}
+
+// JSON-RPC result, or (code, message) for an error response
+async fn dispatch(app: &AppHandle, method: &str, params: Value) -> Result<Value, (i64, String)> {
+    match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": app.package_info().name,
+                "version": app.package_info().version.to_string(),
+            },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => {
+            let name = params
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or((-32602, "Missing tool name".to_string()))?;
+            let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+            let detail = format!("External MCP client calls {} with {}", name, arguments);
+            let result = match approvals::require_approval(app, APPROVAL_ACTION, &detail).await {
+                Ok(()) => call_tool(app, name, arguments).await,
+                Err(e) => Err(e),
+            };
+            // Tool failures are reported to the model, not as protocol errors
+            let (text, is_error) = match result {
+                Ok(value) => (
+                    serde_json::to_string_pretty(&value).unwrap_or_default(),
+                    false,
+                ),
+                Err(e) => (e, true),
+            };
+            Ok(json!({
+                "content": [{ "type": "text", "text": text }],
+                "isError": is_error,
+            }))
+        }
+        _ => Err((-32601, format!("Method not found: {}", method))),
+    }
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream, token: String) {
+    let request = match local_http::read_request(&mut stream, MAX_BODY_BYTES).await {
+        Ok(request) => request,
+        Err(Some(status)) => return local_http::respond(&mut stream, status, JSON, "").await,
+        Err(None) => return,
+    };
+    if request.path != MCP_PATH {
+        return local_http::respond(&mut stream, "404 Not Found", JSON, "").await;
+    }
+    if request.method != "POST" {
+        return local_http::respond(&mut stream, "405 Method Not Allowed", JSON, "").await;
+    }
+    if !request.has_bearer_token(&token) || request.is_browser() {
+        return local_http::respond(&mut stream, "401 Unauthorized", JSON, "").await;
+    }
+
+    let Ok(message) = serde_json::from_slice::<Value>(&request.body) else {
+        let error = json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32700, "message": "Parse error" },
+        });
+        return local_http::respond(&mut stream, "400 Bad Request", JSON, &error.to_string()).await;
+    };
+
+    let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+    // Notifications and responses need no answer
+    let Some(id) = message.get("id").cloned() else {
+        return local_http::respond(&mut stream, "202 Accepted", JSON, "").await;
+    };
+    let params = message.get("params").cloned().unwrap_or(json!({}));
+    let response = match dispatch(&app, method, params).await {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    };
+    local_http::respond(&mut stream, "200 OK", JSON, &response.to_string()).await;
+}
+
+// Starts the server if it is enabled in settings, replacing a running one
+pub fn start(app: &AppHandle) {
+    if !app.state::<SettingsManager>().get().mcp_server_enabled {
+        return LISTENER.stop();
+    }
+    let token = match local_http::token(TOKEN_SECRET) {
+        Ok(token) => token,
+        Err(e) => {
+            log::error!("MCP server failed: {}", e);
+            return LISTENER.stop();
+        }
+    };
+    let app = app.clone();
+    LISTENER.start(port(&app), move |stream| {
+        handle_connection(app.clone(), stream, token.clone())
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_mcp_server_info(app: AppHandle) -> Result<McpServerInfo, AppError> {
+    let enabled = app.state::<SettingsManager>().get().mcp_server_enabled;
+    Ok(McpServerInfo {
+        enabled,
+        url: format!("http://127.0.0.1:{}{}", port(&app), MCP_PATH),
+        token: if enabled {
+            Some(local_http::token(TOKEN_SECRET)?)
+        } else {
+            None
+        },
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_mcp_server_enabled(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<McpServerInfo, AppError> {
+    settings_manager.update(|settings| {
+        settings.mcp_server_enabled = enabled;
+        if port.is_some() {
+            settings.mcp_server_port = port;
+        }
+    })?;
+    start(&app);
+    get_mcp_server_info(app)
+}
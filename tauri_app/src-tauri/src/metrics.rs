@@ -0,0 +1,230 @@
+use serde::Serialize;
+use specta::Type;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::subscriber::Interest;
+use tracing::{Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+// Upper bounds of the latency buckets, the last bucket catches everything slower
+const BUCKET_BOUNDS_MS: [u64; 9] = [10, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_SNAPSHOTS: usize = 60;
+// Tauri opens this span when the webview invokes a command and closes it once
+// the response is sent, so it covers async commands as well as sync ones
+const COMMAND_SPAN: &str = "ipc::request::handle";
+
+static STARTED_AT: LazyLock<Instant> = LazyLock::new(Instant::now);
+static OPERATIONS: LazyLock<Mutex<HashMap<String, Histogram>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static SNAPSHOTS: Mutex<VecDeque<MetricsSnapshot>> = Mutex::new(VecDeque::new());
+// Buffer name -> items dropped because the buffer was full, see `ring_buffer`
+static DROPS: LazyLock<Mutex<HashMap<String, u64>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Histogram {
+    pub count: u64,
+    pub errors: u64,
+    pub total_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub buckets: Vec<u64>,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            errors: 0,
+            total_ms: 0.0,
+            min_ms: f64::MAX,
+            max_ms: 0.0,
+            buckets: vec![0; BUCKET_BOUNDS_MS.len() + 1],
+        }
+    }
+
+    fn observe(&mut self, elapsed: Duration, ok: bool) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        self.count += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.total_ms += ms;
+        self.min_ms = self.min_ms.min(ms);
+        self.max_ms = self.max_ms.max(ms);
+
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| ms <= *bound as f64)
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.buckets[bucket] += 1;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MetricsSnapshot {
+    pub taken_at: String,
+    pub uptime_secs: u64,
+    pub operations: HashMap<String, Histogram>,
+    pub dropped: HashMap<String, u64>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct Metrics {
+    pub bucket_bounds_ms: Vec<u64>,
+    pub current: MetricsSnapshot,
+    pub history: Vec<MetricsSnapshot>,
+}
+
+pub fn record(name: &str, elapsed: Duration, ok: bool) {
+    if let Ok(mut operations) = OPERATIONS.lock() {
+        operations
+            .entry(name.to_string())
+            .or_insert_with(Histogram::new)
+            .observe(elapsed, ok);
+    }
+}
+
+pub fn record_drops(name: &str, count: u64) {
+    if let Ok(mut drops) = DROPS.lock() {
+        *drops.entry(name.to_string()).or_default() += count;
+    }
+}
+
+// Times a fallible operation and records it under `name`
+pub async fn timed<T, E>(
+    name: &str,
+    operation: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let start = Instant::now();
+    let result = operation.await;
+    record(name, start.elapsed(), result.is_ok());
+    result
+}
+
+// When a command's span opened, kept on the span until it closes
+struct CommandStarted {
+    name: String,
+    at: Instant,
+}
+
+#[derive(Default)]
+struct CommandName(Option<String>);
+
+impl Visit for CommandName {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "cmd" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+// Records every command under its name. Only the command span is enabled,
+// so Tauri's and the other crates' instrumentation stays free. A command's
+// result isn't visible here; failures are counted by the operations it runs.
+struct CommandTimer;
+
+impl<S> Layer<S> for CommandTimer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if metadata.name() == COMMAND_SPAN {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut name = CommandName::default();
+        attrs.record(&mut name);
+        if let (Some(name), Some(span)) = (name.0, ctx.span(id)) {
+            span.extensions_mut().insert(CommandStarted {
+                name,
+                at: Instant::now(),
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        if let Some(started) = span.extensions_mut().remove::<CommandStarted>() {
+            record(&started.name, started.at.elapsed(), true);
+        }
+    }
+}
+
+// Times every command the webview invokes, in one place rather than in each
+// command. Runs before the app is built, so no command is missed.
+pub fn time_commands() {
+    let subscriber = tracing_subscriber::registry().with(CommandTimer);
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        log::warn!("Failed to time commands: {}", e);
+    }
+}
+
+pub fn operation_counts() -> HashMap<String, u64> {
+    OPERATIONS
+        .lock()
+        .map(|operations| {
+            operations
+                .iter()
+                .map(|(name, histogram)| (name.clone(), histogram.count))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn snapshot() -> MetricsSnapshot {
+    MetricsSnapshot {
+        taken_at: chrono::Local::now().to_rfc3339(),
+        uptime_secs: STARTED_AT.elapsed().as_secs(),
+        operations: OPERATIONS
+            .lock()
+            .map(|operations| operations.clone())
+            .unwrap_or_default(),
+        dropped: DROPS.lock().map(|drops| drops.clone()).unwrap_or_default(),
+    }
+}
+
+pub fn start_snapshots() {
+    LazyLock::force(&STARTED_AT);
+
+    tauri::async_runtime::spawn(async {
+        loop {
+            tokio::time::sleep(SNAPSHOT_INTERVAL).await;
+
+            let snapshot = snapshot();
+            if let Ok(mut snapshots) = SNAPSHOTS.lock() {
+                if snapshots.len() == MAX_SNAPSHOTS {
+                    snapshots.pop_front();
+                }
+                snapshots.push_back(snapshot);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_metrics() -> Metrics {
+    Metrics {
+        bucket_bounds_ms: BUCKET_BOUNDS_MS.to_vec(),
+        current: snapshot(),
+        history: SNAPSHOTS
+            .lock()
+            .map(|snapshots| snapshots.iter().cloned().collect())
+            .unwrap_or_default(),
+    }
+}
@@ -0,0 +1,273 @@
+use crate::companion_api;
+use crate::error::AppError;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+// Native messaging host for the companion browser extension. The browser
+// starts this binary itself and talks to it over stdin/stdout with
+// length-prefixed JSON. Prompts are forwarded to the running app through the
+// companion API, so the host never starts a second sidecar.
+pub const HOST_NAME: &str = "com.mix.agent";
+// Browsers refuse messages from the host above 1 MiB
+const MAX_OUTGOING_BYTES: usize = 1024 * 1024;
+const MAX_INCOMING_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    Ping,
+    Prompt {
+        text: String,
+        #[serde(default)]
+        url: Option<String>,
+        #[serde(default)]
+        title: Option<String>,
+        #[serde(default)]
+        selection: Option<String>,
+        #[serde(default)]
+        session_id: Option<String>,
+    },
+}
+
+// Chrome passes the calling extension's origin, Firefox the manifest path and
+// the extension id
+pub fn is_host_launch(args: &[String]) -> bool {
+    match args.get(1) {
+        Some(first) if first.starts_with("chrome-extension://") => true,
+        Some(first) => first.ends_with(".json") && args.len() >= 3,
+        None => false,
+    }
+}
+
+fn read_message(input: &mut impl Read) -> Result<Option<Value>, String> {
+    let mut length = [0u8; 4];
+    match input.read_exact(&mut length) {
+        Ok(()) => {}
+        // The browser closed the port
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(format!("Failed to read message: {}", e)),
+    }
+    let length = u32::from_ne_bytes(length) as usize;
+    if length > MAX_INCOMING_BYTES {
+        return Err(format!("Message of {} bytes is too large", length));
+    }
+    let mut body = vec![0u8; length];
+    input
+        .read_exact(&mut body)
+        .map_err(|e| format!("Failed to read message: {}", e))?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| format!("Invalid message: {}", e))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Result<(), String> {
+    let mut body = message.to_string();
+    if body.len() > MAX_OUTGOING_BYTES {
+        body = json!({
+            "id": message.get("id"),
+            "ok": false,
+            "error": "Reply too large for the browser",
+        })
+        .to_string();
+    }
+    output
+        .write_all(&(body.len() as u32).to_ne_bytes())
+        .and_then(|_| output.write_all(body.as_bytes()))
+        .and_then(|_| output.flush())
+        .map_err(|e| format!("Failed to write message: {}", e))
+}
+
+fn page_prompt(
+    text: &str,
+    url: Option<&str>,
+    title: Option<&str>,
+    selection: Option<&str>,
+) -> String {
+    let mut prompt = text.to_string();
+    match (title, url) {
+        (Some(title), Some(url)) => prompt.push_str(&format!("\n\nPage: {} ({})", title, url)),
+        (None, Some(url)) => prompt.push_str(&format!("\n\nPage: {}", url)),
+        _ => {}
+    }
+    if let Some(selection) = selection.filter(|selection| !selection.trim().is_empty()) {
+        prompt.push_str("\n\nSelected text:\n");
+        for line in selection.lines() {
+            prompt.push_str(&format!("> {}\n", line));
+        }
+    }
+    prompt
+}
+
+async fn forward_prompt(
+    app: &AppHandle,
+    prompt: String,
+    session_id: Option<String>,
+) -> Result<Value, String> {
+    let (base_url, token) = companion_api::client_config(app)?.ok_or_else(|| {
+        "Enable the companion API in Mix settings to use the extension".to_string()
+    })?;
+    let response = reqwest::Client::new()
+        .post(format!("{}/prompt", base_url))
+        .bearer_auth(token)
+        .json(&json!({ "prompt": prompt, "session_id": session_id }))
+        .send()
+        .await
+        .map_err(|_| "Mix is not running".to_string())?;
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse reply: {}", e))?;
+    if !status.is_success() {
+        let error = body["error"].as_str().unwrap_or("Prompt failed");
+        return Err(error.to_string());
+    }
+    Ok(body)
+}
+
+async fn handle(app: &AppHandle, request: Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let message = match serde_json::from_value::<Message>(request) {
+        Ok(message) => message,
+        Err(e) => {
+            return json!({ "id": id, "ok": false, "error": format!("Invalid message: {}", e) })
+        }
+    };
+    match message {
+        Message::Ping => json!({ "id": id, "ok": true, "host": HOST_NAME }),
+        Message::Prompt {
+            text,
+            url,
+            title,
+            selection,
+            session_id,
+        } => {
+            let prompt = page_prompt(
+                &text,
+                url.as_deref(),
+                title.as_deref(),
+                selection.as_deref(),
+            );
+            match forward_prompt(app, prompt, session_id).await {
+                Ok(reply) => json!({
+                    "id": id,
+                    "ok": true,
+                    "text": reply["text"],
+                    "trace_id": reply["trace_id"],
+                }),
+                Err(e) => json!({ "id": id, "ok": false, "error": e }),
+            }
+        }
+    }
+}
+
+// Serves messages until the browser disconnects, then exits the app
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut stdout = std::io::stdout().lock();
+        loop {
+            let request = match read_message(&mut stdin) {
+                Ok(Some(request)) => request,
+                Ok(None) => break,
+                Err(e) => {
+                    log::error!("Native messaging: {}", e);
+                    break;
+                }
+            };
+            let reply = tauri::async_runtime::block_on(handle(&app, request));
+            if let Err(e) = write_message(&mut stdout, &reply) {
+                log::error!("Native messaging: {}", e);
+                break;
+            }
+        }
+        app.exit(0);
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn manifest_dir(browser: &str, home: PathBuf) -> Result<PathBuf, String> {
+    let base = home.join("Library/Application Support");
+    Ok(match browser {
+        "chrome" => base.join("Google/Chrome/NativeMessagingHosts"),
+        "chromium" => base.join("Chromium/NativeMessagingHosts"),
+        "brave" => base.join("BraveSoftware/Brave-Browser/NativeMessagingHosts"),
+        "edge" => base.join("Microsoft Edge/NativeMessagingHosts"),
+        "firefox" => base.join("Mozilla/NativeMessagingHosts"),
+        _ => return Err(format!("Unsupported browser {}", browser)),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn manifest_dir(browser: &str, home: PathBuf) -> Result<PathBuf, String> {
+    let base = home.join(".config");
+    Ok(match browser {
+        "chrome" => base.join("google-chrome/NativeMessagingHosts"),
+        "chromium" => base.join("chromium/NativeMessagingHosts"),
+        "brave" => base.join("BraveSoftware/Brave-Browser/NativeMessagingHosts"),
+        "edge" => base.join("microsoft-edge/NativeMessagingHosts"),
+        "firefox" => home.join(".mozilla/native-messaging-hosts"),
+        _ => return Err(format!("Unsupported browser {}", browser)),
+    })
+}
+
+// Windows registers hosts in the registry rather than a directory
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn manifest_dir(browser: &str, _home: PathBuf) -> Result<PathBuf, String> {
+    Err(format!(
+        "Installing the {} host isn't supported on this platform",
+        browser
+    ))
+}
+
+// Writes the host manifest for `browser` so the extension can connect
+#[tauri::command]
+#[specta::specta]
+pub fn install_native_messaging_host(
+    app: AppHandle,
+    browser: String,
+    extension_id: String,
+) -> Result<String, AppError> {
+    if extension_id.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "An extension id is required".to_string(),
+        ));
+    }
+    let home = app
+        .path()
+        .home_dir()
+        .map_err(|e| format!("Failed to resolve home dir: {}", e))?;
+    let dir = manifest_dir(&browser, home)?;
+    let exe =
+        std::env::current_exe().map_err(|e| format!("Failed to resolve app binary: {}", e))?;
+
+    let mut manifest = json!({
+        "name": HOST_NAME,
+        "description": "Mix agent",
+        "path": exe,
+        "type": "stdio",
+    });
+    if browser == "firefox" {
+        manifest["allowed_extensions"] = json!([extension_id]);
+    } else {
+        manifest["allowed_origins"] = json!([format!("chrome-extension://{}/", extension_id)]);
+    }
+
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}.json", HOST_NAME));
+    let contents = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    log::info!(
+        "Installed native messaging host for {} at {}",
+        browser,
+        path.display()
+    );
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,338 @@
+use crate::error::AppError;
+use crate::http_client;
+use crate::secrets;
+use crate::settings::SettingsManager;
+use crate::sidecar::SidecarManager;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::RngCore;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const CALLBACK_PATH: &str = "/callback";
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+// A connection that sends nothing can't hold up the real redirect for longer
+const REDIRECT_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+// Tokens are refreshed once they are this close to expiring
+const REFRESH_MARGIN_SECS: i64 = 300;
+
+const CALLBACK_PAGE: &str =
+    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n\
+    <html><body><p>Login complete, you can close this window.</p></body></html>";
+const BAD_REQUEST: &str = "HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n";
+
+// Non-secret provider details, persisted in settings. Tokens go to the keychain.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OAuthConfig {
+    pub provider: String,
+    pub auth_url: String,
+    pub token_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    // Environment variable the access token is passed to the sidecar in
+    pub env_var: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredTokens {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct OAuthStatus {
+    pub provider: String,
+    pub logged_in: bool,
+    pub expires_at: Option<i64>,
+}
+
+fn secret_name(provider: &str) -> String {
+    format!("oauth:{}", provider)
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn load_tokens(provider: &str) -> Result<Option<StoredTokens>, String> {
+    match secrets::get_secret(&secret_name(provider))? {
+        Some(value) => serde_json::from_str(&value)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse stored tokens for {}: {}", provider, e)),
+        None => Ok(None),
+    }
+}
+
+fn store_tokens(
+    provider: &str,
+    response: TokenResponse,
+    previous_refresh: Option<String>,
+) -> Result<(), String> {
+    let tokens = StoredTokens {
+        access_token: response.access_token,
+        // Providers may omit the refresh token on refresh, keep the old one then
+        refresh_token: response.refresh_token.or(previous_refresh),
+        expires_at: response
+            .expires_in
+            .map(|secs| chrono::Utc::now().timestamp() + secs),
+    };
+    let value =
+        serde_json::to_string(&tokens).map_err(|e| format!("Failed to serialize tokens: {}", e))?;
+    Ok(secrets::set_secret(secret_name(provider), value)?)
+}
+
+async fn request_tokens(
+    config: &OAuthConfig,
+    params: &[(&str, &str)],
+) -> Result<TokenResponse, String> {
+    let response = http_client::shared()
+        .post(&config.token_url)
+        .timeout(TOKEN_REQUEST_TIMEOUT)
+        .form(params)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach token endpoint: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Token endpoint responded with status: {}",
+            response.status()
+        ));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse token response: {}", e))
+}
+
+// Accepts connections on the loopback listener until the redirect carrying
+// our `state` arrives. Anything else, like a favicon fetch or a stray probe,
+// is ignored; the caller's timeout ends the wait.
+async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("Failed to accept redirect: {}", e))?;
+
+        let mut buffer = [0u8; 8192];
+        let read = tokio::time::timeout(REDIRECT_READ_TIMEOUT, stream.read(&mut buffer))
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .unwrap_or(0);
+        let request = String::from_utf8_lossy(&buffer[..read]);
+        let Some(target) = request
+            .lines()
+            .next()
+            .and_then(|line| line.split(' ').nth(1))
+        else {
+            continue;
+        };
+        let Ok(url) = Url::parse(&format!("http://localhost{}", target)) else {
+            continue;
+        };
+        if url.path() != CALLBACK_PATH {
+            continue;
+        }
+
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        if param("state").as_deref() != Some(state) {
+            log::warn!("Ignoring OAuth redirect without the expected state");
+            let _ = stream.write_all(BAD_REQUEST.as_bytes()).await;
+            continue;
+        }
+
+        let _ = stream.write_all(CALLBACK_PAGE.as_bytes()).await;
+        if let Some(error) = param("error") {
+            return Err(format!("Provider denied login: {}", error));
+        }
+        return param("code").ok_or_else(|| "Redirect is missing the code".to_string());
+    }
+}
+
+async fn refresh(config: &OAuthConfig) -> Result<bool, String> {
+    let Some(tokens) = load_tokens(&config.provider)? else {
+        return Ok(false);
+    };
+    let expiring = tokens
+        .expires_at
+        .is_some_and(|at| at - chrono::Utc::now().timestamp() < REFRESH_MARGIN_SECS);
+    let Some(refresh_token) = tokens.refresh_token.filter(|_| expiring) else {
+        return Ok(false);
+    };
+
+    let response = request_tokens(
+        config,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &refresh_token),
+            ("client_id", &config.client_id),
+        ],
+    )
+    .await?;
+    store_tokens(&config.provider, response, Some(refresh_token))?;
+    log::info!("Refreshed OAuth tokens for {}", config.provider);
+    Ok(true)
+}
+
+pub fn sidecar_env(app: &AppHandle) -> Vec<(String, String)> {
+    app.state::<SettingsManager>()
+        .get()
+        .oauth_providers
+        .into_iter()
+        .filter_map(|config| match load_tokens(&config.provider) {
+            Ok(tokens) => tokens.map(|tokens| (config.env_var, tokens.access_token)),
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+// The sidecar only reads tokens at launch, so it is restarted after a refresh
+pub fn start_refresh(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+
+            let mut refreshed = false;
+            for config in app.state::<SettingsManager>().get().oauth_providers {
+                match refresh(&config).await {
+                    Ok(done) => refreshed |= done,
+                    Err(e) => log::warn!("Failed to refresh {} tokens: {}", config.provider, e),
+                }
+            }
+
+            if refreshed {
+                let sidecar_manager = app.state::<Arc<SidecarManager>>();
+                if let Err(e) = sidecar_manager.restart_sidecar(&app).await {
+                    log::error!("Failed to restart sidecar after token refresh: {}", e);
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn oauth_login(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    config: OAuthConfig,
+) -> Result<(), AppError> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| format!("Failed to start login listener: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to start login listener: {}", e))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH);
+
+    // PKCE, so the code is useless to anyone else who sees the redirect
+    let state = random_token();
+    let verifier = random_token();
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+
+    let mut auth_url =
+        Url::parse(&config.auth_url).map_err(|e| format!("Invalid auth URL: {}", e))?;
+    auth_url
+        .query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &config.client_id)
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("scope", &config.scopes.join(" "))
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &challenge)
+        .append_pair("code_challenge_method", "S256");
+
+    app.opener()
+        .open_url(auth_url.as_str(), None::<&str>)
+        .map_err(|e| format!("Failed to open browser: {}", e))?;
+
+    let code = tokio::time::timeout(LOGIN_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| "Timed out waiting for login".to_string())??;
+
+    let response = request_tokens(
+        &config,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", &redirect_uri),
+            ("client_id", &config.client_id),
+            ("code_verifier", &verifier),
+        ],
+    )
+    .await?;
+    store_tokens(&config.provider, response, None)?;
+
+    settings_manager.update(|settings| {
+        settings
+            .oauth_providers
+            .retain(|existing| existing.provider != config.provider);
+        settings.oauth_providers.push(config.clone());
+    })?;
+    log::info!("Logged in to {}", config.provider);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn oauth_logout(
+    settings_manager: State<'_, SettingsManager>,
+    provider: String,
+) -> Result<(), AppError> {
+    secrets::delete_secret(secret_name(&provider))?;
+    settings_manager.update(|settings| {
+        settings
+            .oauth_providers
+            .retain(|existing| existing.provider != provider)
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_oauth_providers(settings_manager: State<'_, SettingsManager>) -> Vec<OAuthStatus> {
+    settings_manager
+        .get()
+        .oauth_providers
+        .into_iter()
+        .map(|config| {
+            let tokens = load_tokens(&config.provider).ok().flatten();
+            OAuthStatus {
+                provider: config.provider,
+                logged_in: tokens.is_some(),
+                expires_at: tokens.and_then(|tokens| tokens.expires_at),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,209 @@
+use crate::coalesce::{Coalesce, Coalescer};
+use crate::error::AppError;
+use crate::metrics;
+use crate::settings::SettingsManager;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+pub const OLLAMA_CHUNK_EVENT: &str = "ollama://chunk";
+const DEFAULT_OLLAMA_URL: &str = "http://127.0.0.1:11434";
+const DETECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Ollama is stateless, so each session's conversation is kept here and resent
+// with every prompt. Sessions routed to the sidecar never touch this.
+static HISTORY: LazyLock<Mutex<HashMap<String, Vec<ChatMessage>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct OllamaStatus {
+    pub available: bool,
+    pub url: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type)]
+pub struct OllamaModel {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub modified_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ChatChunk {
+    pub trace_id: String,
+    pub session_id: String,
+    pub content: String,
+}
+
+impl Coalesce for ChatChunk {
+    fn merge(&mut self, next: &Self) -> bool {
+        if self.trace_id != next.trace_id {
+            return false;
+        }
+        self.content.push_str(&next.content);
+        true
+    }
+}
+
+fn base_url(settings_manager: &SettingsManager) -> String {
+    settings_manager
+        .get()
+        .ollama_url
+        .unwrap_or_else(|| DEFAULT_OLLAMA_URL.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+// Streams the reply as `ollama://chunk` events and returns the full text
+pub async fn chat(
+    app: &AppHandle,
+    session_id: &str,
+    model: &str,
+    prompt: &str,
+    trace_id: &str,
+) -> Result<String, String> {
+    let url = format!("{}/api/chat", base_url(&app.state::<SettingsManager>()));
+    let mut messages = HISTORY
+        .lock()
+        .unwrap()
+        .get(session_id)
+        .cloned()
+        .unwrap_or_default();
+    messages.push(ChatMessage {
+        role: "user".to_string(),
+        content: prompt.to_string(),
+    });
+
+    log::info!("[trace {}] Sending prompt to Ollama ({})", trace_id, model);
+    let mut response = metrics::timed(
+        "ollama.chat",
+        reqwest::Client::new()
+            .post(&url)
+            .json(&json!({ "model": model, "messages": messages, "stream": true }))
+            .send(),
+    )
+    .await
+    .map_err(|e| format!("Ollama request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Ollama request failed with status: {}",
+            response.status()
+        ));
+    }
+
+    // The body is newline-delimited JSON, one object per generated piece
+    let chunks = Coalescer::new(app, OLLAMA_CHUNK_EVENT);
+    let mut reply = String::new();
+    let mut pending: Vec<u8> = Vec::new();
+    while let Some(bytes) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read Ollama response: {}", e))?
+    {
+        pending.extend_from_slice(&bytes);
+        while let Some(newline) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            let Ok(object) = serde_json::from_slice::<Value>(&line) else {
+                continue;
+            };
+            if let Some(error) = object.get("error").and_then(Value::as_str) {
+                return Err(format!("Ollama error: {}", error));
+            }
+            let content = object
+                .pointer("/message/content")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if content.is_empty() {
+                continue;
+            }
+            reply.push_str(content);
+            chunks.push(ChatChunk {
+                trace_id: trace_id.to_string(),
+                session_id: session_id.to_string(),
+                content: content.to_string(),
+            });
+        }
+    }
+
+    // Only completed exchanges are remembered, so a failed prompt can be retried
+    messages.push(ChatMessage {
+        role: "assistant".to_string(),
+        content: reply.clone(),
+    });
+    HISTORY
+        .lock()
+        .unwrap()
+        .insert(session_id.to_string(), messages);
+    Ok(reply)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn detect_ollama(
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<OllamaStatus, AppError> {
+    let url = base_url(&settings_manager);
+    let version = match reqwest::Client::new()
+        .get(format!("{}/api/version", url))
+        .timeout(DETECT_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) if response.status().is_success() => response
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("version")?.as_str().map(str::to_string)),
+        _ => None,
+    };
+    Ok(OllamaStatus {
+        available: version.is_some(),
+        url,
+        version,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_ollama_models(
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<Vec<OllamaModel>, AppError> {
+    #[derive(Deserialize)]
+    struct Tags {
+        models: Vec<OllamaModel>,
+    }
+
+    let url = format!("{}/api/tags", base_url(&settings_manager));
+    let response = reqwest::Client::new()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to list Ollama models: {}", response.status()).into());
+    }
+    let tags: Tags = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Ollama models: {}", e))?;
+    Ok(tags.models)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn clear_ollama_history(session_id: String) {
+    HISTORY.lock().unwrap().remove(&session_id);
+}
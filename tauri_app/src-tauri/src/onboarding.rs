@@ -0,0 +1,130 @@
+use crate::error::AppError;
+use crate::platform::{Native, Permission, Platform};
+use crate::settings::SettingsManager;
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, State};
+use tauri_plugin_macos_permissions::{
+    check_accessibility_permission, check_microphone_permission, check_screen_recording_permission,
+    request_accessibility_permission, request_microphone_permission,
+    request_screen_recording_permission,
+};
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+use tauri_plugin_opener::OpenerExt;
+
+// The checklist, in the order onboarding asks for them
+const PERMISSIONS: [Permission; 4] = [
+    Permission::Accessibility,
+    Permission::ScreenRecording,
+    Permission::Microphone,
+    Permission::Notifications,
+];
+
+#[derive(Debug, Serialize, Type)]
+pub struct PermissionStatus {
+    pub permission: Permission,
+    pub label: &'static str,
+    pub granted: bool,
+    // Where the user grants it by hand, see `open_permission_settings`
+    pub settings_url: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct OnboardingStatus {
+    // True until `complete_onboarding`, so quitting halfway resumes the walk
+    pub first_launch: bool,
+    pub permissions: Vec<PermissionStatus>,
+}
+
+fn label(permission: Permission) -> &'static str {
+    match permission {
+        Permission::Accessibility => "Accessibility",
+        Permission::ScreenRecording => "Screen recording",
+        Permission::Microphone => "Microphone",
+        Permission::Notifications => "Notifications",
+    }
+}
+
+async fn granted(app: &AppHandle, permission: Permission) -> bool {
+    match permission {
+        Permission::Accessibility => check_accessibility_permission().await,
+        Permission::ScreenRecording => check_screen_recording_permission().await,
+        Permission::Microphone => check_microphone_permission().await,
+        Permission::Notifications => matches!(
+            app.notification().permission_state(),
+            Ok(PermissionState::Granted)
+        ),
+    }
+}
+
+async fn status(app: &AppHandle, permission: Permission) -> PermissionStatus {
+    PermissionStatus {
+        permission,
+        label: label(permission),
+        granted: granted(app, permission).await,
+        settings_url: Native::permission_settings_url(permission),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_onboarding(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<OnboardingStatus, AppError> {
+    let first_launch = !settings_manager.get().onboarding_completed;
+    let mut permissions = Vec::with_capacity(PERMISSIONS.len());
+    for permission in PERMISSIONS {
+        permissions.push(status(&app, permission).await);
+    }
+    Ok(OnboardingStatus {
+        first_launch,
+        permissions,
+    })
+}
+
+// Shows the OS prompt where there is one. Accessibility and screen recording
+// only open System Settings once they have been denied, so the returned
+// status may still be ungranted until the user comes back.
+#[tauri::command]
+#[specta::specta]
+pub async fn request_os_permission(
+    app: AppHandle,
+    permission: Permission,
+) -> Result<PermissionStatus, AppError> {
+    match permission {
+        Permission::Accessibility => request_accessibility_permission().await,
+        Permission::ScreenRecording => request_screen_recording_permission().await,
+        Permission::Microphone => request_microphone_permission()
+            .await
+            .map_err(|e| AppError::PermissionDenied(e.to_string()))?,
+        Permission::Notifications => {
+            app.notification()
+                .request_permission()
+                .map_err(|e| format!("Failed to request notifications: {}", e))?;
+        }
+    }
+    Ok(status(&app, permission).await)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn open_permission_settings(app: AppHandle, permission: Permission) -> Result<(), AppError> {
+    let url = Native::permission_settings_url(permission).ok_or_else(|| {
+        AppError::InvalidInput(format!(
+            "{} has no settings pane on this platform",
+            label(permission)
+        ))
+    })?;
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| format!("Failed to open {}: {}", url, e))?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn complete_onboarding(settings_manager: State<'_, SettingsManager>) -> Result<(), AppError> {
+    settings_manager.update(|settings| settings.onboarding_completed = true)?;
+    Ok(())
+}
@@ -0,0 +1,288 @@
+use crate::error::AppError;
+use crate::providers::{self, Provider};
+use crate::settings::SettingsManager;
+use crate::{local_http, ollama};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use tauri::{AppHandle, Manager, State};
+use tokio::net::TcpStream;
+
+// OpenAI-compatible chat API for scripts and tools on this machine, e.g.
+// `OPENAI_BASE_URL=http://127.0.0.1:7332/v1` with the token as the API key
+const DEFAULT_API_PORT: u16 = 7332;
+const TOKEN_SECRET: &str = "openai_api_token";
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+// Requests with this header (or the `user` field) continue an app session;
+// without it every request is a one-shot prompt
+const SESSION_HEADER: &str = "x-mix-session-id";
+// Answered by the session's provider; `ollama/<model>` picks an Ollama model
+const DEFAULT_MODEL: &str = "mix";
+const OLLAMA_MODEL_PREFIX: &str = "ollama/";
+const JSON: &str = "application/json";
+
+static LISTENER: local_http::Listener = local_http::Listener::new("Local OpenAI-compatible API");
+
+#[derive(Debug, Serialize, Type)]
+pub struct OpenAiApiInfo {
+    pub enabled: bool,
+    pub base_url: String,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatRequest {
+    #[serde(default)]
+    model: Option<String>,
+    messages: Vec<ollama::ChatMessage>,
+    #[serde(default)]
+    stream: bool,
+    #[serde(default)]
+    user: Option<String>,
+}
+
+fn port(app: &AppHandle) -> u16 {
+    app.state::<SettingsManager>()
+        .get()
+        .openai_api_port
+        .unwrap_or(DEFAULT_API_PORT)
+}
+
+// The app session `request` continues, None for a one-shot prompt
+fn requested_session(request: &local_http::Request, chat: &ChatRequest) -> Option<String> {
+    request
+        .headers
+        .get(SESSION_HEADER)
+        .cloned()
+        .or_else(|| chat.user.clone())
+        .filter(|session| !session.is_empty())
+}
+
+fn error_body(message: &str) -> String {
+    json!({ "error": { "message": message, "type": "invalid_request_error" } }).to_string()
+}
+
+async fn list_models(app: &AppHandle) -> Value {
+    let mut models = vec![json!({ "id": DEFAULT_MODEL, "object": "model", "owned_by": "mix" })];
+    // Ollama being absent just means fewer models
+    if let Ok(ollama_models) = ollama::list_ollama_models(app.state()).await {
+        models.extend(ollama_models.into_iter().map(|model| {
+            json!({
+                "id": format!("{}{}", OLLAMA_MODEL_PREFIX, model.name),
+                "object": "model",
+                "owned_by": "ollama",
+            })
+        }));
+    }
+    json!({ "object": "list", "data": models })
+}
+
+// The session keeps the conversation, so only the latest user message is sent;
+// an empty `session_id` is a one-shot prompt
+async fn chat_completion(
+    app: &AppHandle,
+    request: ChatRequest,
+    session_id: &str,
+) -> Result<Value, String> {
+    let prompt = request
+        .messages
+        .iter()
+        .rev()
+        .find(|message| message.role == "user")
+        .map(|message| message.content.clone())
+        .ok_or_else(|| "No user message to answer".to_string())?;
+    let model = request.model.unwrap_or_else(|| DEFAULT_MODEL.to_string());
+    let provider = match model.strip_prefix(OLLAMA_MODEL_PREFIX) {
+        Some(name) => Provider::Ollama {
+            model: name.to_string(),
+        },
+        None => providers::provider(&app.state::<SettingsManager>(), session_id),
+    };
+
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    log::info!(
+        "[trace {}] Local API prompt for session {}",
+        trace_id,
+        session_id
+    );
+    let text = providers::send(app, session_id, provider, &prompt, &trace_id).await?;
+
+    Ok(json!({
+        "id": format!("chatcmpl-{}", trace_id),
+        "object": "chat.completion",
+        "created": chrono::Utc::now().timestamp(),
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": text },
+            "finish_reason": "stop",
+        }],
+    }))
+}
+
+// Replies arrive whole, so a streamed completion is a single chunk
+fn as_event_stream(completion: &Value) -> String {
+    let chunk = json!({
+        "id": completion["id"],
+        "object": "chat.completion.chunk",
+        "created": completion["created"],
+        "model": completion["model"],
+        "choices": [{
+            "index": 0,
+            "delta": completion["choices"][0]["message"],
+            "finish_reason": "stop",
+        }],
+    });
+    format!("data: {}\n\ndata: [DONE]\n\n", chunk)
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream, token: String) {
+    let request = match local_http::read_request(&mut stream, MAX_BODY_BYTES).await {
+        Ok(request) => request,
+        Err(Some(status)) => return local_http::respond(&mut stream, status, JSON, "").await,
+        Err(None) => return,
+    };
+    if !request.has_bearer_token(&token) || request.is_browser() {
+        let body = error_body("Invalid API key");
+        return local_http::respond(&mut stream, "401 Unauthorized", JSON, &body).await;
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/v1/models") => {
+            let body = list_models(&app).await.to_string();
+            local_http::respond(&mut stream, "200 OK", JSON, &body).await
+        }
+        ("POST", "/v1/chat/completions") => {
+            let chat = match serde_json::from_slice::<ChatRequest>(&request.body) {
+                Ok(chat) => chat,
+                Err(e) => {
+                    let body = error_body(&format!("Invalid request: {}", e));
+                    return local_http::respond(&mut stream, "400 Bad Request", JSON, &body).await;
+                }
+            };
+            let session_id = requested_session(&request, &chat).unwrap_or_default();
+            let streamed = chat.stream;
+            match chat_completion(&app, chat, &session_id).await {
+                Ok(completion) if streamed => {
+                    let events = as_event_stream(&completion);
+                    local_http::respond(&mut stream, "200 OK", "text/event-stream", &events).await
+                }
+                Ok(completion) => {
+                    let body = completion.to_string();
+                    local_http::respond(&mut stream, "200 OK", JSON, &body).await
+                }
+                Err(e) => {
+                    log::error!("Local API prompt failed: {}", e);
+                    let body = error_body(&e);
+                    local_http::respond(&mut stream, "502 Bad Gateway", JSON, &body).await
+                }
+            }
+        }
+        _ => {
+            let body = error_body(&format!("Unknown endpoint {}", request.path));
+            local_http::respond(&mut stream, "404 Not Found", JSON, &body).await
+        }
+    }
+}
+
+// Starts the API if it is enabled in settings, replacing a running one
+pub fn start(app: &AppHandle) {
+    if !app.state::<SettingsManager>().get().openai_api_enabled {
+        return LISTENER.stop();
+    }
+    let token = match local_http::token(TOKEN_SECRET) {
+        Ok(token) => token,
+        Err(e) => {
+            log::error!("Local OpenAI-compatible API failed: {}", e);
+            return LISTENER.stop();
+        }
+    };
+    let app = app.clone();
+    LISTENER.start(port(&app), move |stream| {
+        handle_connection(app.clone(), stream, token.clone())
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_openai_api_info(app: AppHandle) -> Result<OpenAiApiInfo, AppError> {
+    let enabled = app.state::<SettingsManager>().get().openai_api_enabled;
+    Ok(OpenAiApiInfo {
+        enabled,
+        base_url: format!("http://127.0.0.1:{}/v1", port(&app)),
+        token: if enabled {
+            Some(local_http::token(TOKEN_SECRET)?)
+        } else {
+            None
+        },
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_openai_api_enabled(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<OpenAiApiInfo, AppError> {
+    settings_manager.update(|settings| {
+        settings.openai_api_enabled = enabled;
+        if port.is_some() {
+            settings.openai_api_port = port;
+        }
+    })?;
+    start(&app);
+    get_openai_api_info(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request(headers: &[(&str, &str)]) -> local_http::Request {
+        local_http::Request {
+            method: "POST".to_string(),
+            path: "/v1/chat/completions".to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>(),
+            body: Vec::new(),
+        }
+    }
+
+    fn chat(user: Option<&str>) -> ChatRequest {
+        serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": "hi" }],
+            "user": user,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn requests_with_the_same_header_continue_the_same_session() {
+        let first = requested_session(&request(&[(SESSION_HEADER, "s1")]), &chat(None));
+        let second = requested_session(&request(&[(SESSION_HEADER, "s1")]), &chat(Some("u")));
+
+        assert_eq!(first.as_deref(), Some("s1"));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn falls_back_to_the_user_field() {
+        let session = requested_session(&request(&[]), &chat(Some("u1")));
+
+        assert_eq!(session.as_deref(), Some("u1"));
+    }
+
+    #[test]
+    fn requests_without_a_session_are_one_shot() {
+        assert_eq!(requested_session(&request(&[]), &chat(None)), None);
+        assert_eq!(
+            requested_session(&request(&[(SESSION_HEADER, "")]), &chat(None)),
+            None
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::actions::{self, Action};
+use crate::app_lock;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+// Toggles closer together than this are key repeats or double clicks
+const TOGGLE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Visibility {
+    Hidden,
+    // Waiting on Touch ID before a locked window is shown
+    Unlocking,
+    Shown,
+}
+
+struct PanelState {
+    visibility: Visibility,
+    last_toggle: Option<Instant>,
+}
+
+// The one owner of the main window's visibility. The tray, the global
+// shortcut, AppleScript, the companion API, auto-lock and Escape in the
+// webview all go through it, so their transitions are applied one at a time
+// against the recorded state instead of racing on `is_visible()`.
+pub struct Panel {
+    state: Mutex<PanelState>,
+}
+
+impl Default for Panel {
+    // The main window is built visible
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(PanelState {
+                visibility: Visibility::Shown,
+                last_toggle: None,
+            }),
+        }
+    }
+}
+
+fn reveal(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn conceal(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+}
+
+// Called with the state locked. While locked, Touch ID is tried first;
+// otherwise the window opens on the passphrase screen.
+fn show_locked(app: &AppHandle, state: &mut PanelState) {
+    match state.visibility {
+        Visibility::Unlocking => {}
+        Visibility::Shown => reveal(app),
+        Visibility::Hidden if app_lock::is_locked() => {
+            state.visibility = Visibility::Unlocking;
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                app_lock::unlock_for_show(&app).await;
+                let panel = app.state::<Panel>();
+                let mut state = panel.state.lock().unwrap();
+                // A hide that arrived during the prompt wins
+                if state.visibility == Visibility::Unlocking {
+                    state.visibility = Visibility::Shown;
+                    reveal(&app);
+                }
+            });
+        }
+        Visibility::Hidden => {
+            state.visibility = Visibility::Shown;
+            reveal(app);
+        }
+    }
+}
+
+fn hide_locked(app: &AppHandle, state: &mut PanelState) {
+    if state.visibility != Visibility::Hidden {
+        state.visibility = Visibility::Hidden;
+        conceal(app);
+    }
+}
+
+pub fn show(app: &AppHandle) {
+    let panel = app.state::<Panel>();
+    let mut state = panel.state.lock().unwrap();
+    show_locked(app, &mut state);
+}
+
+pub fn hide(app: &AppHandle) {
+    let panel = app.state::<Panel>();
+    let mut state = panel.state.lock().unwrap();
+    hide_locked(app, &mut state);
+}
+
+// Toggles that arrive within the debounce window of the previous one are
+// dropped, so mashing the hotkey can't interleave shows and hides.
+pub fn toggle(app: &AppHandle) {
+    let panel = app.state::<Panel>();
+    let mut state = panel.state.lock().unwrap();
+    if state
+        .last_toggle
+        .is_some_and(|at| at.elapsed() < TOGGLE_DEBOUNCE)
+    {
+        log::debug!("Ignoring window toggle within debounce window");
+        return;
+    }
+    state.last_toggle = Some(Instant::now());
+
+    match state.visibility {
+        Visibility::Hidden => show_locked(app, &mut state),
+        Visibility::Unlocking | Visibility::Shown => hide_locked(app, &mut state),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn show_panel(app: AppHandle) {
+    show(&app);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn hide_panel(app: AppHandle) {
+    hide(&app);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn toggle_panel(app: AppHandle) {
+    toggle(&app);
+}
+
+pub fn register_actions() {
+    actions::register(
+        Action::new(
+            "window.toggle",
+            "Toggle window",
+            "Window",
+            &["show", "hide"],
+        ),
+        actions::handler(|app| async move {
+            toggle(&app);
+            Ok(())
+        }),
+    );
+    actions::register(
+        Action::new(
+            "window.hide",
+            "Hide window",
+            "Window",
+            &["close", "dismiss"],
+        ),
+        actions::handler(|app| async move {
+            hide(&app);
+            Ok(())
+        }),
+    );
+}
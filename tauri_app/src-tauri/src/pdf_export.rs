@@ -0,0 +1,933 @@
+use crate::actions::{self, Action};
+use crate::error::AppError;
+use crate::sidecar::SidecarManager;
+use crate::transcript::{self, TranscriptMessage};
+use crate::{generated_images, scratch, validation};
+use chrono::{DateTime, Local};
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+use tauri::{AppHandle, Manager};
+use ttf_parser::{Face, GlyphId};
+
+// Renders a conversation as a paginated A4 PDF, for people who don't use the
+// app. Text is set in the bundled DejaVu fonts, embedded as subsets of the
+// glyphs the document uses, so Greek, Cyrillic, Hebrew and the other scripts
+// they cover print as written. Characters the fonts lack print as boxes.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+// Page numbers sit in the bottom margin
+const FOOTER_Y: f32 = MARGIN / 2.0;
+const BODY_SIZE: f32 = 10.5;
+const BODY_LEADING: f32 = 15.0;
+const CODE_SIZE: f32 = 8.5;
+const CODE_LEADING: f32 = 11.5;
+const LIST_INDENT: f32 = 16.0;
+const QUOTE_INDENT: f32 = 12.0;
+// Images are downscaled to this many pixels across before embedding, and
+// never take more than this share of a page's height
+const MAX_IMAGE_PX: u32 = 1600;
+const MAX_IMAGE_HEIGHT: f32 = (PAGE_HEIGHT - 2.0 * MARGIN) * 0.6;
+const COMPRESSION_LEVEL: u8 = 6;
+
+// Parsed once, in `STYLES` order
+static FACES: LazyLock<Vec<Face<'static>>> = LazyLock::new(|| {
+    STYLES
+        .iter()
+        .map(|style| Face::parse(style.font_data(), 0).expect("bundled font is valid"))
+        .collect()
+});
+
+// Fonts with Identity-H encoding name Adobe's identity collection
+const IDENTITY: SystemInfo = SystemInfo {
+    registry: Str(b"Adobe"),
+    ordering: Str(b"Identity"),
+    supplement: 0,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Code,
+}
+
+const STYLES: [Style; 5] = [
+    Style::Regular,
+    Style::Bold,
+    Style::Italic,
+    Style::BoldItalic,
+    Style::Code,
+];
+
+impl Style {
+    fn new(bold: bool, italic: bool) -> Self {
+        match (bold, italic) {
+            (false, false) => Style::Regular,
+            (true, false) => Style::Bold,
+            (false, true) => Style::Italic,
+            (true, true) => Style::BoldItalic,
+        }
+    }
+
+    // Resource name on every page
+    fn font(self) -> Name<'static> {
+        Name(match self {
+            Style::Regular => b"F1",
+            Style::Bold => b"F2",
+            Style::Italic => b"F3",
+            Style::BoldItalic => b"F4",
+            Style::Code => b"F5",
+        })
+    }
+
+    fn font_data(self) -> &'static [u8] {
+        match self {
+            Style::Regular => include_bytes!("../resources/fonts/DejaVuSans.ttf"),
+            Style::Bold => include_bytes!("../resources/fonts/DejaVuSans-Bold.ttf"),
+            Style::Italic => include_bytes!("../resources/fonts/DejaVuSans-Oblique.ttf"),
+            Style::BoldItalic => include_bytes!("../resources/fonts/DejaVuSans-BoldOblique.ttf"),
+            Style::Code => include_bytes!("../resources/fonts/DejaVuSansMono.ttf"),
+        }
+    }
+
+    // The tag before the `+` marks the embedded font as a subset
+    fn base_font(self) -> Name<'static> {
+        Name(match self {
+            Style::Regular => b"MIXAAA+DejaVuSans".as_slice(),
+            Style::Bold => b"MIXAAB+DejaVuSans-Bold",
+            Style::Italic => b"MIXAAC+DejaVuSans-Oblique",
+            Style::BoldItalic => b"MIXAAD+DejaVuSans-BoldOblique",
+            Style::Code => b"MIXAAE+DejaVuSansMono",
+        })
+    }
+
+    fn face(self) -> &'static Face<'static> {
+        &FACES[self as usize]
+    }
+
+    // 0, the font's "missing" box, when it has no glyph for `c`
+    fn glyph(self, c: char) -> u16 {
+        self.face().glyph_index(c).map_or(0, |glyph| glyph.0)
+    }
+
+    // In thousandths of the font size
+    fn glyph_width(self, glyph: u16) -> f32 {
+        let face = self.face();
+        let advance = face.glyph_hor_advance(GlyphId(glyph)).unwrap_or(0);
+        advance as f32 * 1000.0 / face.units_per_em() as f32
+    }
+
+    fn char_width(self, c: char) -> f32 {
+        self.glyph_width(self.glyph(c))
+    }
+
+    fn width(self, text: &str, size: f32) -> f32 {
+        text.chars().map(|c| self.char_width(c)).sum::<f32>() * size / 1000.0
+    }
+}
+
+// The glyphs each style's text used, in `STYLES` order, with the character
+// each one shows. The embedded subsets and the maps that make the text
+// searchable and copyable cover exactly these.
+type UsedGlyphs = [BTreeMap<u16, char>; 5];
+
+// Two bytes per glyph ID, as the Identity-H encoding reads them
+fn encode(style: Style, text: &str, used: &mut UsedGlyphs) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len() * 2);
+    for c in text.chars() {
+        let glyph = style.glyph(c);
+        used[style as usize].entry(glyph).or_insert(c);
+        bytes.extend(glyph.to_be_bytes());
+    }
+    bytes
+}
+
+// A Type0 font over the TrueType subset, with the advance widths and a
+// ToUnicode map of the glyphs in `used`
+fn write_font(pdf: &mut Pdf, style: Style, ids: [Ref; 5], used: &BTreeMap<u16, char>) {
+    let [font_id, cid_font_id, descriptor_id, file_id, cmap_id] = ids;
+    let face = style.face();
+    let scale = 1000.0 / face.units_per_em() as f32;
+
+    pdf.type0_font(font_id)
+        .base_font(style.base_font())
+        .encoding_predefined(Name(b"Identity-H"))
+        .descendant_font(cid_font_id)
+        .to_unicode(cmap_id);
+
+    let mut cid_font = pdf.cid_font(cid_font_id);
+    cid_font
+        .subtype(CidFontType::Type2)
+        .base_font(style.base_font())
+        .system_info(IDENTITY)
+        .font_descriptor(descriptor_id)
+        .cid_to_gid_map_predefined(Name(b"Identity"));
+    let mut widths = cid_font.widths();
+    for &glyph in used.keys() {
+        widths.consecutive(glyph, [style.glyph_width(glyph)]);
+    }
+    widths.finish();
+    cid_font.finish();
+
+    let mut flags = FontFlags::SYMBOLIC;
+    if style == Style::Code {
+        flags |= FontFlags::FIXED_PITCH;
+    }
+    if face.is_italic() {
+        flags |= FontFlags::ITALIC;
+    }
+    let bbox = face.global_bounding_box();
+    pdf.font_descriptor(descriptor_id)
+        .name(style.base_font())
+        .flags(flags)
+        .bbox(Rect::new(
+            bbox.x_min as f32 * scale,
+            bbox.y_min as f32 * scale,
+            bbox.x_max as f32 * scale,
+            bbox.y_max as f32 * scale,
+        ))
+        .italic_angle(face.italic_angle())
+        .ascent(face.ascender() as f32 * scale)
+        .descent(face.descender() as f32 * scale)
+        .cap_height(face.capital_height().unwrap_or(face.ascender()) as f32 * scale)
+        .stem_v(if face.is_bold() { 120.0 } else { 80.0 })
+        .font_file2(file_id);
+
+    // Subsetting keeps the glyph IDs, so a failure only costs file size
+    let glyphs: Vec<u16> = used.keys().copied().collect();
+    let data = subsetter::subset(style.font_data(), 0, subsetter::Profile::pdf(&glyphs))
+        .unwrap_or_else(|e| {
+            log::warn!("Embedding all of {:?}, subsetting failed: {}", style, e);
+            style.font_data().to_vec()
+        });
+    let data = compress_to_vec_zlib(&data, COMPRESSION_LEVEL);
+    pdf.stream(file_id, &data).filter(Filter::FlateDecode);
+
+    // The missing box stands for whichever characters the font lacks
+    let mut cmap = UnicodeCmap::new(Name(b"Custom"), IDENTITY);
+    for (&glyph, &c) in used.iter().filter(|(&glyph, _)| glyph != 0) {
+        cmap.pair(glyph, c);
+    }
+    pdf.cmap(cmap_id, &cmap.finish());
+}
+
+struct Span {
+    text: String,
+    style: Style,
+}
+
+enum Block {
+    Heading(HeadingLevel, Vec<Span>),
+    Paragraph {
+        spans: Vec<Span>,
+        indent: f32,
+        // Bullet or number of a list item's first paragraph
+        marker: Option<String>,
+        quote: bool,
+    },
+    Code(String),
+    Image {
+        src: String,
+        alt: String,
+    },
+    Rule,
+}
+
+// Markdown to the blocks the layout knows. Tables become one line per row,
+// with the cells separated by bars.
+fn blocks(markdown: &str) -> Vec<Block> {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut blocks = Vec::new();
+    let mut spans: Vec<Span> = Vec::new();
+    let mut bold = 0;
+    let mut italic = 0;
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    let mut quotes = 0;
+    let mut marker: Option<String> = None;
+    let mut code: Option<String> = None;
+    let mut image: Option<(String, String)> = None;
+    let mut link: Option<String> = None;
+
+    let flush = |blocks: &mut Vec<Block>,
+                 spans: &mut Vec<Span>,
+                 marker: &mut Option<String>,
+                 lists: usize,
+                 quotes: usize| {
+        if spans.iter().all(|span| span.text.trim().is_empty()) {
+            spans.clear();
+            return;
+        }
+        blocks.push(Block::Paragraph {
+            spans: std::mem::take(spans),
+            indent: lists as f32 * LIST_INDENT + quotes as f32 * QUOTE_INDENT,
+            marker: marker.take(),
+            quote: quotes > 0,
+        });
+    };
+
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::Paragraph | Tag::Heading { .. } | Tag::Table(_)) => {
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+            }
+            Event::End(TagEnd::Paragraph | TagEnd::Item | TagEnd::TableHead | TagEnd::TableRow) => {
+                if spans.last().is_some_and(|span| span.text == " | ") {
+                    spans.pop();
+                }
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+            }
+            Event::End(TagEnd::Heading(level)) => {
+                blocks.push(Block::Heading(level, std::mem::take(&mut spans)));
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+                quotes += 1;
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+                quotes -= 1;
+            }
+            Event::Start(Tag::List(start)) => {
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+                lists.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+                lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+                marker = Some(match lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => "•".to_string(),
+                });
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+                code = Some(String::new());
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(code) = code.take() {
+                    blocks.push(Block::Code(code));
+                }
+            }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+                image = Some((dest_url.to_string(), String::new()));
+            }
+            Event::End(TagEnd::Image) => {
+                if let Some((src, alt)) = image.take() {
+                    blocks.push(Block::Image { src, alt });
+                }
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => link = Some(dest_url.to_string()),
+            // Paper can't be clicked, so web links print their address
+            Event::End(TagEnd::Link) => {
+                if let Some(url) = link.take() {
+                    let text: String = spans.iter().map(|span| span.text.as_str()).collect();
+                    if url.starts_with("http") && !text.ends_with(url.as_str()) {
+                        spans.push(Span {
+                            text: format!(" ({})", url),
+                            style: Style::Regular,
+                        });
+                    }
+                }
+            }
+            Event::Start(Tag::Strong) => bold += 1,
+            Event::End(TagEnd::Strong) => bold -= 1,
+            Event::Start(Tag::Emphasis) => italic += 1,
+            Event::End(TagEnd::Emphasis) => italic -= 1,
+            Event::End(TagEnd::TableCell) => spans.push(Span {
+                text: " | ".to_string(),
+                style: Style::Regular,
+            }),
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                if let Some(code) = code.as_mut() {
+                    code.push_str(&text);
+                } else if let Some((_, alt)) = image.as_mut() {
+                    alt.push_str(&text);
+                } else {
+                    spans.push(Span {
+                        text: text.to_string(),
+                        style: Style::new(bold > 0, italic > 0),
+                    });
+                }
+            }
+            Event::Code(text) | Event::InlineMath(text) | Event::DisplayMath(text) => {
+                spans.push(Span {
+                    text: text.to_string(),
+                    style: Style::Code,
+                })
+            }
+            Event::SoftBreak => spans.push(Span {
+                text: " ".to_string(),
+                style: Style::Regular,
+            }),
+            Event::HardBreak => spans.push(Span {
+                text: "\n".to_string(),
+                style: Style::Regular,
+            }),
+            Event::TaskListMarker(done) => spans.push(Span {
+                text: if done { "[x] " } else { "[ ] " }.to_string(),
+                style: Style::Code,
+            }),
+            Event::Rule => {
+                flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+                blocks.push(Block::Rule);
+            }
+            _ => {}
+        }
+    }
+    flush(&mut blocks, &mut spans, &mut marker, lists.len(), quotes);
+    blocks
+}
+
+enum Token {
+    Word {
+        text: String,
+        style: Style,
+        space_before: bool,
+    },
+    Break,
+}
+
+// Words keep their style; a word split across spans, like `code`, stays
+// glued to the punctuation after it
+fn tokens(spans: &[Span]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut space = false;
+    for span in spans {
+        if span.text == "\n" {
+            tokens.push(Token::Break);
+            space = false;
+            continue;
+        }
+        let mut word = String::new();
+        let mut space_before = space;
+        for c in span.text.chars() {
+            if c.is_whitespace() {
+                if !word.is_empty() {
+                    tokens.push(Token::Word {
+                        text: std::mem::take(&mut word),
+                        style: span.style,
+                        space_before,
+                    });
+                }
+                space = true;
+            } else {
+                if word.is_empty() {
+                    space_before = space;
+                    space = false;
+                }
+                word.push(c);
+            }
+        }
+        if !word.is_empty() {
+            tokens.push(Token::Word {
+                text: word,
+                style: span.style,
+                space_before,
+            });
+        }
+    }
+    tokens
+}
+
+type Line = Vec<(Style, String)>;
+
+fn push_text(line: &mut Line, style: Style, text: &str) {
+    match line.last_mut() {
+        Some((last, pieces)) if *last == style => pieces.push_str(text),
+        _ => line.push((style, text.to_string())),
+    }
+}
+
+// Greedy line breaking; words wider than a line are broken between characters
+fn wrap(spans: &[Span], size: f32, width: f32) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut line: Line = Vec::new();
+    let mut line_width = 0.0;
+    for token in tokens(spans) {
+        let (text, style, space_before) = match token {
+            Token::Break => {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0.0;
+                continue;
+            }
+            Token::Word {
+                text,
+                style,
+                space_before,
+            } => (text, style, space_before),
+        };
+        let word_width = style.width(&text, size);
+        let space_width = if space_before && !line.is_empty() {
+            style.width(" ", size)
+        } else {
+            0.0
+        };
+        if !line.is_empty() && line_width + space_width + word_width > width {
+            lines.push(std::mem::take(&mut line));
+            line_width = 0.0;
+        } else if space_width > 0.0 {
+            push_text(&mut line, style, " ");
+            line_width += space_width;
+        }
+
+        if word_width <= width {
+            push_text(&mut line, style, &text);
+            line_width += word_width;
+            continue;
+        }
+        for c in text.chars() {
+            let char_width = style.char_width(c) * size / 1000.0;
+            if !line.is_empty() && line_width + char_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0.0;
+            }
+            push_text(&mut line, style, c.encode_utf8(&mut [0; 4]));
+            line_width += char_width;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+struct EncodedImage {
+    width: u32,
+    height: u32,
+    rgb: Vec<u8>,
+    alpha: Option<Vec<u8>>,
+}
+
+fn encode_image(bytes: &[u8]) -> Result<EncodedImage, String> {
+    let mut image =
+        image::load_from_memory(bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+    if image.width() > MAX_IMAGE_PX {
+        image = image.thumbnail(MAX_IMAGE_PX, u32::MAX);
+    }
+    let alpha = image.color().has_alpha().then(|| {
+        let alpha: Vec<u8> = image.to_rgba8().pixels().map(|pixel| pixel[3]).collect();
+        compress_to_vec_zlib(&alpha, COMPRESSION_LEVEL)
+    });
+    Ok(EncodedImage {
+        width: image.width(),
+        height: image.height(),
+        rgb: compress_to_vec_zlib(image.to_rgb8().as_raw(), COMPRESSION_LEVEL),
+        alpha,
+    })
+}
+
+fn image_name(index: usize) -> String {
+    format!("Im{}", index + 1)
+}
+
+// Places blocks top to bottom, starting a page when the next line or image
+// doesn't fit. `y` is the top of the free space, in PDF units from the bottom.
+struct Layout {
+    pages: Vec<Content>,
+    page: Content,
+    y: f32,
+    images: Vec<EncodedImage>,
+    glyphs: UsedGlyphs,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Layout {
+            pages: Vec::new(),
+            page: Content::new(),
+            y: PAGE_HEIGHT - MARGIN,
+            images: Vec::new(),
+            glyphs: Default::default(),
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.pages
+            .push(std::mem::replace(&mut self.page, Content::new()));
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y = (self.y - height).max(MARGIN);
+    }
+
+    // Reserves a line of `leading` and returns its baseline
+    fn advance(&mut self, leading: f32) -> f32 {
+        if self.y - leading < MARGIN {
+            self.new_page();
+        }
+        self.y -= leading;
+        self.y + leading * 0.28
+    }
+
+    fn text_at(&mut self, x: f32, baseline: f32, size: f32, gray: f32, line: &[(Style, String)]) {
+        self.page.set_fill_gray(gray);
+        self.page.begin_text();
+        self.page.next_line(x, baseline);
+        for (style, text) in line {
+            self.page.set_font(style.font(), size);
+            self.page.show(Str(&encode(*style, text, &mut self.glyphs)));
+        }
+        self.page.end_text();
+    }
+
+    fn heading(&mut self, level: HeadingLevel, spans: &[Span]) {
+        let size = match level {
+            HeadingLevel::H1 => 18.0,
+            HeadingLevel::H2 => 15.0,
+            HeadingLevel::H3 => 13.0,
+            _ => 11.5,
+        };
+        let spans: Vec<Span> = spans
+            .iter()
+            .map(|span| Span {
+                text: span.text.clone(),
+                style: if span.style == Style::Code {
+                    Style::Code
+                } else {
+                    Style::Bold
+                },
+            })
+            .collect();
+        self.space(size * 0.6);
+        for line in wrap(&spans, size, CONTENT_WIDTH) {
+            let baseline = self.advance(size * 1.35);
+            self.text_at(MARGIN, baseline, size, 0.0, &line);
+        }
+        self.space(2.0);
+    }
+
+    fn paragraph(&mut self, spans: &[Span], indent: f32, marker: Option<&str>, quote: bool) {
+        let gray = if quote { 0.35 } else { 0.0 };
+        let x = MARGIN + indent;
+        for (i, line) in wrap(spans, BODY_SIZE, CONTENT_WIDTH - indent)
+            .iter()
+            .enumerate()
+        {
+            let baseline = self.advance(BODY_LEADING);
+            if quote {
+                self.page.set_fill_gray(0.8);
+                self.page
+                    .rect(MARGIN + indent - QUOTE_INDENT, self.y, 2.0, BODY_LEADING)
+                    .fill_nonzero();
+            }
+            if let (0, Some(marker)) = (i, marker) {
+                let marker = [(Style::Regular, marker.to_string())];
+                self.text_at(x - LIST_INDENT + 4.0, baseline, BODY_SIZE, gray, &marker);
+            }
+            self.text_at(x, baseline, BODY_SIZE, gray, line);
+        }
+        self.space(5.0);
+    }
+
+    fn code(&mut self, code: &str) {
+        let columns = (CONTENT_WIDTH - 12.0) / Style::Code.width(" ", CODE_SIZE);
+        let code = code.trim_end_matches('\n').replace('\t', "    ");
+        self.space(2.0);
+        for line in code.lines() {
+            let chars: Vec<char> = line.chars().collect();
+            let chunks: Vec<String> = if chars.is_empty() {
+                vec![String::new()]
+            } else {
+                chars
+                    .chunks(columns.max(1.0) as usize)
+                    .map(|chunk| chunk.iter().collect())
+                    .collect()
+            };
+            for chunk in chunks {
+                let baseline = self.advance(CODE_LEADING);
+                self.page.set_fill_gray(0.95);
+                self.page
+                    .rect(MARGIN, self.y, CONTENT_WIDTH, CODE_LEADING)
+                    .fill_nonzero();
+                self.text_at(
+                    MARGIN + 6.0,
+                    baseline,
+                    CODE_SIZE,
+                    0.15,
+                    &[(Style::Code, chunk)],
+                );
+            }
+        }
+        self.space(7.0);
+    }
+
+    fn image(&mut self, image: EncodedImage) {
+        // 96 dpi pixels to points, shrunk to fit the column and page
+        let mut width = (image.width as f32 * 0.75).min(CONTENT_WIDTH);
+        let mut height = width * image.height as f32 / image.width as f32;
+        if height > MAX_IMAGE_HEIGHT {
+            width *= MAX_IMAGE_HEIGHT / height;
+            height = MAX_IMAGE_HEIGHT;
+        }
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+        self.y -= height;
+        let name = image_name(self.images.len());
+        self.page.save_state();
+        self.page
+            .transform([width, 0.0, 0.0, height, MARGIN, self.y]);
+        self.page.x_object(Name(name.as_bytes()));
+        self.page.restore_state();
+        self.images.push(image);
+        self.space(8.0);
+    }
+
+    fn rule(&mut self) {
+        self.space(6.0);
+        if self.y - 6.0 < MARGIN {
+            self.new_page();
+        }
+        self.page.set_stroke_gray(0.8);
+        self.page.set_line_width(0.5);
+        self.page.move_to(MARGIN, self.y);
+        self.page.line_to(PAGE_WIDTH - MARGIN, self.y);
+        self.page.stroke();
+        self.space(8.0);
+    }
+
+    fn finish(mut self, title: &str) -> Vec<u8> {
+        self.new_page();
+        let mut ids = (1..).map(Ref::new);
+        let mut id = || ids.next().unwrap();
+        let catalog_id = id();
+        let tree_id = id();
+        let info_id = id();
+        let font_ids: Vec<[Ref; 5]> = STYLES
+            .iter()
+            .map(|_| [id(), id(), id(), id(), id()])
+            .collect();
+        let image_ids: Vec<(Ref, Option<Ref>)> = self
+            .images
+            .iter()
+            .map(|image| (id(), image.alpha.as_ref().map(|_| id())))
+            .collect();
+        let page_ids: Vec<(Ref, Ref)> = self.pages.iter().map(|_| (id(), id())).collect();
+
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(tree_id);
+        pdf.pages(tree_id)
+            .kids(page_ids.iter().map(|(page_id, _)| *page_id))
+            .count(page_ids.len() as i32);
+        pdf.document_info(info_id)
+            .title(TextStr(title))
+            .creator(TextStr("Mix"));
+
+        let count = self.pages.len();
+        for (number, (mut content, (page_id, content_id))) in
+            self.pages.into_iter().zip(&page_ids).enumerate()
+        {
+            let footer = format!("{} / {}", number + 1, count);
+            let footer_x = PAGE_WIDTH - MARGIN - Style::Regular.width(&footer, 8.0);
+            content.set_fill_gray(0.5);
+            for (x, text) in [(MARGIN, title), (footer_x, footer.as_str())] {
+                content.begin_text();
+                content.set_font(Style::Regular.font(), 8.0);
+                content.next_line(x, FOOTER_Y);
+                content.show(Str(&encode(Style::Regular, text, &mut self.glyphs)));
+                content.end_text();
+            }
+
+            let mut page = pdf.page(*page_id);
+            page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+            page.parent(tree_id);
+            page.contents(*content_id);
+            let mut resources = page.resources();
+            let mut fonts = resources.fonts();
+            for (style, [font_id, ..]) in STYLES.iter().zip(&font_ids) {
+                fonts.pair(style.font(), *font_id);
+            }
+            fonts.finish();
+            let mut x_objects = resources.x_objects();
+            for (index, (image_id, _)) in image_ids.iter().enumerate() {
+                let name = image_name(index);
+                x_objects.pair(Name(name.as_bytes()), *image_id);
+            }
+            x_objects.finish();
+            resources.finish();
+            page.finish();
+
+            let stream = compress_to_vec_zlib(&content.finish(), COMPRESSION_LEVEL);
+            pdf.stream(*content_id, &stream).filter(Filter::FlateDecode);
+        }
+
+        for ((style, ids), used) in STYLES.iter().zip(font_ids).zip(&self.glyphs) {
+            write_font(&mut pdf, *style, ids, used);
+        }
+        for (image, (image_id, mask_id)) in self.images.iter().zip(&image_ids) {
+            let mut xobject = pdf.image_xobject(*image_id, &image.rgb);
+            xobject.filter(Filter::FlateDecode);
+            xobject.width(image.width as i32);
+            xobject.height(image.height as i32);
+            xobject.color_space().device_rgb();
+            xobject.bits_per_component(8);
+            if let Some(mask_id) = mask_id {
+                xobject.s_mask(*mask_id);
+            }
+            xobject.finish();
+            if let (Some(alpha), Some(mask_id)) = (&image.alpha, mask_id) {
+                let mut mask = pdf.image_xobject(*mask_id, alpha);
+                mask.filter(Filter::FlateDecode);
+                mask.width(image.width as i32);
+                mask.height(image.height as i32);
+                mask.color_space().device_gray();
+                mask.bits_per_component(8);
+            }
+        }
+        pdf.finish()
+    }
+}
+
+// Local images only, from a scratch dir or a granted folder; anything else
+// prints its alt text
+fn read_image(app: &AppHandle, src: &str) -> Option<Vec<u8>> {
+    let path = src.strip_prefix("file://").unwrap_or(src);
+    if !Path::new(path).is_absolute() {
+        return None;
+    }
+    let resolved = Path::new(path).canonicalize().ok()?;
+    if !scratch::contains(app, &resolved) {
+        validation::existing_path(app, "src", path).ok()?;
+    }
+    fs::read(resolved).ok()
+}
+
+fn render(app: &AppHandle, title: &str, messages: &[TranscriptMessage]) -> Vec<u8> {
+    let mut layout = Layout::new();
+    layout.heading(
+        HeadingLevel::H1,
+        &[Span {
+            text: title.to_string(),
+            style: Style::Bold,
+        }],
+    );
+
+    for message in messages {
+        let saved = generated_images::message_images(app, &message.id);
+        if message.content.trim().is_empty() && saved.is_empty() {
+            continue;
+        }
+        let speaker = if message.role == "user" { "You" } else { "Mix" };
+        let date = DateTime::from_timestamp(message.created_at, 0)
+            .map(|date| {
+                date.with_timezone(&Local)
+                    .format("  %b %-d, %Y %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        layout.space(10.0);
+        let baseline = layout.advance(14.0);
+        layout.text_at(
+            MARGIN,
+            baseline,
+            9.0,
+            0.45,
+            &[(Style::Bold, speaker.to_string()), (Style::Regular, date)],
+        );
+        layout.space(3.0);
+
+        for block in blocks(&message.content) {
+            match block {
+                Block::Heading(level, spans) => layout.heading(level, &spans),
+                Block::Paragraph {
+                    spans,
+                    indent,
+                    marker,
+                    quote,
+                } => layout.paragraph(&spans, indent, marker.as_deref(), quote),
+                Block::Code(code) => layout.code(&code),
+                Block::Image { src, alt } => {
+                    match read_image(app, &src).map(|bytes| encode_image(&bytes)) {
+                        Some(Ok(image)) => layout.image(image),
+                        _ => layout.paragraph(
+                            &[Span {
+                                text: format!(
+                                    "[image: {}]",
+                                    if alt.is_empty() { &src } else { &alt }
+                                ),
+                                style: Style::Italic,
+                            }],
+                            0.0,
+                            None,
+                            false,
+                        ),
+                    }
+                }
+                Block::Rule => layout.rule(),
+            }
+        }
+        for image in saved {
+            match fs::read(&image.path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| encode_image(&bytes))
+            {
+                Ok(image) => layout.image(image),
+                Err(e) => log::warn!("Skipping {} in the PDF: {}", image.path, e),
+            }
+        }
+    }
+    layout.finish(title)
+}
+
+pub fn register_actions() {
+    actions::register_webview(Action::new(
+        "session.export_pdf",
+        "Export session as PDF",
+        "Session",
+        &["print", "pdf", "save", "share"],
+    ));
+}
+
+// Writes the session's transcript, with its code and images, to `path`
+#[tauri::command]
+#[specta::specta]
+pub async fn export_session_pdf(
+    app: AppHandle,
+    session_id: String,
+    path: String,
+) -> Result<(), AppError> {
+    validation::uuid("sessionId", &session_id)?;
+    let path = validation::output_path(&app, "path", &path)?;
+    let sidecar = app.state::<Arc<SidecarManager>>();
+    let transcript::Transcript { title, messages } =
+        transcript::session(&sidecar, &session_id).await?;
+    if messages.is_empty() {
+        return Err(AppError::InvalidInput(
+            "The session has no messages to export".to_string(),
+        ));
+    }
+    let title = if title.trim().is_empty() {
+        "Untitled session".to_string()
+    } else {
+        title
+    };
+
+    let pages = {
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || render(&app, &title, &messages))
+            .await
+            .map_err(|e| format!("Failed to render the PDF: {}", e))?
+    };
+    fs::write(&path, pages)?;
+    log::info!("Exported session {} to {}", session_id, path.display());
+    Ok(())
+}
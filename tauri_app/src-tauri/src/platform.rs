@@ -0,0 +1,175 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::tray::TrayIconBuilder;
+use tauri::webview::WebviewWindowBuilder;
+use tauri::{App, Manager, Runtime, WebviewWindow};
+use tauri_plugin_global_shortcut::Shortcut;
+
+// Everything that differs between the desktop platforms lives behind this
+// trait, with one implementation per OS. Call it through `Native`, which is
+// the implementation for the platform being built.
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "linux")]
+pub use linux::Linux as Native;
+#[cfg(target_os = "macos")]
+pub use macos::MacOs as Native;
+#[cfg(target_os = "windows")]
+pub use windows::Windows as Native;
+
+#[derive(serde::Serialize, specta::Type)]
+pub struct AppInfo {
+    pub name: String,
+    pub icon_png_base64: String,
+}
+
+// OS permissions the onboarding checklist walks through, see `onboarding`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    Accessibility,
+    ScreenRecording,
+    Microphone,
+    Notifications,
+}
+
+// System sounds the app can play, see `sounds`. Each OS has its own take on
+// them, so they're named for what they're used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Sound {
+    Chime,
+    Alert,
+    Attention,
+    Tap,
+}
+
+// What the share sheet hands to the chosen service, see `share`. Only macOS
+// has a share sheet, so elsewhere these are never read.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum ShareItem {
+    Text(String),
+    File(PathBuf),
+}
+
+// Element the share sheet points at, in CSS pixels from the top left of the
+// window's content
+#[derive(Debug, Clone, Copy, serde::Deserialize, specta::Type)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct ShareAnchor {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+// Display and assistive technology settings the UI follows, see
+// `accessibility`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, specta::Type)]
+pub struct AccessibilityPrefs {
+    pub reduce_motion: bool,
+    pub increase_contrast: bool,
+    // VoiceOver, Narrator or Orca is running
+    pub screen_reader: bool,
+}
+
+pub trait Platform {
+    // Window styling, applied before and after the main window is built
+    fn style_window_builder<'a, R: Runtime, M: Manager<R>>(
+        builder: WebviewWindowBuilder<'a, R, M>,
+    ) -> WebviewWindowBuilder<'a, R, M>;
+    fn style_window(window: &WebviewWindow);
+
+    // Tray icon behaviour that the OS dictates
+    fn style_tray<R: Runtime>(builder: TrayIconBuilder<R>) -> TrayIconBuilder<R>;
+
+    // The shortcut that toggles the main window
+    fn toggle_shortcut() -> Shortcut;
+
+    // Process control: keep headless launch modes out of the dock and app
+    // switcher, and report on the rest of the machine
+    fn run_in_background(app: &mut App);
+    fn running_apps() -> Vec<AppInfo>;
+    fn on_battery() -> bool;
+    // PID of the process listening on a local TCP port, when the OS says
+    fn port_owner(port: u16) -> Option<u32>;
+
+    // Deep link to the settings pane that grants `permission`, where the OS
+    // has one
+    fn permission_settings_url(permission: Permission) -> Option<&'static str>;
+
+    // Read fresh on every call, since the OS doesn't tell apps about changes
+    // the same way everywhere
+    fn accessibility_prefs() -> AccessibilityPrefs;
+    // The animation the OS plays when the window is shown or hidden. Runs on
+    // the main thread.
+    fn set_window_animations(window: &WebviewWindow, enabled: bool);
+
+    // Plays `sound` without waiting for it to finish
+    fn play_sound(sound: Sound) -> Result<(), String>;
+    // Output is muted or a Focus/Do Not Disturb mode is on, where the OS says
+    fn sounds_silenced() -> bool;
+
+    // The user's languages as BCP 47 tags, most preferred first
+    fn preferred_languages() -> Vec<String>;
+
+    // Replaces the clipboard's contents with `text`
+    fn copy_text(text: &str) -> Result<(), String>;
+
+    // Makes the image at `path` the desktop picture
+    fn set_wallpaper(path: &Path) -> Result<(), String>;
+
+    // Saves the main display as a PNG at `path`, in its full pixel resolution
+    fn capture_screen(path: &Path) -> Result<(), String>;
+
+    // Shows the system share sheet for `item` over `window`, pointing at
+    // `anchor` or the top of the window. Runs on the main thread.
+    fn share(
+        window: &WebviewWindow,
+        item: ShareItem,
+        anchor: Option<ShareAnchor>,
+    ) -> Result<(), String>;
+
+    // Spellcheck dictionaries of the web engine, see `webview_language`. What
+    // has to be known before the engine starts goes on the builder; the rest
+    // is set on each window once built and again after a change.
+    fn webview_language_builder<'a, R: Runtime, M: Manager<R>>(
+        builder: WebviewWindowBuilder<'a, R, M>,
+        languages: &[String],
+    ) -> WebviewWindowBuilder<'a, R, M>;
+    fn set_spellcheck_languages(
+        window: &WebviewWindow,
+        enabled: bool,
+        languages: &[String],
+    ) -> Result<(), String>;
+}
+
+// Runs `command` with `input` on its stdin, for the clipboard tools each OS
+// ships
+fn pipe_to(mut command: Command, input: &str) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("Failed to write to {}: {}", program, e))?;
+    }
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for {}: {}", program, e))?;
+    if !status.success() {
+        return Err(format!("{} exited with {}", program, status));
+    }
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use super::{
+    pipe_to, AccessibilityPrefs, AppInfo, Permission, Platform, ShareAnchor, ShareItem, Sound,
+};
+use std::path::Path;
+use tauri::tray::TrayIconBuilder;
+use tauri::webview::WebviewWindowBuilder;
+use tauri::{App, Manager, Runtime, WebviewWindow};
+use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
+use webkit2gtk::{WebContextExt, WebViewExt};
+
+pub struct Linux;
+
+impl Platform for Linux {
+    fn style_window_builder<'a, R: Runtime, M: Manager<R>>(
+        builder: WebviewWindowBuilder<'a, R, M>,
+    ) -> WebviewWindowBuilder<'a, R, M> {
+        builder
+    }
+
+    fn style_window(_window: &WebviewWindow) {}
+
+    // AppIndicator trays don't report clicks, so the menu is the only way in
+    fn style_tray<R: Runtime>(builder: TrayIconBuilder<R>) -> TrayIconBuilder<R> {
+        builder.show_menu_on_left_click(true)
+    }
+
+    fn toggle_shortcut() -> Shortcut {
+        Shortcut::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyT)
+    }
+
+    fn run_in_background(_app: &mut App) {}
+
+    fn running_apps() -> Vec<AppInfo> {
+        Vec::new()
+    }
+
+    // On battery when no mains supply reports being online
+    fn on_battery() -> bool {
+        let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+            return false;
+        };
+        let mut has_battery = false;
+        for supply in supplies.flatten() {
+            let path = supply.path();
+            let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+            match kind.trim() {
+                "Mains"
+                    if std::fs::read_to_string(path.join("online"))
+                        .is_ok_and(|online| online.trim() == "1") =>
+                {
+                    return false;
+                }
+                "Battery" => has_battery = true,
+                _ => {}
+            }
+        }
+        has_battery
+    }
+
+    // `ss` ships with iproute2, unlike `lsof`; it prints `pid=<n>` per owner
+    fn port_owner(port: u16) -> Option<u32> {
+        let output = std::process::Command::new("ss")
+            .args(["-Hltnp", &format!("sport = :{}", port)])
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (_, rest) = stdout.split_once("pid=")?;
+        rest.split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    // Desktop environments don't share a settings URL scheme
+    fn permission_settings_url(_permission: Permission) -> Option<&'static str> {
+        None
+    }
+
+    // GNOME's settings, which other desktops' portals mirror
+    fn accessibility_prefs() -> AccessibilityPrefs {
+        let read = |schema: &str, key: &str| {
+            std::process::Command::new("gsettings")
+                .args(["get", schema, key])
+                .output()
+                .ok()
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "true")
+        };
+        AccessibilityPrefs {
+            reduce_motion: read("org.gnome.desktop.interface", "enable-animations")
+                .is_some_and(|enabled| !enabled),
+            increase_contrast: read("org.gnome.desktop.a11y.interface", "high-contrast")
+                .unwrap_or(false),
+            screen_reader: read(
+                "org.gnome.desktop.a11y.applications",
+                "screen-reader-enabled",
+            )
+            .unwrap_or(false),
+        }
+    }
+
+    // Window managers animate windows on their own terms
+    fn set_window_animations(_window: &WebviewWindow, _enabled: bool) {}
+
+    // Names from the freedesktop sound theme, played through libcanberra so
+    // the user's theme and event sound setting apply
+    fn play_sound(sound: Sound) -> Result<(), String> {
+        let id = match sound {
+            Sound::Chime => "complete",
+            Sound::Alert => "dialog-error",
+            Sound::Attention => "dialog-warning",
+            Sound::Tap => "message",
+        };
+        std::process::Command::new("canberra-gtk-play")
+            .args(["-i", id])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to run canberra-gtk-play: {}", e))
+    }
+
+    // The default PulseAudio/PipeWire sink is muted, or GNOME's Do Not
+    // Disturb hides banners
+    fn sounds_silenced() -> bool {
+        let muted = std::process::Command::new("pactl")
+            .args(["get-sink-mute", "@DEFAULT_SINK@"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains("yes"));
+        let do_not_disturb = std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "false");
+        muted || do_not_disturb
+    }
+
+    // GNU's LANGUAGE list wins over the locale, like gettext does; values look
+    // like "de_DE.UTF-8" or "sr_RS@latin"
+    fn preferred_languages() -> Vec<String> {
+        let list = std::env::var("LANGUAGE").unwrap_or_default();
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()));
+        list.split(':')
+            .map(str::to_string)
+            .chain(locale)
+            .filter_map(|value| {
+                let language = value.split(['.', '@']).next()?.replace('_', "-");
+                (!language.is_empty() && language != "C" && language != "POSIX").then_some(language)
+            })
+            .collect()
+    }
+
+    // Whichever clipboard tool is installed, Wayland's first
+    fn copy_text(text: &str) -> Result<(), String> {
+        let tools: [(&str, &[&str]); 3] = [
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ];
+        let mut last_error = String::new();
+        for (program, args) in tools {
+            let mut command = std::process::Command::new(program);
+            command.args(args);
+            match pipe_to(command, text) {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(format!("No clipboard tool worked: {}", last_error))
+    }
+
+    // GNOME and the desktops built on its settings schema; others are left
+    // alone
+    fn set_wallpaper(path: &Path) -> Result<(), String> {
+        let uri = format!("file://{}", path.to_string_lossy());
+        for key in ["picture-uri", "picture-uri-dark"] {
+            let status = std::process::Command::new("gsettings")
+                .args(["set", "org.gnome.desktop.background", key, &uri])
+                .status()
+                .map_err(|e| format!("Failed to run gsettings: {}", e))?;
+            // Older GNOME versions have no dark variant
+            if !status.success() && key == "picture-uri" {
+                return Err(format!("gsettings exited with {}", status));
+            }
+        }
+        Ok(())
+    }
+
+    // grim on wlroots compositors, then GNOME's tool, then ImageMagick on X11
+    fn capture_screen(path: &Path) -> Result<(), String> {
+        let path = path.to_string_lossy();
+        let tools: [(&str, Vec<&str>); 3] = [
+            ("grim", vec![&*path]),
+            ("gnome-screenshot", vec!["-f", &*path]),
+            ("import", vec!["-window", "root", &*path]),
+        ];
+        let mut last_error = String::new();
+        for (program, args) in tools {
+            match std::process::Command::new(program).args(args).status() {
+                Ok(status) if status.success() => return Ok(()),
+                Ok(status) => last_error = format!("{} exited with {}", program, status),
+                Err(e) => last_error = format!("Failed to run {}: {}", program, e),
+            }
+        }
+        Err(format!("No screenshot tool worked: {}", last_error))
+    }
+
+    fn share(
+        _window: &WebviewWindow,
+        _item: ShareItem,
+        _anchor: Option<ShareAnchor>,
+    ) -> Result<(), String> {
+        Err("Sharing is only available on macOS".to_string())
+    }
+
+    fn webview_language_builder<'a, R: Runtime, M: Manager<R>>(
+        builder: WebviewWindowBuilder<'a, R, M>,
+        _languages: &[String],
+    ) -> WebviewWindowBuilder<'a, R, M> {
+        builder
+    }
+
+    // WebKitGTK keeps the dictionaries on the web context and checks every
+    // word against all of them. An empty list leaves its default, the locale's
+    // language.
+    fn set_spellcheck_languages(
+        window: &WebviewWindow,
+        enabled: bool,
+        languages: &[String],
+    ) -> Result<(), String> {
+        // Enchant names dictionaries with underscores, like "en_US"
+        let languages: Vec<String> = languages.iter().map(|tag| tag.replace('-', "_")).collect();
+        window
+            .with_webview(move |webview| {
+                let Some(context) = webview.inner().context() else {
+                    return;
+                };
+                context.set_spell_checking_enabled(enabled);
+                if !languages.is_empty() {
+                    let languages: Vec<&str> = languages.iter().map(String::as_str).collect();
+                    context.set_spell_checking_languages(&languages);
+                }
+            })
+            .map_err(|e| format!("Failed to reach the webview: {}", e))
+    }
+}
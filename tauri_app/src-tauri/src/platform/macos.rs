@@ -0,0 +1,362 @@
+use super::{
+    pipe_to, AccessibilityPrefs, AppInfo, Permission, Platform, ShareAnchor, ShareItem, Sound,
+};
+use base64::engine::general_purpose;
+use base64::Engine;
+use objc2::ffi::nil;
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::{msg_send, AnyThread, ClassType};
+use objc2_app_kit::{
+    NSBitmapImageRep, NSColor, NSSharingServicePicker, NSSound, NSWindow,
+    NSWindowAnimationBehavior, NSWorkspace,
+};
+use objc2_foundation::{NSArray, NSLocale, NSPoint, NSRect, NSRectEdge, NSSize, NSString, NSURL};
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::path::Path;
+use tauri::tray::TrayIconBuilder;
+use tauri::webview::WebviewWindowBuilder;
+use tauri::{ActivationPolicy, App, Manager, Runtime, TitleBarStyle, WebviewWindow};
+use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
+
+pub struct MacOs;
+
+thread_local! {
+    // The picker on screen, kept alive until the next one replaces it
+    static SHARING_PICKER: RefCell<Option<Retained<NSSharingServicePicker>>> =
+        const { RefCell::new(None) };
+}
+
+fn items(object: &AnyObject) -> Retained<NSArray> {
+    NSArray::from_slice(&[object])
+}
+
+impl Platform for MacOs {
+    // Transparent title bar over the dark background set in `style_window`
+    fn style_window_builder<'a, R: Runtime, M: Manager<R>>(
+        builder: WebviewWindowBuilder<'a, R, M>,
+    ) -> WebviewWindowBuilder<'a, R, M> {
+        builder.title_bar_style(TitleBarStyle::Transparent)
+    }
+
+    fn style_window(window: &WebviewWindow) {
+        // The window stays opaque white if AppKit won't hand it over
+        let Ok(ns_window) = window.ns_window() else {
+            log::warn!("Failed to get the native window, skipping background color");
+            return;
+        };
+        unsafe {
+            let bg_color = NSColor::colorWithRed_green_blue_alpha(
+                23.0 / 255.0,
+                23.0 / 255.0,
+                23.0 / 255.0,
+                1.0,
+            );
+            let ns_window_ref = &*(ns_window as *const NSWindow);
+            ns_window_ref.setBackgroundColor(Some(&bg_color));
+        }
+    }
+
+    fn style_tray<R: Runtime>(builder: TrayIconBuilder<R>) -> TrayIconBuilder<R> {
+        builder.show_menu_on_left_click(false)
+    }
+
+    fn toggle_shortcut() -> Shortcut {
+        Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyT)
+    }
+
+    // Headless launch modes stay out of the Dock and the app switcher
+    fn run_in_background(app: &mut App) {
+        app.set_activation_policy(ActivationPolicy::Accessory);
+    }
+
+    fn running_apps() -> Vec<AppInfo> {
+        unsafe {
+            let workspace = NSWorkspace::sharedWorkspace();
+            let apps = workspace.runningApplications();
+            let mut result = Vec::with_capacity(apps.len());
+
+            for app in apps.iter() {
+                // Check if app is visible (not background-only)
+                let is_hidden: bool = msg_send![&*app, isHidden];
+                let activation_policy: i64 = msg_send![&*app, activationPolicy];
+
+                // Only include regular GUI apps (activation policy 0 = NSApplicationActivationPolicyRegular)
+                // Skip accessory apps (1) like browser plugins and prohibited apps (2) like background processes
+                if is_hidden || activation_policy != 0 {
+                    continue;
+                }
+
+                // Get app name
+                let name_ns: *mut AnyObject = msg_send![&*app, localizedName];
+                if name_ns == nil {
+                    continue;
+                }
+                let utf8_ptr: *const std::os::raw::c_char = msg_send![name_ns, UTF8String];
+                let cname = CStr::from_ptr(utf8_ptr).to_string_lossy().into_owned();
+
+                // Skip empty names
+                if cname.is_empty() {
+                    continue;
+                }
+
+                // Get app icon
+                let icon: *mut AnyObject = msg_send![&*app, icon];
+                if icon == nil {
+                    // Skip apps without icons
+                    continue;
+                }
+
+                // Convert icon to PNG data
+                let tiff_data: *mut AnyObject = msg_send![icon, TIFFRepresentation];
+                if tiff_data == nil {
+                    continue;
+                }
+
+                // Create bitmap representation from TIFF data
+                let bitmap_rep: *mut AnyObject = msg_send![NSBitmapImageRep::class(), alloc];
+                let bitmap_rep: *mut AnyObject = msg_send![bitmap_rep, initWithData: tiff_data];
+                if bitmap_rep == nil {
+                    continue;
+                }
+
+                // Convert to PNG data (NSBitmapImageFileTypePNG = 4)
+                let png_data: *mut AnyObject =
+                    msg_send![bitmap_rep, representationUsingType: 4u64, properties: nil];
+                if png_data == nil {
+                    continue;
+                }
+
+                // Extract bytes and base64-encode
+                let bytes: *const u8 = msg_send![png_data, bytes];
+                let len: usize = msg_send![png_data, length];
+                let slice = std::slice::from_raw_parts(bytes, len);
+                let b64 = general_purpose::STANDARD.encode(slice);
+
+                result.push(AppInfo {
+                    name: cname,
+                    icon_png_base64: b64,
+                });
+            }
+
+            result
+        }
+    }
+
+    fn on_battery() -> bool {
+        std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains("'Battery Power'"))
+            .unwrap_or(false)
+    }
+
+    fn port_owner(port: u16) -> Option<u32> {
+        let output = std::process::Command::new("lsof")
+            .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-t"])
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.trim().parse().ok())
+    }
+
+    fn permission_settings_url(permission: Permission) -> Option<&'static str> {
+        Some(match permission {
+            Permission::Accessibility => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+            }
+            Permission::ScreenRecording => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture"
+            }
+            Permission::Microphone => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone"
+            }
+            Permission::Notifications => {
+                "x-apple.systempreferences:com.apple.preference.notifications"
+            }
+        })
+    }
+
+    fn accessibility_prefs() -> AccessibilityPrefs {
+        unsafe {
+            let workspace = NSWorkspace::sharedWorkspace();
+            AccessibilityPrefs {
+                reduce_motion: workspace.accessibilityDisplayShouldReduceMotion(),
+                increase_contrast: workspace.accessibilityDisplayShouldIncreaseContrast(),
+                screen_reader: workspace.isVoiceOverEnabled(),
+            }
+        }
+    }
+
+    fn set_window_animations(window: &WebviewWindow, enabled: bool) {
+        let Ok(ns_window) = window.ns_window() else {
+            return;
+        };
+        let behavior = if enabled {
+            NSWindowAnimationBehavior::Default
+        } else {
+            NSWindowAnimationBehavior::None
+        };
+        unsafe {
+            let ns_window = &*(ns_window as *const NSWindow);
+            ns_window.setAnimationBehavior(behavior);
+        }
+    }
+
+    fn play_sound(sound: Sound) -> Result<(), String> {
+        let name = match sound {
+            Sound::Chime => "Glass",
+            Sound::Alert => "Basso",
+            Sound::Attention => "Ping",
+            Sound::Tap => "Tink",
+        };
+        let sound = unsafe { NSSound::soundNamed(&NSString::from_str(name)) }
+            .ok_or_else(|| format!("The system sound {} is missing", name))?;
+        if !unsafe { sound.play() } {
+            return Err(format!("Failed to play {}", name));
+        }
+        Ok(())
+    }
+
+    // Focus modes are only visible through the assertions file Control Center
+    // keeps, which needs Full Disk Access; without it only muting counts
+    fn sounds_silenced() -> bool {
+        let muted = std::process::Command::new("osascript")
+            .args(["-e", "output muted of (get volume settings)"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "true");
+        let focus = std::env::var_os("HOME")
+            .map(|home| Path::new(&home).join("Library/DoNotDisturb/DB/Assertions.json"))
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|json| serde_json::from_slice::<serde_json::Value>(&json).ok())
+            .is_some_and(|assertions| {
+                assertions["data"].as_array().is_some_and(|data| {
+                    data.iter().any(|entry| {
+                        entry["storeAssertionRecords"]
+                            .as_array()
+                            .is_some_and(|records| !records.is_empty())
+                    })
+                })
+            });
+        muted || focus
+    }
+
+    fn preferred_languages() -> Vec<String> {
+        unsafe { NSLocale::preferredLanguages() }
+            .iter()
+            .map(|language| language.to_string())
+            .collect()
+    }
+
+    fn copy_text(text: &str) -> Result<(), String> {
+        pipe_to(std::process::Command::new("pbcopy"), text)
+    }
+
+    // Every desktop, so all displays and Spaces change together
+    fn set_wallpaper(path: &Path) -> Result<(), String> {
+        let script = format!(
+            "tell application \"System Events\" to tell every desktop to set picture to POSIX file {:?}",
+            path.to_string_lossy()
+        );
+        let status = std::process::Command::new("osascript")
+            .args(["-e", &script])
+            .status()
+            .map_err(|e| format!("Failed to run osascript: {}", e))?;
+        if !status.success() {
+            return Err(format!("osascript exited with {}", status));
+        }
+        Ok(())
+    }
+
+    // Without screen recording permission only the desktop picture and menu
+    // bar are captured
+    fn capture_screen(path: &Path) -> Result<(), String> {
+        let status = std::process::Command::new("screencapture")
+            .args(["-x", "-m", "-t", "png"])
+            .arg(path)
+            .status()
+            .map_err(|e| format!("Failed to run screencapture: {}", e))?;
+        if !status.success() {
+            return Err(format!("screencapture exited with {}", status));
+        }
+        Ok(())
+    }
+
+    fn share(
+        window: &WebviewWindow,
+        item: ShareItem,
+        anchor: Option<ShareAnchor>,
+    ) -> Result<(), String> {
+        let ns_window = window
+            .ns_window()
+            .map_err(|e| format!("Failed to get the native window: {}", e))?;
+        unsafe {
+            let items = match item {
+                ShareItem::Text(text) => items(&NSString::from_str(&text)),
+                ShareItem::File(path) => items(&NSURL::fileURLWithPath(&NSString::from_str(
+                    &path.to_string_lossy(),
+                ))),
+            };
+            let picker =
+                NSSharingServicePicker::initWithItems(NSSharingServicePicker::alloc(), &items);
+
+            let ns_window_ref = &*(ns_window as *const NSWindow);
+            let view = ns_window_ref
+                .contentView()
+                .ok_or("The window has no content view")?;
+            let bounds = view.bounds();
+            // Web coordinates grow downwards, AppKit's only in flipped views
+            let rect = match anchor {
+                Some(anchor) => {
+                    let y = if view.isFlipped() {
+                        anchor.y
+                    } else {
+                        bounds.size.height - anchor.y - anchor.height
+                    };
+                    NSRect::new(
+                        NSPoint::new(anchor.x, y),
+                        NSSize::new(anchor.width, anchor.height),
+                    )
+                }
+                None => {
+                    let top = if view.isFlipped() {
+                        0.0
+                    } else {
+                        bounds.size.height
+                    };
+                    NSRect::new(
+                        NSPoint::new(bounds.size.width / 2.0, top),
+                        NSSize::new(0.0, 0.0),
+                    )
+                }
+            };
+            let edge = if view.isFlipped() {
+                NSRectEdge::MaxY
+            } else {
+                NSRectEdge::MinY
+            };
+            picker.showRelativeToRect_ofView_preferredEdge(rect, &view, edge);
+            SHARING_PICKER.with(|current| current.replace(Some(picker)));
+        }
+        Ok(())
+    }
+
+    // WebKit checks against the languages set in System Settings and picks
+    // one per word itself, and the page's `spellcheck` attribute turns it off
+    fn webview_language_builder<'a, R: Runtime, M: Manager<R>>(
+        builder: WebviewWindowBuilder<'a, R, M>,
+        _languages: &[String],
+    ) -> WebviewWindowBuilder<'a, R, M> {
+        builder
+    }
+
+    fn set_spellcheck_languages(
+        _window: &WebviewWindow,
+        _enabled: bool,
+        _languages: &[String],
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use super::{
+    pipe_to, AccessibilityPrefs, AppInfo, Permission, Platform, ShareAnchor, ShareItem, Sound,
+};
+use std::path::Path;
+use std::sync::OnceLock;
+use tauri::tray::TrayIconBuilder;
+use tauri::webview::WebviewWindowBuilder;
+use tauri::{App, Manager, Runtime, WebviewWindow};
+use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
+
+pub struct Windows;
+
+// Passed by wry unless a window sets its own, which replaces them
+const DEFAULT_BROWSER_ARGS: &str = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+static BROWSER_ARGS: OnceLock<String> = OnceLock::new();
+
+impl Platform for Windows {
+    fn style_window_builder<'a, R: Runtime, M: Manager<R>>(
+        builder: WebviewWindowBuilder<'a, R, M>,
+    ) -> WebviewWindowBuilder<'a, R, M> {
+        builder
+    }
+
+    fn style_window(_window: &WebviewWindow) {}
+
+    fn style_tray<R: Runtime>(builder: TrayIconBuilder<R>) -> TrayIconBuilder<R> {
+        builder.show_menu_on_left_click(false)
+    }
+
+    fn toggle_shortcut() -> Shortcut {
+        Shortcut::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyT)
+    }
+
+    // Background modes have no window, so there is nothing in the taskbar
+    fn run_in_background(_app: &mut App) {}
+
+    fn running_apps() -> Vec<AppInfo> {
+        Vec::new()
+    }
+
+    fn on_battery() -> bool {
+        false
+    }
+
+    // Rows look like `TCP  127.0.0.1:8080  0.0.0.0:0  LISTENING  1234`
+    fn port_owner(port: u16) -> Option<u32> {
+        let output = std::process::Command::new("netstat")
+            .args(["-ano", "-p", "TCP"])
+            .output()
+            .ok()?;
+        let suffix = format!(":{}", port);
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| {
+                let columns: Vec<&str> = line.split_whitespace().collect();
+                match columns.as_slice() {
+                    [_, local, _, "LISTENING", pid] if local.ends_with(&suffix) => pid.parse().ok(),
+                    _ => None,
+                }
+            })
+    }
+
+    // Windows doesn't gate accessibility or screen capture for desktop apps
+    fn permission_settings_url(permission: Permission) -> Option<&'static str> {
+        match permission {
+            Permission::Microphone => Some("ms-settings:privacy-microphone"),
+            Permission::Notifications => Some("ms-settings:notifications"),
+            Permission::Accessibility | Permission::ScreenRecording => None,
+        }
+    }
+
+    // SPI_GETCLIENTAREAANIMATION, the high contrast flag and SPI_GETSCREENREADER,
+    // printed one per line
+    fn accessibility_prefs() -> AccessibilityPrefs {
+        let script = "Add-Type -Namespace Win32 -Name Access -MemberDefinition '[DllImport(\"user32.dll\")] public static extern bool SystemParametersInfo(int action, int param, ref int value, int flags);'; \
+             $animation = 1; [void][Win32.Access]::SystemParametersInfo(0x1042, 0, [ref]$animation, 0); \
+             $reader = 0; [void][Win32.Access]::SystemParametersInfo(0x46, 0, [ref]$reader, 0); \
+             $contrast = [int](Get-ItemProperty 'HKCU:\\Control Panel\\Accessibility\\HighContrast' -ErrorAction SilentlyContinue).Flags; \
+             $animation; $contrast -band 1; $reader";
+        let Ok(output) = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", script])
+            .output()
+        else {
+            return AccessibilityPrefs::default();
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let flags: Vec<bool> = stdout
+            .lines()
+            .map(|line| line.trim().parse::<i32>().is_ok_and(|value| value != 0))
+            .collect();
+        match flags.as_slice() {
+            [animation, contrast, reader] => AccessibilityPrefs {
+                reduce_motion: !animation,
+                increase_contrast: *contrast,
+                screen_reader: *reader,
+            },
+            _ => AccessibilityPrefs::default(),
+        }
+    }
+
+    // Windows has no per-window switch; it follows the same setting itself
+    fn set_window_animations(_window: &WebviewWindow, _enabled: bool) {}
+
+    fn play_sound(sound: Sound) -> Result<(), String> {
+        let file = match sound {
+            Sound::Chime => "Windows Notify System Generic.wav",
+            Sound::Alert => "Windows Critical Stop.wav",
+            Sound::Attention => "Windows Exclamation.wav",
+            Sound::Tap => "Windows Navigation Start.wav",
+        };
+        let script = format!(
+            "(New-Object Media.SoundPlayer \"$env:WINDIR\\Media\\{}\").PlaySync()",
+            file
+        );
+        std::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to run powershell: {}", e))
+    }
+
+    // A muted mixer silences the sound by itself, and Focus Assist has no
+    // public API to ask
+    fn sounds_silenced() -> bool {
+        false
+    }
+
+    // The display language, which is what Windows shows its own UI in
+    fn preferred_languages() -> Vec<String> {
+        let Ok(output) = std::process::Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-Command",
+                "[Globalization.CultureInfo]::CurrentUICulture.Name",
+            ])
+            .output()
+        else {
+            return Vec::new();
+        };
+        let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if name.is_empty() {
+            Vec::new()
+        } else {
+            vec![name]
+        }
+    }
+
+    // `clip` reads stdin in the console code page, so PowerShell is told to
+    // read UTF-8 instead
+    fn copy_text(text: &str) -> Result<(), String> {
+        let mut command = std::process::Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-Command",
+            "[Console]::InputEncoding = [Text.Encoding]::UTF8; Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+        ]);
+        pipe_to(command, text)
+    }
+
+    // SPI_SETDESKWALLPAPER, saved to the profile and broadcast to other apps
+    fn set_wallpaper(path: &Path) -> Result<(), String> {
+        let script = format!(
+            "Add-Type -Namespace Win32 -Name Desktop -MemberDefinition '[DllImport(\"user32.dll\", CharSet = CharSet.Unicode)] public static extern bool SystemParametersInfo(int action, int param, string value, int flags);'; \
+             if (-not [Win32.Desktop]::SystemParametersInfo(20, 0, '{}', 3)) {{ exit 1 }}",
+            path.to_string_lossy().replace('\'', "''")
+        );
+        let status = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .status()
+            .map_err(|e| format!("Failed to run powershell: {}", e))?;
+        if !status.success() {
+            return Err(format!("Setting the wallpaper failed with {}", status));
+        }
+        Ok(())
+    }
+
+    // The primary screen through System.Drawing
+    fn capture_screen(path: &Path) -> Result<(), String> {
+        let script = format!(
+            "Add-Type -AssemblyName System.Windows.Forms, System.Drawing; \
+             $bounds = [Windows.Forms.Screen]::PrimaryScreen.Bounds; \
+             $bitmap = New-Object Drawing.Bitmap $bounds.Width, $bounds.Height; \
+             [Drawing.Graphics]::FromImage($bitmap).CopyFromScreen($bounds.Location, [Drawing.Point]::Empty, $bounds.Size); \
+             $bitmap.Save('{}', [Drawing.Imaging.ImageFormat]::Png)",
+            path.to_string_lossy().replace('\'', "''")
+        );
+        let status = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .status()
+            .map_err(|e| format!("Failed to run powershell: {}", e))?;
+        if !status.success() {
+            return Err(format!("Capturing the screen failed with {}", status));
+        }
+        Ok(())
+    }
+
+    fn share(
+        _window: &WebviewWindow,
+        _item: ShareItem,
+        _anchor: Option<ShareAnchor>,
+    ) -> Result<(), String> {
+        Err("Sharing is only available on macOS".to_string())
+    }
+
+    // WebView2 shares one browser process between windows, and every window
+    // has to start it with the same arguments, so the languages the first
+    // window started with stay until the app restarts
+    fn webview_language_builder<'a, R: Runtime, M: Manager<R>>(
+        builder: WebviewWindowBuilder<'a, R, M>,
+        languages: &[String],
+    ) -> WebviewWindowBuilder<'a, R, M> {
+        let args = BROWSER_ARGS.get_or_init(|| match languages.first() {
+            Some(first) => format!(
+                "{} --lang={} --accept-lang={}",
+                DEFAULT_BROWSER_ARGS,
+                first,
+                languages.join(",")
+            ),
+            None => DEFAULT_BROWSER_ARGS.to_string(),
+        });
+        builder.additional_browser_args(args)
+    }
+
+    fn set_spellcheck_languages(
+        _window: &WebviewWindow,
+        _enabled: bool,
+        _languages: &[String],
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use crate::files::FsError;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use pdfium_render::prelude::{PdfRenderConfig, Pdfium};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_fs::FsExt;
+
+const PREVIEW_DIR_NAME: &str = "previews";
+const DEFAULT_PREVIEW_PX: u32 = 256;
+const MAX_PREVIEW_PX: u32 = 2048;
+// Larger sources are refused rather than decoded into memory
+const MAX_SOURCE_BYTES: u64 = 200 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Type)]
+pub struct Preview {
+    // PNG in the cache dir, readable through the asset protocol
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+// pdfium is loaded from the app resources if bundled there, else from the
+// system. Bound per use since the bindings can't be shared across threads.
+pub fn pdfium(app: &AppHandle) -> Option<Pdfium> {
+    let bundled = app
+        .path()
+        .resource_dir()
+        .ok()
+        .map(|dir| Pdfium::pdfium_platform_library_name_at_path(&dir));
+    let bindings = bundled
+        .and_then(|path| Pdfium::bind_to_library(path).ok())
+        .map(Ok)
+        .unwrap_or_else(Pdfium::bind_to_system_library);
+    match bindings {
+        Ok(bindings) => Some(Pdfium::new(bindings)),
+        Err(e) => {
+            log::warn!(
+                "PDF previews and text extraction unavailable, pdfium not found: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+fn render_pdf(
+    app: &AppHandle,
+    path: &Path,
+    display: &str,
+    max_px: u32,
+) -> Result<DynamicImage, FsError> {
+    let pdfium = pdfium(app).ok_or_else(|| FsError::Invalid {
+        reason: "PDF previews need the pdfium library, which was not found".to_string(),
+    })?;
+    let invalid = |e: pdfium_render::prelude::PdfiumError| FsError::Invalid {
+        reason: format!("Failed to render {}: {}", display, e),
+    };
+
+    let document = pdfium.load_pdf_from_file(path, None).map_err(invalid)?;
+    let page = document.pages().first().map_err(invalid)?;
+    let bitmap = page
+        .render_with_config(
+            &PdfRenderConfig::new()
+                .set_target_width(max_px as i32)
+                .set_maximum_height(max_px as i32),
+        )
+        .map_err(invalid)?;
+    Ok(bitmap.as_image())
+}
+
+fn decode_image(path: &Path, display: &str) -> Result<DynamicImage, FsError> {
+    ImageReader::open(path)
+        .map_err(|e| FsError::io(display, e))?
+        .with_guessed_format()
+        .map_err(|e| FsError::io(display, e))?
+        .decode()
+        .map_err(|_| FsError::Binary {
+            path: display.to_string(),
+        })
+}
+
+// Cache entries are keyed by source path, size, mtime and preview size, so an
+// edited file gets a fresh preview
+fn cache_path(app: &AppHandle, path: &Path, max_px: u32) -> Result<PathBuf, FsError> {
+    let metadata = fs::metadata(path).map_err(|e| FsError::io(&path.to_string_lossy(), e))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+
+    let mut hasher = Sha256::new();
+    hasher.update(path.to_string_lossy().as_bytes());
+    hasher.update(metadata.len().to_le_bytes());
+    hasher.update(modified.to_le_bytes());
+    hasher.update(max_px.to_le_bytes());
+
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| FsError::Io {
+            path: PREVIEW_DIR_NAME.to_string(),
+            reason: e.to_string(),
+        })?
+        .join(PREVIEW_DIR_NAME);
+    fs::create_dir_all(&dir).map_err(|e| FsError::io(&dir.to_string_lossy(), e))?;
+    Ok(dir.join(format!("{:x}.png", hasher.finalize())))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_preview(
+    app: AppHandle,
+    path: String,
+    max_px: Option<u32>,
+) -> Result<Preview, FsError> {
+    preview(&app, path, max_px).await
+}
+
+// Also served as bytes by `asset_protocol`
+pub async fn preview(
+    app: &AppHandle,
+    path: String,
+    max_px: Option<u32>,
+) -> Result<Preview, FsError> {
+    let max_px = max_px
+        .unwrap_or(DEFAULT_PREVIEW_PX)
+        .clamp(1, MAX_PREVIEW_PX);
+    let source = Path::new(&path)
+        .canonicalize()
+        .map_err(|e| FsError::io(&path, e))?;
+    // Attachments can live outside the workspace, but only in granted folders
+    if !app.fs_scope().is_allowed(&source) {
+        return Err(FsError::OutsideScope { path });
+    }
+    let size = fs::metadata(&source)
+        .map_err(|e| FsError::io(&path, e))?
+        .len();
+    if size > MAX_SOURCE_BYTES {
+        return Err(FsError::TooLarge {
+            path,
+            size,
+            limit: MAX_SOURCE_BYTES,
+        });
+    }
+
+    let cached = cache_path(app, &source, max_px)?;
+    let task_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let thumbnail = if cached.exists() {
+            decode_image(&cached, &path)?
+        } else {
+            let is_pdf = source
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"));
+            let image = if is_pdf {
+                render_pdf(&task_app, &source, &path, max_px)?
+            } else {
+                decode_image(&source, &path)?
+            };
+            let thumbnail = image.thumbnail(max_px, max_px);
+            thumbnail
+                .save_with_format(&cached, ImageFormat::Png)
+                .map_err(|e| FsError::Io {
+                    path: cached.to_string_lossy().into_owned(),
+                    reason: e.to_string(),
+                })?;
+            thumbnail
+        };
+
+        Ok(Preview {
+            path: cached.to_string_lossy().into_owned(),
+            width: thumbnail.width(),
+            height: thumbnail.height(),
+        })
+    })
+    .await
+    .map_err(|e| FsError::Invalid {
+        reason: format!("Preview task failed: {}", e),
+    })?
+}
@@ -0,0 +1,190 @@
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::sidecar::SidecarManager;
+use crate::workspace;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+// Opt-in cache for one-shot sidecar prompts, so a template that runs on a
+// schedule or from automation answers instantly instead of being billed again.
+// Only successful replies are kept. Session prompts aren't cached: their
+// answer depends on the conversation so far.
+const MAX_ENTRIES: usize = 500;
+const MIN_TTL_SECS: u64 = 10;
+
+static ENTRIES: LazyLock<Mutex<HashMap<String, CachedReply>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct PromptCacheSettings {
+    pub enabled: bool,
+    pub ttl_secs: u64,
+}
+
+impl Default for PromptCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 3600,
+        }
+    }
+}
+
+struct CachedReply {
+    text: String,
+    stored_at: Instant,
+    expires_at: Instant,
+}
+
+// The sidecar's main agent, as reported by its `agent.info` RPC method
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AgentInfo {
+    model: String,
+    system_prompt_hash: String,
+}
+
+// Everything that changes what the sidecar answers
+struct CacheKey {
+    model: String,
+    system_prompt: String,
+    prompt: String,
+    // The sidecar runs in the workspace, which its file tools read from
+    options: Option<String>,
+}
+
+impl CacheKey {
+    fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            self.model.as_str(),
+            &self.system_prompt,
+            &self.prompt,
+            self.options.as_deref().unwrap_or_default(),
+        ] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+// Whitespace differences from templating shouldn't miss the cache
+fn normalize(prompt: &str) -> String {
+    prompt.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn lookup(key: &str) -> Option<String> {
+    let mut entries = ENTRIES.lock().unwrap();
+    let now = Instant::now();
+    entries.retain(|_, entry| entry.expires_at > now);
+    entries.get(key).map(|entry| entry.text.clone())
+}
+
+fn store(key: String, text: String, ttl: Duration) {
+    let mut entries = ENTRIES.lock().unwrap();
+    if entries.len() >= MAX_ENTRIES && !entries.contains_key(&key) {
+        let oldest = entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.stored_at)
+            .map(|(key, _)| key.clone());
+        if let Some(oldest) = oldest {
+            entries.remove(&oldest);
+        }
+    }
+    let now = Instant::now();
+    entries.insert(
+        key,
+        CachedReply {
+            text,
+            stored_at: now,
+            expires_at: now + ttl,
+        },
+    );
+}
+
+// `SidecarManager::send_session_prompt` with the cache in front of it; an
+// empty `session_id` is a one-shot prompt
+pub async fn send_prompt(
+    app: &AppHandle,
+    session_id: &str,
+    prompt: &str,
+    trace_id: &str,
+) -> Result<String, AppError> {
+    let settings = app.state::<SettingsManager>().get().prompt_cache;
+    let sidecar_manager = app.state::<Arc<SidecarManager>>().inner().clone();
+    // A reply within a session depends on its history, which the key doesn't
+    // cover, so only one-shot prompts are cached
+    if !settings.enabled || !session_id.is_empty() {
+        return sidecar_manager
+            .send_session_prompt(session_id, prompt, trace_id)
+            .await;
+    }
+
+    // Without the model and system prompt a cached reply could be stale, so the
+    // prompt goes straight to the sidecar
+    let agent = match sidecar_manager.rpc("agent.info", json!({})).await {
+        Ok(value) => serde_json::from_value::<AgentInfo>(value).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    let agent = match agent {
+        Ok(agent) => agent,
+        Err(e) => {
+            log::warn!("[trace {}] Skipping prompt cache: {}", trace_id, e);
+            return sidecar_manager.send_prompt(prompt, trace_id).await;
+        }
+    };
+
+    let key = CacheKey {
+        model: agent.model,
+        system_prompt: agent.system_prompt_hash,
+        prompt: normalize(prompt),
+        options: workspace::current(app).map(|path| path.to_string_lossy().into_owned()),
+    }
+    .digest();
+    if let Some(text) = lookup(&key) {
+        log::info!("[trace {}] Serving prompt from cache", trace_id);
+        return Ok(text);
+    }
+
+    let result = sidecar_manager.send_prompt(prompt, trace_id).await;
+    if let Ok(text) = &result {
+        store(
+            key,
+            text.clone(),
+            Duration::from_secs(settings.ttl_secs.max(MIN_TTL_SECS)),
+        );
+    }
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_prompt_cache(
+    settings_manager: State<'_, SettingsManager>,
+    settings: PromptCacheSettings,
+) -> Result<(), AppError> {
+    settings_manager.update(|current| current.prompt_cache = settings)?;
+    if !settings.enabled {
+        ENTRIES.lock().unwrap().clear();
+    }
+    Ok(())
+}
+
+// Returns how many replies were dropped
+#[tauri::command]
+#[specta::specta]
+pub fn clear_prompt_cache() -> usize {
+    let mut entries = ENTRIES.lock().unwrap();
+    let cleared = entries.len();
+    entries.clear();
+    log::info!("Cleared {} cached prompt replies", cleared);
+    cleared
+}
@@ -0,0 +1,129 @@
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::{automation, debug, metrics, ollama, prompt_cache, validation, PromptResponse};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, State};
+
+// Where a session's prompts are answered. Sessions without a selection use
+// the sidecar.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Provider {
+    #[default]
+    Sidecar,
+    // A model on the local Ollama instance, works without the sidecar
+    Ollama {
+        model: String,
+    },
+}
+
+pub fn provider(settings_manager: &SettingsManager, session_id: &str) -> Provider {
+    settings_manager
+        .get()
+        .session_providers
+        .get(session_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_session_provider(
+    settings_manager: State<'_, SettingsManager>,
+    session_id: String,
+) -> Provider {
+    provider(&settings_manager, &session_id)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_session_provider(
+    settings_manager: State<'_, SettingsManager>,
+    session_id: String,
+    provider: Provider,
+) -> Result<(), AppError> {
+    validation::uuid("sessionId", &session_id)?;
+    if let Provider::Ollama { model } = &provider {
+        if model.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "No Ollama model selected".to_string(),
+            ));
+        }
+    }
+    settings_manager.update(|settings| {
+        if provider == Provider::Sidecar {
+            settings.session_providers.remove(&session_id);
+        } else {
+            settings
+                .session_providers
+                .insert(session_id.clone(), provider.clone());
+        }
+    })?;
+    Ok(())
+}
+
+// An empty `session_id` is a one-shot prompt
+pub async fn send(
+    app: &AppHandle,
+    session_id: &str,
+    provider: Provider,
+    prompt: &str,
+    trace_id: &str,
+) -> Result<String, AppError> {
+    match provider {
+        Provider::Sidecar => {
+            metrics::timed(
+                "send_prompt",
+                prompt_cache::send_prompt(app, session_id, prompt, trace_id),
+            )
+            .await
+        }
+        // Ollama keeps history by session, so a one-shot prompt gets a scratch
+        // session that is dropped afterwards
+        Provider::Ollama { model } if session_id.is_empty() => {
+            let scratch = uuid::Uuid::new_v4().to_string();
+            let result = ollama::chat(app, &scratch, &model, prompt, trace_id).await;
+            ollama::clear_ollama_history(scratch);
+            Ok(result?)
+        }
+        Provider::Ollama { model } => {
+            Ok(ollama::chat(app, session_id, &model, prompt, trace_id).await?)
+        }
+    }
+}
+
+// Like `send_prompt`, but routed by the session's provider
+#[tauri::command]
+#[specta::specta]
+pub async fn send_session_prompt(
+    app: AppHandle,
+    session_id: String,
+    prompt: String,
+) -> Result<PromptResponse, AppError> {
+    validation::uuid("sessionId", &session_id)?;
+    validation::prompt("prompt", &prompt)?;
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let start = std::time::Instant::now();
+    let provider = provider(&app.state::<SettingsManager>(), &session_id);
+    let result = send(&app, &session_id, provider, &prompt, &trace_id).await;
+    let elapsed_ms = start.elapsed().as_millis();
+    debug::annotate_prompt(&app, &trace_id, elapsed_ms, result.is_ok());
+
+    match result {
+        Ok(text) => {
+            log::info!("[trace {}] Prompt completed in {}ms", trace_id, elapsed_ms);
+            automation::record_response(&text);
+            Ok(PromptResponse { trace_id, text })
+        }
+        Err(e) => {
+            log::error!(
+                "[trace {}] Prompt failed after {}ms: {}",
+                trace_id,
+                elapsed_ms,
+                e
+            );
+            Err(e.with_trace_id(&trace_id))
+        }
+    }
+}
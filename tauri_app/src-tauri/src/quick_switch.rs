@@ -0,0 +1,153 @@
+use crate::actions::fuzzy_match;
+use crate::settings::SettingsManager;
+use crate::sidecar::SidecarManager;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+const MAX_CANDIDATES: usize = 50;
+// A match on a file's directory ranks below one on its name
+const PATH_PENALTY: i64 = 30;
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateKind {
+    Session,
+    File,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct QuickSwitchCandidate {
+    pub kind: CandidateKind,
+    // Session id, or the file's absolute path
+    pub id: String,
+    pub title: String,
+    // Message count for sessions, the parent directory for files
+    pub detail: Option<String>,
+    // Icon the switcher shows: "session", or "image", "code", "document" or
+    // "file" by extension
+    pub icon: &'static str,
+    pub score: i64,
+    // Character indices of `title` that matched, for highlighting
+    pub positions: Vec<u32>,
+}
+
+// The fields of a `sessions.list` entry the switcher uses
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionSummary {
+    id: String,
+    title: String,
+    #[serde(default)]
+    message_count: i64,
+}
+
+fn file_icon(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("png" | "jpg" | "jpeg" | "gif" | "webp" | "heic" | "svg") => "image",
+        Some(
+            "rs" | "go" | "ts" | "tsx" | "js" | "jsx" | "py" | "swift" | "c" | "cpp" | "h" | "json"
+            | "toml" | "yaml" | "yml" | "sh",
+        ) => "code",
+        Some("md" | "txt" | "pdf" | "rtf" | "doc" | "docx") => "document",
+        _ => "file",
+    }
+}
+
+// Without a query everything matches, in the order it was listed
+fn rank(query: &str, title: &str) -> Option<(i64, Vec<u32>)> {
+    if query.trim().is_empty() {
+        return Some((0, Vec::new()));
+    }
+    fuzzy_match(query, title)
+}
+
+// The sidecar lists the active project's sessions, newest first. While it is
+// down the switcher still offers files.
+async fn sessions(app: &AppHandle, query: &str) -> Vec<QuickSwitchCandidate> {
+    let listed = app
+        .state::<Arc<SidecarManager>>()
+        .rpc("sessions.list", json!({}))
+        .await
+        .and_then(|value| {
+            serde_json::from_value::<Option<Vec<SessionSummary>>>(value)
+                .map_err(|e| format!("Failed to parse sessions: {}", e).into())
+        });
+    let sessions = match listed {
+        Ok(sessions) => sessions.unwrap_or_default(),
+        Err(e) => {
+            log::debug!("Quick switcher has no sessions: {}", e);
+            return Vec::new();
+        }
+    };
+
+    sessions
+        .into_iter()
+        .filter_map(|session| {
+            let title = if session.title.trim().is_empty() {
+                "Untitled session".to_string()
+            } else {
+                session.title
+            };
+            let (score, positions) = rank(query, &title)?;
+            Some(QuickSwitchCandidate {
+                kind: CandidateKind::Session,
+                id: session.id,
+                title,
+                detail: Some(format!("{} messages", session.message_count)),
+                icon: "session",
+                score,
+                positions,
+            })
+        })
+        .collect()
+}
+
+fn files(app: &AppHandle, query: &str) -> Vec<QuickSwitchCandidate> {
+    app.state::<SettingsManager>()
+        .get()
+        .recent_files
+        .into_iter()
+        .filter_map(|file| {
+            let path = Path::new(&file);
+            if !path.is_file() {
+                return None;
+            }
+            let title = path.file_name()?.to_string_lossy().into_owned();
+            let (score, positions) = rank(query, &title).or_else(|| {
+                fuzzy_match(query, &file).map(|(score, _)| (score - PATH_PENALTY, Vec::new()))
+            })?;
+            Some(QuickSwitchCandidate {
+                kind: CandidateKind::File,
+                detail: path.parent().map(|dir| dir.to_string_lossy().into_owned()),
+                icon: file_icon(path),
+                id: file,
+                title,
+                score,
+                positions,
+            })
+        })
+        .collect()
+}
+
+// Sessions and recent files matching `query`, best first
+#[tauri::command]
+#[specta::specta]
+pub async fn quick_switch_candidates(
+    app: AppHandle,
+    query: Option<String>,
+) -> Vec<QuickSwitchCandidate> {
+    let query = query.unwrap_or_default();
+    let mut candidates = sessions(&app, &query).await;
+    candidates.extend(files(&app, &query));
+    // Stable, so equal scores keep the recency order of each list
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.score));
+    candidates.truncate(MAX_CANDIDATES);
+    candidates
+}
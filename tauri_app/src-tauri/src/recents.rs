@@ -0,0 +1,161 @@
+use crate::error::AppError;
+use crate::i18n;
+use crate::settings::SettingsManager;
+use crate::workspace;
+use serde::Serialize;
+use specta::Type;
+use std::path::Path;
+use tauri::menu::{MenuItem, Submenu};
+use tauri::{AppHandle, Manager, State, Wry};
+
+pub const TRAY_ID: &str = "main";
+pub const RECENT_MENU_PREFIX: &str = "recent:";
+const MAX_RECENT_FILES: usize = 20;
+const MAX_TRAY_PROJECTS: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RecentProject {
+    pub path: String,
+    pub name: String,
+    pub pinned: bool,
+    // Recents are kept even if the folder was moved or deleted
+    pub exists: bool,
+}
+
+fn display_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+// Pinned projects first, then the rest in most-recently-used order
+pub fn projects(app: &AppHandle) -> Vec<RecentProject> {
+    let settings = app.state::<SettingsManager>().get();
+    let recent = settings
+        .recent_workspaces
+        .into_iter()
+        .filter(|path| !settings.pinned_workspaces.contains(path));
+    settings
+        .pinned_workspaces
+        .iter()
+        .cloned()
+        .map(|path| (path, true))
+        .chain(recent.map(|path| (path, false)))
+        .map(|(path, pinned)| RecentProject {
+            name: display_name(&path),
+            exists: Path::new(&path).is_dir(),
+            path,
+            pinned,
+        })
+        .collect()
+}
+
+pub fn tray_submenu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let submenu = Submenu::new(app, i18n::t(app, "tray.recent_projects"), true)?;
+    let projects: Vec<RecentProject> = projects(app)
+        .into_iter()
+        .filter(|project| project.exists)
+        .take(MAX_TRAY_PROJECTS)
+        .collect();
+    if projects.is_empty() {
+        submenu.append(&MenuItem::new(
+            app,
+            i18n::t(app, "tray.no_recent_projects"),
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    for project in projects {
+        submenu.append(&MenuItem::with_id(
+            app,
+            format!("{}{}", RECENT_MENU_PREFIX, project.path),
+            project.name,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(submenu)
+}
+
+// Rebuilds the tray menu so its recent projects match the settings
+pub fn refresh_tray(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match crate::tray_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => log::warn!("Failed to rebuild tray menu: {}", e),
+    }
+}
+
+// Tray menu handler for the recent projects submenu
+pub fn open_from_tray(app: &AppHandle, menu_id: &str) {
+    let Some(path) = menu_id.strip_prefix(RECENT_MENU_PREFIX) else {
+        return;
+    };
+    let app = app.clone();
+    let path = path.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = workspace::switch(&app, &path).await {
+            log::error!("Failed to open recent project {}: {}", path, e);
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_recent_projects(app: AppHandle) -> Vec<RecentProject> {
+    projects(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn pin_project(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    path: String,
+    pinned: bool,
+) -> Result<Vec<RecentProject>, AppError> {
+    settings_manager.update(|settings| {
+        settings.pinned_workspaces.retain(|pin| *pin != path);
+        if pinned {
+            settings.pinned_workspaces.push(path.clone());
+        }
+    })?;
+    refresh_tray(&app);
+    Ok(projects(&app))
+}
+
+// Called when a file is opened or attached to a prompt
+#[tauri::command]
+#[specta::specta]
+pub fn record_recent_file(
+    settings_manager: State<'_, SettingsManager>,
+    path: String,
+) -> Result<(), AppError> {
+    let path = Path::new(&path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", path, e))?
+        .to_string_lossy()
+        .into_owned();
+    settings_manager.update(|settings| {
+        settings.recent_files.retain(|recent| *recent != path);
+        settings.recent_files.insert(0, path.clone());
+        settings.recent_files.truncate(MAX_RECENT_FILES);
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_recent_files(settings_manager: State<'_, SettingsManager>) -> Vec<String> {
+    settings_manager
+        .get()
+        .recent_files
+        .into_iter()
+        .filter(|path| Path::new(path).is_file())
+        .collect()
+}
@@ -0,0 +1,177 @@
+use crate::error::AppError;
+use crate::sidecar::SidecarManager;
+use crate::validation;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+// One `PromptChunk` of a streamed reply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedChunk {
+    pub kind: String,
+    pub content: String,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub prompt: String,
+    pub response: Result<String, String>,
+    // Empty unless the reply was streamed
+    #[serde(default)]
+    pub chunks: Vec<RecordedChunk>,
+    pub duration_ms: u128,
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Default)]
+enum Mode {
+    #[default]
+    Off,
+    Recording(PathBuf),
+    Replaying {
+        path: PathBuf,
+        exchanges: Vec<RecordedExchange>,
+    },
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct RecordingStatus {
+    pub mode: &'static str,
+    pub path: Option<String>,
+}
+
+// Captures sidecar prompt exchanges to a JSONL file, or serves them back
+// instead of hitting the live sidecar
+#[derive(Debug, Default)]
+pub struct Recorder {
+    mode: Mutex<Mode>,
+}
+
+impl Recorder {
+    pub fn is_recording(&self) -> bool {
+        matches!(*self.mode.lock().unwrap(), Mode::Recording(_))
+    }
+
+    pub fn record(
+        &self,
+        prompt: &str,
+        response: &Result<String, String>,
+        chunks: Vec<RecordedChunk>,
+        duration_ms: u128,
+    ) {
+        let mode = self.mode.lock().unwrap();
+        let Mode::Recording(path) = &*mode else {
+            return;
+        };
+
+        let exchange = RecordedExchange {
+            prompt: prompt.to_string(),
+            response: response.clone(),
+            chunks,
+            duration_ms,
+            recorded_at: chrono::Local::now().to_rfc3339(),
+        };
+        let result = serde_json::to_string(&exchange)
+            .map_err(|e| e.to_string())
+            .and_then(|line| {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| e.to_string())?;
+                writeln!(file, "{}", line).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            log::error!("Failed to record sidecar exchange: {}", e);
+        }
+    }
+
+    // The recorded reply to `prompt` and the chunks it was streamed in, None
+    // unless replaying
+    pub fn replay(&self, prompt: &str) -> Option<(Result<String, String>, Vec<RecordedChunk>)> {
+        let mode = self.mode.lock().unwrap();
+        let Mode::Replaying { exchanges, .. } = &*mode else {
+            return None;
+        };
+
+        Some(
+            exchanges
+                .iter()
+                .find(|exchange| exchange.prompt == prompt)
+                .map(|exchange| (exchange.response.clone(), exchange.chunks.clone()))
+                .unwrap_or_else(|| {
+                    let error = "No recorded response for this prompt".to_string();
+                    (Err(error), Vec::new())
+                }),
+        )
+    }
+
+    fn status(&self) -> RecordingStatus {
+        match &*self.mode.lock().unwrap() {
+            Mode::Off => RecordingStatus {
+                mode: "off",
+                path: None,
+            },
+            Mode::Recording(path) => RecordingStatus {
+                mode: "recording",
+                path: Some(path.to_string_lossy().into_owned()),
+            },
+            Mode::Replaying { path, .. } => RecordingStatus {
+                mode: "replaying",
+                path: Some(path.to_string_lossy().into_owned()),
+            },
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn start_recording(
+    app: AppHandle,
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+    path: String,
+) -> Result<RecordingStatus, AppError> {
+    let path = validation::output_path(&app, "path", &path)?;
+    *sidecar_manager.recorder.mode.lock().unwrap() = Mode::Recording(path);
+    Ok(sidecar_manager.recorder.status())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn start_replay(
+    app: AppHandle,
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+    path: String,
+) -> Result<RecordingStatus, AppError> {
+    let path = validation::existing_path(&app, "path", &path)?;
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let exchanges = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| format!("Invalid recording line: {}", e))
+        })
+        .collect::<Result<Vec<RecordedExchange>, String>>()?;
+
+    *sidecar_manager.recorder.mode.lock().unwrap() = Mode::Replaying { path, exchanges };
+    Ok(sidecar_manager.recorder.status())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn stop_recording(sidecar_manager: State<'_, Arc<SidecarManager>>) -> RecordingStatus {
+    *sidecar_manager.recorder.mode.lock().unwrap() = Mode::Off;
+    sidecar_manager.recorder.status()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_recording_status(sidecar_manager: State<'_, Arc<SidecarManager>>) -> RecordingStatus {
+    sidecar_manager.recorder.status()
+}
@@ -0,0 +1,87 @@
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::{agent_protocol, logging, metrics};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use tauri::State;
+
+// Bounds for user-configured sizes
+const MIN_CAPACITY: usize = 100;
+const MAX_CAPACITY: usize = 100_000;
+
+// Fixed-capacity FIFO for in-memory buffers that would otherwise grow for as
+// long as a session runs. Once full, each push drops the oldest item and
+// counts it under `name` in the metrics.
+#[derive(Debug)]
+pub struct RingBuffer<T> {
+    name: &'static str,
+    capacity: usize,
+    items: VecDeque<T>,
+}
+
+impl<T> RingBuffer<T> {
+    pub const fn new(name: &'static str, capacity: usize) -> Self {
+        Self {
+            name,
+            capacity,
+            items: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push_back(item);
+        self.trim();
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.trim();
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.items.iter()
+    }
+
+    fn trim(&mut self) {
+        let excess = self.items.len().saturating_sub(self.capacity);
+        if excess > 0 {
+            self.items.drain(..excess);
+            metrics::record_drops(self.name, excess as u64);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct BufferSizes {
+    // Recent log lines, sidecar output included, kept for the log viewer
+    pub log_entries: usize,
+    // Events kept per session for `agent://` stream clients to catch up on
+    pub stream_events: usize,
+}
+
+impl Default for BufferSizes {
+    fn default() -> Self {
+        Self {
+            log_entries: 2000,
+            stream_events: 1000,
+        }
+    }
+}
+
+pub fn apply(sizes: BufferSizes) {
+    logging::set_capacity(sizes.log_entries.clamp(MIN_CAPACITY, MAX_CAPACITY));
+    agent_protocol::set_capacity(sizes.stream_events.clamp(MIN_CAPACITY, MAX_CAPACITY));
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_buffer_sizes(
+    settings_manager: State<'_, SettingsManager>,
+    sizes: BufferSizes,
+) -> Result<(), AppError> {
+    settings_manager.update(|settings| settings.buffer_sizes = sizes)?;
+    apply(sizes);
+    Ok(())
+}
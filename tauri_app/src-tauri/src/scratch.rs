@@ -0,0 +1,167 @@
+use crate::error::AppError;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+// The sidecar's shell tool exports `<root>/<session id>` under the same name
+pub const SCRATCH_DIR_ENV: &str = "MIX_SCRATCH_DIR";
+const SCRATCH_DIR_NAME: &str = "scratch";
+const SHARED_DIR_NAME: &str = "shared";
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Session dirs untouched for this long are removed
+const SCRATCH_RETENTION: Duration = Duration::from_secs(3 * 24 * 60 * 60);
+// Beyond this total size the least recently used session dirs are removed
+const MAX_SCRATCH_BYTES: u64 = 1024 * 1024 * 1024;
+
+fn scratch_root(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to resolve cache dir: {}", e))?;
+    Ok(dir.join(SCRATCH_DIR_NAME))
+}
+
+// Session ids come from the webview, so they must not be able to escape the root
+fn session_dir(app: &AppHandle, session_id: &str) -> Result<PathBuf, String> {
+    if session_id.is_empty()
+        || !session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid session id: {}", session_id));
+    }
+    Ok(scratch_root(app)?.join(session_id))
+}
+
+// A session's scratch dir, or one shared by tools run outside a session,
+// created if needed
+pub fn dir(app: &AppHandle, session_id: Option<&str>) -> Result<PathBuf, String> {
+    let dir = session_dir(app, session_id.unwrap_or(SHARED_DIR_NAME))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create scratch dir: {}", e))?;
+    Ok(dir)
+}
+
+// Whether the canonical `path` lies in a session's scratch dir, where the
+// agent's tools write their outputs
+pub fn contains(app: &AppHandle, path: &Path) -> bool {
+    scratch_root(app)
+        .and_then(|root| root.canonicalize().map_err(|e| e.to_string()))
+        .is_ok_and(|root| path.starts_with(root))
+}
+
+pub fn sidecar_env(app: &AppHandle) -> Vec<(String, String)> {
+    match scratch_root(app).and_then(|root| {
+        fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create scratch dir: {}", e))
+            .map(|_| root)
+    }) {
+        Ok(root) => vec![(
+            SCRATCH_DIR_ENV.to_string(),
+            root.to_string_lossy().into_owned(),
+        )],
+        Err(e) => {
+            log::error!("{}", e);
+            vec![]
+        }
+    }
+}
+
+// Total size and newest modification time of everything under `path`
+fn usage(path: &Path) -> (u64, SystemTime) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return (0, SystemTime::UNIX_EPOCH);
+    };
+    let mut size = metadata.len();
+    let mut modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            let (entry_size, entry_modified) = usage(&entry.path());
+            size += entry_size;
+            modified = modified.max(entry_modified);
+        }
+    }
+    (size, modified)
+}
+
+fn cleanup(app: &AppHandle) -> Result<(), String> {
+    let root = scratch_root(app)?;
+    let Ok(entries) = fs::read_dir(&root) else {
+        return Ok(());
+    };
+
+    let mut sessions: Vec<(PathBuf, u64, SystemTime)> = entries
+        .flatten()
+        .map(|entry| {
+            let (size, modified) = usage(&entry.path());
+            (entry.path(), size, modified)
+        })
+        .collect();
+    // Oldest first, so the size cap evicts the least recently used
+    sessions.sort_by_key(|(_, _, modified)| *modified);
+
+    let now = SystemTime::now();
+    let mut total: u64 = sessions.iter().map(|(_, size, _)| size).sum();
+    for (path, size, modified) in sessions {
+        let expired = now
+            .duration_since(modified)
+            .is_ok_and(|age| age > SCRATCH_RETENTION);
+        if !expired && total <= MAX_SCRATCH_BYTES {
+            continue;
+        }
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        match removed {
+            Ok(()) => {
+                total = total.saturating_sub(size);
+                log::info!("Removed scratch dir {}", path.display());
+            }
+            Err(e) => log::warn!("Failed to remove scratch dir {}: {}", path.display(), e),
+        }
+    }
+    Ok(())
+}
+
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = cleanup(&app) {
+                log::warn!("Scratch cleanup failed: {}", e);
+            }
+            tokio::time::sleep(CLEANUP_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_scratch_dir(app: AppHandle, session_id: String) -> Result<String, AppError> {
+    let dir = session_dir(&app, &session_id)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create scratch dir: {}", e))?;
+    Ok(dir.to_string_lossy().into_owned())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn open_scratch_dir(app: AppHandle, session_id: String) -> Result<(), AppError> {
+    let dir = get_scratch_dir(app.clone(), session_id)?;
+    app.opener()
+        .open_path(dir, None::<&str>)
+        .map_err(|e| AppError::Io(format!("Failed to open scratch dir: {}", e)))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn clear_scratch_dir(app: AppHandle, session_id: String) -> Result<(), AppError> {
+    let dir = session_dir(&app, &session_id)?;
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear scratch dir: {}", e))?;
+    }
+    log::info!("Cleared scratch dir for session {}", session_id);
+    Ok(())
+}
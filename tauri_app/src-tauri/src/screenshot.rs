@@ -0,0 +1,398 @@
+use crate::agent_tools::{self, Content, Progress, Tool};
+use crate::image_edit::{self, Crop, OutputFormat};
+use crate::platform::{Native, Platform};
+use crate::{approvals, scratch};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::f32::consts::{FRAC_PI_6, PI};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// The agent captures the screen, looks at it, then marks it up: boxes,
+// ellipses, lines, arrows and highlights are drawn here in Rust and the
+// result is handed back to the model as an image. Files are written to the
+// session's scratch folder and keep their full size.
+const APPROVAL_ACTION: &str = "screenshot";
+const MAX_SOURCE_BYTES: u64 = 100 * 1024 * 1024;
+// Longest side of the copy the model is shown
+const MAX_PREVIEW_SIDE: u32 = 1568;
+const MAX_ANNOTATIONS: usize = 50;
+const DEFAULT_COLOR: [u8; 3] = [255, 59, 48];
+const DEFAULT_THICKNESS: u32 = 4;
+const MAX_THICKNESS: u32 = 50;
+// Opacity of a highlight's fill
+const HIGHLIGHT_ALPHA: f32 = 0.35;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Shape {
+    Rect,
+    Ellipse,
+    Line,
+    Arrow,
+    Highlight,
+}
+
+// In image pixels. Boxes (rect, ellipse, highlight) span `width` by `height`
+// from (x, y); lines and arrows run from (x, y) to (x2, y2), with the arrow
+// head at the end.
+#[derive(Debug, Clone, Deserialize)]
+struct Annotation {
+    shape: Shape,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    x2: Option<f32>,
+    y2: Option<f32>,
+    // "#rrggbb"
+    color: Option<String>,
+    thickness: Option<u32>,
+}
+
+fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn blend(pixel: &mut Rgba<u8>, color: [u8; 3], alpha: f32) {
+    for (channel, value) in color.into_iter().enumerate() {
+        pixel[channel] =
+            (pixel[channel] as f32 * (1.0 - alpha) + value as f32 * alpha).round() as u8;
+    }
+    pixel[3] = pixel[3].max((alpha * 255.0).round() as u8);
+}
+
+// Blends `color` into the pixels within `(left, top, right, bottom)` whose
+// centres `inside` accepts
+fn paint(
+    image: &mut RgbaImage,
+    (left, top, right, bottom): (f32, f32, f32, f32),
+    color: [u8; 3],
+    alpha: f32,
+    inside: impl Fn(f32, f32) -> bool,
+) {
+    let (width, height) = image.dimensions();
+    let columns = (left.floor().max(0.0) as u32)..(right.ceil().max(0.0) as u32).min(width);
+    let rows = (top.floor().max(0.0) as u32)..(bottom.ceil().max(0.0) as u32).min(height);
+    for y in rows {
+        for x in columns.clone() {
+            if inside(x as f32 + 0.5, y as f32 + 0.5) {
+                blend(image.get_pixel_mut(x, y), color, alpha);
+            }
+        }
+    }
+}
+
+fn segment_distance((px, py): (f32, f32), (ax, ay): (f32, f32), (bx, by): (f32, f32)) -> f32 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let length = dx * dx + dy * dy;
+    let along = if length == 0.0 {
+        0.0
+    } else {
+        (((px - ax) * dx + (py - ay) * dy) / length).clamp(0.0, 1.0)
+    };
+    (px - (ax + along * dx)).hypot(py - (ay + along * dy))
+}
+
+fn stroke(image: &mut RgbaImage, from: (f32, f32), to: (f32, f32), color: [u8; 3], thickness: f32) {
+    let half = thickness / 2.0;
+    let bounds = (
+        from.0.min(to.0) - half,
+        from.1.min(to.1) - half,
+        from.0.max(to.0) + half,
+        from.1.max(to.1) + half,
+    );
+    paint(image, bounds, color, 1.0, |x, y| {
+        segment_distance((x, y), from, to) <= half
+    });
+}
+
+fn draw(image: &mut RgbaImage, annotation: &Annotation, number: usize) -> Result<(), String> {
+    let color = match annotation.color.as_deref() {
+        Some(color) => parse_color(color).ok_or_else(|| {
+            format!(
+                "Annotation {}: color must look like #ff0000, got {}",
+                number, color
+            )
+        })?,
+        None => DEFAULT_COLOR,
+    };
+    let thickness = annotation
+        .thickness
+        .unwrap_or(DEFAULT_THICKNESS)
+        .clamp(1, MAX_THICKNESS) as f32;
+    let (x, y) = (annotation.x, annotation.y);
+    let (right, bottom) = (x + annotation.width, y + annotation.height);
+
+    match annotation.shape {
+        Shape::Line | Shape::Arrow => {
+            let (Some(x2), Some(y2)) = (annotation.x2, annotation.y2) else {
+                return Err(format!(
+                    "Annotation {}: lines and arrows need x2 and y2",
+                    number
+                ));
+            };
+            stroke(image, (x, y), (x2, y2), color, thickness);
+            if matches!(annotation.shape, Shape::Arrow) {
+                let angle = (y2 - y).atan2(x2 - x);
+                let length = (thickness * 4.0).max(12.0);
+                for side in [-1.0, 1.0] {
+                    let wing = angle + PI + side * FRAC_PI_6;
+                    let end = (x2 + length * wing.cos(), y2 + length * wing.sin());
+                    stroke(image, (x2, y2), end, color, thickness);
+                }
+            }
+        }
+        _ if annotation.width <= 0.0 || annotation.height <= 0.0 => {
+            return Err(format!(
+                "Annotation {}: boxes need a width and a height",
+                number
+            ));
+        }
+        Shape::Rect => paint(image, (x, y, right, bottom), color, 1.0, |px, py| {
+            px < x + thickness
+                || px > right - thickness
+                || py < y + thickness
+                || py > bottom - thickness
+        }),
+        Shape::Ellipse => {
+            let (rx, ry) = (annotation.width / 2.0, annotation.height / 2.0);
+            let (cx, cy) = (x + rx, y + ry);
+            let (inner_x, inner_y) = (rx - thickness, ry - thickness);
+            paint(image, (x, y, right, bottom), color, 1.0, |px, py| {
+                let (dx, dy) = (px - cx, py - cy);
+                let within_outer = (dx / rx).powi(2) + (dy / ry).powi(2) <= 1.0;
+                let within_inner = inner_x > 0.0
+                    && inner_y > 0.0
+                    && (dx / inner_x).powi(2) + (dy / inner_y).powi(2) < 1.0;
+                within_outer && !within_inner
+            });
+        }
+        Shape::Highlight => paint(
+            image,
+            (x, y, right, bottom),
+            color,
+            HIGHLIGHT_ALPHA,
+            |_, _| true,
+        ),
+    }
+    Ok(())
+}
+
+fn open(path: &Path) -> Result<DynamicImage, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?
+        .len();
+    if size > MAX_SOURCE_BYTES {
+        return Err(format!(
+            "Images over {} MB can't be annotated",
+            MAX_SOURCE_BYTES / (1024 * 1024)
+        ));
+    }
+    image::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+// Writes `image` to `path` as PNG and describes it to the model, with a copy
+// it can see scaled down to `MAX_PREVIEW_SIDE`
+fn save(image: &DynamicImage, path: &Path) -> Result<Vec<Content>, String> {
+    image
+        .save_with_format(path, ImageFormat::Png)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let longest = image.width().max(image.height());
+    let mut text = format!(
+        "Saved {} ({}x{} pixels). Annotation and crop coordinates are in these pixels",
+        path.display(),
+        image.width(),
+        image.height()
+    );
+    let preview = if longest > MAX_PREVIEW_SIDE {
+        text.push_str(&format!(
+            "; the image below is shown at {:.0}% of that size",
+            MAX_PREVIEW_SIDE as f32 * 100.0 / longest as f32
+        ));
+        image.resize(MAX_PREVIEW_SIDE, MAX_PREVIEW_SIDE, FilterType::Triangle)
+    } else {
+        image.clone()
+    };
+    let mut bytes = Vec::new();
+    preview
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode the preview: {}", e))?;
+    Ok(vec![
+        Content::Text(text),
+        Content::Image {
+            data: STANDARD.encode(bytes),
+            mime_type: "image/png".to_string(),
+        },
+    ])
+}
+
+fn crop_arg(arguments: &Value, key: &str) -> Result<Option<Crop>, String> {
+    match arguments.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(area) => serde_json::from_value(area.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid {}: {}", key, e)),
+    }
+}
+
+fn session_dir(app: &AppHandle, arguments: &Value) -> Result<PathBuf, String> {
+    scratch::dir(app, arguments.get("session_id").and_then(Value::as_str))
+}
+
+async fn capture_screen(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<Vec<Content>, String> {
+    let region = crop_arg(&arguments, "region")?;
+    let dir = session_dir(&app, &arguments)?;
+    approvals::require_approval(
+        &app,
+        APPROVAL_ACTION,
+        "Take a screenshot of the main display",
+    )
+    .await
+    .map_err(|_| "The user declined the screenshot".to_string())?;
+
+    progress.send("Capturing the screen");
+    let content = tauri::async_runtime::spawn_blocking(move || {
+        let name = format!(
+            "screenshot-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let path = image_edit::output_path(&dir, Path::new(&name), "capture", OutputFormat::Png);
+        Native::capture_screen(&path)?;
+        let mut image = open(&path)?;
+        if let Some(area) = region {
+            image = image_edit::crop(image, area).map_err(|e| e.to_string())?;
+        }
+        save(&image, &path)
+    })
+    .await
+    .map_err(|e| format!("Capturing the screen failed: {}", e))??;
+    log::info!("Agent captured the screen");
+    Ok(content)
+}
+
+async fn annotate_image(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<Vec<Content>, String> {
+    let source = agent_tools::string_arg(&arguments, "source")?;
+    let source = image_edit::source_path(&app, &source).map_err(|e| e.to_string())?;
+    let annotations: Vec<Annotation> = match arguments.get("annotations") {
+        Some(annotations) => serde_json::from_value(annotations.clone())
+            .map_err(|e| format!("Invalid annotations: {}", e))?,
+        None => Vec::new(),
+    };
+    let crop = crop_arg(&arguments, "crop")?;
+    if annotations.is_empty() && crop.is_none() {
+        return Err("Give annotations to draw or an area to crop".to_string());
+    }
+    if annotations.len() > MAX_ANNOTATIONS {
+        return Err(format!(
+            "At most {} annotations can be drawn at once",
+            MAX_ANNOTATIONS
+        ));
+    }
+    let dir = session_dir(&app, &arguments)?;
+
+    progress.send(&format!("Annotating {}", source.display()));
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut canvas = open(&source)?.to_rgba8();
+        for (index, annotation) in annotations.iter().enumerate() {
+            draw(&mut canvas, annotation, index + 1)?;
+        }
+        let mut image = DynamicImage::ImageRgba8(canvas);
+        // Cropped last, so annotations and crop share the source's coordinates
+        if let Some(area) = crop {
+            image = image_edit::crop(image, area).map_err(|e| e.to_string())?;
+        }
+        let path = image_edit::output_path(&dir, &source, "annotated", OutputFormat::Png);
+        save(&image, &path)
+    })
+    .await
+    .map_err(|e| format!("Annotating failed: {}", e))?
+}
+
+pub fn register_tools() {
+    let area = json!({
+        "type": "object",
+        "properties": {
+            "x": { "type": "integer", "minimum": 0 },
+            "y": { "type": "integer", "minimum": 0 },
+            "width": { "type": "integer", "minimum": 1 },
+            "height": { "type": "integer", "minimum": 1 }
+        },
+        "required": ["x", "y", "width", "height"]
+    });
+    agent_tools::register(Tool {
+        name: "capture_screen",
+        description: "Take a screenshot of the main display, optionally cropped to `region` in \
+            screen pixels, after the user allows it. The image is saved to the session's \
+            scratch folder and shown to you; mark it up with annotate_image.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "session_id": { "type": "string" },
+                "region": area
+            }
+        }),
+        handler: agent_tools::content_handler(capture_screen),
+    });
+    agent_tools::register(Tool {
+        name: "annotate_image",
+        description: "Draw on an image, such as a capture_screen result, to point something \
+            out: rect and ellipse outline a box, highlight tints it, line and arrow run from \
+            (x, y) to (x2, y2). Coordinates are image pixels. `crop` is applied after drawing. \
+            The annotated copy is saved next to the session's other outputs and shown to you.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "source": { "type": "string", "description": "Absolute path of the image" },
+                "session_id": { "type": "string" },
+                "annotations": {
+                    "type": "array",
+                    "maxItems": MAX_ANNOTATIONS,
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "shape": {
+                                "type": "string",
+                                "enum": ["rect", "ellipse", "highlight", "line", "arrow"]
+                            },
+                            "x": { "type": "number" },
+                            "y": { "type": "number" },
+                            "width": { "type": "number" },
+                            "height": { "type": "number" },
+                            "x2": { "type": "number" },
+                            "y2": { "type": "number" },
+                            "color": { "type": "string", "description": "#rrggbb, red by default" },
+                            "thickness": { "type": "integer", "minimum": 1, "maximum": MAX_THICKNESS }
+                        },
+                        "required": ["shape", "x", "y"]
+                    }
+                },
+                "crop": area
+            },
+            "required": ["source"]
+        }),
+        handler: agent_tools::content_handler(annotate_image),
+    });
+}
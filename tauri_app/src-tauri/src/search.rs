@@ -0,0 +1,142 @@
+use crate::files::{self, FsError};
+use crate::workspace;
+use grep_matcher::Matcher;
+use grep_regex::RegexMatcherBuilder;
+use grep_searcher::sinks::UTF8;
+use grep_searcher::{BinaryDetection, SearcherBuilder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const DEFAULT_MAX_RESULTS: usize = 500;
+const MAX_LINE_CHARS: usize = 500;
+
+#[derive(Debug, Default, Deserialize, Type)]
+pub struct SearchOptions {
+    // Treat the query as a regex instead of a literal string
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    pub globs: Option<Vec<String>>,
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct SearchMatch {
+    // Relative to the workspace root
+    pub path: String,
+    // 1-based, column counts bytes like ripgrep
+    pub line: u64,
+    pub column: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct SearchResults {
+    pub matches: Vec<SearchMatch>,
+    // Set when the result limit cut the search short
+    pub truncated: bool,
+}
+
+// Runs off the main thread, as searching a large repo takes a while
+#[tauri::command]
+#[specta::specta]
+pub async fn search_workspace(
+    app: AppHandle,
+    query: String,
+    opts: Option<SearchOptions>,
+) -> Result<SearchResults, FsError> {
+    if query.is_empty() {
+        return Err(FsError::Invalid {
+            reason: "Search query is empty".to_string(),
+        });
+    }
+    let root = workspace::root(&app)?;
+    let path = root.to_string_lossy().into_owned();
+    tauri::async_runtime::spawn_blocking(move || search(root, &query, opts))
+        .await
+        .map_err(|e| FsError::Io {
+            path,
+            reason: e.to_string(),
+        })?
+}
+
+fn search(
+    root: PathBuf,
+    query: &str,
+    opts: Option<SearchOptions>,
+) -> Result<SearchResults, FsError> {
+    let opts = opts.unwrap_or_default();
+    let max_results = opts.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+
+    let matcher = RegexMatcherBuilder::new()
+        .fixed_strings(!opts.regex)
+        .case_insensitive(!opts.case_sensitive)
+        .build(query)
+        .map_err(|e| FsError::Invalid {
+            reason: format!("Invalid search pattern: {}", e),
+        })?;
+    let mut searcher = SearcherBuilder::new()
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .line_number(true)
+        .build();
+
+    let mut matches: Vec<SearchMatch> = Vec::new();
+    let mut truncated = false;
+    for entry in files::walker(&root, opts.globs)?.build() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let path = entry
+            .path()
+            .strip_prefix(&root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let result = searcher.search_path(
+            &matcher,
+            entry.path(),
+            UTF8(|line_number, line| {
+                if matches.len() == max_results {
+                    truncated = true;
+                    return Ok(false);
+                }
+                let column = matcher
+                    .find(line.as_bytes())
+                    .ok()
+                    .flatten()
+                    .map(|found| found.start() + 1)
+                    .unwrap_or(1);
+                matches.push(SearchMatch {
+                    path: path.clone(),
+                    line: line_number,
+                    column,
+                    text: line
+                        .trim_end_matches(['\r', '\n'])
+                        .chars()
+                        .take(MAX_LINE_CHARS)
+                        .collect(),
+                });
+                Ok(true)
+            }),
+        );
+        // Unreadable and non-UTF-8 files are skipped rather than failing the search
+        if let Err(e) = result {
+            log::debug!("Skipping {} during search: {}", path, e);
+        }
+        if truncated {
+            break;
+        }
+    }
+
+    Ok(SearchResults { matches, truncated })
+}
@@ -0,0 +1,110 @@
+use crate::error::AppError;
+use keyring::Entry;
+use regex::Regex;
+use std::sync::{LazyLock, Mutex};
+
+const KEYCHAIN_SERVICE: &str = "com.mix-tauri-app.app";
+const REDACTED: &str = "[REDACTED]";
+// Shorter values are too likely to collide with ordinary text
+const MIN_REDACTED_LEN: usize = 8;
+
+// Values read from or written to the keychain during this session
+static KNOWN_SECRETS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+// Common provider key shapes, caught even if they never went through the keychain
+static KEY_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(sk-[A-Za-z0-9_-]{16,}|gsk_[A-Za-z0-9]{20,}|AIza[0-9A-Za-z_-]{35})").unwrap()
+});
+
+// Secrets stored under these names are passed to the sidecar as environment
+// variables of the same name
+pub const PROVIDER_KEYS: [&str; 6] = [
+    "ANTHROPIC_API_KEY",
+    "OPENAI_API_KEY",
+    "GEMINI_API_KEY",
+    "GROQ_API_KEY",
+    "OPENROUTER_API_KEY",
+    "AZURE_OPENAI_API_KEY",
+];
+
+fn entry(name: &str) -> Result<Entry, String> {
+    if name.trim().is_empty() {
+        return Err("Secret name must not be empty".to_string());
+    }
+    Entry::new(KEYCHAIN_SERVICE, name).map_err(|e| format!("Failed to open keychain: {}", e))
+}
+
+fn remember(value: &str) {
+    if value.len() < MIN_REDACTED_LEN {
+        return;
+    }
+    let mut known = KNOWN_SECRETS.lock().unwrap();
+    if !known.iter().any(|known| known == value) {
+        known.push(value.to_string());
+    }
+}
+
+pub fn redact(text: &str) -> String {
+    let mut redacted = KEY_PATTERN.replace_all(text, REDACTED).into_owned();
+    for value in KNOWN_SECRETS.lock().unwrap().iter() {
+        if redacted.contains(value.as_str()) {
+            redacted = redacted.replace(value.as_str(), REDACTED);
+        }
+    }
+    redacted
+}
+
+pub fn get_secret(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(value) => {
+            remember(&value);
+            Ok(Some(value))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read secret {}: {}", name, e)),
+    }
+}
+
+pub fn provider_env() -> Vec<(String, String)> {
+    PROVIDER_KEYS
+        .iter()
+        .filter_map(|name| match get_secret(name) {
+            Ok(value) => value.map(|value| (name.to_string(), value)),
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_secret(name: String, value: String) -> Result<(), AppError> {
+    entry(&name)?
+        .set_password(&value)
+        .map_err(|e| format!("Failed to store secret {}: {}", name, e))?;
+    remember(&value);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_secret_exists(name: String) -> Result<bool, AppError> {
+    Ok(get_secret(&name)?.is_some())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_secret(name: String) -> Result<(), AppError> {
+    if let Some(value) = get_secret(&name)? {
+        KNOWN_SECRETS
+            .lock()
+            .unwrap()
+            .retain(|known| *known != value);
+    }
+    match entry(&name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete secret {}: {}", name, e).into()),
+    }
+}
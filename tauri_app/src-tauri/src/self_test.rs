@@ -0,0 +1,135 @@
+use crate::error::AppError;
+use crate::integrity::sidecar_path;
+use crate::settings::SettingsManager;
+use crate::sidecar::{self, SidecarManager};
+use serde::Serialize;
+use specta::Type;
+use std::fs;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_macos_permissions::{
+    check_accessibility_permission, check_microphone_permission, check_screen_recording_permission,
+};
+
+#[derive(Debug, Serialize, Type)]
+pub struct SelfTestCheck {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub passed: bool,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+fn check(id: &'static str, label: &'static str, result: Result<(), String>) -> SelfTestCheck {
+    SelfTestCheck {
+        id,
+        label,
+        passed: result.is_ok(),
+        detail: result.err(),
+    }
+}
+
+fn check_sidecar_binary() -> Result<(), String> {
+    let path = sidecar_path()?;
+    let metadata = fs::metadata(&path)
+        .map_err(|_| format!("Sidecar binary not found at {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!(
+                "Sidecar binary at {} is not executable",
+                path.display()
+            ));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = metadata;
+
+    Ok(())
+}
+
+// Passes when the sidecar could start, including on a fallback port
+fn check_port(sidecar_running: bool, fallback: bool) -> Result<(), String> {
+    // The port is expected to be taken while our own sidecar is up
+    if sidecar_running {
+        return Ok(());
+    }
+    sidecar::choose_port(sidecar::SIDECAR_PORT, fallback)
+        .map(|_| ())
+        .map_err(String::from)
+}
+
+fn check_data_dir(app: &AppHandle) -> Result<(), String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let probe = dir.join(".self-test");
+    fs::write(&probe, b"ok").map_err(|e| format!("{} is not writable: {}", dir.display(), e))?;
+    let _ = fs::remove_file(probe);
+    Ok(())
+}
+
+fn check_permission(granted: bool) -> Result<(), String> {
+    if granted {
+        Ok(())
+    } else {
+        Err("Permission not granted".to_string())
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn run_self_test(
+    app: AppHandle,
+    sidecar_manager: State<'_, Arc<SidecarManager>>,
+) -> Result<SelfTestReport, AppError> {
+    // The file and socket probes block, so they run off the async runtime
+    let sidecar_running = sidecar_manager.is_running();
+    let fallback = app.state::<SettingsManager>().get().sidecar_port_fallback;
+    let probe_app = app.clone();
+    let (binary, port, data_dir) = tauri::async_runtime::spawn_blocking(move || {
+        (
+            check_sidecar_binary(),
+            check_port(sidecar_running, fallback),
+            check_data_dir(&probe_app),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to run self-test: {}", e))?;
+
+    let checks = vec![
+        check("sidecar_binary", "Sidecar binary", binary),
+        check("sidecar_port", "Sidecar port", port),
+        check(
+            "accessibility",
+            "Accessibility permission",
+            check_permission(check_accessibility_permission().await),
+        ),
+        check(
+            "screen_recording",
+            "Screen recording permission",
+            check_permission(check_screen_recording_permission().await),
+        ),
+        check(
+            "microphone",
+            "Microphone permission",
+            check_permission(check_microphone_permission().await),
+        ),
+        check("data_dir", "Data directory writable", data_dir),
+    ];
+
+    Ok(SelfTestReport {
+        passed: checks.iter().all(|check| check.passed),
+        checks,
+    })
+}
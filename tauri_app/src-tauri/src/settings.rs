@@ -0,0 +1,167 @@
+use crate::databases::DatabaseConnection;
+use crate::delivery::DeliveryTarget;
+use crate::editors::Editor;
+use crate::mcp::McpServerConfig;
+use crate::oauth::OAuthConfig;
+use crate::prompt_cache::PromptCacheSettings;
+use crate::providers::Provider;
+use crate::ring_buffer::BufferSizes;
+use crate::shell_tool::ShellToolSettings;
+use crate::sidecar::SidecarTransport;
+use crate::sounds::SoundSettings;
+use crate::warmup::WarmupSettings;
+use crate::web_search::WebSearchSettings;
+use crate::webhooks::WebhookConfig;
+use crate::webview_language::WebviewLanguage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::watch;
+
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct Settings {
+    pub telemetry_enabled: bool,
+    pub telemetry_endpoint: Option<String>,
+    // Directories the user approved for file access, see `fs_access`
+    pub fs_grants: Vec<String>,
+    pub encrypt_history: bool,
+    // Sensitive actions the user chose to "always allow", see `approvals`
+    pub always_allowed_actions: Vec<String>,
+    pub oauth_providers: Vec<OAuthConfig>,
+    pub lock_enabled: bool,
+    // Lock after the window has been in the background this long, 0 disables
+    pub auto_lock_secs: u64,
+    pub workspace: Option<String>,
+    pub recent_workspaces: Vec<String>,
+    // Pinned projects stay at the top of the recents, see `recents`
+    pub pinned_workspaces: Vec<String>,
+    pub recent_files: Vec<String>,
+    pub mcp_servers: Vec<McpServerConfig>,
+    // Serve the app's own tools to other MCP clients, see `mcp_server`
+    pub mcp_server_enabled: bool,
+    pub mcp_server_port: Option<u16>,
+    pub ollama_url: Option<String>,
+    // Session id -> where its prompts go, see `providers`
+    pub session_providers: HashMap<String, Provider>,
+    // Serve `/v1/chat/completions` on localhost, see `openai_api`
+    pub openai_api_enabled: bool,
+    pub openai_api_port: Option<u16>,
+    // Takes effect when the sidecar next starts
+    pub sidecar_transport: SidecarTransport,
+    // Start the sidecar on free ports when its usual ones are taken
+    pub sidecar_port_fallback: bool,
+    // Launcher extension API, see `companion_api`
+    pub companion_api_enabled: bool,
+    pub companion_api_port: Option<u16>,
+    // Incoming webhook triggers, see `webhooks`
+    pub webhooks_enabled: bool,
+    pub webhook_port: Option<u16>,
+    pub webhooks: Vec<WebhookConfig>,
+    // Slack/Discord destinations for results, see `delivery`
+    pub delivery_targets: Vec<DeliveryTarget>,
+    // Notes vault for Markdown exports and the folder inside it, see `vault`
+    pub vault_path: Option<String>,
+    pub vault_folder: Option<String>,
+    // Capacities of the in-memory buffers, see `ring_buffer`
+    pub buffer_sizes: BufferSizes,
+    // Opt-in reuse of identical one-shot prompt replies, see `prompt_cache`
+    pub prompt_cache: PromptCacheSettings,
+    // Which launch profiles send a warm-up prompt once the sidecar is ready
+    pub warmup: WarmupSettings,
+    // Theme streamed replies' code blocks are pre-highlighted with, see
+    // `highlight`; the dark theme when unset
+    pub highlight_theme: Option<String>,
+    // Set once the first-run checklist is done, see `onboarding`
+    #[serde(default = "onboarded_by_default")]
+    pub onboarding_completed: bool,
+    // Editor file references open in, see `editors`; the first one installed
+    // when unset
+    pub preferred_editor: Option<Editor>,
+    // Window label -> spellcheck and input languages, see `webview_language`
+    pub webview_languages: HashMap<String, WebviewLanguage>,
+    // Language of tray menus, dialogs and notifications, see `i18n`; the OS's
+    // when unset
+    pub locale: Option<String>,
+    // Sounds for finished prompts, errors and approvals, see `sounds`
+    pub sounds: SoundSettings,
+    // What the agent's `run_shell` tool may run without asking, see `shell_tool`
+    pub shell_tool: ShellToolSettings,
+    // Provider behind the agent's `web_search` tool, see `web_search`
+    pub web_search: WebSearchSettings,
+    // Read-only databases the agent may query, see `databases`
+    pub database_connections: Vec<DatabaseConnection>,
+    // Show a notification with what the agent's `write_clipboard` tool copied
+    pub clipboard_notifications: bool,
+}
+
+// Settings files from before onboarding existed belong to set-up installs;
+// a fresh install starts from `Settings::default()`, where this is false
+fn onboarded_by_default() -> bool {
+    true
+}
+
+pub struct SettingsManager {
+    path: PathBuf,
+    settings: Mutex<Settings>,
+    // Receives every saved update, see `subscribe`
+    changes: watch::Sender<Settings>,
+}
+
+impl SettingsManager {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let dir = app
+            .path()
+            .app_config_dir()
+            .map_err(|e| format!("Failed to resolve config dir: {}", e))?;
+        let path = dir.join(SETTINGS_FILE_NAME);
+
+        let settings = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to parse settings: {}", e))?,
+            Err(_) => Settings::default(),
+        };
+
+        Ok(Self {
+            path,
+            changes: watch::Sender::new(settings.clone()),
+            settings: Mutex::new(settings),
+        })
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn update(&self, apply: impl FnOnce(&mut Settings)) -> Result<Settings, String> {
+        let mut settings = self.settings.lock().unwrap();
+        apply(&mut settings);
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(&*settings)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        fs::write(&self.path, contents).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+        self.changes.send_replace(settings.clone());
+        Ok(settings.clone())
+    }
+
+    // Resolves after every saved update
+    pub fn subscribe(&self) -> watch::Receiver<Settings> {
+        self.changes.subscribe()
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_settings(settings_manager: State<'_, SettingsManager>) -> Settings {
+    settings_manager.get()
+}
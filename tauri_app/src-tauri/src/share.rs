@@ -0,0 +1,54 @@
+use crate::error::AppError;
+use crate::platform::{Native, Platform, ShareAnchor, ShareItem};
+use crate::validation;
+use serde::Deserialize;
+use specta::Type;
+use tauri::{AppHandle, WebviewWindow};
+
+#[derive(Debug, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShareContent {
+    // A response's text, shared as plain text
+    Text { text: String },
+    // An exported file in a granted folder, shared as the file itself
+    Path { path: String },
+}
+
+// Opens the share sheet over the calling window, so a response or export can
+// go to Mail, Messages or AirDrop. Resolves once the sheet is shown; what the
+// user picks there isn't reported back.
+#[tauri::command]
+#[specta::specta]
+pub async fn share_content(
+    app: AppHandle,
+    window: WebviewWindow,
+    content: ShareContent,
+    anchor: Option<ShareAnchor>,
+) -> Result<(), AppError> {
+    let item = match content {
+        ShareContent::Text { text } => {
+            if text.trim().is_empty() {
+                return Err(AppError::InvalidField {
+                    field: "content.text".to_string(),
+                    message: "must not be empty".to_string(),
+                });
+            }
+            ShareItem::Text(text)
+        }
+        ShareContent::Path { path } => {
+            ShareItem::File(validation::existing_path(&app, "content.path", &path)?)
+        }
+    };
+
+    let (done, shown) = tokio::sync::oneshot::channel();
+    let target = window.clone();
+    window
+        .run_on_main_thread(move || {
+            let _ = done.send(Native::share(&target, item, anchor));
+        })
+        .map_err(|e| format!("Failed to reach the main thread: {}", e))?;
+    shown
+        .await
+        .map_err(|_| "The share sheet was never shown".to_string())??;
+    Ok(())
+}
@@ -0,0 +1,540 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::{approvals, scratch, workspace};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+pub const SHELL_EVENT: &str = "shell://event";
+// Commands off the allowlist are confirmed by the user under this action
+const APPROVAL_ACTION: &str = "run_shell";
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+// What the model gets back; the UI sees everything as it streams
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+// Shell syntax that chains, substitutes or redirects. An allowed prefix says
+// nothing about what runs after one of these, so such commands always ask.
+const CONTROL: [&str; 8] = [";", "&", "|", "`", "$(", ">", "<", "\n"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ShellToolSettings {
+    // Commands that run without asking, matched word by word against the
+    // start of the command, e.g. "git status" or "ls"
+    pub allowed_commands: Vec<String>,
+    // Commands that never run, matched the same way against every part of a
+    // chained command
+    pub denied_commands: Vec<String>,
+    // Upper bound for a command, which may ask for less
+    pub timeout_secs: u64,
+}
+
+impl Default for ShellToolSettings {
+    fn default() -> Self {
+        let strings = |list: &[&str]| list.iter().map(|s| s.to_string()).collect();
+        Self {
+            allowed_commands: strings(&[
+                "ls",
+                "pwd",
+                "cat",
+                "head",
+                "tail",
+                "wc",
+                "echo",
+                "which",
+                "grep",
+                "rg",
+                "git status",
+                "git diff",
+                "git log",
+                "git show",
+                "git branch",
+            ]),
+            denied_commands: strings(&[
+                "sudo", "su", "doas", "rm -rf /", "mkfs", "dd", "shutdown", "reboot", "diskutil",
+            ]),
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellStream {
+    Stdout,
+    Stderr,
+}
+
+// Live view of a command for the webview, by the call's id
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShellEvent {
+    Started {
+        call_id: String,
+        command: String,
+        cwd: String,
+    },
+    Output {
+        call_id: String,
+        stream: ShellStream,
+        text: String,
+    },
+    Exited {
+        call_id: String,
+        // None when the command was killed or timed out
+        exit_code: Option<i32>,
+        timed_out: bool,
+    },
+}
+
+fn words(command: &str) -> Vec<&str> {
+    command.split_whitespace().collect()
+}
+
+// Whether `rule`'s words start `command`, with the program compared by its
+// file name so "/bin/rm" is caught by "rm"
+fn starts_with(rule: &str, command: &[&str]) -> bool {
+    let rule = words(rule);
+    if rule.is_empty() || rule.len() > command.len() {
+        return false;
+    }
+    let program = command[0].rsplit(['/', '\\']).next().unwrap_or(command[0]);
+    rule[0] == program && rule[1..] == command[1..rule.len()]
+}
+
+fn denied_by<'a>(settings: &'a ShellToolSettings, command: &str) -> Option<&'a str> {
+    let mut parts = vec![command.to_string()];
+    for separator in CONTROL.iter().chain(&["(", ")", "{", "}"]) {
+        parts = parts
+            .iter()
+            .flat_map(|part| part.split(separator).map(str::to_string))
+            .collect();
+    }
+    settings
+        .denied_commands
+        .iter()
+        .find(|rule| parts.iter().any(|part| starts_with(rule, &words(part))))
+        .map(String::as_str)
+}
+
+// Flags that make an otherwise read-only allowlisted program run another
+// program, write files or change state. A command using one always asks.
+const UNSAFE_FLAGS: [(&str, &[&str]); 3] = [
+    // --pre runs a program on every file searched; -z runs decompressors
+    ("rg", &["--pre", "--pre-glob", "--search-zip", "-z"]),
+    (
+        "git",
+        &[
+            "--output",
+            "--ext-diff",
+            "--textconv",
+            "--open-files-in-pager",
+            "-O",
+        ],
+    ),
+    // Anything but listing branches
+    (
+        "git branch",
+        &[
+            "-d",
+            "-D",
+            "--delete",
+            "-m",
+            "-M",
+            "--move",
+            "-c",
+            "-C",
+            "--copy",
+            "-f",
+            "--force",
+            "-u",
+            "--set-upstream-to",
+            "--unset-upstream",
+            "--edit-description",
+            "-t",
+            "--track",
+            "--no-track",
+            "--create-reflog",
+        ],
+    ),
+];
+
+// Characters an auto-approved word may contain. Quotes, `$`, globs, `~` and
+// the like are left to the shell to expand, so what they stand for can't be
+// checked here.
+fn is_plain_word(word: &str) -> bool {
+    word.chars()
+        .all(|c| c.is_alphanumeric() || "-_./=,:@+".contains(c))
+}
+
+// Whether `word` is `flag`, `flag=value`, or a bundle of short flags that
+// contains it, e.g. "-Dq" for "-D"
+fn uses_flag(word: &str, flag: &str) -> bool {
+    if let Some(long) = flag.strip_prefix("--") {
+        return word
+            .strip_prefix("--")
+            .is_some_and(|used| used == long || used.starts_with(&format!("{}=", long)));
+    }
+    let short = flag.trim_start_matches('-');
+    !word.starts_with("--") && word.len() > 1 && word.starts_with('-') && word[1..].contains(short)
+}
+
+// A path argument that leaves `dir`: absolute, `..`, or resolving outside it
+// through a symlink. Arguments that aren't existing paths (grep patterns,
+// revisions) only fail the first two checks.
+fn leaves_dir(dir: &Path, argument: &str) -> bool {
+    let value = match argument.split_once('=') {
+        Some((flag, value)) if flag.starts_with('-') => value,
+        _ if argument.starts_with('-') => return false,
+        _ => argument,
+    };
+    let path = Path::new(value);
+    if path.is_absolute()
+        || value.starts_with(['/', '\\'])
+        || path.components().any(|c| matches!(c, Component::ParentDir))
+    {
+        return true;
+    }
+    match (dir.join(path).canonicalize(), dir.canonicalize()) {
+        (Ok(resolved), Ok(dir)) => !resolved.starts_with(dir),
+        _ => false,
+    }
+}
+
+// An allowlisted command runs without asking only when every word is plain,
+// no flag from UNSAFE_FLAGS is used and no path argument leaves `dir`
+fn is_allowed(settings: &ShellToolSettings, command: &str, dir: &Path) -> bool {
+    if CONTROL.iter().any(|control| command.contains(control)) {
+        return false;
+    }
+    let argv = words(command);
+    let Some(rule) = settings
+        .allowed_commands
+        .iter()
+        .map(|rule| words(rule))
+        .filter(|rule| starts_with(&rule.join(" "), &argv))
+        .max_by_key(Vec::len)
+    else {
+        return false;
+    };
+    let arguments = &argv[rule.len()..];
+    if !argv.iter().all(|word| is_plain_word(word)) {
+        return false;
+    }
+    let unsafe_flag = UNSAFE_FLAGS
+        .iter()
+        .filter(|(program, _)| starts_with(program, &argv))
+        .flat_map(|(_, flags)| flags.iter())
+        .any(|flag| arguments.iter().any(|word| uses_flag(word, flag)));
+    if unsafe_flag {
+        return false;
+    }
+    // `git branch <name>` creates a branch; listing takes no names
+    if starts_with("git branch", &argv)
+        && arguments.iter().any(|word| !word.starts_with('-'))
+        && !arguments
+            .iter()
+            .any(|word| matches!(*word, "-l" | "--list" | "-a" | "-r"))
+    {
+        return false;
+    }
+    !arguments.iter().any(|argument| leaves_dir(dir, argument))
+}
+
+// The open workspace, or a scratch dir when there is none or it's asked for
+fn working_dir(app: &AppHandle, arguments: &Value) -> Result<PathBuf, String> {
+    let cwd = arguments
+        .get("cwd")
+        .and_then(Value::as_str)
+        .unwrap_or("workspace");
+    let session_id = arguments.get("session_id").and_then(Value::as_str);
+    match (cwd, workspace::current(app)) {
+        ("workspace", Some(dir)) => Ok(dir),
+        ("workspace" | "scratch", _) => scratch::dir(app, session_id),
+        (other, _) => Err(format!(
+            "cwd must be \"workspace\" or \"scratch\", not {:?}",
+            other
+        )),
+    }
+}
+
+async fn pump(
+    mut pipe: impl AsyncRead + Unpin,
+    stream: ShellStream,
+    chunks: mpsc::UnboundedSender<(ShellStream, String)>,
+) {
+    let mut buf = [0u8; 4096];
+    while let Ok(read) = pipe.read(&mut buf).await {
+        if read == 0 {
+            break;
+        }
+        let text = String::from_utf8_lossy(&buf[..read]).into_owned();
+        if chunks.send((stream, text)).is_err() {
+            break;
+        }
+    }
+}
+
+async fn run_shell(app: AppHandle, arguments: Value, progress: Progress) -> Result<String, String> {
+    let command_line = agent_tools::string_arg(&arguments, "command")?;
+    if command_line.trim().is_empty() {
+        return Err("command must not be empty".to_string());
+    }
+    let settings = app.state::<SettingsManager>().get().shell_tool;
+    let dir = working_dir(&app, &arguments)?;
+    let max_secs = settings
+        .timeout_secs
+        .clamp(1, agent_tools::CALL_TIMEOUT.as_secs());
+    let timeout = Duration::from_secs(
+        arguments
+            .get("timeout_secs")
+            .and_then(Value::as_u64)
+            .unwrap_or(max_secs)
+            .clamp(1, max_secs),
+    );
+
+    if let Some(rule) = denied_by(&settings, &command_line) {
+        return Err(format!("Refused: `{}` is on the shell denylist", rule));
+    }
+    if !is_allowed(&settings, &command_line, &dir) {
+        let detail = format!("Run `{}` in {}", command_line, dir.display());
+        // "Always allow" covers this exact command, never the whole tool
+        let scope = words(&command_line).join(" ");
+        approvals::require_scoped_approval(&app, APPROVAL_ACTION, &scope, &detail)
+            .await
+            .map_err(|_| "The user declined to run the command".to_string())?;
+    }
+
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", &command_line]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", &command_line]);
+        command
+    };
+    command
+        .current_dir(&dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to start the shell: {}", e))?;
+
+    let call_id = uuid::Uuid::new_v4().to_string();
+    let _ = app.emit(
+        SHELL_EVENT,
+        ShellEvent::Started {
+            call_id: call_id.clone(),
+            command: command_line.clone(),
+            cwd: dir.to_string_lossy().into_owned(),
+        },
+    );
+    let (tx, mut chunks) = mpsc::unbounded_channel();
+    if let Some(stdout) = child.stdout.take() {
+        tauri::async_runtime::spawn(pump(stdout, ShellStream::Stdout, tx.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tauri::async_runtime::spawn(pump(stderr, ShellStream::Stderr, tx));
+    }
+
+    let mut output = String::new();
+    let mut truncated = false;
+    let finished = tokio::time::timeout(timeout, async {
+        while let Some((stream, text)) = chunks.recv().await {
+            progress.send(&text);
+            // Keeps what fits of the first chunk past the limit and nothing after
+            // it, so the output has no gaps
+            if !truncated {
+                let mut fits = text.len().min(MAX_OUTPUT_BYTES - output.len());
+                while !text.is_char_boundary(fits) {
+                    fits -= 1;
+                }
+                output.push_str(&text[..fits]);
+                truncated = fits < text.len();
+            }
+            let _ = app.emit(
+                SHELL_EVENT,
+                ShellEvent::Output {
+                    call_id: call_id.clone(),
+                    stream,
+                    text,
+                },
+            );
+        }
+        child.wait().await
+    })
+    .await;
+    // Dropping the child kills it if it's still running
+    let exit_code = match &finished {
+        Ok(Ok(status)) => status.code(),
+        _ => None,
+    };
+    let timed_out = finished.is_err();
+    let _ = app.emit(
+        SHELL_EVENT,
+        ShellEvent::Exited {
+            call_id,
+            exit_code,
+            timed_out,
+        },
+    );
+
+    let mut result = format!("$ {}\n{}", command_line, output);
+    if !result.ends_with('\n') {
+        result.push('\n');
+    }
+    if truncated {
+        result.push_str(&format!(
+            "[output truncated after {} bytes]\n",
+            MAX_OUTPUT_BYTES
+        ));
+    }
+    let ok = exit_code == Some(0);
+    match (timed_out, exit_code) {
+        (true, _) => result.push_str(&format!("[timed out after {}s]", timeout.as_secs())),
+        (false, Some(code)) => result.push_str(&format!("[exit code {}]", code)),
+        (false, None) => result.push_str("[killed]"),
+    }
+    if ok {
+        Ok(result)
+    } else {
+        Err(result)
+    }
+}
+
+pub fn register_tools() {
+    agent_tools::register(Tool {
+        name: "run_shell",
+        description: "Run a shell command in the open workspace or a scratch directory. \
+            Commands off the user's allowlist need their approval first; output is capped.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "command": { "type": "string" },
+                "cwd": { "type": "string", "enum": ["workspace", "scratch"] },
+                "session_id": { "type": "string" },
+                "timeout_secs": { "type": "integer" }
+            },
+            "required": ["command"]
+        }),
+        handler: agent_tools::handler(run_shell),
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_shell_tool_settings(settings_manager: State<'_, SettingsManager>) -> ShellToolSettings {
+    settings_manager.get().shell_tool
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_shell_tool_settings(
+    settings_manager: State<'_, SettingsManager>,
+    settings: ShellToolSettings,
+) -> Result<(), AppError> {
+    if let Some(rule) = settings
+        .allowed_commands
+        .iter()
+        .chain(&settings.denied_commands)
+        .find(|rule| rule.trim().is_empty() || CONTROL.iter().any(|c| rule.contains(c)))
+    {
+        return Err(AppError::InvalidField {
+            field: "settings".to_string(),
+            message: format!("{:?} must be a plain command without shell syntax", rule),
+        });
+    }
+    settings_manager.update(|s| s.shell_tool = settings)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed(command: &str) -> bool {
+        let dir = std::env::current_dir().unwrap();
+        is_allowed(&ShellToolSettings::default(), command, &dir)
+    }
+
+    #[test]
+    fn plain_read_only_commands_are_allowed() {
+        assert!(allowed("ls -la"));
+        assert!(allowed("git status"));
+        assert!(allowed("git log --oneline -5"));
+        assert!(allowed("git diff main"));
+        assert!(allowed("git branch"));
+        assert!(allowed("git branch -a"));
+        assert!(allowed("rg -n fn src"));
+        assert!(allowed("cat Cargo.toml"));
+    }
+
+    #[test]
+    fn unlisted_and_chained_commands_ask() {
+        assert!(!allowed("npm install"));
+        assert!(!allowed("ls; rm -rf build"));
+        assert!(!allowed("cat Cargo.toml | sh"));
+        assert!(!allowed("echo $(whoami)"));
+        assert!(!allowed("git statuses"));
+    }
+
+    #[test]
+    fn flags_that_run_programs_or_write_ask() {
+        assert!(!allowed("rg --pre ./evil pattern"));
+        assert!(!allowed("rg --pre=./evil pattern"));
+        assert!(!allowed("rg -z pattern"));
+        assert!(!allowed("git log --output=notes.txt"));
+        assert!(!allowed("git diff --output notes.txt"));
+        assert!(!allowed("git show --ext-diff"));
+        assert!(!allowed("git diff --textconv"));
+    }
+
+    #[test]
+    fn branch_changes_ask() {
+        assert!(!allowed("git branch -D main"));
+        assert!(!allowed("git branch -qD main"));
+        assert!(!allowed("git branch --delete main"));
+        assert!(!allowed("git branch -m old new"));
+        assert!(!allowed("git branch feature"));
+        assert!(allowed("git branch --list feature"));
+    }
+
+    #[test]
+    fn paths_outside_the_workspace_ask() {
+        assert!(!allowed("cat /etc/passwd"));
+        assert!(!allowed("head -n 5 ../secrets.env"));
+        assert!(!allowed("tail src/../../other/file"));
+        assert!(!allowed("grep --file=/etc/passwd src"));
+        assert!(!allowed("cat ~/.ssh/id_rsa"));
+        assert!(!allowed("cat \"/etc/passwd\""));
+        assert!(!allowed("cat *"));
+    }
+
+    #[test]
+    fn denylist_catches_every_part_of_a_chain() {
+        let settings = ShellToolSettings::default();
+        assert_eq!(denied_by(&settings, "ls && sudo reboot"), Some("sudo"));
+        assert_eq!(denied_by(&settings, "/usr/bin/sudo ls"), Some("sudo"));
+        assert_eq!(denied_by(&settings, "ls -la"), None);
+    }
+
+    #[test]
+    fn flags_are_matched_in_bundles_and_with_values() {
+        assert!(uses_flag("-D", "-D"));
+        assert!(uses_flag("-vD", "-D"));
+        assert!(!uses_flag("--Delete", "-D"));
+        assert!(uses_flag("--output=x", "--output"));
+        assert!(!uses_flag("--output-indicator-new=x", "--output"));
+    }
+}
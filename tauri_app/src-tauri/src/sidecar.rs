@@ -1,227 +1,1486 @@
-// use std::sync::{Arc, Mutex};
-// use tauri::AppHandle;
-// use tauri_plugin_shell::{process::CommandEvent, ShellExt};
-// use tokio::time::{sleep, Duration};
-
-// #[derive(Debug, Clone)]
-// pub struct SidecarManager {
-//     pub is_running: Arc<Mutex<bool>>,
-//     pub child_id: Arc<Mutex<Option<u32>>>,
-//     pub error_message: Arc<Mutex<Option<String>>>,
-// }
-
-// impl SidecarManager {
-//     pub fn new() -> Self {
-//         Self {
-//             is_running: Arc::new(Mutex::new(false)),
-//             child_id: Arc::new(Mutex::new(None)),
-//             error_message: Arc::new(Mutex::new(None)),
-//         }
-//     }
-
-//     pub async fn start_sidecar(&self, app: &AppHandle) -> Result<(), String> {
-//         // Check if already running
-//         if *self.is_running.lock().unwrap() {
-//             return Ok(());
-//         }
-
-//         // Clear any previous error
-//         *self.error_message.lock().unwrap() = None;
-
-//         let shell = app.shell();
-
-//         match shell.sidecar("mix") {
-//             Ok(command) => {
-//                 let command = command.args(["--http-mode"]);
-//                 match command.spawn() {
-//                     Ok((mut rx, child)) => {
-//                         let child_id = child.pid();
-//                         *self.child_id.lock().unwrap() = Some(child_id);
-//                         *self.is_running.lock().unwrap() = true;
-
-//                         // Spawn a task to monitor the process
-//                         let is_running = Arc::clone(&self.is_running);
-//                         let error_message = Arc::clone(&self.error_message);
-//                         let child_id_clone = Arc::clone(&self.child_id);
-
-//                         tokio::spawn(async move {
-//                             while let Some(event) = rx.recv().await {
-//                                 match event {
-//                                     CommandEvent::Stdout(data) => {
-//                                         println!(
-//                                             "Go server stdout: {}",
-//                                             String::from_utf8_lossy(&data)
-//                                         );
-//                                     }
-//                                     CommandEvent::Stderr(data) => {
-//                                         println!(
-//                                             "Go server stderr: {}",
-//                                             String::from_utf8_lossy(&data)
-//                                         );
-//                                     }
-//                                     CommandEvent::Error(err) => {
-//                                         *error_message.lock().unwrap() =
-//                                             Some(format!("Process error: {}", err));
-//                                         *is_running.lock().unwrap() = false;
-//                                         *child_id_clone.lock().unwrap() = None;
-//                                         break;
-//                                     }
-//                                     CommandEvent::Terminated(payload) => {
-//                                         println!(
-//                                             "Go server terminated with code: {:?}",
-//                                             payload.code
-//                                         );
-//                                         *is_running.lock().unwrap() = false;
-//                                         *child_id_clone.lock().unwrap() = None;
-//                                         if payload.code != Some(0) {
-//                                             *error_message.lock().unwrap() = Some(format!(
-//                                                 "Process terminated with code: {:?}",
-//                                                 payload.code
-//                                             ));
-//                                         }
-//                                         break;
-//                                     }
-//                                     _ => {
-//                                         // Handle any other variants that might exist
-//                                     }
-//                                 }
-//                             }
-//                         });
-
-//                         // Wait a moment for the server to start
-//                         sleep(Duration::from_millis(1000)).await;
-
-//                         Ok(())
-//                     }
-//                     Err(e) => {
-//                         let error = format!("Failed to spawn sidecar: {}", e);
-//                         *self.error_message.lock().unwrap() = Some(error.clone());
-//                         Err(error)
-//                     }
-//                 }
-//             }
-//             Err(e) => {
-//                 let error = format!("Failed to create sidecar command: {}", e);
-//                 *self.error_message.lock().unwrap() = Some(error.clone());
-//                 Err(error)
-//             }
-//         }
-//     }
-
-//     pub async fn stop_sidecar(&self, app: &AppHandle) -> Result<(), String> {
-//         if !*self.is_running.lock().unwrap() {
-//             return Ok(());
-//         }
-
-//         if let Some(pid) = *self.child_id.lock().unwrap() {
-//             let _shell = app.shell();
-
-//             // Try to kill the process
-//             #[cfg(unix)]
-//             {
-//                 use std::process::Command;
-//                 match Command::new("kill").arg(pid.to_string()).output() {
-//                     Ok(_) => {
-//                         *self.is_running.lock().unwrap() = false;
-//                         *self.child_id.lock().unwrap() = None;
-//                         Ok(())
-//                     }
-//                     Err(e) => {
-//                         let error = format!("Failed to kill process: {}", e);
-//                         *self.error_message.lock().unwrap() = Some(error.clone());
-//                         Err(error)
-//                     }
-//                 }
-//             }
-
-//             #[cfg(windows)]
-//             {
-//                 use std::process::Command;
-//                 match Command::new("taskkill")
-//                     .args(&["/F", "/PID", &pid.to_string()])
-//                     .output()
-//                 {
-//                     Ok(_) => {
-//                         *self.is_running.lock().unwrap() = false;
-//                         *self.child_id.lock().unwrap() = None;
-//                         Ok(())
-//                     }
-//                     Err(e) => {
-//                         let error = format!("Failed to kill process: {}", e);
-//                         *self.error_message.lock().unwrap() = Some(error.clone());
-//                         Err(error)
-//                     }
-//                 }
-//             }
-//         } else {
-//             Err("No process ID available".to_string())
-//         }
-//     }
-
-//     pub async fn health_check(&self) -> Result<String, String> {
-//         if !*self.is_running.lock().unwrap() {
-//             return Err("Sidecar is not running".to_string());
-//         }
-
-//         match reqwest::get("http://localhost:8080/api/health").await {
-//             Ok(response) => {
-//                 if response.status().is_success() {
-//                     match response.json::<serde_json::Value>().await {
-//                         Ok(data) => {
-//                             if let Some(status) = data.get("status").and_then(|s| s.as_str()) {
-//                                 Ok(format!("Mix health check: {}", status))
-//                             } else {
-//                                 Ok("Mix health check successful".to_string())
-//                             }
-//                         }
-//                         Err(e) => Err(format!("Failed to parse response: {}", e)),
-//                     }
-//                 } else {
-//                     Err(format!(
-//                         "Health check failed with status: {}",
-//                         response.status()
-//                     ))
-//                 }
-//             }
-//             Err(e) => Err(format!("Health check request failed: {}", e)),
-//         }
-//     }
-
-//     pub fn is_running(&self) -> bool {
-//         *self.is_running.lock().unwrap()
-//     }
-
-//     pub fn get_error(&self) -> Option<String> {
-//         self.error_message.lock().unwrap().clone()
-//     }
-
-//     pub async fn send_prompt(&self, prompt: &str) -> Result<String, String> {
-//         if !*self.is_running.lock().unwrap() {
-//             return Err("Sidecar is not running".to_string());
-//         }
-
-//         let client = reqwest::Client::new();
-//         let payload = serde_json::json!({
-//             "prompt": prompt
-//         });
-
-//         match client
-//             .post("http://localhost:8080/api/prompt")
-//             .json(&payload)
-//             .send()
-//             .await
-//         {
-//             Ok(response) => {
-//                 if response.status().is_success() {
-//                     match response.text().await {
-//                         Ok(text) => Ok(text),
-//                         Err(e) => Err(format!("Failed to read response: {}", e)),
-//                     }
-//                 } else {
-//                     Err(format!("Request failed with status: {}", response.status()))
-//                 }
-//             }
-//             Err(e) => Err(format!("Request failed: {}", e)),
-//         }
-//     }
-// }
+use crate::actions::{self, Action};
+use crate::coalesce::{Coalesce, Coalescer};
+use crate::error::AppError;
+use crate::platform::{Native, Platform};
+use crate::recording::{RecordedChunk, Recorder};
+use crate::settings::SettingsManager;
+#[cfg(feature = "grpc")]
+use crate::sidecar_grpc::GrpcClient;
+use crate::{debug, history, integrity, logging, mcp, metrics, oauth, scratch, secrets, workspace};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::TcpListener;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Instant;
+use sysinfo::{Pid, ProcessStatus, ProcessesToUpdate, System};
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::{mpsc, oneshot, watch, Semaphore};
+use tokio::time::{sleep, Duration};
+
+pub const SIDECAR_HOST: &str = "127.0.0.1";
+pub const SIDECAR_PORT: u16 = 8080;
+pub const SIDECAR_GRPC_PORT: u16 = 8081;
+pub const SIDECAR_CHUNK_EVENT: &str = "sidecar://chunk";
+pub const TRACE_ID_HEADER: &str = "X-Trace-Id";
+const IDENTITY_HEADER: &str = "X-Mix-Server-Identity";
+const TOKEN_ENV: &str = "MIX_SIDECAR_TOKEN";
+const IDENTITY_ENV: &str = "MIX_SIDECAR_IDENTITY";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+// Keeps a few warm connections; prompts and event streams run concurrently
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const POOL_MAX_IDLE: usize = 8;
+// Health results younger than this are handed out again instead of asking the
+// sidecar, so pollers and repeated clicks can't pile up requests
+const HEALTH_CHECK_REUSE: Duration = Duration::from_secs(1);
+// Prompts sent while the sidecar starts or restarts wait this long for it,
+// with at most PENDING_PROMPTS_MAX waiting at once
+const PENDING_PROMPT_TIMEOUT: Duration = Duration::from_secs(30);
+const PENDING_PROMPTS_MAX: usize = 16;
+// A started sidecar opens its database before it listens; callers of
+// `start_sidecar` wait for the port rather than the actor
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+// When the last health check finished, and what it returned
+type LastHealth = (Instant, Result<String, AppError>);
+
+// Distinguishes a sidecar that isn't listening or is too slow from one that
+// answered with an error
+fn request_error(operation: &str, e: reqwest::Error) -> AppError {
+    if e.is_timeout() {
+        AppError::Timeout(format!("{} request", operation))
+    } else if e.is_connect() {
+        AppError::SidecarUnavailable
+    } else {
+        AppError::Sidecar(format!("{} request failed: {}", operation, e))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct HealthCheck {
+    pub ok: bool,
+    pub latency_ms: u128,
+    pub checked_at: String,
+}
+
+// How prompts reach the sidecar. The HTTP server always runs; gRPC is served
+// next to it and only used for prompts, health checks and cancellation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum SidecarTransport {
+    #[default]
+    Http,
+    Grpc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PromptChunk {
+    pub trace_id: String,
+    pub session_id: String,
+    // "tool", "content" or "error"
+    pub kind: String,
+    pub content: String,
+    pub done: bool,
+}
+
+// Consecutive chunks of the same kind are sent as one
+impl Coalesce for PromptChunk {
+    fn merge(&mut self, next: &Self) -> bool {
+        if self.done || self.trace_id != next.trace_id || self.kind != next.kind {
+            return false;
+        }
+        self.content.push_str(&next.content);
+        self.done = next.done;
+        true
+    }
+
+    fn is_final(&self) -> bool {
+        self.done
+    }
+}
+
+// Per-launch credentials: the token proves the caller to the sidecar, and the
+// sidecar echoes the identity back to prove it is the process we spawned
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SidecarAuth {
+    pub token: String,
+    pub identity: String,
+}
+
+// Starting, Stopping and Restarting are claimed by the caller that requested
+// them, and the actor settles them into Running or Stopped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum SidecarState {
+    #[default]
+    Stopped,
+    Starting,
+    Running,
+    Stopping,
+    Restarting,
+}
+
+impl SidecarState {
+    fn is_settled(self) -> bool {
+        matches!(self, SidecarState::Stopped | SidecarState::Running)
+    }
+}
+
+impl fmt::Display for SidecarState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SidecarState::Stopped => "stopped",
+            SidecarState::Starting => "starting",
+            SidecarState::Running => "running",
+            SidecarState::Stopping => "stopping",
+            SidecarState::Restarting => "restarting",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operation::Start => "start",
+            Operation::Stop => "stop",
+            Operation::Restart => "restart",
+        })
+    }
+}
+
+// What a request for `operation` does in `state`: Ok(Some(next)) claims the
+// transition, Ok(None) means it is already done or in flight and the caller
+// only waits, and Err is a transition that can't happen from here.
+fn next_state(operation: Operation, state: SidecarState) -> Result<Option<SidecarState>, AppError> {
+    use SidecarState::*;
+    match (operation, state) {
+        (Operation::Start, Stopped) => Ok(Some(Starting)),
+        (Operation::Stop, Running) => Ok(Some(Stopping)),
+        (Operation::Restart, Running) => Ok(Some(Restarting)),
+        (Operation::Start, Starting | Running | Restarting)
+        | (Operation::Stop, Stopped | Stopping)
+        // Nothing to pick up new settings when it isn't running
+        | (Operation::Restart, Stopped | Restarting) => Ok(None),
+        (Operation::Start, Stopping)
+        | (Operation::Stop, Starting | Restarting)
+        | (Operation::Restart, Starting | Stopping) => Err(AppError::InvalidState(format!(
+            "Cannot {} the sidecar while it is {}",
+            operation, state
+        ))),
+    }
+}
+
+// Snapshot of the sidecar process, published by the actor on every change
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct SidecarStatus {
+    pub state: SidecarState,
+    pub child_id: Option<u32>,
+    // When `child_id` started, so a recycled PID isn't mistaken for ours
+    pub started_at: Option<u64>,
+    // HTTP port the sidecar was started on; SIDECAR_PORT unless it was taken
+    pub port: Option<u16>,
+    // Likewise SIDECAR_GRPC_PORT, when the gRPC transport is in use
+    pub grpc_port: Option<u16>,
+    pub error: Option<String>,
+    // Chosen when the sidecar starts, from the `sidecar_transport` setting
+    pub transport: SidecarTransport,
+    pub last_health_check: Option<HealthCheck>,
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+// The sidecar process as far as the actor is concerned
+trait Child: Send {
+    fn pid(&self) -> u32;
+    fn kill(self: Box<Self>) -> Result<(), AppError>;
+}
+
+impl Child for CommandChild {
+    fn pid(&self) -> u32 {
+        CommandChild::pid(self)
+    }
+
+    fn kill(self: Box<Self>) -> Result<(), AppError> {
+        CommandChild::kill(*self)
+            .map_err(|e| AppError::Io(format!("Failed to kill process: {}", e)))
+    }
+}
+
+struct Spawned {
+    child: Box<dyn Child>,
+    started_at: Option<u64>,
+    port: u16,
+    grpc_port: Option<u16>,
+    transport: SidecarTransport,
+}
+
+// Spawns the process for a Start or Restart, reporting its exit on the given
+// sender; see `launcher`
+type Launch =
+    Box<dyn FnOnce(mpsc::UnboundedSender<Command>) -> BoxFuture<Result<Spawned, AppError>> + Send>;
+
+// Requests handled one at a time by the actor, so starts and stops can't
+// interleave. Start, Stop and Restart are only sent by the caller that claimed
+// the matching transition.
+enum Command {
+    Start {
+        launch: Launch,
+        reply: oneshot::Sender<Result<(), AppError>>,
+    },
+    Stop {
+        reply: oneshot::Sender<Result<(), AppError>>,
+    },
+    Restart {
+        launch: Launch,
+        reply: oneshot::Sender<Result<(), AppError>>,
+    },
+    // Sent by the output monitor when the process it watches goes away
+    Exited {
+        pid: u32,
+        error: Option<String>,
+    },
+    RecordHealth {
+        check: HealthCheck,
+        reply: oneshot::Sender<()>,
+    },
+}
+
+// Owns the child process and is the only writer of the status, apart from
+// callers claiming a transition
+struct Actor {
+    // Handed to output monitors so they can report exits
+    commands: mpsc::UnboundedSender<Command>,
+    status: watch::Sender<SidecarStatus>,
+    child: Option<Box<dyn Child>>,
+}
+
+impl Actor {
+    async fn run(mut self, mut inbox: mpsc::UnboundedReceiver<Command>) {
+        while let Some(command) = inbox.recv().await {
+            match command {
+                Command::Start { launch, reply } => {
+                    let _ = reply.send(self.start(launch).await);
+                }
+                Command::Stop { reply } => {
+                    let _ = reply.send(self.stop());
+                }
+                Command::Restart { launch, reply } => {
+                    let _ = reply.send(self.restart(launch).await);
+                }
+                Command::Exited { pid, error } => self.exited(pid, error),
+                Command::RecordHealth { check, reply } => {
+                    self.status
+                        .send_modify(|status| status.last_health_check = Some(check));
+                    let _ = reply.send(());
+                }
+            }
+        }
+    }
+
+    async fn start(&mut self, launch: Launch) -> Result<(), AppError> {
+        if self.child.is_some() {
+            self.status
+                .send_modify(|status| status.state = SidecarState::Running);
+            return Ok(());
+        }
+        // Clear any previous error
+        self.status.send_modify(|status| status.error = None);
+
+        match launch(self.commands.clone()).await {
+            Ok(spawned) => {
+                let pid = spawned.child.pid();
+                self.child = Some(spawned.child);
+                self.status.send_modify(|status| {
+                    status.state = SidecarState::Running;
+                    status.child_id = Some(pid);
+                    status.started_at = spawned.started_at;
+                    status.port = Some(spawned.port);
+                    status.grpc_port = spawned.grpc_port;
+                    status.transport = spawned.transport;
+                });
+                Ok(())
+            }
+            Err(error) => {
+                self.status.send_modify(|status| {
+                    status.state = SidecarState::Stopped;
+                    status.error = Some(error.to_string());
+                });
+                Err(error)
+            }
+        }
+    }
+
+    // Stays Restarting throughout, so no other caller can start in between
+    async fn restart(&mut self, launch: Launch) -> Result<(), AppError> {
+        if let Err(error) = self.kill() {
+            self.status.send_modify(|status| {
+                status.state = SidecarState::Stopped;
+                status.error = Some(error.to_string());
+            });
+            return Err(error);
+        }
+        self.start(launch).await
+    }
+
+    // Ends the child without settling the state
+    fn kill(&mut self) -> Result<(), AppError> {
+        let Some(child) = self.child.take() else {
+            return Ok(());
+        };
+        let result = child.kill();
+        self.status.send_modify(|status| {
+            status.child_id = None;
+            status.started_at = None;
+            status.port = None;
+            status.grpc_port = None;
+        });
+        result
+    }
+
+    fn stop(&mut self) -> Result<(), AppError> {
+        let result = self.kill();
+        self.status.send_modify(|status| {
+            status.state = SidecarState::Stopped;
+            if let Err(error) = &result {
+                status.error = Some(error.to_string());
+            }
+        });
+        result
+    }
+
+    fn exited(&mut self, pid: u32, error: Option<String>) {
+        // A process we already stopped, or one from before a restart
+        if self.status.borrow().child_id != Some(pid) {
+            return;
+        }
+        self.child = None;
+        self.status.send_modify(|status| {
+            status.state = SidecarState::Stopped;
+            status.child_id = None;
+            status.started_at = None;
+            status.port = None;
+            status.grpc_port = None;
+            if error.is_some() {
+                status.error = error;
+            }
+        });
+    }
+}
+
+// Launches the bundled sidecar binary with this app's settings and `auth`
+fn launcher(app: &AppHandle, auth: &SidecarAuth) -> Launch {
+    let app = app.clone();
+    let auth = auth.clone();
+    Box::new(move |commands| Box::pin(async move { spawn(&app, &auth, commands).await }))
+}
+
+// Returns once the process is spawned; callers wait for it to listen
+async fn spawn(
+    app: &AppHandle,
+    auth: &SidecarAuth,
+    commands: mpsc::UnboundedSender<Command>,
+) -> Result<Spawned, AppError> {
+    // Hashing the binary is blocking file IO
+    let verify_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || integrity::verify_sidecar(&verify_app))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to verify sidecar: {}", e)))??;
+
+    let transport =
+        SidecarManager::resolve_transport(app.state::<SettingsManager>().get().sidecar_transport);
+    let fallback = app.state::<SettingsManager>().get().sidecar_port_fallback;
+    let port = choose_port(SIDECAR_PORT, fallback)?;
+    let grpc_port = match transport {
+        SidecarTransport::Grpc => Some(choose_port(SIDECAR_GRPC_PORT, fallback)?),
+        SidecarTransport::Http => None,
+    };
+    let grpc_args = match grpc_port {
+        Some(grpc_port) => vec!["--grpc-port".to_string(), grpc_port.to_string()],
+        None => Vec::new(),
+    };
+
+    let command = app
+        .shell()
+        .sidecar("mix")
+        .map_err(|e| AppError::Internal(format!("Failed to create sidecar command: {}", e)))?
+        .args(["--http-port".to_string(), port.to_string()])
+        .args(grpc_args)
+        .args(workspace::sidecar_args(app))
+        .envs(secrets::provider_env())
+        .envs(history::sidecar_env(app))
+        .envs(oauth::sidecar_env(app))
+        .envs(scratch::sidecar_env(app))
+        .envs(mcp::sidecar_env(app))
+        .envs([
+            (TOKEN_ENV, auth.token.as_str()),
+            (IDENTITY_ENV, auth.identity.as_str()),
+        ]);
+    let (rx, child) = command
+        .spawn()
+        .map_err(|e| AppError::Io(format!("Failed to spawn sidecar: {}", e)))?;
+
+    let pid = child.pid();
+    tauri::async_runtime::spawn(monitor(app.clone(), rx, pid, commands));
+    Ok(Spawned {
+        child: Box::new(child),
+        started_at: process_start_time(pid),
+        port,
+        grpc_port,
+        transport,
+    })
+}
+
+async fn listening(port: u16) -> bool {
+    let connect = tokio::net::TcpStream::connect((SIDECAR_HOST, port));
+    matches!(
+        tokio::time::timeout(PORT_PROBE_TIMEOUT, connect).await,
+        Ok(Ok(_))
+    )
+}
+
+// "name (pid 123)" for whatever listens on `port`
+fn port_holder(port: u16) -> Option<String> {
+    let pid = Native::port_owner(port)?;
+    let mut system = System::new();
+    let process_pid = Pid::from_u32(pid);
+    system.refresh_processes(ProcessesToUpdate::Some(&[process_pid]), true);
+    Some(match system.process(process_pid) {
+        Some(process) => format!("{} (pid {})", process.name().to_string_lossy(), pid),
+        None => format!("pid {}", pid),
+    })
+}
+
+// `preferred` when it is free. When something else holds it, either a free
+// port picked by the OS or, without `fallback`, an error naming the holder.
+pub fn choose_port(preferred: u16, fallback: bool) -> Result<u16, AppError> {
+    match TcpListener::bind((SIDECAR_HOST, preferred)) {
+        Ok(_) => return Ok(preferred),
+        Err(e) if e.kind() != io::ErrorKind::AddrInUse => {
+            return Err(AppError::Io(format!(
+                "Failed to check port {}: {}",
+                preferred, e
+            )));
+        }
+        Err(_) => {}
+    }
+    let holder = port_holder(preferred);
+    if !fallback {
+        return Err(AppError::PortInUse {
+            port: preferred,
+            holder,
+        });
+    }
+    let port = TcpListener::bind((SIDECAR_HOST, 0))
+        .and_then(|listener| listener.local_addr())
+        .map_err(|e| AppError::Io(format!("Failed to find a free port: {}", e)))?
+        .port();
+    log::warn!(
+        "Port {} is in use by {}, starting the sidecar on port {}",
+        preferred,
+        holder.as_deref().unwrap_or("another process"),
+        port
+    );
+    Ok(port)
+}
+
+// None once the process is gone, including when it is a zombie awaiting reaping
+fn process_start_time(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    system
+        .process(pid)
+        .filter(|process| process.status() != ProcessStatus::Zombie)
+        .map(|process| process.start_time())
+}
+
+// Forwards the sidecar's output to the log until it exits
+async fn monitor(
+    app: AppHandle,
+    mut rx: Receiver<CommandEvent>,
+    pid: u32,
+    commands: mpsc::UnboundedSender<Command>,
+) {
+    let mut error = None;
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(data) => {
+                debug::forward_sidecar_output(&app, "stdout", &data);
+                log::info!(
+                    target: logging::SIDECAR_TARGET,
+                    "Go server stdout: {}",
+                    String::from_utf8_lossy(&data)
+                );
+            }
+            CommandEvent::Stderr(data) => {
+                debug::forward_sidecar_output(&app, "stderr", &data);
+                log::info!(
+                    target: logging::SIDECAR_TARGET,
+                    "Go server stderr: {}",
+                    String::from_utf8_lossy(&data)
+                );
+            }
+            CommandEvent::Error(err) => {
+                error = Some(format!("Process error: {}", err));
+                break;
+            }
+            CommandEvent::Terminated(payload) => {
+                log::info!(
+                    target: logging::SIDECAR_TARGET,
+                    "Go server terminated with code: {:?}",
+                    payload.code
+                );
+                if payload.code != Some(0) {
+                    error = Some(format!("Process terminated with code: {:?}", payload.code));
+                }
+                break;
+            }
+            _ => {
+                // Handle any other variants that might exist
+            }
+        }
+    }
+    let _ = commands.send(Command::Exited { pid, error });
+}
+
+// Cheap to clone handle to the actor; status reads never wait on it
+#[derive(Debug, Clone)]
+pub struct SidecarManager {
+    commands: mpsc::UnboundedSender<Command>,
+    status: watch::Receiver<SidecarStatus>,
+    // Used only to claim transitions, see `transition`
+    transitions: watch::Sender<SidecarStatus>,
+    pub recorder: Arc<Recorder>,
+    pub auth: SidecarAuth,
+    #[cfg(feature = "grpc")]
+    // Rebuilt when the sidecar comes up on another gRPC port
+    grpc: Arc<Mutex<Option<(u16, GrpcClient)>>>,
+    // Shared by every HTTP call to the sidecar so connections are reused
+    http: reqwest::Client,
+    // Held for the duration of a health check, so only one is in flight
+    last_health: Arc<tokio::sync::Mutex<Option<LastHealth>>>,
+    // One permit per prompt waiting for the sidecar, see `wait_for_prompt`
+    pending_prompts: Arc<Semaphore>,
+}
+
+impl SidecarManager {
+    pub fn new() -> Self {
+        let auth = SidecarAuth {
+            token: uuid::Uuid::new_v4().simple().to_string(),
+            identity: uuid::Uuid::new_v4().simple().to_string(),
+        };
+        let (commands, inbox) = mpsc::unbounded_channel();
+        let (status_tx, status) = watch::channel(SidecarStatus::default());
+        let actor = Actor {
+            commands: commands.clone(),
+            status: status_tx.clone(),
+            child: None,
+        };
+        tauri::async_runtime::spawn(actor.run(inbox));
+
+        Self {
+            commands,
+            status,
+            transitions: status_tx,
+            recorder: Arc::new(Recorder::default()),
+            auth,
+            #[cfg(feature = "grpc")]
+            grpc: Arc::new(Mutex::new(None)),
+            http: Self::http_client(),
+            last_health: Arc::new(tokio::sync::Mutex::new(None)),
+            pending_prompts: Arc::new(Semaphore::new(PENDING_PROMPTS_MAX)),
+        }
+    }
+
+    // No overall timeout, as prompts and event streams run for minutes; the
+    // sidecar is on loopback, so system proxies are bypassed
+    fn http_client() -> reqwest::Client {
+        reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .pool_max_idle_per_host(POOL_MAX_IDLE)
+            .tcp_nodelay(true)
+            .no_proxy()
+            .build()
+            .unwrap_or_else(|e| {
+                log::error!("Failed to build sidecar HTTP client: {}", e);
+                reqwest::Client::new()
+            })
+    }
+
+    pub fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    async fn request<T>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<T>) -> Command,
+    ) -> Result<T, AppError> {
+        let (reply, rx) = oneshot::channel();
+        self.commands
+            .send(command(reply))
+            .map_err(|_| AppError::Internal("Sidecar manager has shut down".to_string()))?;
+        rx.await
+            .map_err(|_| AppError::Internal("Sidecar manager has shut down".to_string()))
+    }
+
+    pub fn status(&self) -> SidecarStatus {
+        self.status.borrow().clone()
+    }
+
+    // Resolves on every status change, e.g. to restart a crashed sidecar
+    pub fn subscribe(&self) -> watch::Receiver<SidecarStatus> {
+        self.status.clone()
+    }
+
+    pub fn transport(&self) -> SidecarTransport {
+        self.status.borrow().transport
+    }
+
+    // Builds without the `grpc` feature always talk HTTP
+    fn resolve_transport(requested: SidecarTransport) -> SidecarTransport {
+        if requested == SidecarTransport::Grpc && !cfg!(feature = "grpc") {
+            log::warn!("gRPC transport requested but not compiled in, using HTTP");
+            return SidecarTransport::Http;
+        }
+        requested
+    }
+
+    #[cfg(feature = "grpc")]
+    fn grpc_client(&self) -> Result<GrpcClient, AppError> {
+        let port = self.status.borrow().grpc_port.unwrap_or(SIDECAR_GRPC_PORT);
+        let mut grpc = self.grpc.lock().unwrap();
+        if let Some((client_port, client)) = grpc.as_ref() {
+            if *client_port == port {
+                return Ok(client.clone());
+            }
+        }
+        let client = GrpcClient::new(port, &self.auth.token)?;
+        *grpc = Some((port, client.clone()));
+        Ok(client)
+    }
+
+    // The port the sidecar was started on, which is not SIDECAR_PORT when that
+    // was taken. Only meaningful while it runs.
+    pub fn port(&self) -> u16 {
+        self.status.borrow().port.unwrap_or(SIDECAR_PORT)
+    }
+
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("http://{}:{}{}", SIDECAR_HOST, self.port(), path)
+    }
+
+    // Refuse responses from anything other than the sidecar we spawned, e.g.
+    // another local process that grabbed the port first
+    pub(crate) fn verify_identity(&self, response: &reqwest::Response) -> Result<(), AppError> {
+        match response.headers().get(IDENTITY_HEADER) {
+            Some(identity) if identity.as_bytes() == self.auth.identity.as_bytes() => Ok(()),
+            _ => Err(AppError::PermissionDenied(
+                "Sidecar endpoint failed identity check, refusing to use it".to_string(),
+            )),
+        }
+    }
+
+    // The caller that moves the state out of Stopped or Running sends the
+    // command; callers arriving while it is in flight wait for it and share
+    // its outcome, and conflicting ones are refused instead of queued
+    async fn transition(
+        &self,
+        operation: Operation,
+        command: impl FnOnce(oneshot::Sender<Result<(), AppError>>) -> Command,
+    ) -> Result<(), AppError> {
+        let mut claimed = Ok(false);
+        let mut observed = SidecarState::Stopped;
+        self.transitions.send_if_modified(|status| {
+            observed = status.state;
+            match next_state(operation, status.state) {
+                Ok(Some(next)) => {
+                    status.state = next;
+                    claimed = Ok(true);
+                    true
+                }
+                Ok(None) => false,
+                Err(e) => {
+                    claimed = Err(e);
+                    false
+                }
+            }
+        });
+        if claimed? {
+            return self.request(command).await?;
+        }
+        if observed.is_settled() {
+            return Ok(());
+        }
+
+        let mut status = self.status.clone();
+        let settled = status
+            .wait_for(|status| status.state.is_settled())
+            .await
+            .map_err(|_| AppError::Internal("Sidecar manager has shut down".to_string()))?
+            .clone();
+        match (operation, settled.state) {
+            (Operation::Stop, SidecarState::Stopped)
+            | (Operation::Start | Operation::Restart, SidecarState::Running) => Ok(()),
+            (Operation::Stop, state) => Err(AppError::InvalidState(format!(
+                "Sidecar is {} again",
+                state
+            ))),
+            (Operation::Start | Operation::Restart, _) => Err(settled
+                .error
+                .map(AppError::Sidecar)
+                .unwrap_or(AppError::SidecarUnavailable)),
+        }
+    }
+
+    // Returns once the sidecar listens, so callers can use it right away
+    pub async fn start_sidecar(&self, app: &AppHandle) -> Result<(), AppError> {
+        let launch = launcher(app, &self.auth);
+        self.transition(Operation::Start, |reply| Command::Start { launch, reply })
+            .await?;
+        self.wait_until_ready().await
+    }
+
+    pub async fn stop_sidecar(&self) -> Result<(), AppError> {
+        self.transition(Operation::Stop, |reply| Command::Stop { reply })
+            .await
+    }
+
+    // Stops and starts the sidecar so it picks up new settings; a no-op
+    // when it isn't running
+    pub async fn restart_sidecar(&self, app: &AppHandle) -> Result<(), AppError> {
+        let launch = launcher(app, &self.auth);
+        self.transition(Operation::Restart, |reply| Command::Restart {
+            launch,
+            reply,
+        })
+        .await?;
+        if self.is_running() {
+            self.wait_until_ready().await?;
+        }
+        Ok(())
+    }
+
+    // Polls the port outside the actor, so a slow start holds up only the
+    // callers waiting for it
+    async fn wait_until_ready(&self) -> Result<(), AppError> {
+        let started = Instant::now();
+        while started.elapsed() < READY_TIMEOUT {
+            if !self.is_running() {
+                return Err(self
+                    .get_error()
+                    .map(AppError::Sidecar)
+                    .unwrap_or(AppError::SidecarUnavailable));
+            }
+            if listening(self.port()).await {
+                return Ok(());
+            }
+            sleep(READY_POLL_INTERVAL).await;
+        }
+        Err(AppError::Timeout(format!(
+            "Sidecar did not start listening within {}s",
+            READY_TIMEOUT.as_secs()
+        )))
+    }
+
+    // Callers arriving while a check runs wait for it and share its result
+    pub async fn health_check(&self) -> Result<String, AppError> {
+        if !self.is_running() {
+            return Err(AppError::SidecarUnavailable);
+        }
+
+        let mut last_health = self.last_health.lock().await;
+        if let Some((checked_at, result)) = last_health.as_ref() {
+            if checked_at.elapsed() < HEALTH_CHECK_REUSE {
+                return result.clone();
+            }
+        }
+        let result = self.run_health_check().await;
+        *last_health = Some((Instant::now(), result.clone()));
+        result
+    }
+
+    async fn run_health_check(&self) -> Result<String, AppError> {
+        let start = Instant::now();
+        let result = match self.transport() {
+            SidecarTransport::Http => self.health_check_http().await,
+            SidecarTransport::Grpc => self.health_check_grpc().await,
+        };
+
+        let check = HealthCheck {
+            ok: result.is_ok(),
+            latency_ms: start.elapsed().as_millis(),
+            checked_at: chrono::Local::now().to_rfc3339(),
+        };
+        self.request(|reply| Command::RecordHealth { check, reply })
+            .await?;
+
+        result
+    }
+
+    // A real round trip through the JSON-RPC handler, so a server that only
+    // answers `/` doesn't pass
+    async fn health_check_http(&self) -> Result<String, AppError> {
+        let result = metrics::timed(
+            "sidecar.health",
+            self.post_rpc(
+                "health.check",
+                serde_json::Value::Null,
+                Some(HEALTH_CHECK_TIMEOUT),
+                None,
+            ),
+        )
+        .await?;
+        match result.get("status").and_then(|s| s.as_str()) {
+            Some(status) => Ok(format!("Mix health check: {}", status)),
+            None => Ok("Mix health check successful".to_string()),
+        }
+    }
+
+    #[cfg(feature = "grpc")]
+    async fn health_check_grpc(&self) -> Result<String, AppError> {
+        let reply = metrics::timed("sidecar.health", self.grpc_client()?.health())
+            .await
+            .map_err(AppError::Sidecar)?;
+        if reply.identity != self.auth.identity {
+            return Err(AppError::PermissionDenied(
+                "Sidecar endpoint failed identity check, refusing to use it".to_string(),
+            ));
+        }
+        Ok(format!("Mix health check: {}", reply.status))
+    }
+
+    #[cfg(not(feature = "grpc"))]
+    async fn health_check_grpc(&self) -> Result<String, AppError> {
+        self.health_check_http().await
+    }
+
+    pub async fn version(&self, app: &AppHandle) -> Result<String, AppError> {
+        let command = app
+            .shell()
+            .sidecar("mix")
+            .map_err(|e| AppError::Internal(format!("Failed to create sidecar command: {}", e)))?;
+
+        match command.args(["--version"]).output().await {
+            Ok(output) => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            Err(e) => Err(AppError::Io(format!("Failed to run sidecar: {}", e))),
+        }
+    }
+
+    // The flag only changes when the actor hears about an exit, so it would
+    // stay set for a sidecar killed from outside. Check the recorded PID is
+    // still our child, and report the exit to the actor when it isn't.
+    pub fn is_running(&self) -> bool {
+        let (pid, started_at) = {
+            let status = self.status.borrow();
+            match (status.state, status.child_id, status.started_at) {
+                (SidecarState::Running, Some(pid), Some(started_at)) => (pid, started_at),
+                (state, _, _) => return state == SidecarState::Running,
+            }
+        };
+        if process_start_time(pid) == Some(started_at) {
+            return true;
+        }
+        log::warn!("Sidecar process {} is no longer running", pid);
+        let _ = self.commands.send(Command::Exited {
+            pid,
+            error: Some(format!("Sidecar process {} exited unexpectedly", pid)),
+        });
+        false
+    }
+
+    pub fn get_error(&self) -> Option<String> {
+        self.status.borrow().error.clone()
+    }
+
+    // Calls one of the sidecar's JSON-RPC query methods and returns its result
+    pub async fn rpc(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, AppError> {
+        if !self.is_running() {
+            return Err(AppError::SidecarUnavailable);
+        }
+        self.post_rpc(method, params, Some(RPC_TIMEOUT), None).await
+    }
+
+    // Posts to `/rpc` without checking the sidecar is running. Prompts pass no
+    // `timeout`, as the agent can work on one for minutes.
+    async fn post_rpc(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        timeout: Option<Duration>,
+        trace_id: Option<&str>,
+    ) -> Result<serde_json::Value, AppError> {
+        let payload = serde_json::json!({
+            "method": method,
+            "params": params,
+            "id": 1,
+        });
+        let mut request = self
+            .http
+            .post(self.url("/rpc"))
+            .bearer_auth(&self.auth.token)
+            .json(&payload);
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        if let Some(trace_id) = trace_id {
+            request = request.header(TRACE_ID_HEADER, trace_id);
+        }
+        let response = request.send().await.map_err(|e| request_error(method, e))?;
+        self.verify_identity(&response)?;
+        if !response.status().is_success() {
+            return Err(AppError::Sidecar(format!(
+                "{} failed with status: {}",
+                method,
+                response.status()
+            )));
+        }
+        let mut body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| AppError::Sidecar(format!("Failed to parse response: {}", e)))?;
+
+        if let Some(error) = body.get("error") {
+            return Err(AppError::Sidecar(format!(
+                "{} failed: {}",
+                method,
+                error
+                    .get("message")
+                    .and_then(|message| message.as_str())
+                    .unwrap_or("unknown error")
+            )));
+        }
+        Ok(body
+            .get_mut("result")
+            .map(serde_json::Value::take)
+            .unwrap_or_default())
+    }
+
+    // One-shot prompt in a session of its own
+    pub async fn send_prompt(&self, prompt: &str, trace_id: &str) -> Result<String, AppError> {
+        self.send_recorded("", prompt, trace_id).await
+    }
+
+    // Continues `session_id`; an empty id is a one-shot prompt
+    pub async fn send_session_prompt(
+        &self,
+        session_id: &str,
+        prompt: &str,
+        trace_id: &str,
+    ) -> Result<String, AppError> {
+        self.send_recorded(session_id, prompt, trace_id).await
+    }
+
+    // Sends to `session_id`, or a new session when it is empty
+    async fn send_recorded(
+        &self,
+        session_id: &str,
+        prompt: &str,
+        trace_id: &str,
+    ) -> Result<String, AppError> {
+        if let Some((response, _)) = self.recorder.replay(prompt) {
+            log::info!("[trace {}] Serving prompt from recording", trace_id);
+            return response.map_err(AppError::Sidecar);
+        }
+
+        let start = Instant::now();
+        let response = self.send_prompt_live(session_id, prompt, trace_id).await;
+        self.recorder.record(
+            prompt,
+            &response.clone().map_err(String::from),
+            Vec::new(),
+            start.elapsed().as_millis(),
+        );
+        response
+    }
+
+    // Skips recording and replay, a warm-up isn't part of what's being captured
+    pub async fn warm_up(&self, prompt: &str, trace_id: &str) -> Result<String, AppError> {
+        self.send_prompt_live("", prompt, trace_id).await
+    }
+
+    fn ensure_running(&self) -> Result<(), AppError> {
+        if self.is_running() {
+            Ok(())
+        } else {
+            Err(AppError::SidecarUnavailable)
+        }
+    }
+
+    // A prompt arriving while the sidecar is starting or restarting is held
+    // until it is running, rather than failing and leaving the user to retry
+    // Prompts held by `wait_for_prompt` until the sidecar is running
+    pub fn pending_prompts(&self) -> usize {
+        PENDING_PROMPTS_MAX - self.pending_prompts.available_permits()
+    }
+
+    async fn wait_for_prompt(&self, trace_id: &str) -> Result<(), AppError> {
+        let state = self.status.borrow().state;
+        if !matches!(state, SidecarState::Starting | SidecarState::Restarting) {
+            return self.ensure_running();
+        }
+        let Ok(_permit) = self.pending_prompts.try_acquire() else {
+            return Err(AppError::InvalidState(format!(
+                "{} prompts are already waiting for the sidecar",
+                PENDING_PROMPTS_MAX
+            )));
+        };
+
+        log::info!(
+            "[trace {}] Holding prompt until the sidecar is running",
+            trace_id
+        );
+        let mut status = self.status.clone();
+        tokio::time::timeout(
+            PENDING_PROMPT_TIMEOUT,
+            status.wait_for(|status| status.state.is_settled()),
+        )
+        .await
+        .map_err(|_| AppError::Timeout("Waiting for the sidecar to start".to_string()))?
+        .map_err(|_| AppError::Internal("Sidecar manager has shut down".to_string()))?;
+        self.ensure_running()
+    }
+
+    async fn send_prompt_live(
+        &self,
+        session_id: &str,
+        prompt: &str,
+        trace_id: &str,
+    ) -> Result<String, AppError> {
+        self.wait_for_prompt(trace_id).await?;
+
+        log::info!("[trace {}] Sending prompt to sidecar", trace_id);
+
+        #[cfg(feature = "grpc")]
+        if self.transport() == SidecarTransport::Grpc {
+            return metrics::timed(
+                "sidecar.prompt",
+                self.grpc_client()?.prompt(session_id, prompt, trace_id),
+            )
+            .await
+            .map_err(AppError::Sidecar);
+        }
+
+        // `messages.send` needs a session; like the gRPC server, a prompt
+        // without one gets a new session
+        let session_id = if session_id.is_empty() {
+            let session = self
+                .post_rpc(
+                    "sessions.create",
+                    serde_json::json!({ "title": "New Session" }),
+                    Some(RPC_TIMEOUT),
+                    Some(trace_id),
+                )
+                .await?;
+            session
+                .get("id")
+                .and_then(|id| id.as_str())
+                .ok_or_else(|| AppError::Sidecar("sessions.create returned no id".to_string()))?
+                .to_string()
+        } else {
+            session_id.to_string()
+        };
+
+        let result = metrics::timed(
+            "sidecar.prompt",
+            self.post_rpc(
+                "messages.send",
+                serde_json::json!({ "sessionId": session_id, "content": prompt }),
+                None,
+                Some(trace_id),
+            ),
+        )
+        .await?;
+        Ok(result
+            .get("response")
+            .and_then(|response| response.as_str())
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    // Streams tool calls and the reply as `sidecar://chunk` events and returns
+    // the reply. Over HTTP the whole reply arrives as a single chunk, sent to
+    // the same session.
+    pub async fn stream_prompt(
+        &self,
+        app: &AppHandle,
+        session_id: &str,
+        prompt: &str,
+        trace_id: &str,
+    ) -> Result<String, AppError> {
+        let chunks = Coalescer::new(app, SIDECAR_CHUNK_EVENT);
+        let emit = |kind: &str, content: &str, done: bool| {
+            chunks.push(PromptChunk {
+                trace_id: trace_id.to_string(),
+                session_id: session_id.to_string(),
+                kind: kind.to_string(),
+                content: content.to_string(),
+                done,
+            });
+        };
+
+        if let Some((response, recorded)) = self.recorder.replay(prompt) {
+            log::info!(
+                "[trace {}] Serving streamed prompt from recording",
+                trace_id
+            );
+            for chunk in &recorded {
+                emit(&chunk.kind, &chunk.content, chunk.done);
+            }
+            // Exchanges recorded without streaming have no chunks of their own
+            if recorded.is_empty() {
+                match &response {
+                    Ok(text) => emit("content", text, true),
+                    Err(e) => emit("error", e, true),
+                }
+            }
+            return response.map_err(AppError::Sidecar);
+        }
+
+        let recording = self.recorder.is_recording();
+        let recorded = Mutex::new(Vec::new());
+        let emit = |kind: &str, content: &str, done: bool| {
+            if recording {
+                recorded.lock().unwrap().push(RecordedChunk {
+                    kind: kind.to_string(),
+                    content: content.to_string(),
+                    done,
+                });
+            }
+            emit(kind, content, done);
+        };
+        let start = Instant::now();
+        let result = self
+            .stream_prompt_live(session_id, prompt, trace_id, emit)
+            .await;
+        self.recorder.record(
+            prompt,
+            &result.clone().map_err(String::from),
+            recorded.into_inner().unwrap(),
+            start.elapsed().as_millis(),
+        );
+        result
+    }
+
+    async fn stream_prompt_live(
+        &self,
+        session_id: &str,
+        prompt: &str,
+        trace_id: &str,
+        emit: impl Fn(&str, &str, bool),
+    ) -> Result<String, AppError> {
+        #[cfg(feature = "grpc")]
+        if self.transport() == SidecarTransport::Grpc {
+            self.wait_for_prompt(trace_id).await?;
+            log::info!("[trace {}] Streaming prompt from sidecar", trace_id);
+            return metrics::timed(
+                "sidecar.stream_prompt",
+                self.grpc_client()?
+                    .stream_prompt(session_id, prompt, trace_id, |chunk| {
+                        emit(&chunk.kind, &chunk.content, chunk.done)
+                    }),
+            )
+            .await
+            .map_err(AppError::Sidecar);
+        }
+
+        let result = self.send_prompt_live(session_id, prompt, trace_id).await;
+        match &result {
+            Ok(text) => emit("content", text, true),
+            Err(e) => emit("error", &e.to_string(), true),
+        }
+        result
+    }
+
+    // Stops the session's running prompt; only the gRPC transport supports this
+    pub async fn cancel(&self, session_id: &str) -> Result<(), AppError> {
+        if !self.is_running() {
+            return Err(AppError::SidecarUnavailable);
+        }
+        #[cfg(feature = "grpc")]
+        if self.transport() == SidecarTransport::Grpc {
+            return self
+                .grpc_client()?
+                .cancel(session_id)
+                .await
+                .map_err(AppError::Sidecar);
+        }
+        Err(AppError::InvalidInput(format!(
+            "Cancelling session {} needs the gRPC sidecar transport",
+            session_id
+        )))
+    }
+}
+
+// Starts a stopped sidecar, so the action also recovers from a crash
+pub fn register_actions() {
+    actions::register(
+        Action::new(
+            "sidecar.restart",
+            "Restart sidecar",
+            "Sidecar",
+            &["backend", "reload", "start"],
+        ),
+        actions::handler(|app| async move {
+            let manager = app.state::<Arc<SidecarManager>>().inner().clone();
+            if manager.is_running() {
+                manager.restart_sidecar(&app).await
+            } else {
+                manager.start_sidecar(&app).await
+            }
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Counts {
+        launches: AtomicUsize,
+        kills: AtomicUsize,
+    }
+
+    struct FakeChild {
+        pid: u32,
+        counts: Arc<Counts>,
+    }
+
+    impl Child for FakeChild {
+        fn pid(&self) -> u32 {
+            self.pid
+        }
+
+        fn kill(self: Box<Self>) -> Result<(), AppError> {
+            self.counts.kills.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    // Takes a moment to spawn, so concurrent callers arrive mid-transition
+    fn fake_launch(counts: &Arc<Counts>) -> Launch {
+        let counts = counts.clone();
+        Box::new(move |_| {
+            Box::pin(async move {
+                let pid = counts.launches.fetch_add(1, Ordering::SeqCst) as u32 + 1;
+                sleep(Duration::from_millis(50)).await;
+                Ok(Spawned {
+                    child: Box::new(FakeChild { pid, counts }),
+                    started_at: None,
+                    port: SIDECAR_PORT,
+                    grpc_port: None,
+                    transport: SidecarTransport::Http,
+                })
+            })
+        })
+    }
+
+    fn failing_launch() -> Launch {
+        Box::new(|_| Box::pin(async { Err(AppError::Sidecar("no binary".to_string())) }))
+    }
+
+    async fn start(manager: &SidecarManager, counts: &Arc<Counts>) -> Result<(), AppError> {
+        let launch = fake_launch(counts);
+        manager
+            .transition(Operation::Start, |reply| Command::Start { launch, reply })
+            .await
+    }
+
+    async fn stop(manager: &SidecarManager) -> Result<(), AppError> {
+        manager
+            .transition(Operation::Stop, |reply| Command::Stop { reply })
+            .await
+    }
+
+    async fn restart(manager: &SidecarManager, counts: &Arc<Counts>) -> Result<(), AppError> {
+        let launch = fake_launch(counts);
+        manager
+            .transition(Operation::Restart, |reply| Command::Restart {
+                launch,
+                reply,
+            })
+            .await
+    }
+
+    // Runs `n` copies of the call at once, each on its own task
+    async fn concurrently<F, Fut>(n: usize, call: F) -> Vec<Result<(), AppError>>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..n {
+            tasks.spawn(call());
+        }
+        let mut results = Vec::new();
+        while let Some(result) = tasks.join_next().await {
+            results.push(result.unwrap());
+        }
+        results
+    }
+
+    #[test]
+    fn next_state_claims_only_from_settled_states() {
+        use SidecarState::*;
+        assert_eq!(
+            next_state(Operation::Start, Stopped).unwrap(),
+            Some(Starting)
+        );
+        assert_eq!(
+            next_state(Operation::Stop, Running).unwrap(),
+            Some(Stopping)
+        );
+        assert_eq!(
+            next_state(Operation::Restart, Running).unwrap(),
+            Some(Restarting)
+        );
+        assert_eq!(next_state(Operation::Start, Starting).unwrap(), None);
+        assert_eq!(next_state(Operation::Start, Running).unwrap(), None);
+        assert_eq!(next_state(Operation::Stop, Stopped).unwrap(), None);
+        assert_eq!(next_state(Operation::Restart, Stopped).unwrap(), None);
+        assert!(next_state(Operation::Start, Stopping).is_err());
+        assert!(next_state(Operation::Stop, Starting).is_err());
+        assert!(next_state(Operation::Stop, Restarting).is_err());
+        assert!(next_state(Operation::Restart, Starting).is_err());
+    }
+
+    #[tokio::test]
+    async fn concurrent_starts_launch_once() {
+        let manager = SidecarManager::new();
+        let counts = Arc::new(Counts::default());
+
+        let results = concurrently(8, || {
+            let (manager, counts) = (manager.clone(), counts.clone());
+            async move { start(&manager, &counts).await }
+        })
+        .await;
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(counts.launches.load(Ordering::SeqCst), 1);
+        assert_eq!(manager.status().state, SidecarState::Running);
+        assert_eq!(manager.status().child_id, Some(1));
+    }
+
+    #[tokio::test]
+    async fn concurrent_stops_kill_once() {
+        let manager = SidecarManager::new();
+        let counts = Arc::new(Counts::default());
+        start(&manager, &counts).await.unwrap();
+
+        let results = concurrently(8, || {
+            let manager = manager.clone();
+            async move { stop(&manager).await }
+        })
+        .await;
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(counts.kills.load(Ordering::SeqCst), 1);
+        assert_eq!(manager.status().state, SidecarState::Stopped);
+        assert_eq!(manager.status().child_id, None);
+    }
+
+    #[tokio::test]
+    async fn concurrent_restarts_replace_the_process_once() {
+        let manager = SidecarManager::new();
+        let counts = Arc::new(Counts::default());
+        start(&manager, &counts).await.unwrap();
+
+        let results = concurrently(8, || {
+            let (manager, counts) = (manager.clone(), counts.clone());
+            async move { restart(&manager, &counts).await }
+        })
+        .await;
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(counts.kills.load(Ordering::SeqCst), 1);
+        assert_eq!(counts.launches.load(Ordering::SeqCst), 2);
+        assert_eq!(manager.status().child_id, Some(2));
+    }
+
+    #[tokio::test]
+    async fn stop_during_start_is_refused() {
+        let manager = SidecarManager::new();
+        let counts = Arc::new(Counts::default());
+
+        let (started, stopped) = tokio::join!(start(&manager, &counts), async {
+            manager
+                .status
+                .clone()
+                .wait_for(|status| status.state == SidecarState::Starting)
+                .await
+                .unwrap();
+            stop(&manager).await
+        });
+
+        assert!(started.is_ok());
+        assert!(matches!(stopped, Err(AppError::InvalidState(_))));
+        assert_eq!(manager.status().state, SidecarState::Running);
+    }
+
+    #[tokio::test]
+    async fn failed_start_reaches_every_waiter() {
+        let manager = SidecarManager::new();
+        let launch = failing_launch();
+
+        let (first, second) = tokio::join!(
+            manager.transition(Operation::Start, |reply| Command::Start { launch, reply }),
+            async {
+                manager
+                    .status
+                    .clone()
+                    .wait_for(|status| status.state == SidecarState::Starting)
+                    .await
+                    .unwrap();
+                let counts = Arc::new(Counts::default());
+                start(&manager, &counts).await
+            }
+        );
+
+        assert!(matches!(first, Err(AppError::Sidecar(_))));
+        assert!(matches!(second, Err(AppError::Sidecar(_))));
+        assert_eq!(manager.status().state, SidecarState::Stopped);
+    }
+
+    #[tokio::test]
+    async fn exit_of_an_old_process_is_ignored() {
+        let manager = SidecarManager::new();
+        let counts = Arc::new(Counts::default());
+        start(&manager, &counts).await.unwrap();
+        restart(&manager, &counts).await.unwrap();
+
+        manager
+            .commands
+            .send(Command::Exited {
+                pid: 1,
+                error: None,
+            })
+            .unwrap();
+        stop(&manager).await.unwrap();
+
+        assert_eq!(counts.kills.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::sidecar::SIDECAR_HOST;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, Endpoint};
+use tonic::Request;
+
+// Generated by build.rs from the sidecar's service definition in
+// go_backend/proto/mix/sidecar/v1/sidecar.proto
+mod proto {
+    tonic::include_proto!("mix.sidecar.v1");
+}
+
+use proto::sidecar_client::SidecarClient;
+use proto::{CancelRequest, HealthRequest, PromptRequest};
+pub use proto::{HealthReply, PromptChunk};
+
+// gRPC metadata keys are lowercase, otherwise this is `sidecar::TRACE_ID_HEADER`
+const TRACE_ID_METADATA: &str = "x-trace-id";
+
+impl PromptRequest {
+    fn new(session_id: &str, content: &str, trace_id: &str) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            content: content.to_string(),
+            trace_id: trace_id.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GrpcClient {
+    client: SidecarClient<Channel>,
+    token: String,
+}
+
+impl GrpcClient {
+    // Connects on first use, so creating a client never blocks
+    pub fn new(port: u16, token: &str) -> Result<Self, String> {
+        let channel = Endpoint::from_shared(format!("http://{}:{}", SIDECAR_HOST, port))
+            .map_err(|e| format!("Invalid gRPC endpoint: {}", e))?
+            .connect_lazy();
+        Ok(Self {
+            client: SidecarClient::new(channel),
+            token: token.to_string(),
+        })
+    }
+
+    fn request<T>(&self, message: T, trace_id: Option<&str>) -> Result<Request<T>, String> {
+        let mut request = Request::new(message);
+        let metadata = request.metadata_mut();
+        let bearer = MetadataValue::try_from(format!("Bearer {}", self.token))
+            .map_err(|e| format!("Invalid sidecar token: {}", e))?;
+        metadata.insert("authorization", bearer);
+        if let Some(trace_id) = trace_id.and_then(|id| MetadataValue::try_from(id).ok()) {
+            metadata.insert(TRACE_ID_METADATA, trace_id);
+        }
+        Ok(request)
+    }
+
+    pub async fn prompt(
+        &self,
+        session_id: &str,
+        content: &str,
+        trace_id: &str,
+    ) -> Result<String, String> {
+        let request = self.request(
+            PromptRequest::new(session_id, content, trace_id),
+            Some(trace_id),
+        )?;
+        let reply = self
+            .client
+            .clone()
+            .prompt(request)
+            .await
+            .map_err(|status| format!("Prompt failed: {}", status.message()))?;
+        Ok(reply.into_inner().content)
+    }
+
+    // Calls `on_chunk` for every update and returns the final content
+    pub async fn stream_prompt(
+        &self,
+        session_id: &str,
+        content: &str,
+        trace_id: &str,
+        mut on_chunk: impl FnMut(&PromptChunk),
+    ) -> Result<String, String> {
+        let request = self.request(
+            PromptRequest::new(session_id, content, trace_id),
+            Some(trace_id),
+        )?;
+        let mut stream = self
+            .client
+            .clone()
+            .stream_prompt(request)
+            .await
+            .map_err(|status| format!("Streaming prompt failed: {}", status.message()))?
+            .into_inner();
+
+        while let Some(chunk) = stream
+            .message()
+            .await
+            .map_err(|status| format!("Streaming prompt failed: {}", status.message()))?
+        {
+            on_chunk(&chunk);
+            match chunk.kind.as_str() {
+                "error" => return Err(chunk.content),
+                "content" if chunk.done => return Ok(chunk.content),
+                _ => {}
+            }
+        }
+        Err("Sidecar ended the stream without a reply".to_string())
+    }
+
+    pub async fn health(&self) -> Result<HealthReply, String> {
+        let request = self.request(HealthRequest {}, None)?;
+        self.client
+            .clone()
+            .health(request)
+            .await
+            .map(|reply| reply.into_inner())
+            .map_err(|status| format!("Health check failed: {}", status.message()))
+    }
+
+    pub async fn cancel(&self, session_id: &str) -> Result<(), String> {
+        let request = self.request(
+            CancelRequest {
+                session_id: session_id.to_string(),
+            },
+            None,
+        )?;
+        self.client
+            .clone()
+            .cancel(request)
+            .await
+            .map_err(|status| format!("Cancel failed: {}", status.message()))?;
+        Ok(())
+    }
+}
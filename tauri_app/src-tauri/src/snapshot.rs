@@ -0,0 +1,120 @@
+use crate::files::{self, FsError};
+use crate::{validation, workspace};
+use serde::Serialize;
+use specta::Type;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+// Uncompressed size limit, checked before anything is written
+const MAX_SNAPSHOT_BYTES: u64 = 500 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Type)]
+pub struct SnapshotInfo {
+    pub path: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+fn collect_files(root: &Path, dest: &Path) -> Result<(Vec<PathBuf>, u64), FsError> {
+    let mut entries = Vec::new();
+    let mut total: u64 = 0;
+    for entry in files::walker(root, None)?.build() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+            || entry.path() == dest
+        {
+            continue;
+        }
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        total += size;
+        if total > MAX_SNAPSHOT_BYTES {
+            return Err(FsError::TooLarge {
+                path: root.to_string_lossy().into_owned(),
+                size: total,
+                limit: MAX_SNAPSHOT_BYTES,
+            });
+        }
+        entries.push(entry.into_path());
+    }
+    Ok((entries, total))
+}
+
+fn write_zip(root: &Path, dest: &Path, file: File, entries: &[PathBuf]) -> Result<(), FsError> {
+    let dest_name = dest.to_string_lossy();
+    let zip_error = |e: zip::result::ZipError| FsError::Io {
+        path: dest_name.to_string(),
+        reason: e.to_string(),
+    };
+
+    let mut zip = ZipWriter::new(file);
+    for path in entries {
+        let name = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let mut source = File::open(path).map_err(|e| FsError::io(&name, e))?;
+        zip.start_file(name.as_str(), SimpleFileOptions::default())
+            .map_err(zip_error)?;
+        io::copy(&mut source, &mut zip).map_err(|e| FsError::io(&name, e))?;
+    }
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+fn write_snapshot(root: &Path, dest: &Path) -> Result<SnapshotInfo, FsError> {
+    let (entries, bytes) = collect_files(root, dest)?;
+    // Never replaces a file that's already there
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(dest)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => FsError::Invalid {
+                reason: format!("{} already exists", dest.display()),
+            },
+            _ => FsError::io(&dest.to_string_lossy(), e),
+        })?;
+    if let Err(e) = write_zip(root, dest, file, &entries) {
+        // Don't leave a truncated archive behind
+        let _ = fs::remove_file(dest);
+        return Err(e);
+    }
+
+    Ok(SnapshotInfo {
+        path: dest.to_string_lossy().into_owned(),
+        files: entries.len(),
+        bytes,
+    })
+}
+
+// Zips the workspace as the agent sees it, skipping gitignored files
+#[tauri::command]
+#[specta::specta]
+pub async fn snapshot_workspace(app: AppHandle, dest: String) -> Result<SnapshotInfo, FsError> {
+    let root = workspace::root(&app)?;
+    let dest = validation::output_path(&app, "dest", &dest).map_err(|e| FsError::Invalid {
+        reason: e.to_string(),
+    })?;
+    log::info!(
+        "Snapshotting workspace {} to {}",
+        root.display(),
+        dest.display()
+    );
+
+    let task_dest = dest.clone();
+    tauri::async_runtime::spawn_blocking(move || write_snapshot(&root, &task_dest))
+        .await
+        .map_err(|e| FsError::Io {
+            path: dest.to_string_lossy().into_owned(),
+            reason: e.to_string(),
+        })?
+}
@@ -0,0 +1,105 @@
+use crate::error::AppError;
+use crate::platform::{Native, Platform, Sound};
+use crate::settings::SettingsManager;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, State};
+
+// Moments the app can make a sound for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundEvent {
+    PromptComplete,
+    PromptFailed,
+    ApprovalNeeded,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct SoundSettings {
+    pub enabled: bool,
+    // The sound for each event, none to keep it quiet
+    pub prompt_complete: Option<Sound>,
+    pub prompt_failed: Option<Sound>,
+    pub approval_needed: Option<Sound>,
+    // Also play while output is muted or a Focus mode is on, for users who
+    // rely on them
+    pub ignore_silence: bool,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prompt_complete: Some(Sound::Chime),
+            prompt_failed: Some(Sound::Alert),
+            approval_needed: Some(Sound::Attention),
+            ignore_silence: false,
+        }
+    }
+}
+
+impl SoundSettings {
+    fn sound(&self, event: SoundEvent) -> Option<Sound> {
+        match event {
+            SoundEvent::PromptComplete => self.prompt_complete,
+            SoundEvent::PromptFailed => self.prompt_failed,
+            SoundEvent::ApprovalNeeded => self.approval_needed,
+        }
+    }
+}
+
+// Plays the sound set for `event` unless sounds are off or the OS is keeping
+// quiet. Returns whether one was played.
+async fn play_for(app: &AppHandle, event: SoundEvent) -> Result<bool, String> {
+    let settings = app.state::<SettingsManager>().get().sounds;
+    let Some(sound) = settings.sound(event).filter(|_| settings.enabled) else {
+        return Ok(false);
+    };
+    let ignore_silence = settings.ignore_silence;
+    tauri::async_runtime::spawn_blocking(move || {
+        if !ignore_silence && Native::sounds_silenced() {
+            return Ok(false);
+        }
+        Native::play_sound(sound).map(|()| true)
+    })
+    .await
+    .map_err(|e| format!("Failed to play sound: {}", e))?
+}
+
+// For the Rust side's own events; a sound that can't play isn't worth more
+// than a log line
+pub fn play(app: &AppHandle, event: SoundEvent) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = play_for(&app, event).await {
+            log::warn!("Failed to play the {:?} sound: {}", event, e);
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn play_sound(app: AppHandle, event: SoundEvent) -> Result<bool, AppError> {
+    Ok(play_for(&app, event).await?)
+}
+
+// Plays `sound` whatever the settings say, so it can be heard before picking it
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_sound(sound: Sound) -> Result<(), AppError> {
+    tauri::async_runtime::spawn_blocking(move || Native::play_sound(sound))
+        .await
+        .map_err(|e| format!("Failed to play sound: {}", e))??;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_sound_settings(
+    settings_manager: State<'_, SettingsManager>,
+    sounds: SoundSettings,
+) -> Result<(), AppError> {
+    settings_manager.update(|settings| settings.sounds = sounds)?;
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use crate::sidecar::SidecarManager;
+use crate::{warmup, watcher};
+use serde::Serialize;
+use specta::Type;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+
+// Slow startup work runs here after the window is up, so `setup` only does
+// what the first frame needs. Each step is reported as `startup://progress`;
+// the webview may load after some steps finished, so it can also ask for the
+// current state with `get_startup_progress`.
+pub const STARTUP_PROGRESS_EVENT: &str = "startup://progress";
+// Carries every warning so far, see `warn`
+pub const STARTUP_WARNINGS_EVENT: &str = "startup://warnings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum StepState {
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct StartupStep {
+    pub name: &'static str,
+    pub state: StepState,
+    pub error: Option<String>,
+    pub elapsed_ms: u128,
+}
+
+// Spawning the sidecar also opens its database
+const STEPS: [&str; 2] = ["watcher", "sidecar"];
+
+static PROGRESS: Mutex<Vec<StartupStep>> = Mutex::new(Vec::new());
+
+// An optional subsystem that failed to come up; the app runs without it
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct StartupWarning {
+    pub subsystem: &'static str,
+    pub message: String,
+}
+
+static WARNINGS: Mutex<Vec<StartupWarning>> = Mutex::new(Vec::new());
+
+// Launch profile, so regressions in startup time show up as numbers. Phases
+// are offsets from the start of `run`, and deferred steps are included once
+// they finish.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PhaseTiming {
+    pub name: &'static str,
+    pub started_ms: u128,
+    pub duration_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct StartupProfile {
+    pub phases: Vec<PhaseTiming>,
+    // Until the last recorded phase ended
+    pub total_ms: u128,
+}
+
+static LAUNCHED_AT: OnceLock<Instant> = OnceLock::new();
+static PROFILE: Mutex<Vec<PhaseTiming>> = Mutex::new(Vec::new());
+
+pub fn mark_launch() {
+    LAUNCHED_AT.get_or_init(Instant::now);
+}
+
+// Records a phase that began at `started` and ends now
+pub fn record_phase(name: &'static str, started: Instant) {
+    let launched_at = *LAUNCHED_AT.get_or_init(|| started);
+    let timing = PhaseTiming {
+        name,
+        started_ms: started.saturating_duration_since(launched_at).as_millis(),
+        duration_ms: started.elapsed().as_millis(),
+    };
+    log::debug!("Startup phase {} took {}ms", name, timing.duration_ms);
+    PROFILE.lock().unwrap().push(timing);
+}
+
+// Records that `subsystem` is disabled for this run. Warnings raised during
+// `setup` fire before the webview listens, so it also reads them with
+// `get_startup_warnings`.
+pub fn warn(app: &AppHandle, subsystem: &'static str, message: impl Into<String>) {
+    let message = message.into();
+    log::warn!("Starting without {}: {}", subsystem, message);
+    let warnings = {
+        let mut warnings = WARNINGS.lock().unwrap();
+        warnings.push(StartupWarning { subsystem, message });
+        warnings.clone()
+    };
+    let _ = app.emit(STARTUP_WARNINGS_EVENT, warnings);
+}
+
+fn report(app: &AppHandle, step: StartupStep) {
+    {
+        let mut progress = PROGRESS.lock().unwrap();
+        match progress
+            .iter_mut()
+            .find(|existing| existing.name == step.name)
+        {
+            Some(existing) => *existing = step.clone(),
+            None => progress.push(step.clone()),
+        }
+    }
+    let _ = app.emit(STARTUP_PROGRESS_EVENT, step);
+}
+
+async fn run_step<F>(app: &AppHandle, name: &'static str, step: F)
+where
+    F: std::future::Future<Output = Result<StepState, String>>,
+{
+    let start = Instant::now();
+    report(
+        app,
+        StartupStep {
+            name,
+            state: StepState::Running,
+            error: None,
+            elapsed_ms: 0,
+        },
+    );
+    let result = step.await;
+    record_phase(name, start);
+    if let Err(e) = &result {
+        log::error!("Startup step {} failed: {}", name, e);
+        warn(app, name, e.clone());
+    }
+    report(
+        app,
+        StartupStep {
+            name,
+            state: *result.as_ref().unwrap_or(&StepState::Failed),
+            error: result.err(),
+            elapsed_ms: start.elapsed().as_millis(),
+        },
+    );
+}
+
+async fn start_sidecar(app: &AppHandle) -> Result<StepState, String> {
+    let started = Instant::now();
+    // Always our own process: whatever else listens on the default port has
+    // neither our token nor our identity, so the sidecar moves to a fallback
+    // port instead, and the webview asks for it with `get_sidecar_port`
+    let manager = app.state::<Arc<SidecarManager>>().inner().clone();
+    // Returns once the sidecar listens
+    manager.start_sidecar(app).await?;
+    record_phase("sidecar_ready", started);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        warmup::run(&app, warmup::Profile::App).await;
+    });
+    Ok(StepState::Done)
+}
+
+// Watching a large workspace walks the whole tree, so it runs off the main thread
+async fn start_watcher(app: &AppHandle) -> Result<StepState, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || watcher::watch(&app))
+        .await
+        .map_err(|e| format!("Failed to start watcher: {}", e))?;
+    Ok(StepState::Done)
+}
+
+pub fn start(app: &AppHandle) {
+    for name in STEPS {
+        report(
+            app,
+            StartupStep {
+                name,
+                state: StepState::Pending,
+                error: None,
+                elapsed_ms: 0,
+            },
+        );
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let start = Instant::now();
+        // Independent of each other, so they run side by side
+        tokio::join!(
+            run_step(&app, "watcher", start_watcher(&app)),
+            run_step(&app, "sidecar", start_sidecar(&app)),
+        );
+        log::info!("Deferred startup finished in {:?}", start.elapsed());
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_startup_progress() -> Vec<StartupStep> {
+    PROGRESS.lock().unwrap().clone()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_startup_warnings() -> Vec<StartupWarning> {
+    WARNINGS.lock().unwrap().clone()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_startup_profile() -> StartupProfile {
+    let phases = PROFILE.lock().unwrap().clone();
+    StartupProfile {
+        total_ms: phases
+            .iter()
+            .map(|phase| phase.started_ms + phase.duration_ms)
+            .max()
+            .unwrap_or(0),
+        phases,
+    }
+}
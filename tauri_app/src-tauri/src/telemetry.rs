@@ -0,0 +1,271 @@
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::{http_client, metrics};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+const QUEUE_FILE_NAME: &str = "telemetry-queue.jsonl";
+// The batch being uploaded, moved aside so events tracked meanwhile go to a
+// fresh queue. Kept until the upload succeeds, and sent before the next batch.
+const SENDING_FILE_NAME: &str = "telemetry-sending.jsonl";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(300);
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_QUEUED_EVENTS: usize = 1000;
+
+// Events in the queue file, counted once on the first append. Held while the
+// queue is appended to or moved aside.
+static QUEUED: Mutex<Option<usize>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TelemetryEvent {
+    pub name: String,
+    pub timestamp: String,
+    pub properties: Value,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct TelemetryPreview {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    pub queued: Vec<TelemetryEvent>,
+}
+
+fn data_path(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?;
+    Ok(dir.join(name))
+}
+
+fn read_events(app: &AppHandle, name: &str) -> Vec<TelemetryEvent> {
+    let Ok(path) = data_path(app, name) else {
+        return vec![];
+    };
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+// Everything not yet uploaded, the batch in flight first
+fn read_queue(app: &AppHandle) -> Vec<TelemetryEvent> {
+    let mut events = read_events(app, SENDING_FILE_NAME);
+    events.extend(read_events(app, QUEUE_FILE_NAME));
+    events
+}
+
+fn remove(app: &AppHandle, name: &str) -> Result<(), String> {
+    match fs::remove_file(data_path(app, name)?) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            Err(format!("Failed to clear telemetry queue: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn clear_queue(app: &AppHandle) -> Result<(), String> {
+    let mut queued = QUEUED.lock().unwrap();
+    remove(app, SENDING_FILE_NAME)?;
+    remove(app, QUEUE_FILE_NAME)?;
+    *queued = Some(0);
+    Ok(())
+}
+
+// The batch to upload: one left from a failed flush, or else the queue,
+// renamed so the next append starts a new file
+fn take_batch(app: &AppHandle) -> Result<Vec<TelemetryEvent>, String> {
+    let sending = data_path(app, SENDING_FILE_NAME)?;
+    if !sending.exists() {
+        let mut queued = QUEUED.lock().unwrap();
+        match fs::rename(data_path(app, QUEUE_FILE_NAME)?, &sending) {
+            Ok(()) => *queued = Some(0),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(format!("Failed to read telemetry queue: {}", e)),
+        }
+    }
+    Ok(read_events(app, SENDING_FILE_NAME))
+}
+
+fn is_enabled(app: &AppHandle) -> bool {
+    app.try_state::<SettingsManager>()
+        .is_some_and(|settings| settings.get().telemetry_enabled)
+}
+
+// Queues an event on disk, so nothing is lost while offline. No-op without consent.
+pub fn track(app: &AppHandle, name: &str, properties: Value) {
+    if !is_enabled(app) {
+        return;
+    }
+    // Also runs from the panic hook, where a poisoned lock must not panic again
+    let Ok(mut queued) = QUEUED.lock() else {
+        return;
+    };
+    let count = *queued.get_or_insert_with(|| read_events(app, QUEUE_FILE_NAME).len());
+    if count >= MAX_QUEUED_EVENTS {
+        return;
+    }
+
+    let event = TelemetryEvent {
+        name: name.to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        properties,
+    };
+
+    let Ok(path) = data_path(app, QUEUE_FILE_NAME) else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let (Ok(mut file), Ok(line)) = (
+        OpenOptions::new().create(true).append(true).open(path),
+        serde_json::to_string(&event),
+    ) {
+        if writeln!(file, "{}", line).is_ok() {
+            *queued = Some(count + 1);
+        }
+    }
+}
+
+pub async fn flush(app: &AppHandle) -> Result<(), String> {
+    let settings = app.state::<SettingsManager>().get();
+    let Some(endpoint) = settings
+        .telemetry_endpoint
+        .filter(|_| settings.telemetry_enabled)
+    else {
+        return Ok(());
+    };
+
+    let queue_app = app.clone();
+    let events = tauri::async_runtime::spawn_blocking(move || take_batch(&queue_app))
+        .await
+        .map_err(|e| format!("Failed to read telemetry queue: {}", e))??;
+    if events.is_empty() {
+        return remove(app, SENDING_FILE_NAME);
+    }
+
+    let response = http_client::shared()
+        .post(&endpoint)
+        .timeout(UPLOAD_TIMEOUT)
+        .json(&json!({ "events": events }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send telemetry: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Telemetry endpoint responded with status: {}",
+            response.status()
+        ));
+    }
+
+    // Only the uploaded batch; events tracked meanwhile stay queued
+    remove(app, SENDING_FILE_NAME)
+}
+
+// Crash signatures only carry the panic location and a hash of the message,
+// the message itself may contain paths or prompt text
+fn install_panic_hook(app: &AppHandle) {
+    let app = app.clone();
+    let previous_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        let mut hasher = DefaultHasher::new();
+        message.hash(&mut hasher);
+
+        track(
+            &app,
+            "crash",
+            json!({
+                "location": info.location().map(|l| format!("{}:{}", l.file(), l.line())),
+                "message_hash": format!("{:016x}", hasher.finish()),
+                "app_version": app.package_info().version.to_string(),
+                "os": std::env::consts::OS,
+                "arch": std::env::consts::ARCH,
+            }),
+        );
+
+        previous_hook(info);
+    }));
+}
+
+pub fn start(app: &AppHandle) {
+    install_panic_hook(app);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut reported: HashMap<String, u64> = HashMap::new();
+
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+
+            // Feature usage is reported as per-operation call counts since the last flush
+            let counts = metrics::operation_counts();
+            let usage: HashMap<&String, u64> = counts
+                .iter()
+                .map(|(name, count)| (name, count - reported.get(name).copied().unwrap_or(0)))
+                .filter(|(_, count)| *count > 0)
+                .collect();
+            if !usage.is_empty() {
+                track(&app, "usage", json!({ "operations": usage }));
+            }
+            reported = counts;
+
+            if let Err(e) = flush(&app).await {
+                log::debug!("Telemetry flush failed, keeping events queued: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn preview_telemetry(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+) -> TelemetryPreview {
+    let settings = settings_manager.get();
+    TelemetryPreview {
+        enabled: settings.telemetry_enabled,
+        endpoint: settings.telemetry_endpoint,
+        queued: read_queue(&app),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_telemetry_consent(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    enabled: bool,
+    endpoint: Option<String>,
+) -> Result<(), AppError> {
+    settings_manager.update(|settings| {
+        settings.telemetry_enabled = enabled;
+        if endpoint.is_some() {
+            settings.telemetry_endpoint = endpoint;
+        }
+    })?;
+
+    if !enabled {
+        clear_queue(&app)?;
+    }
+    Ok(())
+}
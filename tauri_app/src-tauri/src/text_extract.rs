@@ -0,0 +1,218 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::link_preview::decode_entities;
+use crate::{fetch_tool, previews, scratch, validation};
+use pdfium_render::prelude::PdfiumError;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tauri::AppHandle;
+
+// Larger files are refused rather than read into memory
+const MAX_SOURCE_BYTES: u64 = 100 * 1024 * 1024;
+// A DOCX's document.xml is read up to this much uncompressed
+const MAX_XML_BYTES: u64 = 50 * 1024 * 1024;
+// Pages extracted per call; later pages are reached with `first_page`
+const MAX_PAGES: u16 = 200;
+
+// The parts of a DOCX body that carry text or break it up: runs of text,
+// tabs, line breaks and paragraph ends
+static DOCX_TEXT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<w:t(?:\s[^>]*)?>(.*?)</w:t>|<w:tab/>|<w:(?:br|cr)\b[^>]*/>|</w:p>").unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Pdf,
+    Docx,
+    Text,
+}
+
+impl Kind {
+    fn of(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("pdf") => Kind::Pdf,
+            Some("docx") => Kind::Docx,
+            _ => Kind::Text,
+        }
+    }
+}
+
+// A file in a scratch dir or a folder the user granted, like the attachments
+// dropped on the window
+fn source_path(app: &AppHandle, path: &str) -> Result<PathBuf, String> {
+    let resolved = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", path, e))?;
+    if !scratch::contains(app, &resolved) {
+        validation::existing_path(app, "path", path).map_err(|e| e.to_string())?;
+    }
+    Ok(resolved)
+}
+
+// Pages `first..=last`, 1-based, with a header per page. `last` is capped at
+// the document's length and at `MAX_PAGES` past `first`.
+fn pdf_text(app: &AppHandle, path: &Path, first: u16, last: Option<u16>) -> Result<String, String> {
+    let pdfium = previews::pdfium(app)
+        .ok_or_else(|| "Reading PDFs needs the pdfium library, which was not found".to_string())?;
+    let failed = |e: PdfiumError| format!("Failed to read {}: {}", path.display(), e);
+
+    let document = pdfium.load_pdf_from_file(path, None).map_err(failed)?;
+    let pages = document.pages();
+    let count = pages.len();
+    if first > count {
+        return Err(format!(
+            "first_page is {} but the PDF has {} pages",
+            first, count
+        ));
+    }
+    let requested = last.unwrap_or(count).min(count);
+    let last = requested.min(first.saturating_add(MAX_PAGES - 1));
+
+    let mut text = format!("Pages {}-{} of {}\n", first, last, count);
+    if last < requested {
+        text.push_str(&format!(
+            "[only {} pages are read per call; call again with first_page {} for more]\n",
+            MAX_PAGES,
+            last + 1
+        ));
+    }
+    let mut found = false;
+    for number in first..=last {
+        let page = pages.get(number - 1).map_err(failed)?;
+        let page_text = page.text().map_err(failed)?.all();
+        found |= !page_text.trim().is_empty();
+        text.push_str(&format!(
+            "\n--- Page {} ---\n{}\n",
+            number,
+            page_text.trim_end()
+        ));
+    }
+    if !found {
+        return Ok(format!(
+            "Pages {}-{} have no extractable text; a scanned PDF needs OCR",
+            first, last
+        ));
+    }
+    Ok(text)
+}
+
+// Word documents have no fixed pages, so the whole body is read
+fn docx_text(path: &Path) -> Result<String, String> {
+    let failed = |e: zip::result::ZipError| format!("Failed to read {}: {}", path.display(), e);
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(failed)?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .map_err(failed)?
+        .take(MAX_XML_BYTES)
+        .read_to_string(&mut xml)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut text = String::new();
+    for captures in DOCX_TEXT.captures_iter(&xml) {
+        match (captures.get(1), &captures[0]) {
+            (Some(run), _) => text.push_str(&decode_entities(run.as_str())),
+            (None, "<w:tab/>") => text.push('\t'),
+            _ => text.push('\n'),
+        }
+    }
+    Ok(text)
+}
+
+// Anything else is read as UTF-8, like `files::read_file` does
+fn plain_text(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if bytes.contains(&0) {
+        return Err(format!(
+            "{} is neither a PDF, a DOCX nor a text file",
+            path.display()
+        ));
+    }
+    String::from_utf8(bytes).map_err(|_| format!("{} is not UTF-8 text", path.display()))
+}
+
+fn page_arg(arguments: &Value, key: &str) -> Result<Option<u16>, String> {
+    match arguments.get(key).and_then(Value::as_u64) {
+        None => Ok(None),
+        Some(page) if (1..=u16::MAX as u64).contains(&page) => Ok(Some(page as u16)),
+        Some(page) => Err(format!("{} must be a page number, got {}", key, page)),
+    }
+}
+
+async fn extract_text(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let path = agent_tools::string_arg(&arguments, "path")?;
+    let offset = arguments.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let first_page = page_arg(&arguments, "first_page")?;
+    let last_page = page_arg(&arguments, "last_page")?;
+
+    let source = source_path(&app, &path)?;
+    let size = fs::metadata(&source)
+        .map_err(|e| format!("{}: {}", path, e))?
+        .len();
+    if size > MAX_SOURCE_BYTES {
+        return Err(format!(
+            "{} is {} MB, over the limit of {} MB",
+            path,
+            size / (1024 * 1024),
+            MAX_SOURCE_BYTES / (1024 * 1024)
+        ));
+    }
+    let kind = Kind::of(&source);
+    if kind != Kind::Pdf && (first_page.is_some() || last_page.is_some()) {
+        return Err("first_page and last_page only apply to PDFs".to_string());
+    }
+    let first_page = first_page.unwrap_or(1);
+    if last_page.is_some_and(|last| last < first_page) {
+        return Err("last_page must not be before first_page".to_string());
+    }
+
+    progress.send(&format!("Extracting text from {}", path));
+    let text = tauri::async_runtime::spawn_blocking(move || match kind {
+        Kind::Pdf => pdf_text(&app, &source, first_page, last_page),
+        Kind::Docx => docx_text(&source),
+        Kind::Text => plain_text(&source),
+    })
+    .await
+    .map_err(|e| format!("Text extraction failed: {}", e))??;
+    log::info!(
+        "Agent extracted {} characters from {}",
+        text.chars().count(),
+        path
+    );
+    if text.trim().is_empty() {
+        return Ok(format!("{} has no text", path));
+    }
+    Ok(fetch_tool::page(&text, offset))
+}
+
+pub fn register_tools() {
+    agent_tools::register(Tool {
+        name: "extract_text",
+        description: "Extract the text of a local PDF, DOCX or plain text file (Markdown, CSV, \
+            JSON, source code and the like), such as an attachment dropped on the window. For \
+            PDFs, `first_page` and `last_page` select a page range; up to 200 pages are read \
+            per call. Long results are paged with `offset`.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "Absolute path of the file" },
+                "first_page": { "type": "integer", "minimum": 1 },
+                "last_page": { "type": "integer", "minimum": 1 },
+                "offset": { "type": "integer", "minimum": 0 }
+            },
+            "required": ["path"]
+        }),
+        handler: agent_tools::handler(extract_text),
+    });
+}
@@ -0,0 +1,62 @@
+use crate::sidecar::SidecarManager;
+use serde::Deserialize;
+use serde_json::json;
+
+// Sessions and replies as the sidecar stores them, fetched by the exports
+// that turn them into documents (PDF, notes vault)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMessage {
+    pub id: String,
+    pub session_id: String,
+    pub role: String,
+    pub content: String,
+    #[serde(default)]
+    pub model: String,
+    // Unix seconds
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionInfo {
+    title: String,
+}
+
+pub struct Transcript {
+    // Blank when the session was never titled
+    pub title: String,
+    // Oldest first
+    pub messages: Vec<TranscriptMessage>,
+}
+
+pub async fn session(sidecar: &SidecarManager, session_id: &str) -> Result<Transcript, String> {
+    let session: SessionInfo = serde_json::from_value(
+        sidecar
+            .rpc("sessions.get", json!({ "id": session_id }))
+            .await?,
+    )
+    .map_err(|e| format!("Failed to parse session: {}", e))?;
+    let messages = serde_json::from_value(
+        sidecar
+            .rpc("messages.list", json!({ "sessionId": session_id }))
+            .await?,
+    )
+    .map_err(|e| format!("Failed to parse messages: {}", e))?;
+    Ok(Transcript {
+        title: session.title,
+        messages,
+    })
+}
+
+pub async fn message(
+    sidecar: &SidecarManager,
+    message_id: &str,
+) -> Result<TranscriptMessage, String> {
+    serde_json::from_value(
+        sidecar
+            .rpc("messages.get", json!({ "id": message_id }))
+            .await?,
+    )
+    .map_err(|e| format!("Failed to parse message: {}", e))
+}
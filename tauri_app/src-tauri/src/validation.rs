@@ -0,0 +1,203 @@
+use crate::error::AppError;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_fs::FsExt;
+
+// Argument checks shared by the commands, run before a command acts on its
+// arguments. Failures are `AppError::InvalidField`, named after the argument as
+// the webview passes it (camelCase), so the UI can flag the right input.
+pub const MAX_PROMPT_BYTES: usize = 512 * 1024;
+
+fn invalid(field: &str, message: impl Into<String>) -> AppError {
+    AppError::InvalidField {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+// Prompts go to the sidecar as-is, so reject what it would only fail on later
+pub fn prompt(field: &str, prompt: &str) -> Result<(), AppError> {
+    if prompt.trim().is_empty() {
+        return Err(invalid(field, "must not be empty"));
+    }
+    if prompt.len() > MAX_PROMPT_BYTES {
+        return Err(invalid(
+            field,
+            format!(
+                "is {} bytes, over the limit of {}",
+                prompt.len(),
+                MAX_PROMPT_BYTES
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// Sessions, messages and approvals are identified by UUIDs, hyphenated or not
+pub fn uuid(field: &str, value: &str) -> Result<(), AppError> {
+    uuid::Uuid::parse_str(value)
+        .map(|_| ())
+        .map_err(|_| invalid(field, format!("must be a UUID, got {:?}", value)))
+}
+
+fn absolute<'a>(field: &str, path: &'a str) -> Result<&'a Path, AppError> {
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return Err(invalid(field, "must be an absolute path"));
+    }
+    Ok(path)
+}
+
+// Paths outside the granted folders are refused, like the `asset:` protocol
+fn in_scope(app: &AppHandle, field: &str, path: PathBuf) -> Result<PathBuf, AppError> {
+    if !app.fs_scope().is_allowed(&path) {
+        return Err(invalid(
+            field,
+            format!(
+                "{} is outside the folders the app may access",
+                path.display()
+            ),
+        ));
+    }
+    Ok(path)
+}
+
+fn resolve_existing(field: &str, path: &str) -> Result<PathBuf, AppError> {
+    absolute(field, path)?
+        .canonicalize()
+        .map_err(|e| invalid(field, format!("{}: {}", path, e)))
+}
+
+// The folder is resolved through symlinks; the file itself may not exist yet
+fn resolve_output(field: &str, path: &str) -> Result<PathBuf, AppError> {
+    let path = absolute(field, path)?;
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(invalid(field, "must name a file"));
+    };
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| invalid(field, format!("{}: {}", parent.display(), e)))?;
+    Ok(parent.join(name))
+}
+
+// A file or folder that must exist, resolved through symlinks
+pub fn existing_path(app: &AppHandle, field: &str, path: &str) -> Result<PathBuf, AppError> {
+    in_scope(app, field, resolve_existing(field, path)?)
+}
+
+// A file the command is about to create, whose folder must exist
+pub fn output_path(app: &AppHandle, field: &str, path: &str) -> Result<PathBuf, AppError> {
+    in_scope(app, field, resolve_output(field, path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    // A fresh folder under the system temp dir, resolved through symlinks
+    // (macOS's is one)
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mix-validation-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    fn field_of(error: AppError) -> String {
+        match error {
+            AppError::InvalidField { field, .. } => field,
+            other => panic!("expected an invalid field, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn prompt_must_be_non_empty_and_bounded() {
+        assert!(prompt("prompt", "hello").is_ok());
+        assert_eq!(field_of(prompt("prompt", " \n").unwrap_err()), "prompt");
+        assert!(prompt("prompt", &"a".repeat(MAX_PROMPT_BYTES + 1)).is_err());
+    }
+
+    #[test]
+    fn uuid_accepts_both_forms() {
+        assert!(uuid("id", "67e55044-10b1-426f-9247-bb680e5fe0c8").is_ok());
+        assert!(uuid("id", "67e5504410b1426f9247bb680e5fe0c8").is_ok());
+        assert_eq!(
+            field_of(uuid("sessionId", "../etc").unwrap_err()),
+            "sessionId"
+        );
+    }
+
+    #[test]
+    fn paths_must_be_absolute() {
+        assert_eq!(
+            field_of(resolve_existing("path", "notes.md").unwrap_err()),
+            "path"
+        );
+        assert!(resolve_output("path", "../notes.md").is_err());
+    }
+
+    #[test]
+    fn existing_path_must_exist() {
+        let dir = temp_dir();
+        fs::write(dir.join("a.txt"), "a").unwrap();
+
+        let file = dir.join("a.txt");
+        assert_eq!(
+            resolve_existing("path", file.to_str().unwrap()).unwrap(),
+            file
+        );
+        assert!(resolve_existing("path", dir.join("b.txt").to_str().unwrap()).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn dot_dot_is_resolved_before_the_scope_check() {
+        let dir = temp_dir();
+        fs::create_dir(dir.join("inner")).unwrap();
+        fs::write(dir.join("a.txt"), "a").unwrap();
+
+        let climbing = dir.join("inner").join("..").join("a.txt");
+        assert_eq!(
+            resolve_existing("path", climbing.to_str().unwrap()).unwrap(),
+            dir.join("a.txt")
+        );
+        let climbing = dir.join("inner").join("..").join("out.pdf");
+        assert_eq!(
+            resolve_output("path", climbing.to_str().unwrap()).unwrap(),
+            dir.join("out.pdf")
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_resolved_before_the_scope_check() {
+        let dir = temp_dir();
+        let outside = temp_dir();
+        fs::write(outside.join("secret.txt"), "s").unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("link")).unwrap();
+
+        let linked = dir.join("link").join("secret.txt");
+        assert_eq!(
+            resolve_existing("path", linked.to_str().unwrap()).unwrap(),
+            outside.join("secret.txt")
+        );
+        let linked = dir.join("link").join("new.txt");
+        assert_eq!(
+            resolve_output("path", linked.to_str().unwrap()).unwrap(),
+            outside.join("new.txt")
+        );
+        fs::remove_dir_all(dir).unwrap();
+        fs::remove_dir_all(outside).unwrap();
+    }
+
+    #[test]
+    fn output_path_needs_an_existing_folder_and_a_name() {
+        let dir = temp_dir();
+
+        let missing = dir.join("missing").join("out.pdf");
+        assert!(resolve_output("path", missing.to_str().unwrap()).is_err());
+        assert!(resolve_output("path", "/").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
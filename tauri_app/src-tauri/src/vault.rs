@@ -0,0 +1,406 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::sidecar::SidecarManager;
+use crate::{approvals, transcript, validation};
+use chrono::{DateTime, Local};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+// Exports sessions or single replies as Markdown notes into a notes vault
+// (Obsidian or any folder of Markdown files). The YAML front matter carries
+// the tags, date and model so the vault can index and query them.
+const DEFAULT_FOLDER: &str = "Mix";
+const DEFAULT_TAG: &str = "mix";
+// Characters Obsidian refuses in note names
+const RESERVED_CHARS: &[char] = &[
+    '/', '\\', ':', '*', '?', '"', '<', '>', '|', '#', '^', '[', ']',
+];
+const MAX_TITLE_CHARS: usize = 80;
+// The agent's `create_note` tool files notes under this action, confirmed by
+// the user. On macOS they go to Apple Notes, elsewhere into the vault.
+const NOTE_APPROVAL_ACTION: &str = "notes_write";
+const MAX_NOTE_BYTES: usize = 1024 * 1024;
+
+struct Note {
+    title: String,
+    date: DateTime<Local>,
+    model: Option<String>,
+    session_id: Option<String>,
+    tags: Vec<String>,
+    body: String,
+}
+
+impl Note {
+    fn render(&self) -> String {
+        // JSON strings are valid YAML scalars, which saves escaping by hand
+        let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+        let mut text = String::from("---\n");
+        text.push_str(&format!("title: {}\n", quote(&self.title)));
+        text.push_str(&format!("date: {}\n", self.date.to_rfc3339()));
+        if let Some(model) = &self.model {
+            text.push_str(&format!("model: {}\n", quote(model)));
+        }
+        if let Some(session_id) = &self.session_id {
+            text.push_str(&format!("session: {}\n", quote(session_id)));
+        }
+        text.push_str("tags:\n");
+        for tag in &self.tags {
+            text.push_str(&format!("  - {}\n", quote(tag)));
+        }
+        text.push_str("---\n\n");
+        text.push_str(self.body.trim_end());
+        text.push('\n');
+        text
+    }
+}
+
+fn date(created_at: i64) -> DateTime<Local> {
+    DateTime::from_timestamp(created_at, 0)
+        .map(|date| date.with_timezone(&Local))
+        .unwrap_or_else(Local::now)
+}
+
+// Tags can't contain spaces in Obsidian, and the `#` is implied
+fn tags(extra: Option<Vec<String>>) -> Vec<String> {
+    let mut tags = vec![DEFAULT_TAG.to_string()];
+    for tag in extra.unwrap_or_default() {
+        let tag = tag.trim().trim_start_matches('#').replace(' ', "-");
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn note_name(note: &Note) -> String {
+    let title: String = note
+        .title
+        .chars()
+        .map(|c| if RESERVED_CHARS.contains(&c) { ' ' } else { c })
+        .take(MAX_TITLE_CHARS)
+        .collect();
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    let title = title.trim_start_matches('.');
+    let date = note.date.format("%Y-%m-%d");
+    if title.is_empty() {
+        date.to_string()
+    } else {
+        format!("{} {}", date, title)
+    }
+}
+
+// Never overwrites an earlier export or a note the user wrote
+fn free_path(dir: &Path, name: &str) -> PathBuf {
+    let mut path = dir.join(format!("{}.md", name));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{} {}.md", name, n));
+        n += 1;
+    }
+    path
+}
+
+fn vault_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let settings = app.state::<SettingsManager>().get();
+    let vault = settings
+        .vault_path
+        .ok_or_else(|| "Choose a notes vault in settings first".to_string())?;
+    let vault = PathBuf::from(vault);
+    if !vault.is_dir() {
+        return Err(format!("Vault {} no longer exists", vault.display()));
+    }
+    let folder = settings
+        .vault_folder
+        .unwrap_or_else(|| DEFAULT_FOLDER.to_string());
+    Ok(vault.join(folder.trim_matches('/')))
+}
+
+fn first_line(text: &str) -> String {
+    text.lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+async fn session_note(sidecar: &SidecarManager, session_id: &str) -> Result<Note, String> {
+    let transcript::Transcript { title, messages } =
+        transcript::session(sidecar, session_id).await?;
+    let first = messages
+        .first()
+        .ok_or_else(|| "The session has no messages to export".to_string())?;
+
+    let mut body = String::new();
+    for message in &messages {
+        let heading = if message.role == "user" { "You" } else { "Mix" };
+        body.push_str(&format!("## {}\n\n{}\n\n", heading, message.content.trim()));
+    }
+    let title = if title.trim().is_empty() {
+        first_line(&first.content)
+    } else {
+        title
+    };
+    Ok(Note {
+        title,
+        date: date(first.created_at),
+        model: messages
+            .iter()
+            .rev()
+            .map(|message| message.model.clone())
+            .find(|model| !model.is_empty()),
+        session_id: Some(session_id.to_string()),
+        tags: vec![],
+        body,
+    })
+}
+
+async fn message_note(sidecar: &SidecarManager, message_id: &str) -> Result<Note, String> {
+    let message = transcript::message(sidecar, message_id).await?;
+    if message.content.trim().is_empty() {
+        return Err("The message has no text to export".to_string());
+    }
+    Ok(Note {
+        title: first_line(&message.content),
+        date: date(message.created_at),
+        model: Some(message.model).filter(|model| !model.is_empty()),
+        session_id: Some(message.session_id),
+        tags: vec![],
+        body: message.content,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_vault_path(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    path: Option<String>,
+    folder: Option<String>,
+) -> Result<(), AppError> {
+    if let Some(path) = &path {
+        if !validation::existing_path(&app, "path", path)?.is_dir() {
+            return Err(AppError::InvalidInput(format!("{} is not a folder", path)));
+        }
+    }
+    if folder
+        .as_deref()
+        .is_some_and(|folder| folder.contains(".."))
+    {
+        return Err(AppError::InvalidInput(
+            "The vault folder must stay inside the vault".to_string(),
+        ));
+    }
+    settings_manager.update(|settings| {
+        settings.vault_path = path;
+        settings.vault_folder = folder.filter(|folder| !folder.trim().is_empty());
+    })?;
+    Ok(())
+}
+
+fn write_note(dir: &Path, note: &Note) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = free_path(dir, &note_name(note));
+    fs::write(&path, note.render())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+// Writes one reply (`message_id`) or a whole session as a note and returns
+// the note's path
+#[tauri::command]
+#[specta::specta]
+pub async fn export_to_vault(
+    app: AppHandle,
+    session_id: Option<String>,
+    message_id: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<String, AppError> {
+    if let Some(message_id) = &message_id {
+        validation::uuid("messageId", message_id)?;
+    }
+    if let Some(session_id) = &session_id {
+        validation::uuid("sessionId", session_id)?;
+    }
+    let dir = vault_dir(&app)?;
+    let sidecar = app.state::<Arc<SidecarManager>>().inner().clone();
+    let mut note = match (message_id, session_id) {
+        (Some(message_id), _) => message_note(&sidecar, &message_id).await?,
+        (None, Some(session_id)) => session_note(&sidecar, &session_id).await?,
+        (None, None) => return Err(AppError::InvalidInput("Nothing to export".to_string())),
+    };
+    note.tags = self::tags(tags);
+
+    let path = tauri::async_runtime::spawn_blocking(move || write_note(&dir, &note))
+        .await
+        .map_err(|e| format!("Failed to write note: {}", e))??;
+    log::info!("Exported note to {}", path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+// Notes keeps its body as HTML; the Markdown is kept as plain lines rather
+// than rendered, so nothing the agent wrote is lost
+#[cfg(target_os = "macos")]
+fn notes_html(title: &str, body: &str) -> String {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let mut html = format!("<h1>{}</h1>", escape(title));
+    for line in body.trim_end().lines() {
+        if line.trim().is_empty() {
+            html.push_str("<div><br></div>");
+        } else {
+            html.push_str(&format!("<div>{}</div>", escape(line)));
+        }
+    }
+    html
+}
+
+// Arguments go through argv, so nothing has to be quoted for AppleScript.
+// The folder is created in the default account when missing.
+#[cfg(target_os = "macos")]
+const CREATE_NOTE_SCRIPT: &str = r#"on run argv
+    set {noteTitle, noteBody, folderName} to argv
+    tell application "Notes"
+        set targetAccount to default account
+        if folderName is "" then
+            set targetFolder to default folder of targetAccount
+        else
+            if not (exists folder folderName of targetAccount) then
+                make new folder at targetAccount with properties {name:folderName}
+            end if
+            set targetFolder to folder folderName of targetAccount
+        end if
+        make new note at targetFolder with properties {name:noteTitle, body:noteBody}
+        return name of targetFolder
+    end tell
+end run"#;
+
+// Files the note in Apple Notes and says where it went. The first call shows
+// the system prompt for controlling Notes.
+#[cfg(target_os = "macos")]
+async fn file_note(
+    _app: &AppHandle,
+    title: String,
+    body: String,
+    folder: Option<String>,
+    _session_id: Option<String>,
+) -> Result<String, String> {
+    let html = notes_html(&title, &body);
+    let output = tokio::process::Command::new("osascript")
+        .args(["-e", CREATE_NOTE_SCRIPT, &title, &html])
+        .arg(folder.unwrap_or_default())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Notes refused the note: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let folder = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(format!("Added \"{}\" to Notes in {}", title, folder))
+}
+
+// Elsewhere the note is a Markdown file in the vault, in `folder` below the
+// vault's export folder when given
+#[cfg(not(target_os = "macos"))]
+async fn file_note(
+    app: &AppHandle,
+    title: String,
+    body: String,
+    folder: Option<String>,
+    session_id: Option<String>,
+) -> Result<String, String> {
+    let mut dir = vault_dir(app)?;
+    if let Some(folder) = folder {
+        dir = dir.join(folder.trim_matches('/'));
+    }
+    let note = Note {
+        title,
+        date: Local::now(),
+        model: None,
+        session_id,
+        tags: tags(None),
+        body,
+    };
+    let path = tauri::async_runtime::spawn_blocking(move || write_note(&dir, &note))
+        .await
+        .map_err(|e| format!("Failed to write note: {}", e))??;
+    Ok(format!("Saved the note to {}", path.display()))
+}
+
+async fn create_note(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let title = agent_tools::string_arg(&arguments, "title")?;
+    let title = title.trim().to_string();
+    let body = agent_tools::string_arg(&arguments, "body")?;
+    let folder = arguments
+        .get("folder")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|folder| !folder.is_empty())
+        .map(str::to_string);
+    let session_id = arguments
+        .get("session_id")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    if title.is_empty() {
+        return Err("The note needs a title".to_string());
+    }
+    if body.len() > MAX_NOTE_BYTES {
+        return Err(format!(
+            "The note is {} bytes, over the limit of {}",
+            body.len(),
+            MAX_NOTE_BYTES
+        ));
+    }
+    if folder
+        .as_deref()
+        .is_some_and(|folder| folder.contains("..") || Path::new(folder).is_absolute())
+    {
+        return Err("The folder must stay inside the notes".to_string());
+    }
+
+    let detail = match &folder {
+        Some(folder) => format!("Create the note \"{}\" in {}", title, folder),
+        None => format!("Create the note \"{}\"", title),
+    };
+    approvals::require_approval(&app, NOTE_APPROVAL_ACTION, &detail)
+        .await
+        .map_err(|_| "The user declined to create the note".to_string())?;
+
+    progress.send(&format!("Creating the note {}", title));
+    let filed = file_note(&app, title, body, folder, session_id).await?;
+    log::info!("{}", filed);
+    Ok(filed)
+}
+
+pub fn register_tools() {
+    agent_tools::register(Tool {
+        name: "create_note",
+        description: "File a note in the user's notes: Apple Notes on macOS, a Markdown file in \
+            their notes vault elsewhere. `body` is Markdown; `folder` is a folder name, created \
+            if missing. The user is asked first.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": "string" },
+                "body": { "type": "string" },
+                "folder": { "type": "string" },
+                "session_id": { "type": "string" }
+            },
+            "required": ["title", "body"]
+        }),
+        handler: agent_tools::handler(create_note),
+    });
+}
@@ -0,0 +1,81 @@
+use crate::error::AppError;
+use crate::platform::{Native, Platform};
+use crate::settings::SettingsManager;
+use crate::sidecar::SidecarManager;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Manager, State};
+
+// The first prompt after launch pays for the provider connection and, with
+// local models, for loading the model. A tiny request right after the sidecar
+// is ready moves that cost off the user's first prompt.
+const WARMUP_PROMPT: &str = "Reply with OK.";
+
+// How the app was launched, since a background daemon may not want to spend
+// tokens on a prompt nobody is waiting for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    App,
+    Daemon,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct WarmupSettings {
+    pub app: bool,
+    pub daemon: bool,
+    // Warming up spins up the provider or local model for nothing on battery
+    pub on_battery: bool,
+}
+
+impl Default for WarmupSettings {
+    fn default() -> Self {
+        Self {
+            app: true,
+            daemon: false,
+            on_battery: false,
+        }
+    }
+}
+
+// Sends the warm-up request if `profile` has it enabled. Runs after the
+// sidecar answered its readiness probe.
+pub async fn run(app: &AppHandle, profile: Profile) {
+    let settings = app.state::<SettingsManager>().get().warmup;
+    let enabled = match profile {
+        Profile::App => settings.app,
+        Profile::Daemon => settings.daemon,
+    };
+    if !enabled {
+        return;
+    }
+    if !settings.on_battery {
+        let battery = tauri::async_runtime::spawn_blocking(Native::on_battery)
+            .await
+            .unwrap_or(false);
+        if battery {
+            log::info!("Skipping sidecar warm-up on battery power");
+            return;
+        }
+    }
+
+    let start = Instant::now();
+    let manager = app.state::<Arc<SidecarManager>>().inner().clone();
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    match manager.warm_up(WARMUP_PROMPT, &trace_id).await {
+        Ok(_) => log::info!("Sidecar warmed up in {:?}", start.elapsed()),
+        Err(e) => log::warn!("Sidecar warm-up failed: {}", e),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_warmup(
+    settings_manager: State<'_, SettingsManager>,
+    settings: WarmupSettings,
+) -> Result<(), AppError> {
+    settings_manager.update(|current| current.warmup = settings)?;
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::workspace;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use serde::Serialize;
+use specta::Type;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub const FILES_CHANGED_EVENT: &str = "workspace://changed";
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+// Dropping the debouncer stops its watcher thread
+static WATCHER: Mutex<Option<Debouncer<RecommendedWatcher, RecommendedCache>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FileChange {
+    pub kind: ChangeKind,
+    // Relative to the workspace root
+    pub path: String,
+}
+
+fn gitignore(root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    if let Some(e) = builder.add(root.join(".gitignore")) {
+        log::debug!("No usable .gitignore in {}: {}", root.display(), e);
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+fn is_ignored(root: &Path, ignore: &Gitignore, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return true;
+    };
+    relative
+        .components()
+        .any(|component| component == Component::Normal(".git".as_ref()))
+        || ignore
+            .matched_path_or_any_parents(relative, path.is_dir())
+            .is_ignore()
+}
+
+// Renames surface as a delete of the old path and a create of the new one
+fn classify(kind: &EventKind, paths: &[PathBuf]) -> Vec<(ChangeKind, PathBuf)> {
+    let single = |change: ChangeKind| {
+        paths
+            .iter()
+            .map(|path| (change, path.clone()))
+            .collect::<Vec<_>>()
+    };
+    match kind {
+        EventKind::Create(_) => single(ChangeKind::Created),
+        EventKind::Remove(_) => single(ChangeKind::Deleted),
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => single(ChangeKind::Deleted),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => single(ChangeKind::Created),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => vec![
+            (ChangeKind::Deleted, paths[0].clone()),
+            (ChangeKind::Created, paths[1].clone()),
+        ],
+        EventKind::Modify(ModifyKind::Metadata(_)) | EventKind::Access(_) => vec![],
+        EventKind::Modify(_) => single(ChangeKind::Modified),
+        _ => vec![],
+    }
+}
+
+fn handle_events(app: &AppHandle, root: &Path, ignore: &Gitignore, result: DebounceEventResult) {
+    let events = match result {
+        Ok(events) => events,
+        Err(errors) => {
+            for e in errors {
+                log::warn!("Workspace watcher error: {}", e);
+            }
+            return;
+        }
+    };
+
+    let mut changes: Vec<FileChange> = Vec::new();
+    for event in events {
+        for (kind, path) in classify(&event.kind, &event.paths) {
+            if is_ignored(root, ignore, &path) {
+                continue;
+            }
+            let change = FileChange {
+                kind,
+                path: path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+            };
+            if !changes
+                .iter()
+                .any(|seen| seen.kind == change.kind && seen.path == change.path)
+            {
+                changes.push(change);
+            }
+        }
+    }
+
+    if !changes.is_empty() {
+        let _ = app.emit(FILES_CHANGED_EVENT, &changes);
+    }
+}
+
+// (Re)starts watching the active workspace, replacing any previous watcher
+pub fn watch(app: &AppHandle) {
+    let mut watcher = WATCHER.lock().unwrap();
+    *watcher = None;
+
+    let Some(root) = workspace::current(app) else {
+        return;
+    };
+    let ignore = gitignore(&root);
+    let handler_app = app.clone();
+    let handler_root = root.clone();
+    let debouncer = new_debouncer(DEBOUNCE_TIMEOUT, None, move |result| {
+        handle_events(&handler_app, &handler_root, &ignore, result)
+    })
+    .and_then(|mut debouncer| {
+        debouncer.watch(&root, RecursiveMode::Recursive)?;
+        Ok(debouncer)
+    });
+
+    match debouncer {
+        Ok(debouncer) => {
+            log::info!("Watching workspace {}", root.display());
+            *watcher = Some(debouncer);
+        }
+        Err(e) => log::error!("Failed to watch workspace {}: {}", root.display(), e),
+    }
+}
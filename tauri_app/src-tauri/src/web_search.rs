@@ -0,0 +1,424 @@
+use crate::agent_tools::{self, Progress, Tool};
+use crate::error::AppError;
+use crate::http_client;
+use crate::link_preview::decode_entities;
+use crate::ring_buffer::RingBuffer;
+use crate::secrets;
+use crate::settings::SettingsManager;
+use regex::Regex;
+use reqwest::{header, Method, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_QUERY_CHARS: usize = 400;
+const MAX_RESULTS: u8 = 20;
+const CACHED_SEARCHES: usize = 100;
+const CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+const USER_AGENT: &str = concat!("Mix/", env!("CARGO_PKG_VERSION"), " (web search)");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchProvider {
+    Brave,
+    Tavily,
+    Serper,
+    Searxng,
+}
+
+// How a provider is called and where its response keeps each field
+struct Spec {
+    provider: SearchProvider,
+    name: &'static str,
+    // Secret the API key is stored under; none for self-hosted SearXNG
+    secret: Option<&'static str>,
+    // Shortest gap between two requests, below the free tiers' limits
+    min_interval: Duration,
+    // JSON pointer to the result array, then each result's fields
+    results: &'static str,
+    title: &'static str,
+    url: &'static str,
+    snippet: &'static str,
+    published: &'static str,
+}
+
+const PROVIDERS: [Spec; 4] = [
+    Spec {
+        provider: SearchProvider::Brave,
+        name: "Brave Search",
+        secret: Some("BRAVE_SEARCH_API_KEY"),
+        min_interval: Duration::from_millis(1100),
+        results: "/web/results",
+        title: "title",
+        url: "url",
+        snippet: "description",
+        published: "page_age",
+    },
+    Spec {
+        provider: SearchProvider::Tavily,
+        name: "Tavily",
+        secret: Some("TAVILY_API_KEY"),
+        min_interval: Duration::from_millis(500),
+        results: "/results",
+        title: "title",
+        url: "url",
+        snippet: "content",
+        published: "published_date",
+    },
+    Spec {
+        provider: SearchProvider::Serper,
+        name: "Serper (Google)",
+        secret: Some("SERPER_API_KEY"),
+        min_interval: Duration::from_millis(200),
+        results: "/organic",
+        title: "title",
+        url: "link",
+        snippet: "snippet",
+        published: "date",
+    },
+    Spec {
+        provider: SearchProvider::Searxng,
+        name: "SearXNG",
+        secret: None,
+        min_interval: Duration::from_secs(1),
+        results: "/results",
+        title: "title",
+        url: "url",
+        snippet: "content",
+        published: "publishedDate",
+    },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct WebSearchSettings {
+    pub provider: SearchProvider,
+    // Base URL of the SearXNG instance, which needs JSON output enabled
+    pub searxng_url: Option<String>,
+    // Results per search unless the agent asks for fewer
+    pub max_results: u8,
+}
+
+impl Default for WebSearchSettings {
+    fn default() -> Self {
+        Self {
+            provider: SearchProvider::Brave,
+            searxng_url: None,
+            max_results: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+    // As the provider gives it, e.g. "2024-05-01" or "3 days ago"
+    pub published: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SearchProviderInfo {
+    pub provider: SearchProvider,
+    pub name: &'static str,
+    // Secret to store the API key under with `set_secret`
+    pub secret: Option<&'static str>,
+    // Whether it has what it needs, a key or an instance URL
+    pub configured: bool,
+}
+
+// (provider, query, count, searched at, results)
+type CachedSearch = (SearchProvider, String, u8, Instant, Vec<SearchResult>);
+
+static CACHE: Mutex<RingBuffer<CachedSearch>> =
+    Mutex::new(RingBuffer::new("web_searches", CACHED_SEARCHES));
+// The earliest each provider may be called again
+static NEXT_SLOT: LazyLock<Mutex<HashMap<SearchProvider, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+fn spec(provider: SearchProvider) -> &'static Spec {
+    PROVIDERS
+        .iter()
+        .find(|spec| spec.provider == provider)
+        .unwrap()
+}
+
+// Takes the provider's next free slot and waits for it, so concurrent
+// searches queue up instead of tripping the provider's limit
+async fn wait_turn(spec: &Spec) {
+    let wait = {
+        let mut slots = NEXT_SLOT.lock().unwrap();
+        let now = Instant::now();
+        let slot = slots.get(&spec.provider).copied().unwrap_or(now).max(now);
+        slots.insert(spec.provider, slot + spec.min_interval);
+        slot - now
+    };
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+// Snippets come with highlighting markup and entities
+fn plain(text: &str) -> String {
+    decode_entities(&TAG.replace_all(text, ""))
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn normalize(spec: &Spec, response: &Value, count: u8) -> Vec<SearchResult> {
+    let field = |result: &Value, key: &str| {
+        result
+            .get(key)
+            .and_then(Value::as_str)
+            .map(plain)
+            .filter(|value| !value.is_empty())
+    };
+    response
+        .pointer(spec.results)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|result| {
+            Some(SearchResult {
+                url: field(result, spec.url)?,
+                title: field(result, spec.title).unwrap_or_default(),
+                snippet: field(result, spec.snippet).unwrap_or_default(),
+                published: field(result, spec.published),
+            })
+        })
+        .take(count as usize)
+        .collect()
+}
+
+fn request(
+    spec: &Spec,
+    settings: &WebSearchSettings,
+    query: &str,
+    count: u8,
+) -> Result<reqwest::RequestBuilder, String> {
+    let key = match spec.secret {
+        Some(secret) => secrets::get_secret(secret)?.ok_or_else(|| {
+            format!(
+                "{} needs an API key stored as the {} secret",
+                spec.name, secret
+            )
+        })?,
+        None => String::new(),
+    };
+    let client = http_client::shared();
+    let request = match spec.provider {
+        SearchProvider::Brave => client
+            .get("https://api.search.brave.com/res/v1/web/search")
+            .query(&[("q", query), ("count", &count.to_string())])
+            .header("X-Subscription-Token", key),
+        SearchProvider::Tavily => client
+            .post("https://api.tavily.com/search")
+            .bearer_auth(key)
+            .json(&json!({ "query": query, "max_results": count })),
+        SearchProvider::Serper => client
+            .post("https://google.serper.dev/search")
+            .header("X-API-KEY", key)
+            .json(&json!({ "q": query, "num": count })),
+        SearchProvider::Searxng => {
+            let base = settings
+                .searxng_url
+                .as_deref()
+                .ok_or("SearXNG needs the URL of an instance")?;
+            let url = Url::parse(base)
+                .and_then(|base| base.join("search"))
+                .map_err(|e| format!("Invalid SearXNG URL {}: {}", base, e))?;
+            client
+                .request(Method::GET, url)
+                .query(&[("q", query), ("format", "json")])
+        }
+    };
+    Ok(request
+        .timeout(REQUEST_TIMEOUT)
+        .header(header::USER_AGENT, USER_AGENT))
+}
+
+// Results for `query` from the configured provider, cached for a while so
+// an agent repeating itself doesn't spend the user's quota
+pub async fn search(
+    app: &AppHandle,
+    query: &str,
+    count: Option<u8>,
+) -> Result<Vec<SearchResult>, String> {
+    let query = query.trim();
+    if query.is_empty() || query.chars().count() > MAX_QUERY_CHARS {
+        return Err(format!(
+            "The query must have 1 to {} characters",
+            MAX_QUERY_CHARS
+        ));
+    }
+    let settings = app.state::<SettingsManager>().get().web_search;
+    let count = count.unwrap_or(settings.max_results).clamp(1, MAX_RESULTS);
+    let spec = spec(settings.provider);
+
+    let cached = CACHE
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|(provider, cached_query, cached_count, searched_at, _)| {
+            *provider == spec.provider
+                && cached_query == query
+                && *cached_count == count
+                && searched_at.elapsed() < CACHE_TTL
+        })
+        .map(|(.., results)| results.clone());
+    if let Some(results) = cached {
+        return Ok(results);
+    }
+
+    let request = request(spec, &settings, query, count)?;
+    wait_turn(spec).await;
+    let response = request
+        .send()
+        .await
+        .map_err(|e| secrets::redact(&format!("{} request failed: {}", spec.name, e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(match status.as_u16() {
+            401 | 403 => format!("{} rejected the API key ({})", spec.name, status),
+            429 => format!("{} is rate limiting searches, try again later", spec.name),
+            _ => format!("{} returned {}", spec.name, status),
+        });
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("{} sent an unreadable response: {}", spec.name, e))?;
+    let results = normalize(spec, &body, count);
+    log::info!(
+        "{} found {} results for a web search",
+        spec.name,
+        results.len()
+    );
+    CACHE.lock().unwrap().push((
+        spec.provider,
+        query.to_string(),
+        count,
+        Instant::now(),
+        results.clone(),
+    ));
+    Ok(results)
+}
+
+async fn web_search(
+    app: AppHandle,
+    arguments: Value,
+    progress: Progress,
+) -> Result<String, String> {
+    let query = agent_tools::string_arg(&arguments, "query")?;
+    let count = arguments
+        .get("count")
+        .and_then(Value::as_u64)
+        .map(|count| count.min(MAX_RESULTS as u64) as u8);
+    progress.send(&format!("Searching for {}", query));
+    let results = search(&app, &query, count).await?;
+    if results.is_empty() {
+        return Ok(format!("No results for {:?}", query));
+    }
+    Ok(results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            let mut entry = format!("{}. {}\n   {}", i + 1, result.title, result.url);
+            if let Some(published) = &result.published {
+                entry.push_str(&format!("\n   Published: {}", published));
+            }
+            if !result.snippet.is_empty() {
+                entry.push_str(&format!("\n   {}", result.snippet));
+            }
+            entry
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+pub fn register_tools() {
+    agent_tools::register(Tool {
+        name: "web_search",
+        description: "Search the web and get back titles, URLs and snippets of the top \
+            results. Read a result in full with fetch_url.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "count": { "type": "integer", "minimum": 1, "maximum": MAX_RESULTS }
+            },
+            "required": ["query"]
+        }),
+        handler: agent_tools::handler(web_search),
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_search_providers(
+    settings_manager: State<'_, SettingsManager>,
+) -> Vec<SearchProviderInfo> {
+    let settings = settings_manager.get().web_search;
+    PROVIDERS
+        .iter()
+        .map(|spec| SearchProviderInfo {
+            provider: spec.provider,
+            name: spec.name,
+            secret: spec.secret,
+            configured: match spec.secret {
+                Some(secret) => secrets::get_secret(secret).is_ok_and(|key| key.is_some()),
+                None => settings.searxng_url.is_some(),
+            },
+        })
+        .collect()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_web_search_settings(settings_manager: State<'_, SettingsManager>) -> WebSearchSettings {
+    settings_manager.get().web_search
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_web_search_settings(
+    settings_manager: State<'_, SettingsManager>,
+    web_search: WebSearchSettings,
+) -> Result<(), AppError> {
+    if !(1..=MAX_RESULTS).contains(&web_search.max_results) {
+        return Err(AppError::InvalidField {
+            field: "max_results".to_string(),
+            message: format!("must be between 1 and {}", MAX_RESULTS),
+        });
+    }
+    if let Some(url) = &web_search.searxng_url {
+        if !Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https")) {
+            return Err(AppError::InvalidField {
+                field: "searxng_url".to_string(),
+                message: "must be an http or https URL".to_string(),
+            });
+        }
+    }
+    settings_manager.update(|settings| settings.web_search = web_search)?;
+    Ok(())
+}
+
+// Runs a search from the webview, e.g. to check a provider is set up
+#[tauri::command]
+#[specta::specta]
+pub async fn search_web(
+    app: AppHandle,
+    query: String,
+    count: Option<u8>,
+) -> Result<Vec<SearchResult>, AppError> {
+    Ok(search(&app, &query, count).await?)
+}
@@ -0,0 +1,352 @@
+use crate::actions::{self, Action, Entry};
+use crate::delivery::{self, DeliveryMessage};
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use crate::{i18n, local_http, providers, secrets, validation};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+use tokio::net::TcpStream;
+
+// Incoming webhooks: `POST /hooks/<id>` renders the hook's prompt template
+// with the request body and sends it to the agent; the reply is shown as a
+// notification, emitted as `webhook://result` and posted to the hook's
+// delivery targets. The listener is loopback
+// only, so outside services (e.g. CI) reach it through a tunnel.
+pub const WEBHOOK_RESULT_EVENT: &str = "webhook://result";
+const DEFAULT_WEBHOOK_PORT: u16 = 7334;
+const SECRET_PREFIX: &str = "webhook_secret_";
+// GitHub-style signature; a bearer token with the raw secret also works
+const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+// Longest request body pasted into a prompt
+const MAX_TEMPLATE_BODY_CHARS: usize = 16 * 1024;
+const NOTIFICATION_CHARS: usize = 200;
+const JSON: &str = "application/json";
+
+static LISTENER: local_http::Listener = local_http::Listener::new("Webhooks");
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WebhookConfig {
+    pub id: String,
+    pub name: String,
+    // `{{body}}` is the raw request body, `{{a.b}}` a field of a JSON body
+    pub prompt_template: String,
+    // Continue this session instead of starting a new one per call
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    // Ids of `delivery` targets that also receive the result
+    #[serde(default)]
+    pub deliver_to: Vec<String>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct WebhookInfo {
+    #[serde(flatten)]
+    pub config: WebhookConfig,
+    pub url: String,
+    // Only returned by `create_webhook`; stored in the keychain afterwards
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WebhookResult {
+    pub webhook_id: String,
+    pub name: String,
+    pub trace_id: String,
+    pub ok: bool,
+    pub text: Option<String>,
+    pub error: Option<String>,
+}
+
+fn port(app: &AppHandle) -> u16 {
+    app.state::<SettingsManager>()
+        .get()
+        .webhook_port
+        .unwrap_or(DEFAULT_WEBHOOK_PORT)
+}
+
+fn url(app: &AppHandle, id: &str) -> String {
+    format!("http://127.0.0.1:{}/hooks/{}", port(app), id)
+}
+
+fn info(app: &AppHandle, config: WebhookConfig, secret: Option<String>) -> WebhookInfo {
+    WebhookInfo {
+        url: url(app, &config.id),
+        config,
+        secret,
+    }
+}
+
+fn is_authorized(request: &local_http::Request, secret: &str) -> bool {
+    if let Some(signature) = request
+        .headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.strip_prefix("sha256="))
+    {
+        let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+            return false;
+        };
+        mac.update(&request.body);
+        let expected: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        return local_http::constant_time_eq(
+            expected.as_bytes(),
+            signature.to_ascii_lowercase().as_bytes(),
+        );
+    }
+    request.has_bearer_token(secret)
+}
+
+fn render_template(template: &str, body: &[u8]) -> String {
+    let text: String = String::from_utf8_lossy(body)
+        .chars()
+        .take(MAX_TEMPLATE_BODY_CHARS)
+        .collect();
+    let payload: Option<Value> = serde_json::from_slice(body).ok();
+
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let key = rest[start + 2..start + end].trim();
+        if key == "body" {
+            rendered.push_str(&text);
+        } else {
+            let pointer = format!("/{}", key.replace('.', "/"));
+            match payload
+                .as_ref()
+                .and_then(|payload| payload.pointer(&pointer))
+            {
+                Some(Value::String(value)) => rendered.push_str(value),
+                Some(value) => rendered.push_str(&value.to_string()),
+                None => {}
+            }
+        }
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn notify(app: &AppHandle, result: &WebhookResult) {
+    let body = match (&result.text, &result.error) {
+        (Some(text), _) => text.chars().take(NOTIFICATION_CHARS).collect(),
+        (None, Some(error)) => i18n::t_args(app, "webhook.failed", &[("error", error)]),
+        (None, None) => String::new(),
+    };
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(&result.name)
+        .body(body)
+        .show()
+    {
+        log::warn!("Failed to show webhook notification: {}", e);
+    }
+}
+
+async fn run(app: AppHandle, webhook: WebhookConfig, body: Vec<u8>, trace_id: String) {
+    let prompt = render_template(&webhook.prompt_template, &body);
+    // Without a configured session every call is a one-shot prompt
+    let session_id = webhook.session_id.clone().unwrap_or_default();
+    let provider = providers::provider(&app.state::<SettingsManager>(), &session_id);
+    let reply = providers::send(&app, &session_id, provider, &prompt, &trace_id).await;
+    if let Err(e) = &reply {
+        log::error!(
+            "[trace {}] Webhook {} failed: {}",
+            trace_id,
+            webhook.name,
+            e
+        );
+    }
+
+    let result = WebhookResult {
+        webhook_id: webhook.id,
+        name: webhook.name,
+        trace_id,
+        ok: reply.is_ok(),
+        error: reply.as_ref().err().map(ToString::to_string),
+        text: reply.ok(),
+    };
+    notify(&app, &result);
+    delivery::deliver_all(
+        &app,
+        &webhook.deliver_to,
+        DeliveryMessage {
+            title: result.name.clone(),
+            trace_id: result.trace_id.clone(),
+            text: result
+                .text
+                .clone()
+                .or(result.error.clone())
+                .unwrap_or_default(),
+            ok: result.ok,
+        },
+    );
+    let _ = app.emit(WEBHOOK_RESULT_EVENT, result);
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream) {
+    let request = match local_http::read_request(&mut stream, MAX_BODY_BYTES).await {
+        Ok(request) => request,
+        Err(Some(status)) => return local_http::respond(&mut stream, status, JSON, "").await,
+        Err(None) => return,
+    };
+
+    let webhook = request.path.strip_prefix("/hooks/").and_then(|id| {
+        app.state::<SettingsManager>()
+            .get()
+            .webhooks
+            .into_iter()
+            .find(|webhook| webhook.id == id && webhook.enabled)
+    });
+    let Some(webhook) = webhook.filter(|_| request.method == "POST") else {
+        let body = json!({ "error": "Unknown webhook" }).to_string();
+        return local_http::respond(&mut stream, "404 Not Found", JSON, &body).await;
+    };
+    let secret = secrets::get_secret(&format!("{}{}", SECRET_PREFIX, webhook.id))
+        .ok()
+        .flatten();
+    let authorized = secret.is_some_and(|secret| is_authorized(&request, &secret));
+    if !authorized || request.is_browser() {
+        let body = json!({ "error": "Invalid signature" }).to_string();
+        return local_http::respond(&mut stream, "401 Unauthorized", JSON, &body).await;
+    }
+
+    // Prompts take longer than most senders wait, so answer right away
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    log::info!("[trace {}] Webhook {} triggered", trace_id, webhook.name);
+    let body = json!({ "accepted": true, "trace_id": trace_id }).to_string();
+    local_http::respond(&mut stream, "202 Accepted", JSON, &body).await;
+    run(app, webhook, request.body, trace_id).await;
+}
+
+// Starts the listener if it is enabled in settings, replacing a running one
+pub fn start(app: &AppHandle) {
+    if !app.state::<SettingsManager>().get().webhooks_enabled {
+        return LISTENER.stop();
+    }
+    let app = app.clone();
+    LISTENER.start(port(&app), move |stream| {
+        handle_connection(app.clone(), stream)
+    });
+}
+
+// Each webhook's prompt template can also be run by hand from the palette,
+// with an empty body
+fn template_actions(app: &AppHandle) -> Vec<Entry> {
+    app.state::<SettingsManager>()
+        .get()
+        .webhooks
+        .into_iter()
+        .map(|webhook| Entry {
+            action: Action::new(
+                &format!("template.{}", webhook.id),
+                &format!("Run template: {}", webhook.name),
+                "Templates",
+                &["webhook", "prompt"],
+            ),
+            handler: actions::handler(move |app| {
+                let webhook = webhook.clone();
+                async move {
+                    let trace_id = uuid::Uuid::new_v4().to_string();
+                    tauri::async_runtime::spawn(run(app, webhook, Vec::new(), trace_id));
+                    Ok(())
+                }
+            }),
+        })
+        .collect()
+}
+
+pub fn register_actions() {
+    actions::register_source(template_actions);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_webhooks(app: AppHandle) -> Vec<WebhookInfo> {
+    app.state::<SettingsManager>()
+        .get()
+        .webhooks
+        .into_iter()
+        .map(|config| info(&app, config, None))
+        .collect()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn create_webhook(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    name: String,
+    prompt_template: String,
+    session_id: Option<String>,
+    deliver_to: Option<Vec<String>>,
+) -> Result<WebhookInfo, AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Webhooks need a name".to_string()));
+    }
+    validation::prompt("promptTemplate", &prompt_template)?;
+    if let Some(session_id) = &session_id {
+        validation::uuid("sessionId", session_id)?;
+    }
+    let config = WebhookConfig {
+        id: uuid::Uuid::new_v4().simple().to_string(),
+        name,
+        prompt_template,
+        session_id,
+        enabled: true,
+        deliver_to: deliver_to.unwrap_or_default(),
+    };
+    let secret = uuid::Uuid::new_v4().simple().to_string();
+    secrets::set_secret(format!("{}{}", SECRET_PREFIX, config.id), secret.clone())?;
+    settings_manager.update(|settings| settings.webhooks.push(config.clone()))?;
+    Ok(info(&app, config, Some(secret)))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_webhook(
+    settings_manager: State<'_, SettingsManager>,
+    id: String,
+) -> Result<(), AppError> {
+    validation::uuid("id", &id)?;
+    settings_manager.update(|settings| settings.webhooks.retain(|webhook| webhook.id != id))?;
+    secrets::delete_secret(format!("{}{}", SECRET_PREFIX, id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_webhooks_enabled(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<(), AppError> {
+    settings_manager.update(|settings| {
+        settings.webhooks_enabled = enabled;
+        if port.is_some() {
+            settings.webhook_port = port;
+        }
+    })?;
+    start(&app);
+    Ok(())
+}
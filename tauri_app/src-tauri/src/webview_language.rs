@@ -0,0 +1,170 @@
+use crate::error::AppError;
+use crate::logging::LOG_VIEWER_LABEL;
+use crate::platform::{Native, Platform};
+use crate::settings::{Settings, SettingsManager};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::webview::WebviewWindowBuilder;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Runtime, State, WebviewWindow};
+
+pub const WEBVIEW_LANGUAGE_EVENT: &str = "webview://language";
+
+const MAX_LANGUAGES: usize = 8;
+// Windows the setting can be made for
+const WINDOW_LABELS: [&str; 2] = ["main", LOG_VIEWER_LABEL];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct WebviewLanguage {
+    // BCP 47 tags like "en-US" or "de", the first being the input locale; the
+    // OS's languages when empty
+    pub languages: Vec<String>,
+    pub spellcheck: bool,
+}
+
+impl Default for WebviewLanguage {
+    fn default() -> Self {
+        Self {
+            languages: Vec::new(),
+            spellcheck: true,
+        }
+    }
+}
+
+fn for_window(settings: &Settings, label: &str) -> WebviewLanguage {
+    settings
+        .webview_languages
+        .get(label)
+        .cloned()
+        .unwrap_or_default()
+}
+
+// What the web engine is set up with. Its dictionaries are shared by every
+// window, so they cover all windows' languages, the main window's first; each
+// page then marks its own language and turns spellcheck off where unwanted.
+fn engine_languages(settings: &Settings) -> (bool, Vec<String>) {
+    let mut windows: Vec<_> = settings.webview_languages.iter().collect();
+    windows.sort_by_key(|(label, _)| (label.as_str() != "main", label.as_str()));
+
+    let mut languages: Vec<String> = Vec::new();
+    for (_, language) in &windows {
+        for tag in &language.languages {
+            if !languages
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(tag))
+            {
+                languages.push(tag.clone());
+            }
+        }
+    }
+    let spellcheck = WINDOW_LABELS
+        .iter()
+        .any(|label| for_window(settings, label).spellcheck);
+    (spellcheck, languages)
+}
+
+fn is_language_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+// Part of building a window: the languages the engine has to be started with
+pub fn configure<'a, R: Runtime, M: Manager<R>>(
+    app: &AppHandle,
+    builder: WebviewWindowBuilder<'a, R, M>,
+) -> WebviewWindowBuilder<'a, R, M> {
+    let (_, languages) = engine_languages(&app.state::<SettingsManager>().get());
+    Native::webview_language_builder(builder, &languages)
+}
+
+// Once a window is built, and again for every open window after a change
+pub fn apply(window: &WebviewWindow) {
+    let (spellcheck, languages) =
+        engine_languages(&window.app_handle().state::<SettingsManager>().get());
+    if let Err(e) = Native::set_spellcheck_languages(window, spellcheck, &languages) {
+        log::warn!(
+            "Failed to set spellcheck languages for {}: {}",
+            window.label(),
+            e
+        );
+    }
+}
+
+// Languages and spellcheck of the calling window, which it marks its page with
+#[tauri::command]
+#[specta::specta]
+pub fn get_webview_language(
+    window: WebviewWindow,
+    settings_manager: State<'_, SettingsManager>,
+) -> WebviewLanguage {
+    for_window(&settings_manager.get(), window.label())
+}
+
+// Saves the languages a window spellchecks prompts in. Open windows pick the
+// change up right away, except on Windows, where the engine's languages are
+// fixed when the app starts.
+#[tauri::command]
+#[specta::specta]
+pub fn set_webview_language(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+    label: String,
+    language: WebviewLanguage,
+) -> Result<WebviewLanguage, AppError> {
+    if !WINDOW_LABELS.contains(&label.as_str()) {
+        return Err(AppError::InvalidField {
+            field: "label".to_string(),
+            message: format!("must be one of {}", WINDOW_LABELS.join(", ")),
+        });
+    }
+    if language.languages.len() > MAX_LANGUAGES {
+        return Err(AppError::InvalidField {
+            field: "language.languages".to_string(),
+            message: format!("must have at most {} entries", MAX_LANGUAGES),
+        });
+    }
+    let mut languages: Vec<String> = Vec::new();
+    for tag in &language.languages {
+        let tag = tag.trim();
+        if !is_language_tag(tag) {
+            return Err(AppError::InvalidField {
+                field: "language.languages".to_string(),
+                message: format!("{:?} is not a language tag like \"en-US\"", tag),
+            });
+        }
+        if !languages
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(tag))
+        {
+            languages.push(tag.to_string());
+        }
+    }
+    let language = WebviewLanguage {
+        languages,
+        spellcheck: language.spellcheck,
+    };
+
+    settings_manager.update(|settings| {
+        if language == WebviewLanguage::default() {
+            settings.webview_languages.remove(&label);
+        } else {
+            settings
+                .webview_languages
+                .insert(label.clone(), language.clone());
+        }
+    })?;
+    for window in app.webview_windows().values() {
+        apply(window);
+    }
+    let _ = app.emit_to(
+        EventTarget::webview_window(&label),
+        WEBVIEW_LANGUAGE_EVENT,
+        &language,
+    );
+    Ok(language)
+}
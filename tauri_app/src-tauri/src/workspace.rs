@@ -0,0 +1,98 @@
+use crate::error::AppError;
+use crate::files::FsError;
+use crate::settings::{Settings, SettingsManager};
+use crate::sidecar::SidecarManager;
+use crate::{fs_access, recents, watcher};
+use serde::Serialize;
+use specta::Type;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+pub const WORKSPACE_SWITCHED_EVENT: &str = "workspace://switched";
+const MAX_RECENT_WORKSPACES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct WorkspaceInfo {
+    pub path: Option<String>,
+    pub recent: Vec<String>,
+}
+
+pub fn current(app: &AppHandle) -> Option<PathBuf> {
+    app.state::<SettingsManager>()
+        .get()
+        .workspace
+        .map(PathBuf::from)
+}
+
+pub fn root(app: &AppHandle) -> Result<PathBuf, FsError> {
+    current(app).ok_or(FsError::NoWorkspace)
+}
+
+// The sidecar runs with the workspace as its working directory, which scopes
+// its file tools and sessions to the project
+pub fn sidecar_args(app: &AppHandle) -> Vec<String> {
+    match current(app) {
+        Some(path) => vec!["--cwd".to_string(), path.to_string_lossy().into_owned()],
+        None => vec![],
+    }
+}
+
+pub fn info(settings: Settings) -> WorkspaceInfo {
+    WorkspaceInfo {
+        path: settings.workspace,
+        recent: settings.recent_workspaces,
+    }
+}
+
+// Makes `path` the active workspace, used by the picker and the tray's recent
+// projects menu
+pub async fn switch(app: &AppHandle, path: &str) -> Result<WorkspaceInfo, String> {
+    let settings_manager = app.state::<SettingsManager>();
+    let sidecar_manager = app.state::<Arc<SidecarManager>>();
+
+    // The same consent as any other folder, unless it was given before
+    let Some(path) = fs_access::grant(app, path).await? else {
+        return Err(format!("Access to {} was declined", path));
+    };
+    let workspace = path.to_string_lossy().into_owned();
+
+    settings_manager.update(|settings| {
+        settings.workspace = Some(workspace.clone());
+        settings
+            .recent_workspaces
+            .retain(|recent| *recent != workspace);
+        settings.recent_workspaces.insert(0, workspace.clone());
+        settings.recent_workspaces.truncate(MAX_RECENT_WORKSPACES);
+    })?;
+
+    // The sidecar only takes its working directory at launch
+    if sidecar_manager.is_running() {
+        sidecar_manager.stop_sidecar().await?;
+        sidecar_manager.start_sidecar(app).await?;
+    }
+
+    // Watching walks the whole tree
+    let watch_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || watcher::watch(&watch_app))
+        .await
+        .map_err(|e| format!("Failed to start watcher: {}", e))?;
+    recents::refresh_tray(app);
+
+    log::info!("Workspace set to {}", workspace);
+    let info = info(settings_manager.get());
+    let _ = app.emit(WORKSPACE_SWITCHED_EVENT, &info);
+    Ok(info)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_workspace(app: AppHandle, path: String) -> Result<WorkspaceInfo, AppError> {
+    Ok(switch(&app, &path).await?)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_workspace(settings_manager: State<'_, SettingsManager>) -> WorkspaceInfo {
+    info(settings_manager.get())
+}
@@ -5,14 +5,24 @@ import { useEffect } from 'react';
 import { QueryClient, QueryClientProvider } from '@tanstack/react-query';
 import { ThemeProvider } from '@/components/ui/theme-provider';
 import {ChatApp} from '@/components/chat-app';
+import { LogViewer } from '@/components/log-viewer';
+import { useAccessibilityPrefs } from '@/hooks/useAccessibilityPrefs';
 import { fetchVisibleApps } from '@/hooks/useOpenApps';
+import { useWebviewLanguage } from '@/hooks/useWebviewLanguage';
 
 
 const queryClient = new QueryClient();
 
+// Other windows load the same page and pick their view by hash, e.g. the log
+// viewer `open_log_viewer` opens at `#/logs`
+const route = window.location.hash.replace(/^#/, '');
+
 
 
 const App = () => {
+  useWebviewLanguage();
+  useAccessibilityPrefs();
+
   useEffect(() => {
     queryClient.prefetchQuery({
       queryKey: ['openApps'],
@@ -24,7 +34,7 @@ const App = () => {
     <QueryClientProvider client={queryClient}>
       <ThemeProvider defaultTheme="dark" storageKey="vite-ui-theme">
 
-        <ChatApp />
+        {route === '/logs' ? <LogViewer /> : <ChatApp />}
 
       </ThemeProvider>
     </QueryClientProvider>
@@ -0,0 +1,1289 @@
+// @ts-nocheck
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+async listAppsWithIcons() : Promise<Result<AppInfo[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_apps_with_icons") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startSidecar() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_sidecar") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopSidecar() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_sidecar") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sidecarStatus() : Promise<boolean> {
+    return await TAURI_INVOKE("sidecar_status");
+},
+async sidecarHealth() : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sidecar_health") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sidecarError() : Promise<string | null> {
+    return await TAURI_INVOKE("sidecar_error");
+},
+async getSidecarAuth() : Promise<SidecarAuth | null> {
+    return await TAURI_INVOKE("get_sidecar_auth");
+},
+async getSidecarPort() : Promise<number | null> {
+    return await TAURI_INVOKE("get_sidecar_port");
+},
+async sendPrompt(prompt: string) : Promise<Result<PromptResponse, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_prompt", { prompt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async streamPrompt(sessionId: string, prompt: string) : Promise<Result<PromptResponse, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stream_prompt", { sessionId, prompt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelPrompt(sessionId: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_prompt", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setSidecarTransport(transport: SidecarTransport) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sidecar_transport", { transport }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setSidecarPortFallback(enabled: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sidecar_port_fallback", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getLockStatus() : Promise<LockStatus> {
+    return await TAURI_INVOKE("get_lock_status");
+},
+async configureAppLock(enabled: boolean, passphrase: string | null, autoLockSecs: number | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("configure_app_lock", { enabled, passphrase, autoLockSecs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async lockApp() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lock_app") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async unlockWithPassphrase(passphrase: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlock_with_passphrase", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async unlockWithBiometrics() : Promise<boolean> {
+    return await TAURI_INVOKE("unlock_with_biometrics");
+},
+async requestPermission(action: string, detail: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_permission", { action, detail }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async respondPermissionRequest(id: string, approved: boolean, always: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("respond_permission_request", { id, approved, always }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listAlwaysAllowed() : Promise<string[]> {
+    return await TAURI_INVOKE("list_always_allowed");
+},
+async revokeAlwaysAllowed(action: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("revoke_always_allowed", { action }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async recordAgentAction(payload: AgentAction) : Promise<void> {
+    await TAURI_INVOKE("record_agent_action", { payload });
+},
+async getAuditLog(filter: AuditFilter | null) : Promise<Result<AuditLog, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_audit_log", { filter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setDebugMode(enabled: boolean) : Promise<void> {
+    await TAURI_INVOKE("set_debug_mode", { enabled });
+},
+async getDebugMode() : Promise<boolean> {
+    return await TAURI_INVOKE("get_debug_mode");
+},
+async exportDiagnostics(path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_diagnostics", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async requestFsAccess(path: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_fs_access", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listFsGrants() : Promise<string[]> {
+    return await TAURI_INVOKE("list_fs_grants");
+},
+async revokeFsGrant(path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("revoke_fs_grant", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHealthReport() : Promise<Result<HealthReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_health_report") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async enableHistoryEncryption() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enable_history_encryption") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryEncryption() : Promise<boolean> {
+    return await TAURI_INVOKE("get_history_encryption");
+},
+async getLogs(filter: LogFilter | null) : Promise<Result<LogEntry[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_logs", { filter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openLogViewer() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_log_viewer") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async reportFrontendError(payload: FrontendError) : Promise<void> {
+    await TAURI_INVOKE("report_frontend_error", { payload });
+},
+async getMetrics() : Promise<Metrics> {
+    return await TAURI_INVOKE("get_metrics");
+},
+async oauthLogin(config: OAuthConfig) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("oauth_login", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async oauthLogout(provider: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("oauth_logout", { provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listOauthProviders() : Promise<OAuthStatus[]> {
+    return await TAURI_INVOKE("list_oauth_providers");
+},
+async getOnboarding() : Promise<Result<OnboardingStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_onboarding") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async requestOsPermission(permission: Permission) : Promise<Result<PermissionStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_os_permission", { permission }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openPermissionSettings(permission: Permission) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_permission_settings", { permission }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async completeOnboarding() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("complete_onboarding") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAppState() : Promise<AppState> {
+    return await TAURI_INVOKE("get_app_state");
+},
+async setActiveSession(id: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_active_session", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listActions(query: string | null) : Promise<ActionMatch[]> {
+    return await TAURI_INVOKE("list_actions", { query });
+},
+async runAction(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_action", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async showPanel() : Promise<void> {
+    await TAURI_INVOKE("show_panel");
+},
+async hidePanel() : Promise<void> {
+    await TAURI_INVOKE("hide_panel");
+},
+async togglePanel() : Promise<void> {
+    await TAURI_INVOKE("toggle_panel");
+},
+async startRecording(path: string) : Promise<Result<RecordingStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_recording", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startReplay(path: string) : Promise<Result<RecordingStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_replay", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopRecording() : Promise<RecordingStatus> {
+    return await TAURI_INVOKE("stop_recording");
+},
+async getRecordingStatus() : Promise<RecordingStatus> {
+    return await TAURI_INVOKE("get_recording_status");
+},
+async setSecret(name: string, value: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_secret", { name, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSecretExists(name: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_secret_exists", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteSecret(name: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_secret", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async runSelfTest() : Promise<Result<SelfTestReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_self_test") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSettings() : Promise<Settings> {
+    return await TAURI_INVOKE("get_settings");
+},
+async previewTelemetry() : Promise<TelemetryPreview> {
+    return await TAURI_INVOKE("preview_telemetry");
+},
+async setTelemetryConsent(enabled: boolean, endpoint: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_telemetry_consent", { enabled, endpoint }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setWorkspace(path: string) : Promise<Result<WorkspaceInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_workspace", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getWorkspace() : Promise<WorkspaceInfo> {
+    return await TAURI_INVOKE("get_workspace");
+},
+async listWorkspaceTree(depth: number | null, globs: string[] | null) : Promise<Result<WorkspaceTree, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_workspace_tree", { depth, globs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readFile(path: string) : Promise<Result<string, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_file", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readFileChunk(path: string, offset: number | null, maxBytes: number | null) : Promise<Result<FileChunk, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_file_chunk", { path, offset, maxBytes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async writeFile(path: string, content: string, sessionId: string | null) : Promise<Result<null, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_file", { path, content, sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async diffFile(path: string, newContent: string) : Promise<Result<FileDiff, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diff_file", { path, newContent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async applyPatch(path: string, unifiedDiff: string, sessionId: string | null) : Promise<Result<PatchResult, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_patch", { path, unifiedDiff, sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async gitStatus() : Promise<Result<GitFileStatus[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async gitCurrentBranch() : Promise<Result<string | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_current_branch") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async gitDiff(path: string | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_diff", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async gitCommit(message: string, paths: string[]) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_commit", { message, paths }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchWorkspace(query: string, opts: SearchOptions | null) : Promise<Result<SearchResults, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_workspace", { query, opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getRecentProjects() : Promise<RecentProject[]> {
+    return await TAURI_INVOKE("get_recent_projects");
+},
+async pinProject(path: string, pinned: boolean) : Promise<Result<RecentProject[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("pin_project", { path, pinned }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async recordRecentFile(path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("record_recent_file", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getRecentFiles() : Promise<string[]> {
+    return await TAURI_INVOKE("get_recent_files");
+},
+async quickSwitchCandidates(query: string | null) : Promise<QuickSwitchCandidate[]> {
+    return await TAURI_INVOKE("quick_switch_candidates", { query });
+},
+async getScratchDir(sessionId: string) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_scratch_dir", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openScratchDir(sessionId: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_scratch_dir", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearScratchDir(sessionId: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_scratch_dir", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async snapshotWorkspace(dest: string) : Promise<Result<SnapshotInfo, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("snapshot_workspace", { dest }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async generatePreview(path: string, maxPx: number | null) : Promise<Result<Preview, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_preview", { path, maxPx }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listAppliedEdits(sessionId: string) : Promise<AppliedEdit[]> {
+    return await TAURI_INVOKE("list_applied_edits", { sessionId });
+},
+async undoLastEdit(sessionId: string) : Promise<Result<AppliedEdit | null, FsError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("undo_last_edit", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listMcpServers() : Promise<McpServerConfig[]> {
+    return await TAURI_INVOKE("list_mcp_servers");
+},
+async saveMcpServer(config: McpServerConfig) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_mcp_server", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeMcpServer(name: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_mcp_server", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listMcpTools(server: string) : Promise<Result<McpTool[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_mcp_tools", { server }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listMcpResources(server: string) : Promise<Result<McpResource[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_mcp_resources", { server }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMcpServerInfo() : Promise<Result<McpServerInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mcp_server_info") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setMcpServerEnabled(enabled: boolean, port: number | null) : Promise<Result<McpServerInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_mcp_server_enabled", { enabled, port }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async detectOllama() : Promise<Result<OllamaStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("detect_ollama") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listOllamaModels() : Promise<Result<OllamaModel[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_ollama_models") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearOllamaHistory(sessionId: string) : Promise<void> {
+    await TAURI_INVOKE("clear_ollama_history", { sessionId });
+},
+async getSessionProvider(sessionId: string) : Promise<Provider> {
+    return await TAURI_INVOKE("get_session_provider", { sessionId });
+},
+async setSessionProvider(sessionId: string, provider: Provider) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_session_provider", { sessionId, provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sendSessionPrompt(sessionId: string, prompt: string) : Promise<Result<PromptResponse, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_session_prompt", { sessionId, prompt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getOpenaiApiInfo() : Promise<Result<OpenAiApiInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_openai_api_info") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setOpenaiApiEnabled(enabled: boolean, port: number | null) : Promise<Result<OpenAiApiInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_openai_api_enabled", { enabled, port }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCompanionApiInfo() : Promise<Result<CompanionApiInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_companion_api_info") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setCompanionApiEnabled(enabled: boolean, port: number | null) : Promise<Result<CompanionApiInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_companion_api_enabled", { enabled, port }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async rotateCompanionApiToken() : Promise<Result<CompanionApiInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rotate_companion_api_token") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCodeBlocks(messageId: string) : Promise<Result<CodeBlock[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_code_blocks", { messageId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async copyCodeBlock(messageId: string, index: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("copy_code_block", { messageId, index }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveCodeBlock(messageId: string, index: number, path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_code_block", { messageId, index, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async highlightCode(code: string, lang: string | null, theme: string | null, format: HighlightFormat | null) : Promise<Result<HighlightedCode, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("highlight_code", { code, lang, theme, format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setHighlightTheme(theme: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_highlight_theme", { theme }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveGeneratedImage(messageId: string, source: ImageSource) : Promise<Result<SavedImage, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_generated_image", { messageId, source }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMessageImages(messageId: string) : Promise<Result<SavedImage[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_message_images", { messageId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async revealImage(path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reveal_image", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setWallpaper(path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_wallpaper", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listEditors() : Promise<EditorInfo[]> {
+    return await TAURI_INVOKE("list_editors");
+},
+async setPreferredEditor(editor: Editor | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_preferred_editor", { editor }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openInEditor(path: string, line: number | null, column: number | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_in_editor", { path, line, column }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async fetchLinkPreview(url: string) : Promise<Result<LinkPreview, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fetch_link_preview", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async shareContent(content: ShareContent, anchor: ShareAnchor | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("share_content", { content, anchor }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportSessionPdf(sessionId: string, path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_session_pdf", { sessionId, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getWebviewLanguage() : Promise<WebviewLanguage> {
+    return await TAURI_INVOKE("get_webview_language");
+},
+async setWebviewLanguage(label: string, language: WebviewLanguage) : Promise<Result<WebviewLanguage, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_webview_language", { label, language }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getLocale() : Promise<LocaleInfo> {
+    return await TAURI_INVOKE("get_locale");
+},
+async setLocale(locale: string | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_locale", { locale }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getTranslations(locale: string | null) : Promise<Result<Partial<{ [key in string]: string }>, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_translations", { locale }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAccessibilityPrefs() : Promise<AccessibilityPrefs> {
+    return await TAURI_INVOKE("get_accessibility_prefs");
+},
+async playSound(event: SoundEvent) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("play_sound", { event }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async previewSound(sound: Sound) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_sound", { sound }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setSoundSettings(sounds: SoundSettings) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sound_settings", { sounds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getShellToolSettings() : Promise<ShellToolSettings> {
+    return await TAURI_INVOKE("get_shell_tool_settings");
+},
+async setShellToolSettings(settings: ShellToolSettings) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_shell_tool_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listSearchProviders() : Promise<SearchProviderInfo[]> {
+    return await TAURI_INVOKE("list_search_providers");
+},
+async getWebSearchSettings() : Promise<WebSearchSettings> {
+    return await TAURI_INVOKE("get_web_search_settings");
+},
+async setWebSearchSettings(webSearch: WebSearchSettings) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_web_search_settings", { webSearch }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchWeb(query: string, count: number | null) : Promise<Result<SearchResult[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_web", { query, count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCodeEvalStatus() : Promise<CodeEvalStatus> {
+    return await TAURI_INVOKE("get_code_eval_status");
+},
+async listDatabaseConnections() : Promise<DatabaseConnection[]> {
+    return await TAURI_INVOKE("list_database_connections");
+},
+async saveDatabaseConnection(connection: DatabaseConnection, password: string | null) : Promise<Result<DatabaseConnection, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_database_connection", { connection, password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteDatabaseConnection(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_database_connection", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async testDatabaseConnection(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_database_connection", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async editImage(sessionId: string, edit: ImageEdit) : Promise<Result<EditedImage, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("edit_image", { sessionId, edit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listWebhooks() : Promise<WebhookInfo[]> {
+    return await TAURI_INVOKE("list_webhooks");
+},
+async createWebhook(name: string, promptTemplate: string, sessionId: string | null, deliverTo: string[] | null) : Promise<Result<WebhookInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_webhook", { name, promptTemplate, sessionId, deliverTo }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteWebhook(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_webhook", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setWebhooksEnabled(enabled: boolean, port: number | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_webhooks_enabled", { enabled, port }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listDeliveryTargets() : Promise<DeliveryTarget[]> {
+    return await TAURI_INVOKE("list_delivery_targets");
+},
+async saveDeliveryTarget(target: DeliveryTarget, url: string | null) : Promise<Result<DeliveryTarget, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_delivery_target", { target, url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteDeliveryTarget(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_delivery_target", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async testDeliveryTarget(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_delivery_target", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async installNativeMessagingHost(browser: string, extensionId: string) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_native_messaging_host", { browser, extensionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setVaultPath(path: string | null, folder: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_vault_path", { path, folder }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportToVault(sessionId: string | null, messageId: string | null, tags: string[] | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_to_vault", { sessionId, messageId, tags }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async calendarAccessStatus() : Promise<CalendarAccess> {
+    return await TAURI_INVOKE("calendar_access_status");
+},
+async previewCalendarItems(output: string | null, items: ScheduleItem[] | null) : Promise<Result<PlannedItem[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_calendar_items", { output, items }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createCalendarItems(items: ScheduleItem[], dryRun: boolean | null) : Promise<Result<PlannedItem[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_calendar_items", { items, dryRun }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getStartupProgress() : Promise<StartupStep[]> {
+    return await TAURI_INVOKE("get_startup_progress");
+},
+async getStartupWarnings() : Promise<StartupWarning[]> {
+    return await TAURI_INVOKE("get_startup_warnings");
+},
+async getStartupProfile() : Promise<StartupProfile> {
+    return await TAURI_INVOKE("get_startup_profile");
+},
+async setBufferSizes(sizes: BufferSizes) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_buffer_sizes", { sizes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setPromptCache(settings: PromptCacheSettings) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_prompt_cache", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearPromptCache() : Promise<number> {
+    return await TAURI_INVOKE("clear_prompt_cache");
+},
+async setWarmup(settings: WarmupSettings) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_warmup", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+export const WEBHOOK_RESULT_EVENT = "webhook://result" as const;
+export const LOG_ENTRY_EVENT = "log://entry" as const;
+export const SIDECAR_CHUNK_EVENT = "sidecar://chunk" as const;
+export const WORKSPACE_SWITCHED_EVENT = "workspace://switched" as const;
+export const UNLOCKED_EVENT = "lock://unlocked" as const;
+export const PROMPT_TIMING_EVENT = "prompt://timing" as const;
+export const LOCKED_EVENT = "lock://locked" as const;
+export const APPROVAL_REQUEST_EVENT = "approval://request" as const;
+export const SIDECAR_OUTPUT_EVENT = "sidecar://output" as const;
+export const STARTUP_WARNINGS_EVENT = "startup://warnings" as const;
+export const OLLAMA_CHUNK_EVENT = "ollama://chunk" as const;
+export const STARTUP_PROGRESS_EVENT = "startup://progress" as const;
+export const INTEGRITY_ERROR_EVENT = "sidecar://integrity-error" as const;
+export const FILES_CHANGED_EVENT = "workspace://changed" as const;
+export const ACTION_EVENT = "actions://run" as const;
+export const STATE_PATCH_EVENT = "state://patch" as const;
+export const CODE_HIGHLIGHTED_EVENT = "code://highlighted" as const;
+export const WEBVIEW_LANGUAGE_EVENT = "webview://language" as const;
+export const LOCALE_CHANGED_EVENT = "locale://changed" as const;
+export const ACCESSIBILITY_CHANGED_EVENT = "accessibility://changed" as const;
+export const SHELL_EVENT = "shell://event" as const;
+
+/** user-defined types **/
+
+export type AccessibilityPrefs = { reduce_motion: boolean; increase_contrast: boolean; screen_reader: boolean }
+export type Action = { id: string; title: string; group: string; keywords: string[] }
+export type ActionMatch = { action: Action; score: number; positions: number[] }
+export type AgentAction = { action: string; args: JsonValue; ok: boolean; result: string | null }
+export type AppError = { code: ErrorCode; message: string; field?: string | null }
+export type AppInfo = { name: string; icon_png_base64: string }
+export type AppState = { version: number; sidecar: SidecarStatus; active_session: string | null; workspace: WorkspaceInfo; settings: SettingsDigest }
+export type AppliedEdit = { kind: EditKind; path: string; backup: string | null; timestamp: string }
+export type ApprovalRequest = { id: string; action: string; detail: string }
+export type AuditEntry = { seq: number; timestamp: string; actor: string; action: string; args: JsonValue; ok: boolean; result: string | null; prev_hash: string; hash: string }
+export type AuditFilter = { actor: string | null; action: string | null; since: string | null; until: string | null; limit: number | null }
+export type AuditLog = { entries: AuditEntry[]; verified: boolean }
+export type BufferSizes = { log_entries: number; stream_events: number }
+export type CalendarAccess = { events: string; reminders: string }
+export type CandidateKind = "session" | "file"
+export type ChangeKind = "created" | "modified" | "deleted"
+export type ChatChunk = { trace_id: string; session_id: string; content: string }
+export type CodeBlock = { index: number; language: string | null; code: string }
+export type CodeEvalStatus = { available: boolean }
+export type CodeHighlighted = { trace_id: string; blocks: HighlightedBlock[] }
+export type CompanionApiInfo = { enabled: boolean; base_url: string; token: string | null }
+export type Crop = { x: number; y: number; width: number; height: number }
+export type DatabaseConnection = { id: string; name: string; kind: DatabaseKind; location: string }
+export type DatabaseKind = "sqlite" | "postgres"
+export type DeliveryKind = "slack" | "discord"
+export type DeliveryTarget = { id: string; name: string; kind: DeliveryKind; message_template?: string | null }
+export type DiffHunk = { old_start: number; old_lines: number; new_start: number; new_lines: number; lines: DiffLine[] }
+export type DiffLine = { kind: LineKind; content: string }
+export type DiskHealth = { data_dir: string; available_bytes: number; total_bytes: number }
+export type EditKind = "write" | "patch"
+export type EditedImage = { path: string; width: number; height: number; format: OutputFormat; bytes: number }
+export type Editor = "vs_code" | "cursor" | "zed" | "idea" | "web_storm" | "py_charm" | "go_land" | "rust_rover"
+export type EditorInfo = { editor: Editor; name: string; preferred: boolean }
+export type EntryKind = "file" | "dir" | "symlink"
+export type ErrorCode = "sidecar_unavailable" | "sidecar" | "timeout" | "invalid_input" | "invalid_state" | "io" | "port_in_use" | "permission_denied" | "internal"
+export type FileChange = { kind: ChangeKind; path: string }
+export type FileChunk = { content: string; offset: number; next_offset: number; size: number; eof: boolean }
+export type FileDiff = { path: string; hunks: DiffHunk[]; additions: number; deletions: number }
+export type Fit = "contain" | "cover" | "fill"
+export type FrontendError = { kind: string; message: string; stack?: string | null; url?: string | null; line?: number | null; column?: number | null; session_id?: string | null; trace_id?: string | null }
+export type FsError = { kind: "no_workspace" } | { kind: "not_found"; path: string } | { kind: "permission_denied"; path: string } | { kind: "outside_scope"; path: string } | { kind: "symlink_escapes_scope"; path: string; target: string } | { kind: "too_large"; path: string; size: number; limit: number } | { kind: "binary"; path: string } | { kind: "invalid"; reason: string } | { kind: "io"; path: string; reason: string }
+export type GitFileStatus = { path: string; index: string | null; worktree: string | null }
+export type HealthCheck = { ok: boolean; latency_ms: number; checked_at: string }
+export type HealthReport = { sidecar: SidecarHealth; disk: DiskHealth | null; network_reachable: boolean; pending_queue_depth: number }
+export type HighlightFormat = "html" | "ansi"
+export type HighlightedBlock = { index: number; code: HighlightedCode }
+export type HighlightedCode = { language: string; theme: string; output: string; background: string | null }
+export type Histogram = { count: number; errors: number; total_ms: number; min_ms: number; max_ms: number; buckets: number[] }
+export type ImageEdit = { source: string; crop: Crop | null; resize: Resize | null; format: OutputFormat | null; quality: number | null; max_bytes: number | null }
+export type ImageSource = { kind: "path"; path: string } | { kind: "data"; data: string }
+export type IntegrityError = { kind: "manifest_missing"; path: string } | { kind: "manifest_invalid"; reason: string } | { kind: "not_in_manifest"; binary: string } | { kind: "unreadable"; path: string; reason: string } | { kind: "mismatch"; binary: string; expected: string; actual: string }
+export type ItemKind = "event" | "reminder"
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+export type LineKind = "context" | "add" | "remove"
+export type LinkPreview = { url: string; title: string | null; description: string | null; image: string | null; site_name: string | null; favicon: string | null }
+export type LocaleInfo = { active: string; selected: string | null; system: string[]; available: LocaleOption[] }
+export type LocaleOption = { locale: string; name: string }
+export type LockStatus = { enabled: boolean; locked: boolean; auto_lock_secs: number; biometrics_available: boolean }
+export type LogEntry = { timestamp: string; level: string; source: string; message: string }
+export type LogFilter = { level: string | null; source: string | null; search: string | null; limit: number | null }
+export type McpResource = { server: string; uri: string; name: string; description: string; mimeType: string }
+export type McpServerConfig = { name: string; transport: McpTransport; command: string; args: string[]; env: Partial<{ [key in string]: string }>; url: string; headers: Partial<{ [key in string]: string }>; allowed_tools: string[]; denied_tools: string[]; enabled: boolean }
+export type McpServerInfo = { enabled: boolean; url: string; token: string | null }
+export type McpTool = { name: string; description: string }
+export type McpTransport = "stdio" | "sse"
+export type Metrics = { bucket_bounds_ms: number[]; current: MetricsSnapshot; history: MetricsSnapshot[] }
+export type MetricsSnapshot = { taken_at: string; uptime_secs: number; operations: Partial<{ [key in string]: Histogram }>; dropped: Partial<{ [key in string]: number }> }
+export type OAuthConfig = { provider: string; auth_url: string; token_url: string; client_id: string; scopes?: string[]; env_var: string }
+export type OAuthStatus = { provider: string; logged_in: boolean; expires_at: number | null }
+export type OllamaModel = { name: string; size?: number; modified_at?: string }
+export type OllamaStatus = { available: boolean; url: string; version: string | null }
+export type OnboardingStatus = { first_launch: boolean; permissions: PermissionStatus[] }
+export type OpenAiApiInfo = { enabled: boolean; base_url: string; token: string | null }
+export type OutputFormat = "png" | "jpeg" | "webp" | "gif" | "bmp" | "tiff"
+export type PatchConflict = { hunk: number; old_start: number; expected: string[]; actual: string[] }
+export type PatchResult = { applied: boolean; backup: string | null; conflicts: PatchConflict[] }
+export type Permission = "accessibility" | "screen_recording" | "microphone" | "notifications"
+export type PermissionStatus = { permission: Permission; label: string; granted: boolean; settings_url: string | null }
+export type PhaseTiming = { name: string; started_ms: number; duration_ms: number }
+export type PlannedItem = { kind: ItemKind; title: string; start: string | null; end: string | null; all_day: boolean; notes: string | null; id: string | null }
+export type Preview = { path: string; width: number; height: number }
+export type PromptCacheSettings = { enabled: boolean; ttl_secs: number }
+export type PromptChunk = { trace_id: string; session_id: string; kind: string; content: string; done: boolean }
+export type PromptResponse = { trace_id: string; text: string }
+export type PromptTiming = { trace_id: string; duration_ms: number; ok: boolean }
+export type Provider = { kind: "sidecar" } | { kind: "ollama"; model: string }
+export type QuickSwitchCandidate = { kind: CandidateKind; id: string; title: string; detail: string | null; icon: string; score: number; positions: number[] }
+export type RecentProject = { path: string; name: string; pinned: boolean; exists: boolean }
+export type RecordingStatus = { mode: string; path: string | null }
+export type Resize = { width: number | null; height: number | null; fit?: Fit }
+export type SavedImage = { message_id: string; path: string; saved_at: string }
+export type ScheduleItem = { kind?: ItemKind; title: string; start?: string | null; end?: string | null; duration_minutes?: number | null; notes?: string | null }
+export type SearchMatch = { path: string; line: number; column: number; text: string }
+export type SearchOptions = { regex?: boolean; case_sensitive?: boolean; globs: string[] | null; max_results: number | null }
+export type SearchProvider = "brave" | "tavily" | "serper" | "searxng"
+export type SearchProviderInfo = { provider: SearchProvider; name: string; secret: string | null; configured: boolean }
+export type SearchResult = { title: string; url: string; snippet: string; published: string | null }
+export type SearchResults = { matches: SearchMatch[]; truncated: boolean }
+export type SelfTestCheck = { id: string; label: string; passed: boolean; detail: string | null }
+export type SelfTestReport = { passed: boolean; checks: SelfTestCheck[] }
+export type Settings = { telemetry_enabled: boolean; telemetry_endpoint: string | null; fs_grants: string[]; encrypt_history: boolean; always_allowed_actions: string[]; oauth_providers: OAuthConfig[]; lock_enabled: boolean; auto_lock_secs: number; workspace: string | null; recent_workspaces: string[]; pinned_workspaces: string[]; recent_files: string[]; mcp_servers: McpServerConfig[]; mcp_server_enabled: boolean; mcp_server_port: number | null; ollama_url: string | null; session_providers: Partial<{ [key in string]: Provider }>; openai_api_enabled: boolean; openai_api_port: number | null; sidecar_transport: SidecarTransport; sidecar_port_fallback: boolean; companion_api_enabled: boolean; companion_api_port: number | null; webhooks_enabled: boolean; webhook_port: number | null; webhooks: WebhookConfig[]; delivery_targets: DeliveryTarget[]; vault_path: string | null; vault_folder: string | null; buffer_sizes: BufferSizes; prompt_cache: PromptCacheSettings; warmup: WarmupSettings; highlight_theme: string | null; onboarding_completed?: boolean; preferred_editor: Editor | null; webview_languages: Partial<{ [key in string]: WebviewLanguage }>; locale: string | null; sounds: SoundSettings; shell_tool: ShellToolSettings; web_search: WebSearchSettings; database_connections: DatabaseConnection[]; clipboard_notifications: boolean }
+export type SettingsDigest = { hash: string; sidecar_transport: SidecarTransport; lock_enabled: boolean; telemetry_enabled: boolean; encrypt_history: boolean }
+export type ShareAnchor = { x: number; y: number; width: number; height: number }
+export type ShareContent = { kind: "text"; text: string } | { kind: "path"; path: string }
+export type ShellEvent = { kind: "started"; call_id: string; command: string; cwd: string } | { kind: "output"; call_id: string; stream: ShellStream; text: string } | { kind: "exited"; call_id: string; exit_code: number | null; timed_out: boolean }
+export type ShellStream = "stdout" | "stderr"
+export type ShellToolSettings = { allowed_commands: string[]; denied_commands: string[]; timeout_secs: number }
+export type SidecarAuth = { token: string; identity: string }
+export type SidecarHealth = { running: boolean; error: string | null; last_health_check: HealthCheck | null }
+export type SidecarOutput = { stream: string; data: string }
+export type SidecarState = "stopped" | "starting" | "running" | "stopping" | "restarting"
+export type SidecarStatus = { state: SidecarState; child_id: number | null; started_at: number | null; port: number | null; grpc_port: number | null; error: string | null; transport: SidecarTransport; last_health_check: HealthCheck | null }
+export type SidecarTransport = "http" | "grpc"
+export type SnapshotInfo = { path: string; files: number; bytes: number }
+export type Sound = "chime" | "alert" | "attention" | "tap"
+export type SoundEvent = "prompt_complete" | "prompt_failed" | "approval_needed"
+export type SoundSettings = { enabled: boolean; prompt_complete: Sound | null; prompt_failed: Sound | null; approval_needed: Sound | null; ignore_silence: boolean }
+export type StartupProfile = { phases: PhaseTiming[]; total_ms: number }
+export type StartupStep = { name: string; state: StepState; error: string | null; elapsed_ms: number }
+export type StartupWarning = { subsystem: string; message: string }
+export type StatePatch = { key: "sidecar"; value: SidecarStatus } | { key: "active_session"; value: string | null } | { key: "workspace"; value: WorkspaceInfo } | { key: "settings"; value: SettingsDigest }
+export type StatePatchEvent = { version: number; changes: StatePatch[] }
+export type StepState = "pending" | "running" | "done" | "failed"
+export type TelemetryEvent = { name: string; timestamp: string; properties: JsonValue }
+export type TelemetryPreview = { enabled: boolean; endpoint: string | null; queued: TelemetryEvent[] }
+export type TreeNode = { name: string; path: string; kind: EntryKind; size: number; mtime: number | null; children: TreeNode[] }
+export type WarmupSettings = { app: boolean; daemon: boolean; on_battery: boolean }
+export type WebSearchSettings = { provider: SearchProvider; searxng_url: string | null; max_results: number }
+export type WebhookConfig = { id: string; name: string; prompt_template: string; session_id?: string | null; enabled?: boolean; deliver_to?: string[] }
+export type WebhookInfo = ({ id: string; name: string; prompt_template: string; session_id?: string | null; enabled?: boolean; deliver_to?: string[] }) & { url: string; secret: string | null }
+export type WebhookResult = { webhook_id: string; name: string; trace_id: string; ok: boolean; text: string | null; error: string | null }
+export type WebviewLanguage = { languages: string[]; spellcheck: boolean }
+export type WorkspaceInfo = { path: string | null; recent: string[] }
+export type WorkspaceTree = { root: string; entries: TreeNode[]; truncated: boolean }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
@@ -30,6 +30,8 @@ import {
 import { FolderIcon } from 'lucide-react';
 import { type FormEventHandler, useState, useEffect, useRef, useCallback, useMemo } from 'react';
 import { TooltipProvider } from '@/components/ui/tooltip';
+import { commands } from '@/bindings';
+import { save } from '@tauri-apps/plugin-dialog';
 
 import { useSession, useCreateSession } from '@/hooks/useSession';
 import { useSendMessage } from '@/hooks/useMessages';
@@ -43,9 +45,11 @@ import { useFolderSelection } from '@/hooks/useFolderSelection';
 import { useMessageHistoryNavigation } from '@/hooks/useMessageHistoryNavigation';
 import { useMessageScrolling } from '@/hooks/useMessageScrolling';
 import { LoadingDots } from './loading-dots';
+import { CommandPalette } from './command-palette';
 import { AttachmentPreview } from './attachment-preview';
 import { CommandSlash, shouldShowSlashCommands, handleSlashCommandNavigation, slashCommands } from './command-slash';
 import { ResponseRenderer } from './response-renderer';
+import { ShareButton } from './share-button';
 import { MessageAttachmentDisplay } from './message-attachment-display';
 import { TodoList } from './todo-list';
 import { PlanDisplay } from './plan-display';
@@ -281,6 +285,12 @@ export function ChatApp() {
       showSlashCommands || fileRef.show
     );
     if (historyHandled) return;
+
+    // Escape with nothing left to dismiss hides the window
+    if (e.key === 'Escape') {
+      e.preventDefault();
+      commands.hidePanel();
+    }
   };
 
 
@@ -395,6 +405,19 @@ export function ChatApp() {
     setShowPlanOptions(null);
   };
 
+  const handleExportPdf = async () => {
+    if (!session?.id) return;
+    const path = await save({
+      defaultPath: 'Conversation.pdf',
+      filters: [{ name: 'PDF', extensions: ['pdf'] }],
+    });
+    if (!path) return;
+    const result = await commands.exportSessionPdf(session.id, path);
+    if (result.status === 'error') {
+      console.error('Failed to export PDF:', result.error);
+    }
+  };
+
   // Handle plan option button clicks
   const handlePlanProceed = (messageIndex: number) => {
     setIsPlanMode(false);
@@ -448,6 +471,7 @@ export function ChatApp() {
                       </AIReasoning>
                     )}
                     <ResponseRenderer content={message.content} />
+                    <ShareButton text={message.content} />
                   </>
                 ) : (
                   <div>
@@ -648,6 +672,7 @@ export function ChatApp() {
           />
         )}
       </div>
+      <CommandPalette onNewSession={handleNewSession} onExportPdf={handleExportPdf} />
     </div>
     </TooltipProvider>
   );
@@ -0,0 +1,109 @@
+import { useEffect, useRef, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import {
+  CommandDialog,
+  CommandEmpty,
+  CommandGroup,
+  CommandInput,
+  CommandItem,
+  CommandList,
+} from '@/components/ui/command';
+import { ACTION_EVENT, commands, type ActionMatch } from '@/bindings';
+
+interface CommandPaletteProps {
+  onNewSession: () => void;
+  onExportPdf: () => void;
+}
+
+// Title with the characters the Rust matcher matched in bold
+function HighlightedTitle({ title, positions }: { title: string; positions: number[] }) {
+  const matched = new Set(positions);
+  return (
+    <span>
+      {Array.from(title).map((char, i) =>
+        matched.has(i) ? <strong key={i}>{char}</strong> : <span key={i}>{char}</span>
+      )}
+    </span>
+  );
+}
+
+export function CommandPalette({ onNewSession, onExportPdf }: CommandPaletteProps) {
+  const [open, setOpen] = useState(false);
+  const [query, setQuery] = useState('');
+  const [matches, setMatches] = useState<ActionMatch[]>([]);
+  const onNewSessionRef = useRef(onNewSession);
+  onNewSessionRef.current = onNewSession;
+  const onExportPdfRef = useRef(onExportPdf);
+  onExportPdfRef.current = onExportPdf;
+
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.key === 'k' && (e.metaKey || e.ctrlKey)) {
+        e.preventDefault();
+        setOpen((open) => !open);
+      }
+    };
+    window.addEventListener('keydown', handleKeyDown);
+    return () => window.removeEventListener('keydown', handleKeyDown);
+  }, []);
+
+  // Actions the Rust side hands back to the webview
+  useEffect(() => {
+    const unlisten = listen<string>(ACTION_EVENT, (event) => {
+      if (event.payload === 'session.new') {
+        onNewSessionRef.current();
+      } else if (event.payload === 'session.export_pdf') {
+        onExportPdfRef.current();
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Matching and ranking happen in Rust, so cmdk's own filter is off
+  useEffect(() => {
+    if (!open) return;
+    let cancelled = false;
+    commands.listActions(query || null).then((result) => {
+      if (!cancelled) setMatches(result);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [open, query]);
+
+  const runAction = async (id: string) => {
+    setOpen(false);
+    setQuery('');
+    const result = await commands.runAction(id);
+    if (result.status === 'error') {
+      console.error(`Failed to run action ${id}:`, result.error.message);
+    }
+  };
+
+  // Groups in the order their best match appears
+  const groups = matches.reduce<Map<string, ActionMatch[]>>((groups, match) => {
+    const group = groups.get(match.action.group) ?? [];
+    group.push(match);
+    return groups.set(match.action.group, group);
+  }, new Map());
+
+  return (
+    <CommandDialog open={open} onOpenChange={setOpen} shouldFilter={false}>
+      <CommandInput placeholder="Type an action..." value={query} onValueChange={setQuery} />
+      <CommandList>
+        <CommandEmpty>No matching actions.</CommandEmpty>
+        {Array.from(groups, ([group, items]) => (
+          <CommandGroup key={group} heading={group}>
+            {items.map(({ action, positions }) => (
+              <CommandItem key={action.id} value={action.id} onSelect={() => runAction(action.id)}>
+                <HighlightedTitle title={action.title} positions={positions} />
+              </CommandItem>
+            ))}
+          </CommandGroup>
+        ))}
+      </CommandList>
+    </CommandDialog>
+  );
+}
@@ -0,0 +1,68 @@
+import { useState } from 'react';
+import { useQuery } from '@tanstack/react-query';
+import { commands, type LinkPreview } from '@/bindings';
+import { cn } from '@/lib/utils';
+
+const fetchLinkPreview = async (url: string): Promise<LinkPreview> => {
+  const result = await commands.fetchLinkPreview(url);
+  if (result.status === 'error') {
+    throw new Error(result.error.message);
+  }
+  return result.data;
+};
+
+interface LinkPreviewCardProps {
+  href: string;
+  className?: string;
+  children: React.ReactNode;
+}
+
+// A link that shows the page's title, description and image on hover. The
+// preview is fetched on the first hover, by the Rust side.
+export function LinkPreviewCard({ href, className, children }: LinkPreviewCardProps) {
+  const [open, setOpen] = useState(false);
+  const { data } = useQuery({
+    queryKey: ['linkPreview', href],
+    queryFn: () => fetchLinkPreview(href),
+    enabled: open,
+    staleTime: Infinity,
+    retry: false,
+  });
+  const hasContent = data && (data.title || data.description || data.image);
+
+  return (
+    <span
+      className="relative inline-block"
+      onMouseEnter={() => setOpen(true)}
+      onMouseLeave={() => setOpen(false)}
+    >
+      <a className={className} href={href} rel="noreferrer" target="_blank">
+        {children}
+      </a>
+      {open && hasContent && (
+        <span
+          className={cn(
+            'absolute bottom-full left-0 z-50 mb-2 flex w-80 flex-col overflow-hidden',
+            'rounded-md border bg-popover text-popover-foreground shadow-lg'
+          )}
+        >
+          {data.image && (
+            <img alt="" className="h-36 w-full object-cover" src={data.image} />
+          )}
+          <span className="flex flex-col gap-1 p-3">
+            <span className="flex items-center gap-2 text-muted-foreground text-xs">
+              {data.favicon && <img alt="" className="size-4" src={data.favicon} />}
+              {data.site_name ?? new URL(data.url).hostname}
+            </span>
+            {data.title && <span className="font-semibold text-sm">{data.title}</span>}
+            {data.description && (
+              <span className="line-clamp-3 text-muted-foreground text-xs">
+                {data.description}
+              </span>
+            )}
+          </span>
+        </span>
+      )}
+    </span>
+  );
+}
@@ -0,0 +1,144 @@
+import { useEffect, useRef, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { commands, LOG_ENTRY_EVENT, type LogEntry } from '@/bindings';
+
+// Matches the Rust-side buffer, so the viewer never shows more than it keeps
+const MAX_ENTRIES = 2000;
+// Most severe first, as `get_logs` orders them
+const LEVELS = ['ERROR', 'WARN', 'INFO', 'DEBUG', 'TRACE'];
+const SOURCES = ['app', 'sidecar', 'frontend'];
+
+const LEVEL_COLORS: Record<string, string> = {
+  ERROR: 'text-red-400',
+  WARN: 'text-yellow-400',
+  INFO: 'text-sky-400',
+  DEBUG: 'text-muted-foreground',
+  TRACE: 'text-muted-foreground',
+};
+
+interface Filter {
+  level: string;
+  source: string;
+  search: string;
+}
+
+// Same rules as `get_logs`, applied to entries that arrive live
+const matches = (entry: LogEntry, { level, source, search }: Filter) =>
+  (!level || LEVELS.indexOf(entry.level) <= LEVELS.indexOf(level)) &&
+  (!source || entry.source === source) &&
+  (!search || entry.message.toLowerCase().includes(search.toLowerCase()));
+
+const selectClass = 'rounded-md border border-border bg-background px-2 py-1 text-sm';
+
+// The `#/logs` window: the recent log buffer, then every entry as it's logged
+export function LogViewer() {
+  const [entries, setEntries] = useState<LogEntry[]>([]);
+  const [filter, setFilter] = useState<Filter>({ level: '', source: '', search: '' });
+  const [follow, setFollow] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+  const bottomRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    // Entries logged while the buffer loads are held back, then added after it
+    let live: LogEntry[] | null = [];
+    const unlisten = listen<LogEntry>(LOG_ENTRY_EVENT, ({ payload }) => {
+      if (!matches(payload, filter)) return;
+      if (live) {
+        live.push(payload);
+      } else {
+        setEntries((entries) => [...entries, payload].slice(-MAX_ENTRIES));
+      }
+    });
+
+    commands
+      .getLogs({
+        level: filter.level || null,
+        source: filter.source || null,
+        search: filter.search || null,
+        limit: MAX_ENTRIES,
+      })
+      .then((result) => {
+        if (cancelled) return;
+        if (result.status === 'error') {
+          setError(result.error.message);
+          return;
+        }
+        const last = result.data[result.data.length - 1]?.timestamp ?? '';
+        const newer = (live ?? []).filter((entry) => entry.timestamp > last);
+        live = null;
+        setError(null);
+        setEntries([...result.data, ...newer].slice(-MAX_ENTRIES));
+      });
+
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+    };
+  }, [filter]);
+
+  useEffect(() => {
+    if (follow) bottomRef.current?.scrollIntoView({ block: 'end' });
+  }, [entries, follow]);
+
+  const update = (change: Partial<Filter>) => setFilter((filter) => ({ ...filter, ...change }));
+
+  return (
+    <div className="flex h-screen flex-col bg-background text-foreground">
+      <div className="flex items-center gap-2 border-b border-border p-2">
+        <select
+          className={selectClass}
+          value={filter.level}
+          onChange={(e) => update({ level: e.target.value })}
+          aria-label="Level"
+        >
+          <option value="">All levels</option>
+          {LEVELS.map((level) => (
+            <option key={level} value={level}>
+              {level} and above
+            </option>
+          ))}
+        </select>
+        <select
+          className={selectClass}
+          value={filter.source}
+          onChange={(e) => update({ source: e.target.value })}
+          aria-label="Source"
+        >
+          <option value="">All sources</option>
+          {SOURCES.map((source) => (
+            <option key={source} value={source}>
+              {source}
+            </option>
+          ))}
+        </select>
+        <input
+          className={`${selectClass} flex-1`}
+          type="search"
+          placeholder="Search messages..."
+          value={filter.search}
+          onChange={(e) => update({ search: e.target.value })}
+        />
+        <label className="flex items-center gap-1 text-sm text-muted-foreground">
+          <input type="checkbox" checked={follow} onChange={(e) => setFollow(e.target.checked)} />
+          Follow
+        </label>
+      </div>
+      {error && <div className="p-2 text-sm text-red-400">Failed to load logs: {error}</div>}
+      <div className="flex-1 overflow-auto p-2 font-mono text-xs">
+        {entries.length === 0 && !error && (
+          <div className="text-muted-foreground">No matching log entries.</div>
+        )}
+        {entries.map((entry, i) => (
+          <div key={i} className="flex gap-2 whitespace-pre-wrap break-all">
+            <span className="shrink-0 text-muted-foreground">{entry.timestamp.slice(11)}</span>
+            <span className={`w-12 shrink-0 ${LEVEL_COLORS[entry.level] ?? ''}`}>{entry.level}</span>
+            <span className="w-16 shrink-0 text-muted-foreground">{entry.source}</span>
+            <span>{entry.message}</span>
+          </div>
+        ))}
+        <div ref={bottomRef} />
+      </div>
+    </div>
+  );
+}
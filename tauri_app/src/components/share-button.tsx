@@ -0,0 +1,41 @@
+import type { MouseEvent } from 'react';
+import { ShareIcon } from 'lucide-react';
+import { commands } from '@/bindings';
+
+// Only macOS has a share sheet
+const canShare = navigator.userAgent.includes('Mac');
+
+interface ShareButtonProps {
+  text?: string;
+  path?: string;
+}
+
+// Opens the share sheet for a response's text or an exported file, pointing
+// at the button
+export function ShareButton({ text, path }: ShareButtonProps) {
+  if (!canShare || (!text?.trim() && !path)) {
+    return null;
+  }
+
+  const share = async (event: MouseEvent<HTMLButtonElement>) => {
+    const rect = event.currentTarget.getBoundingClientRect();
+    const result = await commands.shareContent(
+      path ? { kind: 'path', path } : { kind: 'text', text: text ?? '' },
+      { x: rect.x, y: rect.y, width: rect.width, height: rect.height }
+    );
+    if (result.status === 'error') {
+      console.error('Failed to share:', result.error);
+    }
+  };
+
+  return (
+    <button
+      className="mt-2 rounded p-1 text-muted-foreground hover:bg-muted hover:text-foreground"
+      onMouseDown={share}
+      title="Share"
+      type="button"
+    >
+      <ShareIcon className="size-4" />
+    </button>
+  );
+}
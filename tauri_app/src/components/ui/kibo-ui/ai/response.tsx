@@ -22,13 +22,64 @@ import { memo } from 'react';
 import ReactMarkdown, { type Options } from 'react-markdown';
 import remarkGfm from 'remark-gfm';
 import { cn } from '@/lib/utils';
+import { commands } from '@/bindings';
+import { LinkPreviewCard } from '@/components/link-preview';
 
 export type AIResponseProps = HTMLAttributes<HTMLDivElement> & {
   options?: Options;
   children: Options['children'];
 };
 
+// `src/app.ts`, `~/notes/todo.md:12` or `/tmp/out.py:3:8`
+const FILE_REFERENCE = /^((?:~|\.{1,2})?\/?(?:[\w.-]+\/)*[\w-][\w.-]*\.[A-Za-z0-9]+)(?::(\d+))?(?::(\d+))?$/;
+
+const InlineCode: NonNullable<Options['components']>['code'] = ({
+  node,
+  className,
+  children,
+  ...props
+}) => {
+  const match =
+    typeof children === 'string' ? FILE_REFERENCE.exec(children) : null;
+  if (!match) {
+    return (
+      <code className={className} {...props}>
+        {children}
+      </code>
+    );
+  }
+
+  const [, path, line, column] = match;
+  const open = () =>
+    commands
+      .openInEditor(
+        path,
+        line ? Number(line) : null,
+        column ? Number(column) : null
+      )
+      .then((result) => {
+        if (result.status === 'error') {
+          console.error('Failed to open in editor:', result.error);
+        }
+      });
+
+  return (
+    <code
+      className={cn('cursor-pointer underline decoration-dotted', className)}
+      onClick={open}
+      onKeyDown={(event) => event.key === 'Enter' && open()}
+      role="link"
+      tabIndex={0}
+      title="Open in editor"
+      {...props}
+    >
+      {children}
+    </code>
+  );
+};
+
 const components: Options['components'] = {
+  code: InlineCode,
   ol: ({ node, children, className, ...props }) => (
     <ol className={cn('ml-4 list-inside list-decimal', className)} {...props}>
       {children}
@@ -49,16 +100,25 @@ const components: Options['components'] = {
       {children}
     </span>
   ),
-  a: ({ node, children, className, ...props }) => (
-    <a
-      className={cn('font-medium text-primary underline', className)}
-      rel="noreferrer"
-      target="_blank"
-      {...props}
-    >
-      {children}
-    </a>
-  ),
+  a: ({ node, children, className, href, ...props }) =>
+    href && /^https?:\/\//.test(href) ? (
+      <LinkPreviewCard
+        className={cn('font-medium text-primary underline', className)}
+        href={href}
+      >
+        {children}
+      </LinkPreviewCard>
+    ) : (
+      <a
+        className={cn('font-medium text-primary underline', className)}
+        href={href}
+        rel="noreferrer"
+        target="_blank"
+        {...props}
+      >
+        {children}
+      </a>
+    ),
   h1: ({ node, children, className, ...props }) => (
     <h1
       className={cn('mt-6 mb-2 font-semibold text-3xl', className)}
@@ -109,7 +169,7 @@ const components: Options['components'] = {
       typeof children === 'object' &&
       children !== null &&
       'type' in children &&
-      children.type === 'code';
+      (children.type === 'code' || children.type === InlineCode);
 
     if (!childrenIsCode) {
       return <pre>{children}</pre>;
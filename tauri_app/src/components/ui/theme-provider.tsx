@@ -1,4 +1,5 @@
 import { createContext, useContext, useEffect, useState } from "react"
+import { commands } from "@/bindings"
 
 type Theme = "dark" | "light" | "system"
 
@@ -35,17 +36,16 @@ export function ThemeProvider({
 
     root.classList.remove("light", "dark")
 
-    if (theme === "system") {
-      const systemTheme = window.matchMedia("(prefers-color-scheme: dark)")
-        .matches
-        ? "dark"
-        : "light"
+    const resolved =
+      theme === "system"
+        ? window.matchMedia("(prefers-color-scheme: dark)").matches
+          ? "dark"
+          : "light"
+        : theme
 
-      root.classList.add(systemTheme)
-      return
-    }
-
-    root.classList.add(theme)
+    root.classList.add(resolved)
+    // Code blocks highlighted in Rust follow the same theme
+    commands.setHighlightTheme(resolved)
   }, [theme])
 
   const value = {
@@ -0,0 +1,32 @@
+import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import {
+  ACCESSIBILITY_CHANGED_EVENT,
+  commands,
+  type AccessibilityPrefs,
+} from '@/bindings';
+
+// The webview's media queries don't follow every OS setting everywhere, so
+// the Rust side's reading is put on the root as classes App.css styles
+const applyPrefs = (prefs: AccessibilityPrefs) => {
+  const root = document.documentElement;
+  root.classList.toggle('reduce-motion', prefs.reduce_motion);
+  root.classList.toggle('high-contrast', prefs.increase_contrast);
+};
+
+export const useAccessibilityPrefs = () => {
+  useEffect(() => {
+    let cancelled = false;
+    const unlisten = listen<AccessibilityPrefs>(ACCESSIBILITY_CHANGED_EVENT, ({ payload }) =>
+      applyPrefs(payload)
+    );
+    commands.getAccessibilityPrefs().then((prefs) => {
+      if (!cancelled) applyPrefs(prefs);
+    });
+
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+};
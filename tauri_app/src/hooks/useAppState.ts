@@ -0,0 +1,56 @@
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import {
+  commands,
+  STATE_PATCH_EVENT,
+  type AppState,
+  type StatePatch,
+  type StatePatchEvent,
+} from '@/bindings';
+
+const applyPatch = (state: AppState, patch: StatePatch): AppState => {
+  switch (patch.key) {
+    case 'sidecar':
+      return { ...state, sidecar: patch.value };
+    case 'active_session':
+      return { ...state, active_session: patch.value };
+    case 'workspace':
+      return { ...state, workspace: patch.value };
+    case 'settings':
+      return { ...state, settings: patch.value };
+  }
+};
+
+// Snapshot of the Rust side's state, kept current by `state://patch` events.
+// A patch that doesn't follow the held version means one was missed, so the
+// whole snapshot is fetched again.
+export const useAppState = () => {
+  const [state, setState] = useState<AppState | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    const refetch = () =>
+      commands.getAppState().then((snapshot) => {
+        if (!cancelled) setState(snapshot);
+      });
+
+    const unlisten = listen<StatePatchEvent>(STATE_PATCH_EVENT, ({ payload }) => {
+      setState((state) => {
+        if (!state || payload.version !== state.version + 1) {
+          refetch();
+          return state;
+        }
+        const next = payload.changes.reduce(applyPatch, state);
+        return { ...next, version: payload.version };
+      });
+    });
+    refetch();
+
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return state;
+};
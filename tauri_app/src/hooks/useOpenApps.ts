@@ -1,18 +1,14 @@
 import { useQuery } from '@tanstack/react-query';
-import { invoke } from '@tauri-apps/api/core';
+import { commands, type AppInfo } from '@/bindings';
 
-export interface OpenApp {
-  name: string;
-  icon_png_base64: string;
-}
+export type OpenApp = AppInfo;
 
 export const fetchVisibleApps = async (): Promise<OpenApp[]> => {
-  try {
-    const apps = await invoke<OpenApp[]>('list_apps_with_icons');
-    return apps;
-  } catch (error) {
-    throw error;
+  const result = await commands.listAppsWithIcons();
+  if (result.status === 'error') {
+    throw new Error(result.error.message);
   }
+  return result.data;
 };
 
 export function useOpenApps() {
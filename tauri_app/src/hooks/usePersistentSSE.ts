@@ -1,4 +1,6 @@
 import { useState, useEffect, useRef, useCallback } from 'react';
+import { commands, type JsonValue } from '@/bindings';
+import { AGENT_URL } from '@/lib/rpc';
 
 export type SSEToolCall = {
   name: string;
@@ -75,7 +77,7 @@ export function usePersistentSSE(sessionId: string): PersistentSSEHook {
     toolCallsRef.current.clear();
     currentSessionRef.current = sessionId;
 
-    const url = `http://localhost:8088/stream?sessionId=${encodeURIComponent(sessionId)}`;
+    const url = `${AGENT_URL}/stream?sessionId=${encodeURIComponent(sessionId)}`;
     
     const eventSource = new EventSource(url);
     eventSourceRef.current = eventSource;
@@ -112,6 +114,16 @@ export function usePersistentSSE(sessionId: string): PersistentSSEHook {
           error: data.error,
         };
 
+        // Finished tool calls go to the app's audit log
+        if (toolCall.status === 'completed' || toolCall.status === 'error') {
+          commands.recordAgentAction({
+            action: `tool:${toolCall.name}`,
+            args: parameters as JsonValue,
+            ok: toolCall.status === 'completed',
+            result: toolCall.error ?? toolCall.result ?? null,
+          }).catch((err) => console.error('Failed to record agent action:', err));
+        }
+
         toolCallsRef.current.set(toolCall.id, toolCall);
         
         setState(prev => ({
@@ -165,21 +177,19 @@ export function usePersistentSSE(sessionId: string): PersistentSSEHook {
       }
     });
 
+    eventSource.onopen = () => {
+      setState(prev => ({ ...prev, connected: true, connecting: false }));
+    };
+
     eventSource.onerror = (event) => {
-      // For persistent connections, we want to be more resilient to temporary drops
+      // The agent:// proxy ends every response after a poll and the browser
+      // reconnects right away, so CONNECTING alone isn't a dropped stream
       if (eventSource.readyState === EventSource.CLOSED) {
         setState(prev => ({ 
           ...prev, 
           connected: false,
           connecting: true // Try to reconnect
         }));
-      } else if (eventSource.readyState === EventSource.CONNECTING) {
-        setState(prev => ({ 
-          ...prev, 
-          connected: false,
-          connecting: true,
-          error: null // Clear any previous errors
-        }));
       }
     };
 
@@ -231,7 +241,7 @@ export function usePersistentSSE(sessionId: string): PersistentSSEHook {
     toolCallsRef.current.clear();
 
     try {
-      const response = await fetch(`http://localhost:8088/stream/${encodeURIComponent(sessionId)}/message`, {
+      const response = await fetch(`${AGENT_URL}/stream/${encodeURIComponent(sessionId)}/message`, {
         method: 'POST',
         headers: {
           'Content-Type': 'application/json',
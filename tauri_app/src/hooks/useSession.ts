@@ -1,5 +1,6 @@
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import { rpcCall } from '@/lib/rpc';
+import { commands } from '@/bindings';
 
 interface CreateSessionParams {
   title: string;
@@ -17,9 +18,16 @@ const createSession = async (params: CreateSessionParams): Promise<Session> => {
     throw new Error('No session ID returned from server');
   }
 
+  await commands.setActiveSession(sessionId);
   return { id: sessionId };
 };
 
+// After a webview reload, pick up the session the Rust side recorded as open
+const resumeOrCreateSession = async (): Promise<Session> => {
+  const { active_session } = await commands.getAppState();
+  return active_session ? { id: active_session } : createSession({ title: "Chat Session" });
+};
+
 export const useCreateSession = () => {
   const queryClient = useQueryClient();
 
@@ -34,7 +42,7 @@ export const useCreateSession = () => {
 export const useSession = () => {
   return useQuery({
     queryKey: ['session'],
-    queryFn: () => createSession({ title: "Chat Session" }),
+    queryFn: resumeOrCreateSession,
     staleTime: Infinity,
     refetchOnWindowFocus: false,
   });
@@ -0,0 +1,34 @@
+import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { commands, WEBVIEW_LANGUAGE_EVENT, type WebviewLanguage } from '@/bindings';
+
+// `lang` and `spellcheck` are inherited, so setting them on the root covers
+// the prompt box and every other editable field in the window
+const applyLanguage = ({ languages, spellcheck }: WebviewLanguage) => {
+  const root = document.documentElement;
+  if (languages.length > 0) {
+    root.lang = languages[0];
+  } else {
+    root.removeAttribute('lang');
+  }
+  root.spellcheck = spellcheck;
+};
+
+// Marks the page with this window's input language and spellcheck setting,
+// and follows changes made from any window
+export const useWebviewLanguage = () => {
+  useEffect(() => {
+    let cancelled = false;
+    const unlisten = listen<WebviewLanguage>(WEBVIEW_LANGUAGE_EVENT, ({ payload }) =>
+      applyLanguage(payload)
+    );
+    commands.getWebviewLanguage().then((language) => {
+      if (!cancelled) applyLanguage(language);
+    });
+
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+};
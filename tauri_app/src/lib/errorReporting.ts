@@ -0,0 +1,29 @@
+import { commands, type FrontendError } from '@/bindings';
+
+const report = (payload: FrontendError) => {
+  commands.reportFrontendError(payload).catch(() => {
+    // Nothing left to report to if the bridge itself fails
+  });
+};
+
+export function installErrorReporting() {
+  window.addEventListener('error', (event) => {
+    report({
+      kind: 'error',
+      message: event.message,
+      stack: event.error instanceof Error ? event.error.stack : undefined,
+      url: event.filename,
+      line: event.lineno,
+      column: event.colno,
+    });
+  });
+
+  window.addEventListener('unhandledrejection', (event) => {
+    const reason = event.reason;
+    report({
+      kind: 'unhandledrejection',
+      message: reason instanceof Error ? reason.message : String(reason),
+      stack: reason instanceof Error ? reason.stack : undefined,
+    });
+  });
+}
@@ -1,10 +1,76 @@
+import { convertFileSrc } from '@tauri-apps/api/core';
+import { commands, type AppError, type SidecarAuth } from '@/bindings';
+
+const SIDECAR_HOST = '127.0.0.1';
+const IDENTITY_HEADER = 'X-Mix-Server-Identity';
+
+// Credentials for the sidecar spawned by the app
+let sidecarAuth: SidecarAuth | null = null;
+
+// A standalone server (`MIX_SIDECAR_TOKEN=... air` in go_backend) used in
+// place of the app's sidecar while working on the frontend. Only dev builds
+// read it, and only when both VITE_SIDECAR_TOKEN and VITE_SIDECAR_PORT are set
+// in `.env.local`; the server echoes no identity unless it was given
+// MIX_SIDECAR_IDENTITY too.
+const devSidecar =
+  import.meta.env.DEV && import.meta.env.VITE_SIDECAR_TOKEN && import.meta.env.VITE_SIDECAR_PORT
+    ? {
+        token: import.meta.env.VITE_SIDECAR_TOKEN as string,
+        identity: (import.meta.env.VITE_SIDECAR_IDENTITY as string | undefined) ?? null,
+        port: Number(import.meta.env.VITE_SIDECAR_PORT)
+      }
+    : null;
+
+export async function loadSidecarAuth(): Promise<void> {
+  if (!sidecarAuth) {
+    sidecarAuth = await commands.getSidecarAuth().catch(() => null);
+  }
+}
+
+// Throws rather than sending a request without credentials, which the
+// sidecar would refuse anyway
+export function authHeaders(): Record<string, string> {
+  const token = sidecarAuth?.token ?? devSidecar?.token;
+  if (!token) {
+    throw new Error('Sidecar is not running');
+  }
+  return { Authorization: `Bearer ${token}` };
+}
+
+// Session streams go through the app's `agent://` protocol, which adds the
+// sidecar token in Rust. The base URL differs per platform.
+export const AGENT_URL = convertFileSrc('', 'agent').replace(/\/$/, '');
+
+export function verifySidecarIdentity(response: Response): void {
+  const expected = sidecarAuth ? sidecarAuth.identity : devSidecar?.identity;
+  if (expected === undefined) {
+    throw new Error('Sidecar is not running');
+  }
+  // Only a dev server started without MIX_SIDECAR_IDENTITY has none
+  if (expected !== null && response.headers.get(IDENTITY_HEADER) !== expected) {
+    throw new Error('Sidecar endpoint failed identity check, refusing to use it');
+  }
+}
+
+// Asked for on every call: the sidecar falls back to another port when the
+// default one is taken, and may come back on a different one after a restart
+export async function sidecarUrl(): Promise<string> {
+  const port = sidecarAuth ? await commands.getSidecarPort() : devSidecar?.port;
+  if (port === null || port === undefined) {
+    throw new Error('Sidecar is not running');
+  }
+  return `http://${SIDECAR_HOST}:${port}`;
+}
+
 export async function rpcCall<T>(method: string, params: any): Promise<T> {
-  const response = await fetch('http://localhost:8088/rpc', {
+  await loadSidecarAuth();
+  const response = await fetch(`${await sidecarUrl()}/rpc`, {
     method: 'POST',
-    headers: { 'Content-Type': 'application/json' },
+    headers: { 'Content-Type': 'application/json', ...authHeaders() },
     body: JSON.stringify({ method, params, id: 1 })
   });
 
+  verifySidecarIdentity(response);
   if (!response.ok) {
     throw new Error(`HTTP error! status: ${response.status}`);
   }
@@ -15,4 +81,11 @@ export async function rpcCall<T>(method: string, params: any): Promise<T> {
   }
 
   return data.result;
-}
\ No newline at end of file
+}
+
+// Rust commands reject with `{ code, message }`, see `error.rs`
+export type { AppError, ErrorCode } from '@/bindings';
+
+export function isAppError(error: unknown): error is AppError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
@@ -132,6 +132,29 @@ export const useAttachmentStore = create<AttachmentState>((set, get) => ({
 }));
 
 // Utility functions
+// Attachment bytes are served by the `asset-agent://` protocol, which handles
+// Range requests for audio and video and can return thumbnails
+const ASSET_SCHEME = 'asset-agent';
+
+export const assetUrl = (path: string, previewPx?: number): string => {
+  const url = convertFileSrc(path, ASSET_SCHEME);
+  return previewPx ? `${url}?preview=${previewPx}` : url;
+};
+
+// Sends pasted or dropped bytes as a raw body instead of base64 through invoke,
+// and returns the path they were saved to
+export const uploadAttachment = async (data: Blob, name: string): Promise<string> => {
+  const response = await fetch(`${convertFileSrc('upload', ASSET_SCHEME)}?name=${encodeURIComponent(name)}`, {
+    method: 'POST',
+    body: data,
+  });
+  if (!response.ok) {
+    throw new Error(await response.text());
+  }
+  const { path } = await response.json();
+  return path;
+};
+
 export const createFileAttachment = (filePath: string): Attachment | null => {
   const fileName = filePath.split('/').pop() || filePath;
   const fileType = getFileType(fileName);
@@ -146,7 +169,7 @@ export const createFileAttachment = (filePath: string): Attachment | null => {
     name: fileName,
     type: fileType,
     path: filePath,
-    preview: convertFileSrc(filePath),
+    preview: assetUrl(filePath),
     extension: fileName.split('.').pop()?.toLowerCase()
   };
 };
@@ -191,7 +214,7 @@ export const filterAndSortEntries = (entries: any[], basePath = ''): Attachment[
         type: entry.isDirectory ? 'folder' as const : fileType!,
         isDirectory: entry.isDirectory,
         extension,
-        preview: !entry.isDirectory && fileType ? convertFileSrc(path) : undefined
+        preview: !entry.isDirectory && fileType ? assetUrl(path) : undefined
       };
     })
     .sort((a, b) => {